@@ -111,6 +111,24 @@ pub enum QuantizationType {
     Int8,
 }
 
+/// ONNX Runtime execution provider used for Parakeet inference.
+///
+/// Providers other than CPU need ONNX Runtime built with support for them.
+/// If the requested provider can't be registered, inference falls back to
+/// the CPU provider.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExecutionProvider {
+    /// Plain CPU inference (default)
+    #[default]
+    Cpu,
+    /// Apple CoreML (macOS)
+    CoreML,
+    /// NVIDIA CUDA
+    Cuda,
+    /// DirectML (Windows)
+    DirectML,
+}
+
 /// Parameters for configuring Parakeet model loading.
 ///
 /// Controls model quantization settings for balancing performance vs accuracy.
@@ -121,6 +139,8 @@ pub struct ParakeetModelParams {
     /// Number of threads to use for CPU inference (None = auto-detect)
     /// Higher values can improve performance on multi-core CPUs
     pub intra_threads: Option<usize>,
+    /// Execution provider to try before falling back to CPU
+    pub execution_provider: ExecutionProvider,
 }
 
 impl ParakeetModelParams {
@@ -139,6 +159,7 @@ impl ParakeetModelParams {
         Self {
             quantization: QuantizationType::FP32,
             intra_threads: None,
+            execution_provider: ExecutionProvider::Cpu,
         }
     }
 
@@ -157,6 +178,7 @@ impl ParakeetModelParams {
         Self {
             quantization: QuantizationType::Int8,
             intra_threads: None,
+            execution_provider: ExecutionProvider::Cpu,
         }
     }
 
@@ -177,6 +199,7 @@ impl ParakeetModelParams {
         Self {
             quantization,
             intra_threads: None,
+            execution_provider: ExecutionProvider::Cpu,
         }
     }
 
@@ -197,6 +220,24 @@ impl ParakeetModelParams {
         self.intra_threads = threads;
         self
     }
+
+    /// Set the execution provider to use for inference.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - The execution provider, falling back to CPU if unavailable
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use transcribe_rs::engines::parakeet::{ExecutionProvider, ParakeetModelParams};
+    ///
+    /// let params = ParakeetModelParams::int8().with_execution_provider(ExecutionProvider::CoreML);
+    /// ```
+    pub fn with_execution_provider(mut self, provider: ExecutionProvider) -> Self {
+        self.execution_provider = provider;
+        self
+    }
 }
 
 /// Parameters for configuring Parakeet inference behavior.
@@ -288,7 +329,12 @@ impl TranscriptionEngine for ParakeetEngine {
             QuantizationType::FP32 => false,
             QuantizationType::Int8 => true,
         };
-        let model = ParakeetModel::new_with_threads(model_path, quantized, params.intra_threads)?;
+        let model = ParakeetModel::new_with_options(
+            model_path,
+            quantized,
+            params.intra_threads,
+            params.execution_provider,
+        )?;
 
         self.model = Some(model);
         self.loaded_model_path = Some(model_path.to_path_buf());
@@ -77,8 +77,8 @@ pub mod model;
 pub mod timestamps;
 
 pub use engine::{
-    ParakeetEngine, ParakeetInferenceParams, ParakeetModelParams, QuantizationType,
-    TimestampGranularity,
+    ExecutionProvider, ParakeetEngine, ParakeetInferenceParams, ParakeetModelParams,
+    QuantizationType, TimestampGranularity,
 };
 pub use model::{ParakeetError, ParakeetModel, TimestampedResult};
 pub use timestamps::{convert_timestamps, WordBoundary};
@@ -1,12 +1,17 @@
 use ndarray::{Array, Array1, Array2, Array3, ArrayD, ArrayViewD, IxDyn};
 use once_cell::sync::Lazy;
-use ort::execution_providers::CPUExecutionProvider;
+use ort::execution_providers::{
+    CPUExecutionProvider, CUDAExecutionProvider, CoreMLExecutionProvider,
+    DirectMLExecutionProvider, ExecutionProviderDispatch,
+};
 use ort::inputs;
 use ort::session::builder::GraphOptimizationLevel;
 use ort::session::Session;
 use ort::value::TensorRef;
 use regex::Regex;
 
+use super::engine::ExecutionProvider;
+
 use std::fs;
 use std::path::Path;
 
@@ -67,10 +72,37 @@ impl ParakeetModel {
         quantized: bool,
         intra_threads: Option<usize>,
     ) -> Result<Self, ParakeetError> {
-        let encoder = Self::init_session(&model_dir, "encoder-model", intra_threads, quantized)?;
-        let decoder_joint =
-            Self::init_session(&model_dir, "decoder_joint-model", intra_threads, quantized)?;
-        let preprocessor = Self::init_session(&model_dir, "nemo128", intra_threads, false)?;
+        Self::new_with_options(model_dir, quantized, intra_threads, ExecutionProvider::Cpu)
+    }
+
+    pub fn new_with_options<P: AsRef<Path>>(
+        model_dir: P,
+        quantized: bool,
+        intra_threads: Option<usize>,
+        execution_provider: ExecutionProvider,
+    ) -> Result<Self, ParakeetError> {
+        let encoder = Self::init_session(
+            &model_dir,
+            "encoder-model",
+            intra_threads,
+            quantized,
+            execution_provider,
+        )?;
+        let decoder_joint = Self::init_session(
+            &model_dir,
+            "decoder_joint-model",
+            intra_threads,
+            quantized,
+            execution_provider,
+        )?;
+        // The preprocessor is small; keep it on the CPU
+        let preprocessor = Self::init_session(
+            &model_dir,
+            "nemo128",
+            intra_threads,
+            false,
+            ExecutionProvider::Cpu,
+        )?;
 
         let (vocab, blank_idx) = Self::load_vocab(&model_dir)?;
         let vocab_size = vocab.len();
@@ -97,8 +129,16 @@ impl ParakeetModel {
         model_name: &str,
         intra_threads: Option<usize>,
         try_quantized: bool,
+        execution_provider: ExecutionProvider,
     ) -> Result<Session, ParakeetError> {
-        let providers = vec![CPUExecutionProvider::default().build()];
+        // Providers that fail to register are skipped, so CPU always remains
+        let mut providers: Vec<ExecutionProviderDispatch> = match execution_provider {
+            ExecutionProvider::Cpu => Vec::new(),
+            ExecutionProvider::CoreML => vec![CoreMLExecutionProvider::default().build()],
+            ExecutionProvider::Cuda => vec![CUDAExecutionProvider::default().build()],
+            ExecutionProvider::DirectML => vec![DirectMLExecutionProvider::default().build()],
+        };
+        providers.push(CPUExecutionProvider::default().build());
 
         // Try quantized version first if requested, fallback to regular version
         let model_filename = if try_quantized {
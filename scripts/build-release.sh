@@ -41,6 +41,14 @@ SCRIPT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)"
 PROJECT_ROOT="$(cd "$SCRIPT_DIR/.." && pwd)"
 cd "$PROJECT_ROOT"
 
+# Bundled models without a checksum can't be verified before installing
+if grep -q '"sha256": null' src-tauri/resources/model_catalog.json; then
+    echo -e "${RED}Error: some models in the bundled catalog have no checksum${NC}"
+    echo ""
+    echo "Run: ./scripts/update-catalog-checksums.sh"
+    exit 1
+fi
+
 # Read current version from tauri.conf.json
 CURRENT_VERSION=$(grep -o '"version": "[^"]*"' src-tauri/tauri.conf.json | cut -d'"' -f4)
 echo -e "${BLUE}Current version:${NC} $CURRENT_VERSION"
@@ -0,0 +1,56 @@
+#!/bin/bash
+
+# Fills in the sha256 of every model in the bundled catalog that doesn't have
+# one yet, by downloading its archive from the first URL.
+#
+# Usage: ./scripts/update-catalog-checksums.sh
+
+set -e  # Exit on error
+
+GREEN='\033[0;32m'
+BLUE='\033[0;34m'
+RED='\033[0;31m'
+NC='\033[0m' # No Color
+
+SCRIPT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)"
+PROJECT_ROOT="$(cd "$SCRIPT_DIR/.." && pwd)"
+CATALOG="$PROJECT_ROOT/src-tauri/resources/model_catalog.json"
+
+WORK_DIR="$(mktemp -d)"
+trap 'rm -rf "$WORK_DIR"' EXIT
+
+# "filename url" for each model still missing a checksum
+MISSING=$(python3 - "$CATALOG" <<'EOF'
+import json, sys
+for model in json.load(open(sys.argv[1]))["models"]:
+    if model.get("sha256") is None and model.get("urls"):
+        print(model["filename"], model["urls"][0])
+EOF
+)
+
+if [ -z "$MISSING" ]; then
+    echo -e "${GREEN}Every catalog model already has a checksum${NC}"
+    exit 0
+fi
+
+while read -r FILENAME URL; do
+    echo -e "${BLUE}Downloading${NC} $URL"
+    if ! curl -fL --retry 3 -o "$WORK_DIR/$FILENAME" "$URL"; then
+        echo -e "${RED}Error: couldn't download $URL${NC}"
+        exit 1
+    fi
+    DIGEST=$(shasum -a 256 "$WORK_DIR/$FILENAME" | cut -d' ' -f1)
+    rm -f "$WORK_DIR/$FILENAME"
+
+    # Edit the entry in place so the file's layout is kept
+    python3 - "$CATALOG" "$FILENAME" "$DIGEST" <<'EOF'
+import re, sys
+path, filename, digest = sys.argv[1:]
+text = open(path).read()
+start = text.index(f'"filename": "{filename}"')
+match = re.compile(r'"sha256": null').search(text, start)
+text = text[: match.start()] + f'"sha256": "{digest}"' + text[match.end() :]
+open(path, "w").write(text)
+EOF
+    echo -e "${GREEN}$FILENAME${NC} $DIGEST"
+done <<< "$MISSING"
@@ -1,23 +1,24 @@
-use crate::medical_vocab::MedicalVocabulary;
 // // // #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
 // // // use crate::apple_intelligence;
 use crate::audio_feedback::{play_feedback_sound, play_feedback_sound_blocking, SoundType};
+use crate::audio_toolkit::audio::AutoStopReason;
 use crate::managers::audio::AudioRecordingManager;
+use crate::managers::queue::TranscriptionQueue;
 // EPHEMERAL MODE: HistoryManager no longer used
 // use crate::managers::history::HistoryManager;
 use crate::managers::transcription::TranscriptionManager;
-use crate::settings::{get_settings, AppSettings};
+use crate::settings::get_settings;
 use crate::shortcut;
 use crate::tray::{change_tray_icon, TrayIconState};
 use crate::utils::{self, show_recording_overlay, show_transcribing_overlay};
-use ferrous_opencc::{config::BuiltinConfig, OpenCC};
+use crate::ManagedToggleState;
 use log::{debug, error};
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Instant;
 use tauri::AppHandle;
-use tauri::Manager;
+use tauri::{Emitter, Manager};
 
 // Shortcut Action Trait
 pub trait ShortcutAction: Send + Sync {
@@ -28,53 +29,6 @@ pub trait ShortcutAction: Send + Sync {
 // Transcribe Action
 struct TranscribeAction;
 
-// LLM post-processing has been removed for privacy and HIPAA compliance
-// All transcription is now processed locally only
-
-async fn maybe_convert_chinese_variant(
-    settings: &AppSettings,
-    transcription: &str,
-) -> Option<String> {
-    // Check if language is set to Simplified or Traditional Chinese
-    let is_simplified = settings.selected_language == "zh-Hans";
-    let is_traditional = settings.selected_language == "zh-Hant";
-
-    if !is_simplified && !is_traditional {
-        debug!("selected_language is not Simplified or Traditional Chinese; skipping translation");
-        return None;
-    }
-
-    debug!(
-        "Starting Chinese translation using OpenCC for language: {}",
-        settings.selected_language
-    );
-
-    // Use OpenCC to convert based on selected language
-    let config = if is_simplified {
-        // Convert Traditional Chinese to Simplified Chinese
-        BuiltinConfig::Tw2sp
-    } else {
-        // Convert Simplified Chinese to Traditional Chinese
-        BuiltinConfig::S2twp
-    };
-
-    match OpenCC::from_config(config) {
-        Ok(converter) => {
-            let converted = converter.convert(transcription);
-            debug!(
-                "OpenCC translation completed. Input length: {}, Output length: {}",
-                transcription.len(),
-                converted.len()
-            );
-            Some(converted)
-        }
-        Err(e) => {
-            error!("Failed to initialize OpenCC converter: {}. Falling back to original transcription.", e);
-            None
-        }
-    }
-}
-
 impl ShortcutAction for TranscribeAction {
     fn start(&self, app: &AppHandle, binding_id: &str, _shortcut_str: &str) {
         let start_time = Instant::now();
@@ -89,6 +43,7 @@ impl ShortcutAction for TranscribeAction {
         show_recording_overlay(app);
 
         let rm = app.state::<Arc<AudioRecordingManager>>();
+        let queue = app.state::<Arc<TranscriptionQueue>>();
 
         // Get the microphone mode to determine audio feedback timing
         let settings = get_settings(app);
@@ -146,8 +101,15 @@ impl ShortcutAction for TranscribeAction {
         };
 
         if recording_started {
+            // Each recording becomes its own job so the next one can start while
+            // this one is still being transcribed
+            let job_id = queue.begin_recording(&binding_id);
+            debug!("Recording for binding {} is job {}", binding_id, job_id);
+
             // Dynamically register the cancel shortcut in a separate task to avoid deadlock
             shortcut::register_cancel_shortcut(app);
+        } else {
+            queue.refresh_ui();
         }
 
         debug!(
@@ -163,9 +125,15 @@ impl ShortcutAction for TranscribeAction {
         let stop_time = Instant::now();
         debug!("TranscribeAction::stop called for binding: {}", binding_id);
 
-        let ah = app.clone();
         let rm = Arc::clone(&app.state::<Arc<AudioRecordingManager>>());
-        let tm = Arc::clone(&app.state::<Arc<TranscriptionManager>>());
+        if !rm.is_recording() {
+            // Auto-stop (or a failed start) already ended it, e.g. before the
+            // push-to-talk key was released
+            debug!("Not recording; nothing to stop for binding {}", binding_id);
+            return;
+        }
+        let queue = Arc::clone(&app.state::<Arc<TranscriptionQueue>>());
+        let job_id = queue.recording_job();
 
         change_tray_icon(app, TrayIconState::Transcribing);
         show_transcribing_overlay(app);
@@ -179,84 +147,29 @@ impl ShortcutAction for TranscribeAction {
         let binding_id = binding_id.to_string(); // Clone binding_id for the async task
 
         tauri::async_runtime::spawn(async move {
-            let binding_id = binding_id.clone(); // Clone for the inner async task
-            debug!(
-                "Starting async transcription task for binding: {}",
-                binding_id
-            );
-
             let stop_recording_time = Instant::now();
-            if let Some(samples) = rm.stop_recording(&binding_id) {
-                debug!(
-                    "Recording stopped and samples retrieved in {:?}, sample count: {}",
-                    stop_recording_time.elapsed(),
-                    samples.len()
-                );
-
-                let transcription_time = Instant::now();
-                match tm.transcribe(samples) {
-                    Ok(transcription) => {
-                        debug!(
-                            "Transcription completed in {:?}: '{}'",
-                            transcription_time.elapsed(),
-                            transcription
-                        );
-                        if !transcription.is_empty() {
-                            let settings = get_settings(&ah);
-                            let mut final_text = transcription.clone();
-
-                            // Apply medical vocabulary processing if enabled
-                            if settings.medical_mode_enabled {
-                                let mut medical_vocab = MedicalVocabulary::new();
-                                final_text = medical_vocab.process_text(&final_text);
-                            }
-
-                            // Check if Chinese variant conversion is needed (local processing only)
-                            if let Some(converted_text) =
-                                maybe_convert_chinese_variant(&settings, &transcription).await
-                            {
-                                final_text = converted_text;
-                            }
-
-                            // EPHEMERAL MODE: Transcriptions are not saved to disk
-                            // Audio and text are processed in-memory only for privacy compliance
-                            // This eliminates the need for data-at-rest encryption (PIPEDA Section 4.5)
-
-                            // Paste the final text (either processed or original)
-                            let ah_clone = ah.clone();
-                            let paste_time = Instant::now();
-                            ah.run_on_main_thread(move || {
-                                match utils::paste(final_text, ah_clone.clone()) {
-                                    Ok(()) => debug!(
-                                        "Text pasted successfully in {:?}",
-                                        paste_time.elapsed()
-                                    ),
-                                    Err(e) => error!("Failed to paste transcription: {}", e),
-                                }
-                                // Hide the overlay after transcription is complete
-                                utils::hide_recording_overlay(&ah_clone);
-                                change_tray_icon(&ah_clone, TrayIconState::Idle);
-                            })
-                            .unwrap_or_else(|e| {
-                                error!("Failed to run paste on main thread: {:?}", e);
-                                utils::hide_recording_overlay(&ah);
-                                change_tray_icon(&ah, TrayIconState::Idle);
-                            });
-                        } else {
-                            utils::hide_recording_overlay(&ah);
-                            change_tray_icon(&ah, TrayIconState::Idle);
-                        }
-                    }
-                    Err(err) => {
-                        debug!("Global Shortcut Transcription error: {}", err);
-                        utils::hide_recording_overlay(&ah);
-                        change_tray_icon(&ah, TrayIconState::Idle);
-                    }
+            let samples = rm.stop_recording(&binding_id);
+
+            let Some(job_id) = job_id else {
+                debug!("No transcription job for binding {}", binding_id);
+                queue.refresh_ui();
+                return;
+            };
+
+            match samples {
+                Some(samples) => {
+                    debug!(
+                        "Recording stopped and samples retrieved in {:?}, sample count: {}",
+                        stop_recording_time.elapsed(),
+                        samples.len()
+                    );
+                    // Transcription and paste happen on the queue worker, in recording order
+                    queue.submit(job_id, samples);
+                }
+                None => {
+                    debug!("No samples retrieved from recording stop");
+                    queue.discard(job_id);
                 }
-            } else {
-                debug!("No samples retrieved from recording stop");
-                utils::hide_recording_overlay(&ah);
-                change_tray_icon(&ah, TrayIconState::Idle);
             }
         });
 
@@ -303,6 +216,98 @@ impl ShortcutAction for TestAction {
     }
 }
 
+/// Ends a recording that hit an auto-stop limit the same way a second press
+/// of its shortcut would. A recording with no speech in it is cancelled.
+pub fn auto_stop_recording(app: &AppHandle, binding_id: &str, reason: AutoStopReason) {
+    debug!("Auto-stopping binding {}: {:?}", binding_id, reason);
+    let _ = app.emit("recording-auto-stopped", reason);
+
+    if reason == AutoStopReason::NoSpeech {
+        utils::cancel_current_operation(app);
+        return;
+    }
+
+    // Toggle mode would otherwise treat the next press as "stop"
+    if let Ok(mut states) = app.state::<ManagedToggleState>().lock() {
+        states.active_toggles.insert(binding_id.to_string(), false);
+    }
+    if let Some(action) = ACTION_MAP.get(binding_id) {
+        action.stop(app, binding_id, "auto-stop");
+    }
+}
+
+/// Starts or stops a binding's action from outside its shortcut (signals, the
+/// control API), keeping toggle mode in step so the next press does the
+/// opposite. Returns false if the binding was already in that state.
+pub fn set_binding_active(
+    app: &AppHandle,
+    binding_id: &str,
+    active: bool,
+    source: &str,
+) -> Result<bool, String> {
+    if binding_id == "cancel" {
+        return Err("The cancel binding can't be started or stopped".to_string());
+    }
+    let action = ACTION_MAP
+        .get(binding_id)
+        .ok_or_else(|| format!("Unknown binding: {}", binding_id))?;
+    let records = binding_id == "transcribe";
+    let rm = Arc::clone(&app.state::<Arc<AudioRecordingManager>>());
+
+    // Update the state while holding the lock, but release it before calling
+    // the action, which may need it (e.g. cancel_current_operation)
+    {
+        let toggle_state_manager = app.state::<ManagedToggleState>();
+        let mut states = toggle_state_manager
+            .lock()
+            .map_err(|e| format!("Failed to lock toggle state manager: {}", e))?;
+        let toggled = states
+            .active_toggles
+            .entry(binding_id.to_string())
+            .or_insert(false);
+        // Push-to-talk and auto-stop don't touch the toggle, so ask the recorder
+        let was_active = if records { rm.is_recording() } else { *toggled };
+        *toggled = active;
+        if was_active == active {
+            return Ok(false);
+        }
+    }
+
+    if !active {
+        action.stop(app, binding_id, source);
+        return Ok(true);
+    }
+
+    action.start(app, binding_id, source);
+    if records && !rm.is_recording() {
+        if let Ok(mut states) = app.state::<ManagedToggleState>().lock() {
+            states.active_toggles.insert(binding_id.to_string(), false);
+        }
+        return Err("Failed to start recording".to_string());
+    }
+    Ok(true)
+}
+
+/// Flips a binding the way a press in toggle mode would. Returns whether it
+/// is now active.
+pub fn toggle_binding(app: &AppHandle, binding_id: &str, source: &str) -> Result<bool, String> {
+    let active = if binding_id == "transcribe" {
+        app.state::<Arc<AudioRecordingManager>>().is_recording()
+    } else {
+        let toggle_state_manager = app.state::<ManagedToggleState>();
+        let states = toggle_state_manager
+            .lock()
+            .map_err(|e| format!("Failed to lock toggle state manager: {}", e))?;
+        states
+            .active_toggles
+            .get(binding_id)
+            .copied()
+            .unwrap_or(false)
+    };
+    set_binding_active(app, binding_id, !active, source)?;
+    Ok(!active)
+}
+
 // Static Action Map
 pub static ACTION_MAP: Lazy<HashMap<String, Arc<dyn ShortcutAction>>> = Lazy::new(|| {
     let mut map = HashMap::new();
@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// How a multi-channel input is turned into the mono signal we record.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, Type)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum ChannelPolicy {
+    /// Average all channels
+    #[default]
+    Average,
+    /// Use one channel (zero-based) and ignore the rest
+    Channel { index: u16 },
+    /// Use whichever channel is loudest in each block
+    Loudest,
+}
+
+/// Applies a [`ChannelPolicy`] to interleaved blocks from one device.
+pub(crate) struct ChannelMixer {
+    policy: ChannelPolicy,
+    channels: usize,
+}
+
+impl ChannelMixer {
+    /// A channel the device doesn't have falls back to averaging.
+    pub fn new(policy: ChannelPolicy, channels: usize) -> Self {
+        let policy = match policy {
+            ChannelPolicy::Channel { index } if index as usize >= channels => {
+                log::warn!(
+                    "Channel {} requested but the device has {}; averaging instead",
+                    index,
+                    channels
+                );
+                ChannelPolicy::Average
+            }
+            policy => policy,
+        };
+        Self {
+            policy,
+            channels: channels.max(1),
+        }
+    }
+
+    /// Appends the mono version of `interleaved` to `out`. A trailing
+    /// partial frame is dropped.
+    pub fn mix(&self, interleaved: &[f32], out: &mut Vec<f32>) {
+        let channels = self.channels;
+        if channels == 1 {
+            out.extend_from_slice(interleaved);
+            return;
+        }
+
+        let frames = interleaved.chunks_exact(channels);
+        out.reserve(frames.len());
+        match self.policy {
+            ChannelPolicy::Average => {
+                out.extend(frames.map(|frame| frame.iter().sum::<f32>() / channels as f32))
+            }
+            ChannelPolicy::Channel { index } => {
+                out.extend(frames.map(|frame| frame[index as usize]))
+            }
+            ChannelPolicy::Loudest => {
+                let loudest = Self::loudest_channel(interleaved, channels);
+                out.extend(frames.map(|frame| frame[loudest]))
+            }
+        }
+    }
+
+    fn loudest_channel(interleaved: &[f32], channels: usize) -> usize {
+        let mut energy = vec![0.0f32; channels];
+        for frame in interleaved.chunks_exact(channels) {
+            for (e, &s) in energy.iter_mut().zip(frame) {
+                *e += s * s;
+            }
+        }
+        energy
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map_or(0, |(i, _)| i)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Three channels: a quiet tone, silence and a loud tone.
+    fn block(frames: usize) -> Vec<f32> {
+        (0..frames)
+            .flat_map(|i| {
+                let s = (i as f32 * 0.3).sin();
+                [0.1 * s, 0.0, 0.8 * s]
+            })
+            .collect()
+    }
+
+    fn mix(policy: ChannelPolicy, interleaved: &[f32], channels: usize) -> Vec<f32> {
+        let mut out = Vec::new();
+        ChannelMixer::new(policy, channels).mix(interleaved, &mut out);
+        out
+    }
+
+    #[test]
+    fn test_average() {
+        let input = block(100);
+        let out = mix(ChannelPolicy::Average, &input, 3);
+        assert_eq!(out.len(), 100);
+        for (frame, mixed) in input.chunks_exact(3).zip(&out) {
+            assert!((mixed - (frame[0] + frame[2]) / 3.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_pick_channel() {
+        let input = block(100);
+        let out = mix(ChannelPolicy::Channel { index: 0 }, &input, 3);
+        let expected: Vec<f32> = input.chunks_exact(3).map(|f| f[0]).collect();
+        assert_eq!(out, expected);
+
+        // Out of range falls back to averaging
+        let out = mix(ChannelPolicy::Channel { index: 5 }, &input, 3);
+        assert_eq!(out, mix(ChannelPolicy::Average, &input, 3));
+    }
+
+    #[test]
+    fn test_loudest_follows_the_signal() {
+        let input = block(100);
+        let out = mix(ChannelPolicy::Loudest, &input, 3);
+        let expected: Vec<f32> = input.chunks_exact(3).map(|f| f[2]).collect();
+        assert_eq!(out, expected);
+
+        // Swap the loud and quiet channels for the next block
+        let swapped: Vec<f32> = input
+            .chunks_exact(3)
+            .flat_map(|f| [f[2], f[1], f[0]])
+            .collect();
+        let out = mix(ChannelPolicy::Loudest, &swapped, 3);
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn test_mono_and_partial_frames() {
+        let out = mix(ChannelPolicy::Loudest, &[0.1, 0.2, 0.3], 1);
+        assert_eq!(out, vec![0.1, 0.2, 0.3]);
+
+        let out = mix(ChannelPolicy::Average, &[0.2, 0.4, 0.6], 2);
+        assert_eq!(out.len(), 1);
+    }
+}
@@ -1,12 +1,40 @@
 use cpal::traits::{DeviceTrait, HostTrait};
+use cpal::SupportedStreamConfigRange;
+
+/// Rates reported in [`CpalDeviceInfo::sample_rates`] when a device supports them.
+const COMMON_SAMPLE_RATES: [u32; 11] = [
+    8000, 11025, 16000, 22050, 32000, 44100, 48000, 88200, 96000, 176400, 192000,
+];
 
 pub struct CpalDeviceInfo {
     pub index: String,
     pub name: String,
     pub is_default: bool,
+    /// Channel counts the device can be opened with, ascending
+    pub channels: Vec<u16>,
+    /// Common sample rates the device supports, ascending
+    pub sample_rates: Vec<u32>,
     pub device: cpal::Device,
 }
 
+fn capabilities(configs: impl Iterator<Item = SupportedStreamConfigRange>) -> (Vec<u16>, Vec<u32>) {
+    let mut channels = Vec::new();
+    let mut sample_rates = Vec::new();
+
+    for config in configs {
+        channels.push(config.channels());
+        sample_rates.extend(COMMON_SAMPLE_RATES.iter().filter(|&&rate| {
+            config.min_sample_rate().0 <= rate && rate <= config.max_sample_rate().0
+        }));
+    }
+
+    channels.sort_unstable();
+    channels.dedup();
+    sample_rates.sort_unstable();
+    sample_rates.dedup();
+    (channels, sample_rates)
+}
+
 pub fn list_input_devices() -> Result<Vec<CpalDeviceInfo>, Box<dyn std::error::Error>> {
     let host = crate::audio_toolkit::get_cpal_host();
     let default_name = host.default_input_device().and_then(|d| d.name().ok());
@@ -17,11 +45,17 @@ pub fn list_input_devices() -> Result<Vec<CpalDeviceInfo>, Box<dyn std::error::E
         let name = device.name().unwrap_or_else(|_| "Unknown".into());
 
         let is_default = Some(name.clone()) == default_name;
+        let (channels, sample_rates) = match device.supported_input_configs() {
+            Ok(configs) => capabilities(configs),
+            Err(_) => Default::default(),
+        };
 
         out.push(CpalDeviceInfo {
             index: index.to_string(),
             name,
             is_default,
+            channels,
+            sample_rates,
             device,
         });
     }
@@ -29,6 +63,16 @@ pub fn list_input_devices() -> Result<Vec<CpalDeviceInfo>, Box<dyn std::error::E
     Ok(out)
 }
 
+/// Just the names of the input devices, without querying what each one
+/// supports. Cheap enough to poll.
+pub fn list_input_device_names() -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let host = crate::audio_toolkit::get_cpal_host();
+    Ok(host
+        .input_devices()?
+        .map(|device| device.name().unwrap_or_else(|_| "Unknown".into()))
+        .collect())
+}
+
 pub fn list_output_devices() -> Result<Vec<CpalDeviceInfo>, Box<dyn std::error::Error>> {
     let host = crate::audio_toolkit::get_cpal_host();
     let default_name = host.default_output_device().and_then(|d| d.name().ok());
@@ -39,11 +83,17 @@ pub fn list_output_devices() -> Result<Vec<CpalDeviceInfo>, Box<dyn std::error::
         let name = device.name().unwrap_or_else(|_| "Unknown".into());
 
         let is_default = Some(name.clone()) == default_name;
+        let (channels, sample_rates) = match device.supported_output_configs() {
+            Ok(configs) => capabilities(configs),
+            Err(_) => Default::default(),
+        };
 
         out.push(CpalDeviceInfo {
             index: index.to_string(),
             name,
             is_default,
+            channels,
+            sample_rates,
             device,
         });
     }
@@ -0,0 +1,510 @@
+use rustfft::{num_complex::Complex32, Fft, FftPlanner};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::VecDeque;
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+/// Optional clean-up between the resampler and the VAD. Each stage can be
+/// turned on separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Type)]
+#[serde(default)]
+pub struct DspConfig {
+    /// Remove DC offset and rumble below speech
+    pub high_pass: bool,
+    /// Spectral subtraction of steady background noise (HVAC, fans)
+    pub noise_suppression: bool,
+    /// Bring quiet or very loud recordings to a standard level before transcription
+    pub normalize_gain: bool,
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+const HIGH_PASS_HZ: f64 = 80.0;
+
+/// Second-order Butterworth high-pass (RBJ cookbook biquad).
+pub(crate) struct HighPass {
+    b: [f64; 3],
+    a: [f64; 2],
+    x: [f64; 2],
+    y: [f64; 2],
+}
+
+impl HighPass {
+    pub fn new(sample_rate: u32, cutoff_hz: f64) -> Self {
+        let w0 = 2.0 * std::f64::consts::PI * cutoff_hz / sample_rate as f64;
+        let (sin, cos) = w0.sin_cos();
+        let alpha = sin / std::f64::consts::SQRT_2; // Q = 1/sqrt(2)
+        let a0 = 1.0 + alpha;
+        Self {
+            b: [
+                (1.0 + cos) / 2.0 / a0,
+                -(1.0 + cos) / a0,
+                (1.0 + cos) / 2.0 / a0,
+            ],
+            a: [-2.0 * cos / a0, (1.0 - alpha) / a0],
+            x: [0.0; 2],
+            y: [0.0; 2],
+        }
+    }
+
+    pub fn process(&mut self, samples: &mut [f32]) {
+        for sample in samples.iter_mut() {
+            let x = *sample as f64;
+            let y = self.b[0] * x + self.b[1] * self.x[0] + self.b[2] * self.x[1]
+                - self.a[0] * self.y[0]
+                - self.a[1] * self.y[1];
+            self.x = [x, self.x[0]];
+            self.y = [y, self.y[0]];
+            *sample = y as f32;
+        }
+    }
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+/// Background noise level by frequency, as power spectral density (power
+/// per Hz) so it carries over between sample rates and FFT sizes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoiseProfile {
+    /// (frequency in Hz, PSD), ascending by frequency
+    points: Vec<(f32, f32)>,
+}
+
+impl NoiseProfile {
+    pub fn new(mut points: Vec<(f32, f32)>) -> Self {
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { points }
+    }
+
+    /// Linear interpolation between points; flat beyond either end.
+    fn psd_at(&self, hz: f32) -> f32 {
+        let Some(&(first_hz, first)) = self.points.first() else {
+            return 0.0;
+        };
+        if hz <= first_hz {
+            return first;
+        }
+        for pair in self.points.windows(2) {
+            let ((lo_hz, lo), (hi_hz, hi)) = (pair[0], pair[1]);
+            if hz <= hi_hz {
+                let t = (hz - lo_hz) / (hi_hz - lo_hz).max(f32::EPSILON);
+                return lo + (hi - lo) * t;
+            }
+        }
+        self.points[self.points.len() - 1].1
+    }
+}
+
+const FFT_SIZE: usize = 512;
+const HOP: usize = FFT_SIZE / 2;
+/// Subtract more than the estimate; the estimate is an average and the
+/// noise in any one window peaks well above it.
+const OVER_SUBTRACTION: f32 = 2.0;
+/// Never attenuate a bin by more than 20 dB. Gating bins completely leaves
+/// "musical" chirps that hurt recognition more than the noise did.
+const GAIN_FLOOR: f32 = 0.1;
+
+/// Spectral subtraction with 50% overlap-add. Output is delayed by
+/// `FFT_SIZE` samples (32 ms at 16 kHz) so that it always comes out in
+/// the same frame sizes as it went in.
+pub(crate) struct SpectralDenoiser {
+    sample_rate: u32,
+    fft: Arc<dyn Fft<f32>>,
+    ifft: Arc<dyn Fft<f32>>,
+    /// Square root of a periodic Hann window, applied before and after, so
+    /// the two together sum to one across overlapping hops
+    window: Vec<f32>,
+    spectrum: Vec<Complex32>,
+    input: Vec<f32>,
+    overlap: Vec<f32>,
+    output: VecDeque<f32>,
+    /// Expected noise power in each bin, 0..=FFT_SIZE/2; empty until a
+    /// profile is set
+    noise_power: Vec<f32>,
+}
+
+impl SpectralDenoiser {
+    pub fn new(sample_rate: u32) -> Self {
+        let mut planner = FftPlanner::<f32>::new();
+        let window = (0..FFT_SIZE)
+            .map(|i| (0.5 * (1.0 - (2.0 * PI * i as f32 / FFT_SIZE as f32).cos())).sqrt())
+            .collect();
+        let mut denoiser = Self {
+            sample_rate,
+            fft: planner.plan_fft_forward(FFT_SIZE),
+            ifft: planner.plan_fft_inverse(FFT_SIZE),
+            window,
+            spectrum: vec![Complex32::new(0.0, 0.0); FFT_SIZE],
+            input: Vec::with_capacity(FFT_SIZE * 2),
+            overlap: vec![0.0; FFT_SIZE],
+            output: VecDeque::with_capacity(FFT_SIZE * 2),
+            noise_power: Vec::new(),
+        };
+        denoiser.reset();
+        denoiser
+    }
+
+    pub fn reset(&mut self) {
+        self.input.clear();
+        self.input.resize(HOP, 0.0);
+        self.overlap.fill(0.0);
+        self.output.clear();
+        self.output.resize(HOP, 0.0);
+    }
+
+    /// Hands back the `FFT_SIZE` samples still in flight and starts afresh,
+    /// keeping the noise profile.
+    pub fn flush(&mut self) -> Vec<f32> {
+        let mut tail = vec![0.0; FFT_SIZE];
+        self.process(&mut tail);
+        self.reset();
+        tail
+    }
+
+    pub fn set_noise_profile(&mut self, profile: &NoiseProfile) {
+        // Noise power per bin is PSD x sample rate x window energy, and the
+        // window energy of a square-root Hann is half its length
+        let window_energy = FFT_SIZE as f32 / 2.0;
+        let bin_hz = self.sample_rate as f32 / FFT_SIZE as f32;
+        self.noise_power = (0..=FFT_SIZE / 2)
+            .map(|k| profile.psd_at(k as f32 * bin_hz) * self.sample_rate as f32 * window_energy)
+            .collect();
+    }
+
+    pub fn process(&mut self, samples: &mut [f32]) {
+        self.input.extend_from_slice(samples);
+        while self.input.len() >= FFT_SIZE {
+            self.process_window();
+            self.input.drain(..HOP);
+        }
+
+        for sample in samples.iter_mut() {
+            *sample = self.output.pop_front().unwrap_or(0.0);
+        }
+    }
+
+    fn process_window(&mut self) {
+        for (i, slot) in self.spectrum.iter_mut().enumerate() {
+            *slot = Complex32::new(self.input[i] * self.window[i], 0.0);
+        }
+        self.fft.process(&mut self.spectrum);
+
+        if !self.noise_power.is_empty() {
+            for (k, bin) in self.spectrum.iter_mut().enumerate() {
+                let noise = self.noise_power[k.min(FFT_SIZE - k)];
+                let power = bin.norm_sqr();
+                let gain = if power > 0.0 {
+                    (1.0 - OVER_SUBTRACTION * noise / power)
+                        .max(GAIN_FLOOR * GAIN_FLOOR)
+                        .sqrt()
+                } else {
+                    GAIN_FLOOR
+                };
+                *bin *= gain;
+            }
+        }
+
+        self.ifft.process(&mut self.spectrum);
+        for (i, bin) in self.spectrum.iter().enumerate() {
+            self.overlap[i] += bin.re / FFT_SIZE as f32 * self.window[i];
+        }
+
+        self.output.extend(self.overlap.drain(..HOP));
+        self.overlap.resize(FFT_SIZE, 0.0);
+    }
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+const TARGET_RMS_DB: f32 = -20.0;
+const TARGET_PEAK_DB: f32 = -1.0;
+/// Quiet speakers are brought up, but a near-silent recording isn't turned
+/// into loud noise.
+const MAX_GAIN_DB: f32 = 20.0;
+
+fn db_to_linear(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// Scales a whole recording towards a standard speech level without
+/// letting its peaks clip.
+pub fn normalize_gain(samples: &mut [f32]) {
+    if samples.is_empty() {
+        return;
+    }
+    let peak = samples.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+    if peak == 0.0 {
+        return;
+    }
+    let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+
+    let gain = (db_to_linear(TARGET_RMS_DB) / rms)
+        .min(db_to_linear(TARGET_PEAK_DB) / peak)
+        .min(db_to_linear(MAX_GAIN_DB));
+    for sample in samples.iter_mut() {
+        *sample *= gain;
+    }
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+/// The configured stages, run on each resampled frame.
+pub(crate) struct DspChain {
+    config: DspConfig,
+    high_pass: HighPass,
+    denoiser: SpectralDenoiser,
+    scratch: Vec<f32>,
+}
+
+impl DspChain {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            config: DspConfig::default(),
+            high_pass: HighPass::new(sample_rate, HIGH_PASS_HZ),
+            denoiser: SpectralDenoiser::new(sample_rate),
+            scratch: Vec::new(),
+        }
+    }
+
+    pub fn config(&self) -> DspConfig {
+        self.config
+    }
+
+    pub fn set_config(&mut self, config: DspConfig) {
+        if config.noise_suppression && !self.config.noise_suppression {
+            self.denoiser.reset();
+        }
+        self.config = config;
+    }
+
+    pub fn set_noise_profile(&mut self, profile: &NoiseProfile) {
+        self.denoiser.set_noise_profile(profile);
+    }
+
+    pub fn process(&mut self, frame: &[f32]) -> &[f32] {
+        self.scratch.clear();
+        self.scratch.extend_from_slice(frame);
+        if self.config.high_pass {
+            self.high_pass.process(&mut self.scratch);
+        }
+        if self.config.noise_suppression {
+            self.denoiser.process(&mut self.scratch);
+        }
+        &self.scratch
+    }
+
+    /// Audio the stages are still holding on to, which `process` would
+    /// only have given back with later frames. The chain starts afresh.
+    pub fn flush(&mut self) -> Vec<f32> {
+        if !self.config.noise_suppression {
+            return Vec::new();
+        }
+        self.denoiser.flush()
+    }
+
+    /// Whole-recording stages, run once recording stops.
+    pub fn finish(&self, samples: &mut [f32]) {
+        if self.config.normalize_gain {
+            normalize_gain(samples);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio_toolkit::audio::AudioVisualiser;
+
+    const RATE: u32 = 16000;
+
+    fn tone(freq: f32, amplitude: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| amplitude * (2.0 * PI * freq * i as f32 / RATE as f32).sin())
+            .collect()
+    }
+
+    fn noise(amplitude: f32, len: usize) -> Vec<f32> {
+        let mut seed = 0x1234_5678u32;
+        (0..len)
+            .map(|_| {
+                seed ^= seed << 13;
+                seed ^= seed >> 17;
+                seed ^= seed << 5;
+                amplitude * (seed as f32 / u32::MAX as f32 * 2.0 - 1.0)
+            })
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    fn db(ratio: f32) -> f32 {
+        20.0 * ratio.log10()
+    }
+
+    /// Runs `samples` through `f` in 30 ms frames, as the recorder does.
+    fn in_frames(samples: &[f32], mut f: impl FnMut(&mut [f32])) -> Vec<f32> {
+        let mut out = samples.to_vec();
+        for frame in out.chunks_mut(480) {
+            f(frame);
+        }
+        out
+    }
+
+    #[test]
+    fn test_high_pass_removes_dc_and_hum_but_keeps_speech_band() {
+        let len = RATE as usize * 2;
+        let settled = len / 2..len;
+
+        let mut hp = HighPass::new(RATE, HIGH_PASS_HZ);
+        let dc: Vec<f32> = tone(1000.0, 0.2, len).iter().map(|s| s + 0.3).collect();
+        let out = in_frames(&dc, |f| hp.process(f));
+        let mean = out[settled.clone()].iter().sum::<f32>() / settled.len() as f32;
+        assert!(mean.abs() < 1e-3, "mean {mean}");
+
+        for (freq, max_db) in [(20.0, -20.0), (50.0, -8.0)] {
+            let mut hp = HighPass::new(RATE, HIGH_PASS_HZ);
+            let rumble = tone(freq, 0.2, len);
+            let out = in_frames(&rumble, |f| hp.process(f));
+            let change = db(rms(&out[settled.clone()]) / rms(&rumble[settled.clone()]));
+            assert!(change < max_db, "{freq} Hz changed by {change} dB");
+        }
+
+        let mut hp = HighPass::new(RATE, HIGH_PASS_HZ);
+        let voice = tone(1000.0, 0.2, len);
+        let out = in_frames(&voice, |f| hp.process(f));
+        let change = db(rms(&out[settled.clone()]) / rms(&voice[settled]));
+        assert!(change.abs() < 0.5, "changed by {change} dB");
+    }
+
+    #[test]
+    fn test_denoiser_without_profile_is_a_delay() {
+        let input = noise(0.3, RATE as usize);
+        let mut denoiser = SpectralDenoiser::new(RATE);
+        let out = in_frames(&input, |f| denoiser.process(f));
+
+        for (i, (a, b)) in input.iter().zip(&out[FFT_SIZE..]).enumerate() {
+            assert!((a - b).abs() < 1e-4, "sample {i}: {a} vs {b}");
+        }
+    }
+
+    #[test]
+    fn test_denoiser_flush_returns_the_tail() {
+        let input = noise(0.3, 4800);
+        let mut denoiser = SpectralDenoiser::new(RATE);
+        let mut out = in_frames(&input, |f| denoiser.process(f));
+        out.extend(denoiser.flush());
+
+        let out = &out[FFT_SIZE..];
+        assert_eq!(out.len(), input.len());
+        for (i, (a, b)) in input.iter().zip(out).enumerate() {
+            assert!((a - b).abs() < 1e-4, "sample {i}: {a} vs {b}");
+        }
+
+        // Nothing from before the flush comes out afterwards
+        let after = in_frames(&[0.0; 960], |f| denoiser.process(f));
+        assert!(after.iter().all(|s| s.abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_denoiser_removes_white_noise_and_keeps_tone() {
+        let len = RATE as usize * 2;
+        let amplitude = 0.02;
+        // Uniform noise in [-a, a] has variance a^2 / 3, spread evenly up to Nyquist
+        let psd = amplitude * amplitude / 3.0 / RATE as f32;
+        let profile = NoiseProfile::new(vec![(0.0, psd), (8000.0, psd)]);
+
+        let background = noise(amplitude, len);
+        let mut denoiser = SpectralDenoiser::new(RATE);
+        denoiser.set_noise_profile(&profile);
+        let out = in_frames(&background, |f| denoiser.process(f));
+        let reduction = db(rms(&out[FFT_SIZE * 4..]) / rms(&background));
+        assert!(reduction < -6.0, "noise reduced by {reduction} dB");
+
+        let voice = tone(440.0, 0.2, len);
+        let mixed: Vec<f32> = voice.iter().zip(&background).map(|(v, n)| v + n).collect();
+        let mut denoiser = SpectralDenoiser::new(RATE);
+        denoiser.set_noise_profile(&profile);
+        let out = in_frames(&mixed, |f| denoiser.process(f));
+        let kept = db(rms(&out[FFT_SIZE * 4..]) / rms(&voice));
+        assert!(kept.abs() < 1.0, "tone changed by {kept} dB");
+    }
+
+    #[test]
+    fn test_noise_profile_interpolates() {
+        let profile = NoiseProfile::new(vec![(1000.0, 3.0), (500.0, 1.0)]);
+        assert_eq!(profile.psd_at(0.0), 1.0);
+        assert_eq!(profile.psd_at(750.0), 2.0);
+        assert_eq!(profile.psd_at(5000.0), 3.0);
+        assert_eq!(NoiseProfile::new(Vec::new()).psd_at(100.0), 0.0);
+    }
+
+    #[test]
+    fn test_profile_from_visualiser_noise_floor() {
+        let amplitude = 0.02;
+        let psd = amplitude * amplitude / 3.0 / RATE as f32;
+
+        let mut visualiser = AudioVisualiser::new(RATE, 512, 16, 400.0, 4000.0);
+        for chunk in noise(amplitude, RATE as usize * 20).chunks(480) {
+            visualiser.feed(chunk);
+        }
+
+        // The floor is a smoothed log average, so only roughly the true level.
+        // It covers the whole spectrum, not just the displayed vocal range.
+        let profile = visualiser.noise_profile();
+        for hz in [100.0, 500.0, 1000.0, 3000.0, 6000.0] {
+            let error = 10.0 * (profile.psd_at(hz) / psd).log10();
+            assert!(error.abs() < 3.0, "{hz} Hz off by {error} dB");
+        }
+
+        // Starting a recording doesn't make it learn the room again
+        visualiser.reset();
+        assert_eq!(visualiser.noise_profile(), profile);
+    }
+
+    #[test]
+    fn test_normalize_gain() {
+        // A quiet speaker is brought up to the target level
+        let mut quiet = tone(300.0, 0.03, 16000);
+        normalize_gain(&mut quiet);
+        assert!((db(rms(&quiet)) - TARGET_RMS_DB).abs() < 0.5);
+
+        // Peaks are kept below the ceiling even if that leaves it quieter
+        let mut spiky = tone(300.0, 0.03, 16000);
+        spiky[100] = 0.5;
+        normalize_gain(&mut spiky);
+        let peak = spiky.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+        assert!(db(peak) <= TARGET_PEAK_DB + 0.01);
+
+        // Near silence is boosted by at most MAX_GAIN_DB
+        let mut hiss = tone(300.0, 1e-4, 16000);
+        normalize_gain(&mut hiss);
+        assert!((db(rms(&hiss) / rms(&tone(300.0, 1e-4, 16000))) - MAX_GAIN_DB).abs() < 0.01);
+
+        let mut silence = vec![0.0; 100];
+        normalize_gain(&mut silence);
+        assert!(silence.iter().all(|s| *s == 0.0));
+        normalize_gain(&mut []);
+    }
+
+    #[test]
+    fn test_chain_stages_are_toggled_separately() {
+        let input: Vec<f32> = tone(1000.0, 0.2, 4800).iter().map(|s| s + 0.3).collect();
+
+        let mut chain = DspChain::new(RATE);
+        assert_eq!(chain.process(&input[..480]), &input[..480]);
+
+        chain.set_config(DspConfig {
+            high_pass: true,
+            ..Default::default()
+        });
+        let mut last = Vec::new();
+        for frame in input.chunks(480) {
+            last = chain.process(frame).to_vec();
+        }
+        let mean = last.iter().sum::<f32>() / last.len() as f32;
+        assert!(mean.abs() < 0.05, "mean {mean}");
+    }
+}
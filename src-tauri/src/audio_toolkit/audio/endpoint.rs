@@ -0,0 +1,196 @@
+use serde::Serialize;
+use specta::Type;
+use std::time::Duration;
+
+/// When a recording should end by itself. `None` disables a limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AutoStopConfig {
+    /// Silence after speech that ends the utterance
+    pub trailing_silence: Option<Duration>,
+    /// Hard cap on the length of a recording
+    pub max_duration: Option<Duration>,
+    /// Give up when nothing has been said for this long since the start
+    pub no_speech_timeout: Option<Duration>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum AutoStopReason {
+    TrailingSilence,
+    MaxDuration,
+    NoSpeech,
+}
+
+/// Decides when to end a recording from the stream of VAD decisions. Time is
+/// counted in recorded samples, so it follows the audio rather than the
+/// clock and behaves the same for live and file sources.
+pub(crate) struct Endpointer {
+    config: Option<AutoStopConfig>,
+    sample_rate: u32,
+    elapsed: u64,
+    /// Samples since the last speech frame; `None` until speech is heard
+    silence: Option<u64>,
+    fired: bool,
+}
+
+impl Endpointer {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            config: None,
+            sample_rate,
+            elapsed: 0,
+            silence: None,
+            fired: false,
+        }
+    }
+
+    pub fn set_config(&mut self, config: Option<AutoStopConfig>) {
+        self.config = config;
+    }
+
+    pub fn reset(&mut self) {
+        self.elapsed = 0;
+        self.silence = None;
+        self.fired = false;
+    }
+
+    fn samples(&self, duration: Duration) -> u64 {
+        (duration.as_secs_f64() * self.sample_rate as f64) as u64
+    }
+
+    /// Accounts for one frame of `len` samples. `speech` is the VAD's
+    /// decision, or `None` without a VAD. Returns a reason at most once per
+    /// recording.
+    pub fn push(&mut self, len: usize, speech: Option<bool>) -> Option<AutoStopReason> {
+        let config = self.config?;
+        if self.fired {
+            return None;
+        }
+
+        self.elapsed += len as u64;
+        match speech {
+            Some(true) => self.silence = Some(0),
+            Some(false) => {
+                if let Some(silence) = self.silence.as_mut() {
+                    *silence += len as u64;
+                }
+            }
+            None => {}
+        }
+
+        let reason = if config
+            .max_duration
+            .is_some_and(|d| self.elapsed >= self.samples(d))
+        {
+            Some(AutoStopReason::MaxDuration)
+        } else if let (Some(limit), Some(silence)) = (config.trailing_silence, self.silence) {
+            (silence >= self.samples(limit)).then_some(AutoStopReason::TrailingSilence)
+        } else if let (Some(limit), None, Some(_)) =
+            (config.no_speech_timeout, self.silence, speech)
+        {
+            // Without a VAD there is no telling whether anything was said
+            (self.elapsed >= self.samples(limit)).then_some(AutoStopReason::NoSpeech)
+        } else {
+            None
+        };
+
+        self.fired = reason.is_some();
+        reason
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 16000;
+    const FRAME: usize = 480; // 30 ms
+
+    fn config() -> AutoStopConfig {
+        AutoStopConfig {
+            trailing_silence: Some(Duration::from_millis(900)),
+            max_duration: Some(Duration::from_secs(10)),
+            no_speech_timeout: Some(Duration::from_secs(3)),
+        }
+    }
+
+    /// Pushes `frames` frames and returns the index of the one that fired.
+    fn run(
+        ep: &mut Endpointer,
+        frames: usize,
+        speech: Option<bool>,
+    ) -> Option<(usize, AutoStopReason)> {
+        (0..frames).find_map(|i| ep.push(FRAME, speech).map(|r| (i, r)))
+    }
+
+    #[test]
+    fn test_trailing_silence_after_speech() {
+        let mut ep = Endpointer::new(RATE);
+        ep.set_config(Some(config()));
+
+        assert_eq!(run(&mut ep, 20, Some(false)), None);
+        assert_eq!(run(&mut ep, 50, Some(true)), None);
+        // 900 ms is 30 frames of silence
+        assert_eq!(
+            run(&mut ep, 100, Some(false)),
+            Some((29, AutoStopReason::TrailingSilence))
+        );
+        // Fires once per recording
+        assert_eq!(run(&mut ep, 100, Some(false)), None);
+
+        ep.reset();
+        assert_eq!(run(&mut ep, 10, Some(true)), None);
+        assert_eq!(
+            run(&mut ep, 100, Some(false)),
+            Some((29, AutoStopReason::TrailingSilence))
+        );
+    }
+
+    #[test]
+    fn test_pauses_shorter_than_the_limit_continue() {
+        let mut ep = Endpointer::new(RATE);
+        ep.set_config(Some(config()));
+
+        for _ in 0..5 {
+            assert_eq!(run(&mut ep, 10, Some(true)), None);
+            assert_eq!(run(&mut ep, 25, Some(false)), None);
+        }
+    }
+
+    #[test]
+    fn test_no_speech_timeout() {
+        let mut ep = Endpointer::new(RATE);
+        ep.set_config(Some(config()));
+
+        // 3 s is 100 frames
+        assert_eq!(
+            run(&mut ep, 200, Some(false)),
+            Some((99, AutoStopReason::NoSpeech))
+        );
+    }
+
+    #[test]
+    fn test_max_duration() {
+        let mut ep = Endpointer::new(RATE);
+        ep.set_config(Some(AutoStopConfig {
+            trailing_silence: None,
+            ..config()
+        }));
+        // Continuous speech, and no VAD at all
+        let cap = run(&mut ep, 1000, Some(true));
+        assert_eq!(cap.map(|(_, r)| r), Some(AutoStopReason::MaxDuration));
+
+        ep.reset();
+        let cap = run(&mut ep, 1000, None);
+        assert_eq!(cap.map(|(_, r)| r), Some(AutoStopReason::MaxDuration));
+    }
+
+    #[test]
+    fn test_disabled() {
+        let mut ep = Endpointer::new(RATE);
+        assert_eq!(run(&mut ep, 1000, Some(false)), None);
+
+        ep.set_config(Some(AutoStopConfig::default()));
+        assert_eq!(run(&mut ep, 1000, Some(false)), None);
+    }
+}
@@ -0,0 +1,202 @@
+use serde::Serialize;
+use specta::Type;
+
+/// A sample at or above this magnitude counts as clipped.
+const CLIP_LEVEL: f32 = 0.999;
+/// Clipped samples above this share of the recording are worth a warning.
+const CLIP_WARN_RATIO: f32 = 0.001;
+/// Below this peak the input is effectively digital silence (-80 dBFS).
+const SILENT_PEAK: f32 = 1e-4;
+/// Speech (or overall level without a VAD) quieter than this is too quiet.
+const QUIET_DB: f32 = -40.0;
+
+/// Reported in dBFS for digital silence, instead of minus infinity.
+const FLOOR_DB: f32 = -120.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum QualityWarning {
+    /// Nothing but zeros or near-zeros; the microphone is probably muted
+    Silent,
+    TooQuiet,
+    Clipping,
+}
+
+/// Input levels over one recording, measured before any processing.
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct QualityReport {
+    pub duration_secs: f32,
+    pub rms_db: f32,
+    pub peak_db: f32,
+    /// Share of samples at full scale
+    pub clipping_ratio: f32,
+    /// Speech level over the noise level, when the VAD saw both
+    pub snr_db: Option<f32>,
+    pub warnings: Vec<QualityWarning>,
+}
+
+fn db(power: f64) -> f32 {
+    if power > 0.0 {
+        (10.0 * power.log10()).max(FLOOR_DB as f64) as f32
+    } else {
+        FLOOR_DB
+    }
+}
+
+/// Accumulates the levels behind a [`QualityReport`]. Raw samples give the
+/// level, peak and clipping; frames with the VAD's decision give the
+/// speech-to-noise estimate.
+#[derive(Default)]
+pub(crate) struct Meter {
+    samples: u64,
+    sum_sq: f64,
+    peak: f32,
+    clipped: u64,
+    frame_samples: u64,
+    speech: (f64, u64),
+    noise: (f64, u64),
+}
+
+impl Meter {
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn push_samples(&mut self, samples: &[f32]) {
+        for &s in samples {
+            let magnitude = s.abs();
+            self.sum_sq += (s as f64) * (s as f64);
+            self.peak = self.peak.max(magnitude);
+            if magnitude >= CLIP_LEVEL {
+                self.clipped += 1;
+            }
+        }
+        self.samples += samples.len() as u64;
+    }
+
+    /// Accounts for one 16 kHz frame and the VAD's decision on it, if any.
+    pub fn push_frame(&mut self, frame: &[f32], speech: Option<bool>) {
+        self.frame_samples += frame.len() as u64;
+        let energy: f64 = frame.iter().map(|&s| (s as f64) * (s as f64)).sum();
+        let bin = match speech {
+            Some(true) => &mut self.speech,
+            Some(false) => &mut self.noise,
+            None => return,
+        };
+        bin.0 += energy;
+        bin.1 += frame.len() as u64;
+    }
+
+    pub fn report(&self, frame_rate: u32) -> QualityReport {
+        let mean = |(sum, n): (f64, u64)| (n > 0).then(|| sum / n as f64);
+
+        let rms_db = mean((self.sum_sq, self.samples)).map_or(FLOOR_DB, db);
+        let peak_db = db((self.peak as f64).powi(2));
+        let clipping_ratio = if self.samples > 0 {
+            self.clipped as f32 / self.samples as f32
+        } else {
+            0.0
+        };
+
+        let speech_power = mean(self.speech);
+        let snr_db = match (speech_power, mean(self.noise)) {
+            (Some(speech), Some(noise)) if noise > 0.0 && speech > 0.0 => {
+                Some(10.0 * (speech / noise).log10() as f32)
+            }
+            _ => None,
+        };
+
+        let mut warnings = Vec::new();
+        if self.peak < SILENT_PEAK {
+            warnings.push(QualityWarning::Silent);
+        } else if speech_power.map_or(rms_db, db) < QUIET_DB {
+            warnings.push(QualityWarning::TooQuiet);
+        }
+        if clipping_ratio > CLIP_WARN_RATIO {
+            warnings.push(QualityWarning::Clipping);
+        }
+
+        QualityReport {
+            duration_secs: self.frame_samples as f32 / frame_rate as f32,
+            rms_db,
+            peak_db,
+            clipping_ratio,
+            snr_db,
+            warnings,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 16000;
+
+    fn tone(amplitude: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| amplitude * (i as f32 * 0.2).sin())
+            .collect()
+    }
+
+    fn metered(samples: &[f32], speech: Option<bool>) -> Meter {
+        let mut meter = Meter::default();
+        meter.push_samples(samples);
+        for frame in samples.chunks(480) {
+            meter.push_frame(frame, speech);
+        }
+        meter
+    }
+
+    #[test]
+    fn test_levels_of_a_tone() {
+        let report = metered(&tone(0.5, RATE as usize), None).report(RATE);
+        assert!((report.duration_secs - 1.0).abs() < 1e-3);
+        // A sine's RMS is 3 dB under its peak
+        assert!((report.peak_db - -6.02).abs() < 0.1, "{report:?}");
+        assert!((report.rms_db - -9.03).abs() < 0.1, "{report:?}");
+        assert_eq!(report.clipping_ratio, 0.0);
+        assert_eq!(report.snr_db, None);
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_silence_and_quiet_input() {
+        let report = metered(&vec![0.0; 16000], Some(false)).report(RATE);
+        assert_eq!(report.warnings, vec![QualityWarning::Silent]);
+        assert_eq!(report.rms_db, FLOOR_DB);
+
+        let report = metered(&tone(0.005, 16000), None).report(RATE);
+        assert_eq!(report.warnings, vec![QualityWarning::TooQuiet]);
+    }
+
+    #[test]
+    fn test_clipping() {
+        let clipped: Vec<f32> = tone(2.0, 16000)
+            .iter()
+            .map(|s| s.clamp(-1.0, 1.0))
+            .collect();
+        let report = metered(&clipped, None).report(RATE);
+        assert!(report.clipping_ratio > 0.1, "{report:?}");
+        assert_eq!(report.warnings, vec![QualityWarning::Clipping]);
+    }
+
+    #[test]
+    fn test_speech_to_noise() {
+        let mut meter = Meter::default();
+        let speech = tone(0.3, 4800);
+        let noise = tone(0.003, 4800);
+        meter.push_samples(&speech);
+        meter.push_samples(&noise);
+        meter.push_frame(&speech, Some(true));
+        meter.push_frame(&noise, Some(false));
+
+        let report = meter.report(RATE);
+        // 100x the amplitude is 40 dB
+        let snr = report.snr_db.unwrap();
+        assert!((snr - 40.0).abs() < 0.1, "{snr}");
+
+        // The level judgement follows the speech, not the pauses
+        assert!(report.warnings.is_empty());
+    }
+}
@@ -1,12 +1,28 @@
 // Re-export all audio components
+mod channels;
 mod device;
+mod dsp;
+mod endpoint;
+mod meter;
+mod pre_roll;
 mod recorder;
 mod resampler;
+mod source;
 mod utils;
 mod visualizer;
 
-pub use device::{list_input_devices, list_output_devices, CpalDeviceInfo};
+pub use channels::ChannelPolicy;
+pub use device::{
+    list_input_device_names, list_input_devices, list_output_devices, CpalDeviceInfo,
+};
+pub use dsp::{normalize_gain, DspConfig, NoiseProfile};
+pub use endpoint::{AutoStopConfig, AutoStopReason};
+pub use meter::{QualityReport, QualityWarning};
 pub use recorder::AudioRecorder;
 pub use resampler::FrameResampler;
+pub use source::{
+    decode_audio_file, decode_wav_bytes, ActiveSource, AudioSource, CpalSource, FileSource, Pacing,
+    PcmFormat, PcmSource, StreamErrorCallback, SyntheticSource,
+};
 pub use utils::save_wav_file;
 pub use visualizer::AudioVisualiser;
@@ -0,0 +1,139 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{compiler_fence, Ordering};
+
+/// Overwrites audio so it doesn't linger in freed memory.
+fn wipe(buf: &mut [f32]) {
+    for sample in buf.iter_mut() {
+        // Volatile so the stores aren't optimised away before the free
+        unsafe { std::ptr::write_volatile(sample, 0.0) };
+    }
+    compiler_fence(Ordering::SeqCst);
+}
+
+/// The most recent audio from before a recording started, so words spoken
+/// while the hotkey goes down aren't lost. Memory only; every frame is
+/// wiped as it is evicted, replayed or cleared.
+#[derive(Default)]
+pub(crate) struct PreRoll {
+    frames: VecDeque<Vec<f32>>,
+    /// In samples; 0 disables the buffer
+    capacity: usize,
+    len: usize,
+}
+
+impl PreRoll {
+    pub fn set_capacity(&mut self, samples: usize) {
+        self.capacity = samples;
+        self.evict();
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn push(&mut self, frame: &[f32]) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.frames.push_back(frame.to_vec());
+        self.len += frame.len();
+        self.evict();
+    }
+
+    /// Hands the buffered frames to `f`, oldest first, and empties the buffer.
+    pub fn replay(&mut self, mut f: impl FnMut(&[f32])) {
+        while let Some(mut frame) = self.frames.pop_front() {
+            f(&frame);
+            wipe(&mut frame);
+        }
+        self.len = 0;
+    }
+
+    pub fn clear(&mut self) {
+        self.replay(|_| {});
+    }
+
+    fn evict(&mut self) {
+        while self.len > self.capacity {
+            let Some(mut frame) = self.frames.pop_front() else {
+                break;
+            };
+            self.len -= frame.len();
+            wipe(&mut frame);
+        }
+    }
+}
+
+impl Drop for PreRoll {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(value: f32) -> Vec<f32> {
+        vec![value; 480]
+    }
+
+    fn replayed(pre_roll: &mut PreRoll) -> Vec<f32> {
+        let mut firsts = Vec::new();
+        pre_roll.replay(|f| firsts.push(f[0]));
+        firsts
+    }
+
+    #[test]
+    fn test_keeps_most_recent_frames() {
+        let mut pre_roll = PreRoll::default();
+        pre_roll.set_capacity(480 * 3);
+        for i in 0..10 {
+            pre_roll.push(&frame(i as f32));
+        }
+        assert_eq!(pre_roll.len(), 480 * 3);
+        assert_eq!(replayed(&mut pre_roll), vec![7.0, 8.0, 9.0]);
+
+        // Replay empties it
+        assert_eq!(pre_roll.len(), 0);
+        assert!(replayed(&mut pre_roll).is_empty());
+    }
+
+    #[test]
+    fn test_partial_frames_never_exceed_capacity() {
+        let mut pre_roll = PreRoll::default();
+        pre_roll.set_capacity(1000);
+        for i in 0..5 {
+            pre_roll.push(&frame(i as f32));
+        }
+        // Two 480-sample frames fit, a third would not
+        assert_eq!(pre_roll.len(), 960);
+        assert_eq!(replayed(&mut pre_roll), vec![3.0, 4.0]);
+    }
+
+    #[test]
+    fn test_disabled_and_shrunk() {
+        let mut pre_roll = PreRoll::default();
+        pre_roll.push(&frame(1.0));
+        assert_eq!(pre_roll.len(), 0);
+
+        pre_roll.set_capacity(480 * 4);
+        for i in 0..4 {
+            pre_roll.push(&frame(i as f32));
+        }
+        pre_roll.set_capacity(480);
+        assert_eq!(replayed(&mut pre_roll), vec![3.0]);
+
+        pre_roll.set_capacity(480 * 4);
+        pre_roll.push(&frame(5.0));
+        pre_roll.set_capacity(0);
+        assert_eq!(pre_roll.len(), 0);
+    }
+
+    #[test]
+    fn test_wipe() {
+        let mut buf = frame(0.5);
+        wipe(&mut buf);
+        assert!(buf.iter().all(|s| *s == 0.0));
+    }
+}
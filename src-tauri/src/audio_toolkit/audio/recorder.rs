@@ -4,30 +4,62 @@ use std::{
     time::Duration,
 };
 
-use cpal::{
-    traits::{DeviceTrait, HostTrait, StreamTrait},
-    Device, Sample, SizedSample,
-};
+use cpal::{traits::HostTrait, Device};
 
+use super::dsp::DspChain;
+use super::endpoint::Endpointer;
+use super::meter::{Meter, QualityReport};
+use super::pre_roll::PreRoll;
 use crate::audio_toolkit::{
-    audio::{AudioVisualiser, FrameResampler},
+    audio::{
+        ActiveSource, AudioSource, AudioVisualiser, AutoStopConfig, AutoStopReason, ChannelPolicy,
+        CpalSource, DspConfig, FrameResampler, StreamErrorCallback,
+    },
     constants,
-    vad::{self, VadFrame},
+    vad::{self, VadDecision, VadFrame},
     VoiceActivityDetector,
 };
 
 enum Cmd {
     Start,
-    Stop(mpsc::Sender<Vec<f32>>),
+    Stop(mpsc::Sender<(Vec<f32>, QualityReport)>),
+    /// Stop once the source has no more audio
+    Finish(mpsc::Sender<Vec<f32>>),
+    /// Run the VAD on incoming audio without recording it
+    Calibrate(bool),
+    /// Limits for recordings started from now on
+    AutoStop(Option<AutoStopConfig>),
+    /// How much audio from before `Start` to keep; zero turns it off
+    PreRoll(Duration),
+    Dsp(DspConfig),
+    /// Carry on from a different source, keeping the recording so far
+    Switch(Box<dyn AudioSource>),
     Shutdown,
 }
 
+impl Cmd {
+    /// Only changes settings; nothing needs the source's audio yet.
+    fn is_config(&self) -> bool {
+        matches!(
+            self,
+            Cmd::Calibrate(_) | Cmd::AutoStop(_) | Cmd::PreRoll(_) | Cmd::Dsp(_)
+        )
+    }
+}
+
+type VadCallback = Arc<dyn Fn(VadDecision) + Send + Sync + 'static>;
+type AutoStopCallback = Arc<dyn Fn(AutoStopReason) + Send + Sync + 'static>;
+
 pub struct AudioRecorder {
     device: Option<Device>,
     cmd_tx: Option<mpsc::Sender<Cmd>>,
     worker_handle: Option<std::thread::JoinHandle<()>>,
     vad: Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
     level_cb: Option<Arc<dyn Fn(Vec<f32>) + Send + Sync + 'static>>,
+    vad_cb: Option<VadCallback>,
+    auto_stop_cb: Option<AutoStopCallback>,
+    error_cb: Option<StreamErrorCallback>,
+    channel_policy: ChannelPolicy,
 }
 
 impl AudioRecorder {
@@ -38,6 +70,10 @@ impl AudioRecorder {
             worker_handle: None,
             vad: None,
             level_cb: None,
+            vad_cb: None,
+            auto_stop_cb: None,
+            error_cb: None,
+            channel_policy: ChannelPolicy::default(),
         })
     }
 
@@ -54,74 +90,163 @@ impl AudioRecorder {
         self
     }
 
+    /// Called with the VAD's decision for every frame it sees.
+    pub fn with_vad_callback<F>(mut self, cb: F) -> Self
+    where
+        F: Fn(VadDecision) + Send + Sync + 'static,
+    {
+        self.vad_cb = Some(Arc::new(cb));
+        self
+    }
+
+    /// Called from the audio thread, once per recording, when an auto-stop
+    /// limit set with [`set_auto_stop`](Self::set_auto_stop) is reached. The
+    /// recorder keeps recording until it is stopped, so the callback must not
+    /// block on `stop()` itself.
+    pub fn with_auto_stop_callback<F>(mut self, cb: F) -> Self
+    where
+        F: Fn(AutoStopReason) + Send + Sync + 'static,
+    {
+        self.auto_stop_cb = Some(Arc::new(cb));
+        self
+    }
+
+    /// Called when the source can't be started or a live stream fails. The
+    /// recorder keeps what it has recorded and keeps answering commands, so
+    /// the caller can [`switch_device`](Self::switch_device) or `stop()`.
+    pub fn with_error_callback<F>(mut self, cb: F) -> Self
+    where
+        F: Fn(String) + Send + Sync + 'static,
+    {
+        self.error_cb = Some(Arc::new(cb));
+        self
+    }
+
+    /// Replaces the VAD. An open stream picks it up from the next frame if
+    /// the recorder already had one; otherwise on the next `open`.
+    pub fn set_vad(&mut self, vad: Box<dyn VoiceActivityDetector>) {
+        match &self.vad {
+            Some(vad_arc) => *vad_arc.lock().unwrap() = vad,
+            None => self.vad = Some(Arc::new(Mutex::new(vad))),
+        }
+    }
+
+    /// How the channels of the next device opened (or switched to) are
+    /// folded into mono.
+    pub fn set_channel_policy(&mut self, policy: ChannelPolicy) {
+        self.channel_policy = policy;
+    }
+
     pub fn open(&mut self, device: Option<Device>) -> Result<(), Box<dyn std::error::Error>> {
         if self.worker_handle.is_some() {
             return Ok(()); // already open
         }
 
-        let (sample_tx, sample_rx) = mpsc::channel::<Vec<f32>>();
-        let (cmd_tx, cmd_rx) = mpsc::channel::<Cmd>();
+        let device = Self::device_or_default(device)?;
+        self.open_source(Box::new(self.cpal_source(&device)))?;
+        self.device = Some(device);
 
-        let host = crate::audio_toolkit::get_cpal_host();
-        let device = match device {
-            Some(dev) => dev,
-            None => host
+        Ok(())
+    }
+
+    /// Moves an open recorder to another input device without losing the
+    /// recording in progress; opens it if it isn't open yet.
+    pub fn switch_device(
+        &mut self,
+        device: Option<Device>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if self.worker_handle.is_none() {
+            return self.open(device);
+        }
+
+        let device = Self::device_or_default(device)?;
+        self.switch_source(Box::new(self.cpal_source(&device)))?;
+        self.device = Some(device);
+        Ok(())
+    }
+
+    /// Replaces the source of an open recorder. A recording in progress
+    /// carries on from the new source; the new source starts straight away
+    /// even if it is finite.
+    pub fn switch_source(
+        &self,
+        source: Box<dyn AudioSource>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        match &self.cmd_tx {
+            Some(tx) => Ok(tx.send(Cmd::Switch(source))?),
+            None => {
+                Err(Error::new(std::io::ErrorKind::NotConnected, "Recorder is not open").into())
+            }
+        }
+    }
+
+    fn device_or_default(device: Option<Device>) -> Result<Device, Error> {
+        match device {
+            Some(dev) => Ok(dev),
+            None => crate::audio_toolkit::get_cpal_host()
                 .default_input_device()
-                .ok_or_else(|| Error::new(std::io::ErrorKind::NotFound, "No input device found"))?,
-        };
+                .ok_or_else(|| Error::new(std::io::ErrorKind::NotFound, "No input device found")),
+        }
+    }
+
+    fn cpal_source(&self, device: &Device) -> CpalSource {
+        let source = CpalSource::new(device.clone()).with_channel_policy(self.channel_policy);
+        match &self.error_cb {
+            Some(cb) => source.with_error_callback(cb.clone()),
+            None => source,
+        }
+    }
+
+    /// Opens the recorder on any [`AudioSource`]: a device, a file, a pipe or
+    /// generated audio. The VAD and resampling pipeline is the same for all.
+    pub fn open_source(
+        &mut self,
+        source: Box<dyn AudioSource>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if self.worker_handle.is_some() {
+            return Ok(()); // already open
+        }
+
+        let (cmd_tx, cmd_rx) = mpsc::channel::<Cmd>();
 
-        let thread_device = device.clone();
         let vad = self.vad.clone();
         // Move the optional level callback into the worker thread
         let level_cb = self.level_cb.clone();
+        let vad_cb = self.vad_cb.clone();
+        let auto_stop_cb = self.auto_stop_cb.clone();
+        let error_cb = self.error_cb.clone();
 
         let worker = std::thread::spawn(move || {
-            let config = AudioRecorder::get_preferred_config(&thread_device)
-                .expect("failed to fetch preferred config");
-
-            let sample_rate = config.sample_rate().0;
-            let channels = config.channels() as usize;
-
-            log::info!(
-                "Using device: {:?}\nSample rate: {}\nChannels: {}\nFormat: {:?}",
-                thread_device.name(),
-                sample_rate,
-                channels,
-                config.sample_format()
-            );
-
-            let stream = match config.sample_format() {
-                cpal::SampleFormat::U8 => {
-                    AudioRecorder::build_stream::<u8>(&thread_device, &config, sample_tx, channels)
-                        .unwrap()
-                }
-                cpal::SampleFormat::I8 => {
-                    AudioRecorder::build_stream::<i8>(&thread_device, &config, sample_tx, channels)
-                        .unwrap()
-                }
-                cpal::SampleFormat::I16 => {
-                    AudioRecorder::build_stream::<i16>(&thread_device, &config, sample_tx, channels)
-                        .unwrap()
-                }
-                cpal::SampleFormat::I32 => {
-                    AudioRecorder::build_stream::<i32>(&thread_device, &config, sample_tx, channels)
-                        .unwrap()
-                }
-                cpal::SampleFormat::F32 => {
-                    AudioRecorder::build_stream::<f32>(&thread_device, &config, sample_tx, channels)
-                        .unwrap()
+            // A finite source would otherwise race ahead of the first start(),
+            // so it is held back while the recorder is only being configured
+            let mut first_cmds = Vec::new();
+            if !source.is_live() {
+                loop {
+                    match cmd_rx.recv() {
+                        Ok(Cmd::Shutdown) | Err(_) => return,
+                        Ok(cmd) => {
+                            let is_config = cmd.is_config();
+                            first_cmds.push(cmd);
+                            if !is_config {
+                                break;
+                            }
+                        }
+                    }
                 }
-                _ => panic!("unsupported sample format"),
-            };
-
-            stream.play().expect("failed to start stream");
+            }
 
-            // keep the stream alive while we process samples
-            run_consumer(sample_rate, vad, sample_rx, cmd_rx, level_cb);
-            // stream is dropped here, after run_consumer returns
+            run_consumer(
+                source,
+                vad,
+                cmd_rx,
+                first_cmds,
+                level_cb,
+                vad_cb,
+                auto_stop_cb,
+                error_cb,
+            );
         });
 
-        self.device = Some(device);
         self.cmd_tx = Some(cmd_tx);
         self.worker_handle = Some(worker);
 
@@ -136,6 +261,14 @@ impl AudioRecorder {
     }
 
     pub fn stop(&self) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+        Ok(self.stop_with_report()?.0)
+    }
+
+    /// Stops like [`stop`](Self::stop) and also returns the input levels
+    /// measured over the recording.
+    pub fn stop_with_report(
+        &self,
+    ) -> Result<(Vec<f32>, QualityReport), Box<dyn std::error::Error>> {
         let (resp_tx, resp_rx) = mpsc::channel();
         if let Some(tx) = &self.cmd_tx {
             tx.send(Cmd::Stop(resp_tx))?;
@@ -143,6 +276,55 @@ impl AudioRecorder {
         Ok(resp_rx.recv()?) // wait for the samples
     }
 
+    /// While calibrating, every frame goes through the VAD (and the VAD
+    /// callback) even when nothing is being recorded.
+    pub fn set_calibrating(&self, calibrating: bool) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(tx) = &self.cmd_tx {
+            tx.send(Cmd::Calibrate(calibrating))?;
+        }
+        Ok(())
+    }
+
+    /// Sets the auto-stop limits for the following recordings; `None` turns
+    /// auto-stop off.
+    pub fn set_auto_stop(
+        &self,
+        config: Option<AutoStopConfig>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(tx) = &self.cmd_tx {
+            tx.send(Cmd::AutoStop(config))?;
+        }
+        Ok(())
+    }
+
+    /// Keeps up to `duration` of audio while not recording and prepends it
+    /// to the next recording. The buffer only lives in memory and is wiped
+    /// whenever audio leaves it. Zero turns it off.
+    pub fn set_pre_roll(&self, duration: Duration) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(tx) = &self.cmd_tx {
+            tx.send(Cmd::PreRoll(duration))?;
+        }
+        Ok(())
+    }
+
+    /// Turns the filtering, noise suppression and gain stages on or off.
+    pub fn set_dsp(&self, config: DspConfig) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(tx) = &self.cmd_tx {
+            tx.send(Cmd::Dsp(config))?;
+        }
+        Ok(())
+    }
+
+    /// Waits for a finite source to run out, then stops and returns the
+    /// recording. Never returns for a live device; use [`stop`](Self::stop).
+    pub fn finish(&self) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+        let (resp_tx, resp_rx) = mpsc::channel();
+        if let Some(tx) = &self.cmd_tx {
+            tx.send(Cmd::Finish(resp_tx))?;
+        }
+        Ok(resp_rx.recv()?)
+    }
+
     pub fn close(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         if let Some(tx) = self.cmd_tx.take() {
             let _ = tx.send(Cmd::Shutdown);
@@ -153,187 +335,338 @@ impl AudioRecorder {
         self.device = None;
         Ok(())
     }
+}
 
-    fn build_stream<T>(
-        device: &cpal::Device,
-        config: &cpal::SupportedStreamConfig,
-        sample_tx: mpsc::Sender<Vec<f32>>,
-        channels: usize,
-    ) -> Result<cpal::Stream, cpal::BuildStreamError>
-    where
-        T: Sample + SizedSample + Send + 'static,
-        f32: cpal::FromSample<T>,
-    {
-        // Pre-allocate two buffers for double-buffering (swap instead of clone)
-        let mut output_buffer_a = Vec::new();
-        let mut output_buffer_b = Vec::new();
-        let mut use_buffer_a = true;
-
-        let stream_cb = move |data: &[T], _: &cpal::InputCallbackInfo| {
-            // Swap buffers to avoid cloning
-            let output_buffer = if use_buffer_a {
-                &mut output_buffer_a
-            } else {
-                &mut output_buffer_b
-            };
-            output_buffer.clear();
-
-            if channels == 1 {
-                // Direct conversion without intermediate Vec
-                output_buffer.extend(data.iter().map(|&sample| sample.to_sample::<f32>()));
-            } else {
-                // Convert to mono directly
-                let frame_count = data.len() / channels;
-                output_buffer.reserve(frame_count);
-
-                for frame in data.chunks_exact(channels) {
-                    let mono_sample = frame
-                        .iter()
-                        .map(|&sample| sample.to_sample::<f32>())
-                        .sum::<f32>()
-                        / channels as f32;
-                    output_buffer.push(mono_sample);
+/// Per-frame state owned by the worker thread: everything downstream of the
+/// resampler.
+struct Pipeline {
+    vad: Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
+    vad_cb: Option<VadCallback>,
+    endpointer: Endpointer,
+    auto_stop_cb: Option<AutoStopCallback>,
+    pre_roll: PreRoll,
+    processed_samples: Vec<f32>,
+    recording: bool,
+    calibrating: bool,
+}
+
+impl Pipeline {
+    /// Runs the VAD over a 30 ms frame and keeps it if it's speech. Returns
+    /// the VAD's decision, or `None` when no VAD ran.
+    fn classify(&mut self, samples: &[f32], recording: bool) -> Option<bool> {
+        if !recording && !self.calibrating {
+            return None;
+        }
+
+        if let Some(vad_arc) = &self.vad {
+            let mut det = vad_arc.lock().unwrap();
+            let speech = match det.push_frame(samples).unwrap_or(VadFrame::Speech(samples)) {
+                VadFrame::Speech(buf) => {
+                    if recording {
+                        self.processed_samples.extend_from_slice(buf);
+                    }
+                    true
                 }
+                VadFrame::Noise => false,
+            };
+            if let Some(cb) = &self.vad_cb {
+                cb(VadDecision {
+                    speech,
+                    score: det.last_score(),
+                });
             }
-
-            // Take ownership using mem::take, send it, swap buffers
-            if sample_tx.send(std::mem::take(output_buffer)).is_err() {
-                log::error!("Failed to send samples");
+            Some(speech)
+        } else {
+            if recording {
+                self.processed_samples.extend_from_slice(samples);
             }
-            use_buffer_a = !use_buffer_a;
-        };
-
-        device.build_input_stream(
-            &config.clone().into(),
-            stream_cb,
-            |err| log::error!("Stream error: {}", err),
-            None,
-        )
+            None
+        }
     }
 
-    fn get_preferred_config(
-        device: &cpal::Device,
-    ) -> Result<cpal::SupportedStreamConfig, Box<dyn std::error::Error>> {
-        let supported_configs = device.supported_input_configs()?;
-        let mut best_config: Option<cpal::SupportedStreamConfigRange> = None;
-
-        // Try to find a config that supports 16kHz, prioritizing better formats
-        for config_range in supported_configs {
-            if config_range.min_sample_rate().0 <= constants::WHISPER_SAMPLE_RATE
-                && config_range.max_sample_rate().0 >= constants::WHISPER_SAMPLE_RATE
-            {
-                match best_config {
-                    None => best_config = Some(config_range),
-                    Some(ref current) => {
-                        // Prioritize F32 > I16 > I32 > others
-                        let score = |fmt: cpal::SampleFormat| match fmt {
-                            cpal::SampleFormat::F32 => 4,
-                            cpal::SampleFormat::I16 => 3,
-                            cpal::SampleFormat::I32 => 2,
-                            _ => 1,
-                        };
-
-                        if score(config_range.sample_format()) > score(current.sample_format()) {
-                            best_config = Some(config_range);
-                        }
-                    }
-                }
+    /// Returns the VAD's decision on the frame, if it ran.
+    fn frame(&mut self, samples: &[f32]) -> Option<bool> {
+        if !self.recording {
+            self.pre_roll.push(samples);
+            return self.classify(samples, false);
+        }
+
+        let speech = self.classify(samples, true);
+        if let Some(reason) = self.endpointer.push(samples.len(), speech) {
+            log::debug!("Auto-stop: {:?}", reason);
+            if let Some(cb) = &self.auto_stop_cb {
+                cb(reason);
             }
         }
+        speech
+    }
 
-        if let Some(config) = best_config {
-            return Ok(config.with_sample_rate(cpal::SampleRate(constants::WHISPER_SAMPLE_RATE)));
+    fn start(&mut self) {
+        self.processed_samples.clear();
+        self.recording = true;
+        self.endpointer.reset();
+        if let Some(v) = &self.vad {
+            v.lock().unwrap().reset();
         }
 
-        // If no config supports 16kHz, fall back to default
-        Ok(device.default_input_config()?)
+        // Audio from just before the start goes through the VAD like the rest
+        let pre_roll_samples = self.pre_roll.len();
+        let mut pre_roll = std::mem::take(&mut self.pre_roll);
+        pre_roll.replay(|frame| {
+            self.frame(frame);
+        });
+        self.pre_roll = pre_roll;
+        if pre_roll_samples > 0 {
+            log::debug!(
+                "Prepended {} ms of pre-roll",
+                pre_roll_samples * 1000 / constants::WHISPER_SAMPLE_RATE as usize
+            );
+        }
     }
 }
 
-fn run_consumer(
-    in_sample_rate: u32,
-    vad: Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
+/// A started source and the channel it delivers on.
+struct Input {
     sample_rx: mpsc::Receiver<Vec<f32>>,
-    cmd_rx: mpsc::Receiver<Cmd>,
-    level_cb: Option<Arc<dyn Fn(Vec<f32>) + Send + Sync + 'static>>,
-) {
-    let mut frame_resampler = FrameResampler::new(
-        in_sample_rate as usize,
-        constants::WHISPER_SAMPLE_RATE as usize,
-        Duration::from_millis(30),
-    );
-
-    let mut processed_samples = Vec::<f32>::new();
-    let mut recording = false;
-
-    // ---------- spectrum visualisation setup ---------------------------- //
-    const BUCKETS: usize = 16;
-    const WINDOW_SIZE: usize = 512;
-    let mut visualizer = AudioVisualiser::new(
-        in_sample_rate,
-        WINDOW_SIZE,
-        BUCKETS,
-        400.0,  // vocal_min_hz
-        4000.0, // vocal_max_hz
-    );
-
-    fn handle_frame(
-        samples: &[f32],
-        recording: bool,
-        vad: &Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
-        out_buf: &mut Vec<f32>,
-    ) {
-        if !recording {
-            return;
-        }
+    active: ActiveSource,
+}
 
-        if let Some(vad_arc) = vad {
-            let mut det = vad_arc.lock().unwrap();
-            match det.push_frame(samples).unwrap_or(VadFrame::Speech(samples)) {
-                VadFrame::Speech(buf) => out_buf.extend_from_slice(buf),
-                VadFrame::Noise => {}
+impl Input {
+    fn start(source: Box<dyn AudioSource>, error_cb: Option<&StreamErrorCallback>) -> Option<Self> {
+        let description = source.describe();
+        let (sample_tx, sample_rx) = mpsc::channel::<Vec<f32>>();
+        match source.start(sample_tx) {
+            Ok(active) => Some(Self { sample_rx, active }),
+            Err(e) => {
+                log::error!("Failed to start audio source {}: {}", description, e);
+                if let Some(cb) = error_cb {
+                    cb(format!("Failed to start {}: {}", description, e));
+                }
+                None
             }
-        } else {
-            out_buf.extend_from_slice(samples);
+        }
+    }
+}
+
+const BUCKETS: usize = 16;
+const WINDOW_SIZE: usize = 512;
+/// 30 ms at 16 kHz, what the resampler hands on
+const FRAME_SAMPLES: usize = constants::WHISPER_SAMPLE_RATE as usize * 30 / 1000;
+
+/// How long to wait for audio before looking at commands again, so a stream
+/// that has gone quiet (an unplugged device) can still be stopped.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Recording state owned by the worker thread.
+struct Consumer {
+    /// Where the audio comes from; `None` once it has ended or failed
+    input: Option<Input>,
+    in_sample_rate: u32,
+    error_cb: Option<StreamErrorCallback>,
+    frame_resampler: FrameResampler,
+    visualizer: AudioVisualiser,
+    level_cb: Option<Arc<dyn Fn(Vec<f32>) + Send + Sync + 'static>>,
+    dsp: DspChain,
+    /// Input levels of the current recording
+    meter: Meter,
+    pipeline: Pipeline,
+    /// `finish()` callers waiting for the source to end
+    finish_waiters: Vec<mpsc::Sender<Vec<f32>>>,
+}
+
+impl Consumer {
+    fn front_end(in_sample_rate: u32) -> (FrameResampler, AudioVisualiser) {
+        (
+            FrameResampler::new(
+                in_sample_rate as usize,
+                constants::WHISPER_SAMPLE_RATE as usize,
+                Duration::from_millis(30),
+            ),
+            AudioVisualiser::new(
+                in_sample_rate,
+                WINDOW_SIZE,
+                BUCKETS,
+                400.0,  // vocal_min_hz
+                4000.0, // vocal_max_hz
+            ),
+        )
+    }
+
+    /// Replaces the source. Everything past the resampler, including the
+    /// recording so far, carries over.
+    fn switch(&mut self, source: Box<dyn AudioSource>) {
+        log::info!("Switching audio source to {}", source.describe());
+        // Release the old device before the new one is opened
+        self.input = None;
+        self.input = Input::start(source, self.error_cb.as_ref());
+
+        let Some(rate) = self.input.as_ref().map(|i| i.active.sample_rate) else {
+            return;
+        };
+        if rate != self.in_sample_rate {
+            let (dsp, pipeline) = (&mut self.dsp, &mut self.pipeline);
+            self.frame_resampler.finish(&mut |frame: &[f32]| {
+                pipeline.frame(dsp.process(frame));
+            });
+            (self.frame_resampler, self.visualizer) = Self::front_end(rate);
+            self.in_sample_rate = rate;
         }
     }
 
-    while let Ok(raw) = sample_rx.recv() {
+    fn feed(&mut self, raw: &[f32]) {
         // ---------- spectrum processing ---------------------------------- //
-        if let Some(buckets) = visualizer.feed(&raw) {
-            if let Some(cb) = &level_cb {
+        if let Some(buckets) = self.visualizer.feed(raw) {
+            if let Some(cb) = &self.level_cb {
                 cb(buckets);
             }
+            // Noise suppression follows the noise floor the visualiser tracks
+            if self.dsp.config().noise_suppression {
+                self.dsp.set_noise_profile(&self.visualizer.noise_profile());
+            }
         }
 
         // ---------- existing pipeline ------------------------------------ //
-        frame_resampler.push(&raw, &mut |frame: &[f32]| {
-            handle_frame(frame, recording, &vad, &mut processed_samples)
+        let (dsp, pipeline, meter) = (&mut self.dsp, &mut self.pipeline, &mut self.meter);
+        if pipeline.recording {
+            meter.push_samples(raw);
+        }
+        self.frame_resampler.push(raw, &mut |frame: &[f32]| {
+            let speech = pipeline.frame(dsp.process(frame));
+            if pipeline.recording {
+                // Levels are judged on the input, not the processed audio
+                meter.push_frame(frame, speech);
+            }
         });
+    }
 
-        // non-blocking check for a command
-        while let Ok(cmd) = cmd_rx.try_recv() {
-            match cmd {
-                Cmd::Start => {
-                    processed_samples.clear();
-                    recording = true;
-                    visualizer.reset(); // Reset visualization buffer
-                    if let Some(v) = &vad {
-                        v.lock().unwrap().reset();
-                    }
+    fn take_recording(&mut self) -> Vec<f32> {
+        let (dsp, pipeline) = (&mut self.dsp, &mut self.pipeline);
+        pipeline.recording = false;
+        self.frame_resampler.finish(&mut |frame: &[f32]| {
+            // we still want to process the last few frames
+            pipeline.classify(dsp.process(frame), true);
+        });
+        // ...and what the DSP was still holding back
+        let mut tail = dsp.flush();
+        tail.resize(tail.len().next_multiple_of(FRAME_SAMPLES), 0.0);
+        for frame in tail.chunks_exact(FRAME_SAMPLES) {
+            pipeline.classify(frame, true);
+        }
+
+        let mut samples = std::mem::take(&mut pipeline.processed_samples);
+        self.dsp.finish(&mut samples);
+        samples
+    }
+
+    /// Returns false once the worker should shut down.
+    fn handle_cmd(&mut self, cmd: Cmd) -> bool {
+        match cmd {
+            Cmd::Start => {
+                self.visualizer.reset(); // Reset visualization buffer
+                self.meter.reset();
+                // What the DSP holds was heard before the start, so it can
+                // only be pre-roll; the recording starts with a fresh chain
+                for frame in self.dsp.flush().chunks_exact(FRAME_SAMPLES) {
+                    self.pipeline.frame(frame);
                 }
-                Cmd::Stop(reply_tx) => {
-                    recording = false;
+                self.pipeline.start();
+            }
+            Cmd::Stop(reply_tx) => {
+                let samples = self.take_recording();
+                let report = self.meter.report(constants::WHISPER_SAMPLE_RATE);
+                let _ = reply_tx.send((samples, report));
+            }
+            Cmd::Finish(reply_tx) => self.finish_waiters.push(reply_tx),
+            Cmd::Calibrate(on) => self.pipeline.calibrating = on,
+            Cmd::AutoStop(config) => self.pipeline.endpointer.set_config(config),
+            Cmd::Dsp(config) => self.dsp.set_config(config),
+            Cmd::PreRoll(duration) => {
+                let samples = duration.as_secs_f64() * constants::WHISPER_SAMPLE_RATE as f64;
+                self.pipeline.pre_roll.set_capacity(samples as usize);
+            }
+            Cmd::Switch(source) => self.switch(source),
+            Cmd::Shutdown => return false,
+        }
+        true
+    }
+}
 
-                    frame_resampler.finish(&mut |frame: &[f32]| {
-                        // we still want to process the last few frames
-                        handle_frame(frame, true, &vad, &mut processed_samples)
-                    });
+#[allow(clippy::too_many_arguments)]
+fn run_consumer(
+    source: Box<dyn AudioSource>,
+    vad: Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
+    cmd_rx: mpsc::Receiver<Cmd>,
+    first_cmds: Vec<Cmd>,
+    level_cb: Option<Arc<dyn Fn(Vec<f32>) + Send + Sync + 'static>>,
+    vad_cb: Option<VadCallback>,
+    auto_stop_cb: Option<AutoStopCallback>,
+    error_cb: Option<StreamErrorCallback>,
+) {
+    let input = Input::start(source, error_cb.as_ref());
+    let in_sample_rate = input
+        .as_ref()
+        .map_or(constants::WHISPER_SAMPLE_RATE, |i| i.active.sample_rate);
+    let (frame_resampler, visualizer) = Consumer::front_end(in_sample_rate);
+
+    let mut consumer = Consumer {
+        input,
+        in_sample_rate,
+        error_cb,
+        frame_resampler,
+        visualizer,
+        level_cb,
+        dsp: DspChain::new(constants::WHISPER_SAMPLE_RATE),
+        meter: Meter::default(),
+        pipeline: Pipeline {
+            vad,
+            vad_cb,
+            endpointer: Endpointer::new(constants::WHISPER_SAMPLE_RATE),
+            auto_stop_cb,
+            pre_roll: PreRoll::default(),
+            processed_samples: Vec::new(),
+            recording: false,
+            calibrating: false,
+        },
+        finish_waiters: Vec::new(),
+    };
+
+    for cmd in first_cmds {
+        if !consumer.handle_cmd(cmd) {
+            return;
+        }
+    }
 
-                    let _ = reply_tx.send(std::mem::take(&mut processed_samples));
+    loop {
+        if let Some(input) = &consumer.input {
+            match input.sample_rx.recv_timeout(POLL_INTERVAL) {
+                Ok(raw) => consumer.feed(&raw),
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                // The source ran out (end of file or pipe)
+                Err(mpsc::RecvTimeoutError::Disconnected) => consumer.input = None,
+            }
+
+            // non-blocking check for a command
+            while let Ok(cmd) = cmd_rx.try_recv() {
+                if !consumer.handle_cmd(cmd) {
+                    return;
                 }
-                Cmd::Shutdown => return,
             }
+            continue;
+        }
+
+        // No source. Keep answering commands so the caller can still collect
+        // what was recorded, or switch to another source.
+        let waiters = std::mem::take(&mut consumer.finish_waiters);
+        if !waiters.is_empty() {
+            let samples = consumer.take_recording();
+            for reply_tx in waiters {
+                let _ = reply_tx.send(samples.clone());
+            }
+        }
+
+        let Ok(cmd) = cmd_rx.recv() else { return };
+        if !consumer.handle_cmd(cmd) {
+            return;
         }
     }
 }
@@ -0,0 +1,676 @@
+use std::{
+    any::Any,
+    f32::consts::PI,
+    io::Read,
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc},
+    time::{Duration, Instant},
+};
+
+use cpal::{
+    traits::{DeviceTrait, StreamTrait},
+    Device, Sample, SizedSample,
+};
+
+use super::channels::{ChannelMixer, ChannelPolicy};
+use crate::audio_toolkit::constants;
+
+/// Something the recorder can pull mono f32 audio from.
+///
+/// `start` runs on the recorder's worker thread. The source delivers chunks
+/// of any length at its native sample rate; the recorder resamples them.
+/// Dropping the sender marks the end of the stream.
+pub trait AudioSource: Send {
+    /// Live sources (microphones) produce audio on their own clock. Finite
+    /// sources are held back until recording starts so nothing is missed.
+    fn is_live(&self) -> bool {
+        false
+    }
+
+    /// Human-readable name for logs.
+    fn describe(&self) -> String;
+
+    fn start(
+        self: Box<Self>,
+        sample_tx: mpsc::Sender<Vec<f32>>,
+    ) -> Result<ActiveSource, Box<dyn std::error::Error>>;
+}
+
+/// A running source. Keeps whatever drives it (a cpal stream, a reader
+/// thread) alive until dropped.
+pub struct ActiveSource {
+    pub sample_rate: u32,
+    _guard: Box<dyn Any>,
+}
+
+impl ActiveSource {
+    pub fn new(sample_rate: u32, guard: impl Any) -> Self {
+        Self {
+            sample_rate,
+            _guard: Box::new(guard),
+        }
+    }
+}
+
+/// How a finite source feeds its samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pacing {
+    /// One second of audio per wall-clock second, like a microphone
+    RealTime,
+    /// As fast as the recorder can take them
+    Unpaced,
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+/// Called with a description of a stream error.
+pub type StreamErrorCallback = Arc<dyn Fn(String) + Send + Sync + 'static>;
+
+/// Input from a cpal device.
+pub struct CpalSource {
+    device: Device,
+    channel_policy: ChannelPolicy,
+    error_cb: Option<StreamErrorCallback>,
+}
+
+impl CpalSource {
+    pub fn new(device: Device) -> Self {
+        Self {
+            device,
+            channel_policy: ChannelPolicy::default(),
+            error_cb: None,
+        }
+    }
+
+    /// How the device's channels are folded into mono.
+    pub fn with_channel_policy(mut self, policy: ChannelPolicy) -> Self {
+        self.channel_policy = policy;
+        self
+    }
+
+    /// Called from cpal's thread when the stream fails, e.g. because the
+    /// device was unplugged. The stream delivers nothing more afterwards.
+    pub fn with_error_callback(mut self, cb: StreamErrorCallback) -> Self {
+        self.error_cb = Some(cb);
+        self
+    }
+
+    /// How many channels `device` is opened with, which is what a
+    /// [`ChannelPolicy::Channel`] index counts against.
+    pub fn opened_channels(device: &Device) -> Result<u16, Box<dyn std::error::Error>> {
+        Ok(Self::get_preferred_config(device)?.channels())
+    }
+
+    fn build_stream<T>(
+        device: &cpal::Device,
+        config: &cpal::SupportedStreamConfig,
+        sample_tx: mpsc::Sender<Vec<f32>>,
+        mixer: ChannelMixer,
+        error_cb: Option<StreamErrorCallback>,
+    ) -> Result<cpal::Stream, cpal::BuildStreamError>
+    where
+        T: Sample + SizedSample + Send + 'static,
+        f32: cpal::FromSample<T>,
+    {
+        // Pre-allocate two buffers for double-buffering (swap instead of clone)
+        let mut output_buffer_a = Vec::new();
+        let mut output_buffer_b = Vec::new();
+        let mut use_buffer_a = true;
+        let mut interleaved = Vec::new();
+        let channels = config.channels() as usize;
+
+        let stream_cb = move |data: &[T], _: &cpal::InputCallbackInfo| {
+            // Swap buffers to avoid cloning
+            let output_buffer = if use_buffer_a {
+                &mut output_buffer_a
+            } else {
+                &mut output_buffer_b
+            };
+            output_buffer.clear();
+
+            if channels == 1 {
+                // Direct conversion without intermediate Vec
+                output_buffer.extend(data.iter().map(|&sample| sample.to_sample::<f32>()));
+            } else {
+                // Convert, then fold the channels into mono per the policy
+                interleaved.clear();
+                interleaved.extend(data.iter().map(|&sample| sample.to_sample::<f32>()));
+                mixer.mix(&interleaved, output_buffer);
+            }
+
+            // Take ownership using mem::take, send it, swap buffers
+            if sample_tx.send(std::mem::take(output_buffer)).is_err() {
+                log::error!("Failed to send samples");
+            }
+            use_buffer_a = !use_buffer_a;
+        };
+
+        device.build_input_stream(
+            &config.clone().into(),
+            stream_cb,
+            move |err| {
+                log::error!("Stream error: {}", err);
+                if let Some(cb) = &error_cb {
+                    cb(err.to_string());
+                }
+            },
+            None,
+        )
+    }
+
+    fn get_preferred_config(
+        device: &cpal::Device,
+    ) -> Result<cpal::SupportedStreamConfig, Box<dyn std::error::Error>> {
+        let supported_configs = device.supported_input_configs()?;
+        let mut best_config: Option<cpal::SupportedStreamConfigRange> = None;
+
+        // Try to find a config that supports 16kHz, prioritizing better formats
+        for config_range in supported_configs {
+            if config_range.min_sample_rate().0 <= constants::WHISPER_SAMPLE_RATE
+                && config_range.max_sample_rate().0 >= constants::WHISPER_SAMPLE_RATE
+            {
+                match best_config {
+                    None => best_config = Some(config_range),
+                    Some(ref current) => {
+                        // Prioritize F32 > I16 > I32 > others
+                        let score = |fmt: cpal::SampleFormat| match fmt {
+                            cpal::SampleFormat::F32 => 4,
+                            cpal::SampleFormat::I16 => 3,
+                            cpal::SampleFormat::I32 => 2,
+                            _ => 1,
+                        };
+
+                        if score(config_range.sample_format()) > score(current.sample_format()) {
+                            best_config = Some(config_range);
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(config) = best_config {
+            return Ok(config.with_sample_rate(cpal::SampleRate(constants::WHISPER_SAMPLE_RATE)));
+        }
+
+        // If no config supports 16kHz, fall back to default
+        Ok(device.default_input_config()?)
+    }
+}
+
+impl AudioSource for CpalSource {
+    fn is_live(&self) -> bool {
+        true
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "input device {}",
+            self.device.name().unwrap_or_else(|_| "<unknown>".into())
+        )
+    }
+
+    fn start(
+        self: Box<Self>,
+        sample_tx: mpsc::Sender<Vec<f32>>,
+    ) -> Result<ActiveSource, Box<dyn std::error::Error>> {
+        let device = &self.device;
+        let error_cb = self.error_cb.clone();
+        let config = Self::get_preferred_config(device)?;
+
+        let sample_rate = config.sample_rate().0;
+        let channels = config.channels() as usize;
+
+        log::info!(
+            "Using device: {:?}\nSample rate: {}\nChannels: {} ({:?})\nFormat: {:?}",
+            device.name(),
+            sample_rate,
+            channels,
+            self.channel_policy,
+            config.sample_format()
+        );
+        let mixer = ChannelMixer::new(self.channel_policy, channels);
+
+        let stream = match config.sample_format() {
+            cpal::SampleFormat::U8 => {
+                Self::build_stream::<u8>(device, &config, sample_tx, mixer, error_cb)?
+            }
+            cpal::SampleFormat::I8 => {
+                Self::build_stream::<i8>(device, &config, sample_tx, mixer, error_cb)?
+            }
+            cpal::SampleFormat::I16 => {
+                Self::build_stream::<i16>(device, &config, sample_tx, mixer, error_cb)?
+            }
+            cpal::SampleFormat::I32 => {
+                Self::build_stream::<i32>(device, &config, sample_tx, mixer, error_cb)?
+            }
+            cpal::SampleFormat::F32 => {
+                Self::build_stream::<f32>(device, &config, sample_tx, mixer, error_cb)?
+            }
+            format => return Err(format!("unsupported sample format {:?}", format).into()),
+        };
+
+        stream.play()?;
+
+        Ok(ActiveSource::new(sample_rate, stream))
+    }
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+/// Feeds pre-decoded samples from a background thread in 10 ms chunks.
+fn spawn_feeder(
+    samples: Vec<f32>,
+    sample_rate: u32,
+    pacing: Pacing,
+    sample_tx: mpsc::Sender<Vec<f32>>,
+) -> std::io::Result<std::thread::JoinHandle<()>> {
+    std::thread::Builder::new()
+        .name("audio-source-feeder".into())
+        .spawn(move || {
+            let chunk_len = (sample_rate as usize / 100).max(1);
+            let started = Instant::now();
+            let mut sent = 0usize;
+
+            for chunk in samples.chunks(chunk_len) {
+                if pacing == Pacing::RealTime {
+                    let due = started + Duration::from_secs_f64(sent as f64 / sample_rate as f64);
+                    if let Some(wait) = due.checked_duration_since(Instant::now()) {
+                        std::thread::sleep(wait);
+                    }
+                }
+                if sample_tx.send(chunk.to_vec()).is_err() {
+                    return; // recorder closed
+                }
+                sent += chunk.len();
+            }
+        })
+}
+
+fn downmix(interleaved: &[f32], channels: usize) -> Vec<f32> {
+    if channels <= 1 {
+        return interleaved.to_vec();
+    }
+    interleaved
+        .chunks_exact(channels)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        .collect()
+}
+
+/// Decodes a WAV or FLAC file to mono f32 at its native sample rate.
+pub fn decode_audio_file(path: &Path) -> Result<(Vec<f32>, u32), Box<dyn std::error::Error>> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase());
+
+    match extension.as_deref() {
+        Some("flac") => decode_flac(path),
+        Some("wav") | Some("wave") => decode_wav(path),
+        _ => Err(format!(
+            "Unsupported audio file {} (expected .wav or .flac)",
+            path.display()
+        )
+        .into()),
+    }
+}
+
+fn decode_wav(path: &Path) -> Result<(Vec<f32>, u32), Box<dyn std::error::Error>> {
+    decode_wav_reader(hound::WavReader::open(path)?)
+}
+
+/// Decodes an in-memory WAV file to mono f32 at its native sample rate.
+pub fn decode_wav_bytes(bytes: &[u8]) -> Result<(Vec<f32>, u32), Box<dyn std::error::Error>> {
+    decode_wav_reader(hound::WavReader::new(std::io::Cursor::new(bytes))?)
+}
+
+fn decode_wav_reader<R: Read>(
+    reader: hound::WavReader<R>,
+) -> Result<(Vec<f32>, u32), Box<dyn std::error::Error>> {
+    let spec = reader.spec();
+
+    let interleaved: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.into_samples::<f32>().collect::<Result<_, _>>()?,
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .into_samples::<i32>()
+                .map(|s| s.map(|s| s as f32 / scale))
+                .collect::<Result<_, _>>()?
+        }
+    };
+
+    Ok((
+        downmix(&interleaved, spec.channels as usize),
+        spec.sample_rate,
+    ))
+}
+
+fn decode_flac(path: &Path) -> Result<(Vec<f32>, u32), Box<dyn std::error::Error>> {
+    let mut reader = claxon::FlacReader::open(path)?;
+    let info = reader.streaminfo();
+    let scale = (1i64 << (info.bits_per_sample - 1)) as f32;
+
+    let interleaved: Vec<f32> = reader
+        .samples()
+        .map(|s| s.map(|s| s as f32 / scale))
+        .collect::<Result<_, _>>()?;
+
+    Ok((
+        downmix(&interleaved, info.channels as usize),
+        info.sample_rate,
+    ))
+}
+
+/// A WAV or FLAC file, decoded up front and fed like a microphone would be.
+pub struct FileSource {
+    path: PathBuf,
+    pacing: Pacing,
+}
+
+impl FileSource {
+    pub fn new(path: impl Into<PathBuf>, pacing: Pacing) -> Self {
+        Self {
+            path: path.into(),
+            pacing,
+        }
+    }
+}
+
+impl AudioSource for FileSource {
+    fn describe(&self) -> String {
+        format!("file {}", self.path.display())
+    }
+
+    fn start(
+        self: Box<Self>,
+        sample_tx: mpsc::Sender<Vec<f32>>,
+    ) -> Result<ActiveSource, Box<dyn std::error::Error>> {
+        let (samples, sample_rate) = decode_audio_file(&self.path)?;
+        log::info!(
+            "Playing {} ({:.1}s at {} Hz, {:?})",
+            self.path.display(),
+            samples.len() as f32 / sample_rate as f32,
+            sample_rate,
+            self.pacing
+        );
+        let feeder = spawn_feeder(samples, sample_rate, self.pacing, sample_tx)?;
+        Ok(ActiveSource::new(sample_rate, feeder))
+    }
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+/// Sample encoding of a raw PCM stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PcmFormat {
+    S16Le,
+    F32Le,
+}
+
+impl PcmFormat {
+    fn bytes_per_sample(self) -> usize {
+        match self {
+            PcmFormat::S16Le => 2,
+            PcmFormat::F32Le => 4,
+        }
+    }
+
+    fn decode(self, bytes: &[u8]) -> f32 {
+        match self {
+            PcmFormat::S16Le => i16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 32768.0,
+            PcmFormat::F32Le => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        }
+    }
+}
+
+/// Raw interleaved PCM from a reader, typically stdin
+/// (`arecord -f S16_LE -r 16000 | handy ...`). The pipe sets the pace.
+pub struct PcmSource<R> {
+    reader: R,
+    sample_rate: u32,
+    channels: u16,
+    format: PcmFormat,
+}
+
+impl<R: Read + Send + 'static> PcmSource<R> {
+    pub fn new(reader: R, sample_rate: u32, channels: u16, format: PcmFormat) -> Self {
+        Self {
+            reader,
+            sample_rate,
+            channels: channels.max(1),
+            format,
+        }
+    }
+}
+
+impl PcmSource<std::io::Stdin> {
+    pub fn stdin(sample_rate: u32, channels: u16, format: PcmFormat) -> Self {
+        Self::new(std::io::stdin(), sample_rate, channels, format)
+    }
+}
+
+impl<R: Read + Send + 'static> AudioSource for PcmSource<R> {
+    fn describe(&self) -> String {
+        format!(
+            "raw {:?} PCM ({} Hz, {} ch)",
+            self.format, self.sample_rate, self.channels
+        )
+    }
+
+    fn start(
+        self: Box<Self>,
+        sample_tx: mpsc::Sender<Vec<f32>>,
+    ) -> Result<ActiveSource, Box<dyn std::error::Error>> {
+        let PcmSource {
+            mut reader,
+            sample_rate,
+            channels,
+            format,
+        } = *self;
+        let frame_bytes = format.bytes_per_sample() * channels as usize;
+
+        let handle = std::thread::Builder::new()
+            .name("audio-source-pcm".into())
+            .spawn(move || {
+                let mut buf = vec![0u8; frame_bytes * 1024];
+                // Bytes of a partial frame carried over from the previous read
+                let mut pending = 0usize;
+
+                loop {
+                    let n = match reader.read(&mut buf[pending..]) {
+                        Ok(0) => return,
+                        Ok(n) => n,
+                        Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                        Err(e) => {
+                            log::error!("PCM source read failed: {}", e);
+                            return;
+                        }
+                    };
+
+                    let filled = pending + n;
+                    let whole = filled - filled % frame_bytes;
+                    let interleaved: Vec<f32> = buf[..whole]
+                        .chunks_exact(format.bytes_per_sample())
+                        .map(|bytes| format.decode(bytes))
+                        .collect();
+
+                    buf.copy_within(whole..filled, 0);
+                    pending = filled - whole;
+
+                    if !interleaved.is_empty()
+                        && sample_tx
+                            .send(downmix(&interleaved, channels as usize))
+                            .is_err()
+                    {
+                        return; // recorder closed
+                    }
+                }
+            })?;
+
+        Ok(ActiveSource::new(sample_rate, handle))
+    }
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+/// Generated test audio: tones, silence and noise, back to back.
+pub struct SyntheticSource {
+    sample_rate: u32,
+    pacing: Pacing,
+    samples: Vec<f32>,
+    noise_state: u32,
+}
+
+impl SyntheticSource {
+    pub fn new(sample_rate: u32, pacing: Pacing) -> Self {
+        Self {
+            sample_rate,
+            pacing,
+            samples: Vec::new(),
+            noise_state: 0x9E37_79B9,
+        }
+    }
+
+    fn len_of(&self, duration: Duration) -> usize {
+        (duration.as_secs_f64() * self.sample_rate as f64).round() as usize
+    }
+
+    pub fn silence(mut self, duration: Duration) -> Self {
+        let len = self.len_of(duration);
+        self.samples.extend(std::iter::repeat_n(0.0, len));
+        self
+    }
+
+    pub fn tone(mut self, freq_hz: f32, amplitude: f32, duration: Duration) -> Self {
+        let len = self.len_of(duration);
+        let rate = self.sample_rate as f32;
+        self.samples
+            .extend((0..len).map(|i| amplitude * (2.0 * PI * freq_hz * i as f32 / rate).sin()));
+        self
+    }
+
+    /// Deterministic white noise, so test runs are reproducible.
+    pub fn noise(mut self, amplitude: f32, duration: Duration) -> Self {
+        for _ in 0..self.len_of(duration) {
+            // xorshift32
+            let mut x = self.noise_state;
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            self.noise_state = x;
+            let unit = x as f32 / u32::MAX as f32 * 2.0 - 1.0;
+            self.samples.push(amplitude * unit);
+        }
+        self
+    }
+
+    /// Appends pre-made samples, e.g. a decoded speech clip.
+    pub fn samples(mut self, samples: &[f32]) -> Self {
+        self.samples.extend_from_slice(samples);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+}
+
+impl AudioSource for SyntheticSource {
+    fn describe(&self) -> String {
+        format!(
+            "synthetic audio ({:.1}s at {} Hz)",
+            self.samples.len() as f32 / self.sample_rate as f32,
+            self.sample_rate
+        )
+    }
+
+    fn start(
+        self: Box<Self>,
+        sample_tx: mpsc::Sender<Vec<f32>>,
+    ) -> Result<ActiveSource, Box<dyn std::error::Error>> {
+        let feeder = spawn_feeder(self.samples, self.sample_rate, self.pacing, sample_tx)?;
+        Ok(ActiveSource::new(self.sample_rate, feeder))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(source: Box<dyn AudioSource>) -> (Vec<f32>, u32) {
+        let (tx, rx) = mpsc::channel();
+        let active = source.start(tx).unwrap();
+        let samples = rx.iter().flatten().collect();
+        (samples, active.sample_rate)
+    }
+
+    #[test]
+    fn test_pcm_source_downmixes_and_handles_partial_frames() {
+        // Two stereo s16 frames, read one byte at a time to split frames across reads
+        struct Trickle(std::vec::IntoIter<u8>);
+        impl Read for Trickle {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                match self.0.next() {
+                    Some(b) => {
+                        buf[0] = b;
+                        Ok(1)
+                    }
+                    None => Ok(0),
+                }
+            }
+        }
+
+        let mut bytes = Vec::new();
+        for s in [16384i16, 0, -16384, -16384] {
+            bytes.extend_from_slice(&s.to_le_bytes());
+        }
+        let source = PcmSource::new(Trickle(bytes.into_iter()), 8000, 2, PcmFormat::S16Le);
+
+        let (samples, rate) = collect(Box::new(source));
+        assert_eq!(rate, 8000);
+        assert_eq!(samples, vec![0.25, -0.5]);
+    }
+
+    #[test]
+    fn test_synthetic_source_lengths() {
+        let source = SyntheticSource::new(16000, Pacing::Unpaced)
+            .silence(Duration::from_millis(500))
+            .tone(440.0, 0.5, Duration::from_secs(1))
+            .noise(0.1, Duration::from_millis(250));
+        assert_eq!(source.len(), 28000);
+
+        let (samples, _) = collect(Box::new(source));
+        assert_eq!(samples.len(), 28000);
+        assert!(samples[..8000].iter().all(|&s| s == 0.0));
+        assert!(samples.iter().all(|s| s.abs() <= 0.5));
+    }
+
+    #[test]
+    fn test_wav_roundtrip_via_file_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("stereo.wav");
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 22050,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for _ in 0..441 {
+            writer.write_sample(16384i16).unwrap();
+            writer.write_sample(0i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let (samples, rate) = collect(Box::new(FileSource::new(&path, Pacing::Unpaced)));
+        assert_eq!(rate, 22050);
+        assert_eq!(samples.len(), 441);
+        assert!(samples.iter().all(|&s| (s - 0.25).abs() < 1e-6));
+
+        assert!(decode_audio_file(&dir.path().join("notes.mp3")).is_err());
+    }
+}
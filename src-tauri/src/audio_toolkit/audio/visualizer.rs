@@ -1,3 +1,4 @@
+use super::dsp::NoiseProfile;
 use rustfft::{num_complex::Complex32, Fft, FftPlanner};
 use std::sync::Arc;
 
@@ -5,6 +6,9 @@ const DB_MIN: f32 = -55.0;
 const DB_MAX: f32 = -8.0;
 const GAIN: f32 = 1.3;
 const CURVE_POWER: f32 = 0.7;
+/// Bands the room's noise is tracked in, across the whole spectrum
+const NOISE_BANDS: usize = 16;
+const INITIAL_NOISE_DB: f32 = -40.0;
 
 pub struct AudioVisualiser {
     fft: Arc<dyn Fft<f32>>,
@@ -12,9 +16,14 @@ pub struct AudioVisualiser {
     bucket_ranges: Vec<(usize, usize)>,
     fft_input: Vec<Complex32>,
     noise_floor: Vec<f32>,
+    noise_bands: Vec<(usize, usize)>,
+    /// Noise floor in each of `noise_bands`, for noise suppression. It
+    /// describes the room rather than the recording, so it survives `reset`
+    room_noise: Vec<f32>,
     buffer: Vec<f32>,
     bucket_output: Vec<f32>, // Pre-allocated output buffer
     window_size: usize,
+    sample_rate: u32,
 }
 
 impl AudioVisualiser {
@@ -65,15 +74,19 @@ impl AudioVisualiser {
             bucket_ranges.push((start_bin, end_bin));
         }
 
+        let noise_bands = noise_bands(window_size);
         Self {
             fft,
             window,
             bucket_ranges,
             fft_input: vec![Complex32::new(0.0, 0.0); window_size],
-            noise_floor: vec![-40.0; buckets], // Initialize to reasonable noise floor
+            noise_floor: vec![INITIAL_NOISE_DB; buckets], // Initialize to reasonable noise floor
+            room_noise: vec![INITIAL_NOISE_DB; noise_bands.len()],
+            noise_bands,
             buffer: Vec::with_capacity(window_size * 2),
             bucket_output: vec![0.0; buckets], // Pre-allocate output buffer
             window_size,
+            sample_rate,
         }
     }
 
@@ -109,34 +122,19 @@ impl AudioVisualiser {
                 continue;
             }
 
-            // Calculate average power in this frequency range
-            let mut power_sum = 0.0;
-            for bin_idx in start_bin..end_bin {
-                let magnitude = self.fft_input[bin_idx].norm();
-                power_sum += magnitude * magnitude;
-            }
-
-            let avg_power = power_sum / (end_bin - start_bin) as f32;
-
-            // Convert to dB with proper scaling
-            let db = if avg_power > 1e-12 {
-                20.0 * (avg_power.sqrt() / self.window_size as f32).log10()
-            } else {
-                -80.0 // Very low floor for zero power
-            };
-
-            // Only update noise floor when signal is quiet (below current floor + 10dB)
-            if db < self.noise_floor[bucket_idx] + 10.0 {
-                const NOISE_ALPHA: f32 = 0.001; // Very slow adaptation
-                self.noise_floor[bucket_idx] =
-                    NOISE_ALPHA * db + (1.0 - NOISE_ALPHA) * self.noise_floor[bucket_idx];
-            }
+            let db = self.band_db(start_bin, end_bin);
+            track_noise_floor(&mut self.noise_floor[bucket_idx], db);
 
             // Map configurable dB range to 0-1 with gain and curve shaping
             let normalized = ((db - DB_MIN) / (DB_MAX - DB_MIN)).clamp(0.0, 1.0);
             self.bucket_output[bucket_idx] = (normalized * GAIN).powf(CURVE_POWER).clamp(0.0, 1.0);
         }
 
+        for (band, &(start_bin, end_bin)) in self.noise_bands.iter().enumerate() {
+            let db = self.band_db(start_bin, end_bin);
+            track_noise_floor(&mut self.room_noise[band], db);
+        }
+
         // Apply light smoothing to reduce jitter (in-place)
         for i in 1..self.bucket_output.len() - 1 {
             let smoothed = self.bucket_output[i] * 0.7
@@ -151,9 +149,77 @@ impl AudioVisualiser {
         Some(self.bucket_output.clone())
     }
 
+    /// Level of bins `start_bin..end_bin` of the last window, in dB.
+    fn band_db(&self, start_bin: usize, end_bin: usize) -> f32 {
+        // Calculate average power in this frequency range
+        let power_sum: f32 = self.fft_input[start_bin..end_bin]
+            .iter()
+            .map(|bin| bin.norm_sqr())
+            .sum();
+        let avg_power = power_sum / (end_bin - start_bin) as f32;
+
+        // Convert to dB with proper scaling
+        if avg_power > 1e-12 {
+            20.0 * (avg_power.sqrt() / self.window_size as f32).log10()
+        } else {
+            -80.0 // Very low floor for zero power
+        }
+    }
+
+    /// The room's noise floor as a spectrum, for noise suppression. Unlike
+    /// the display buckets it covers everything up to Nyquist, hum included.
+    pub fn noise_profile(&self) -> NoiseProfile {
+        let bin_hz = self.sample_rate as f32 / self.window_size as f32;
+        // Energy of the Hann window, sum of w^2
+        let window_energy = 3.0 * self.window_size as f32 / 8.0;
+        let points = self
+            .noise_bands
+            .iter()
+            .zip(&self.room_noise)
+            .map(|(&(start_bin, end_bin), &db)| {
+                // Undo the dB scaling in feed() to get back to power per bin
+                let magnitude = 10f32.powf(db / 20.0) * self.window_size as f32;
+                let psd = magnitude * magnitude / (self.sample_rate as f32 * window_energy);
+                ((start_bin + end_bin) as f32 / 2.0 * bin_hz, psd)
+            })
+            .collect();
+        NoiseProfile::new(points)
+    }
+
     pub fn reset(&mut self) {
         self.buffer.clear();
-        // Reset noise floor to initial values
-        self.noise_floor.fill(-40.0);
+        self.noise_floor.fill(INITIAL_NOISE_DB);
+    }
+}
+
+/// Only moves the floor while the signal is quiet (below the floor + 10 dB),
+/// and follows quieter levels quickly so it isn't overestimated.
+fn track_noise_floor(floor: &mut f32, db: f32) {
+    const NOISE_ALPHA: f32 = 0.001; // Very slow adaptation
+    const NOISE_ALPHA_DOWN: f32 = 0.01;
+    if db < *floor + 10.0 {
+        let alpha = if db < *floor {
+            NOISE_ALPHA_DOWN
+        } else {
+            NOISE_ALPHA
+        };
+        *floor = alpha * db + (1.0 - alpha) * *floor;
+    }
+}
+
+/// Bin ranges spaced like the display buckets but from the first bin past
+/// DC (which `feed` removes) up to Nyquist.
+fn noise_bands(window_size: usize) -> Vec<(usize, usize)> {
+    let half = window_size / 2;
+    let mut bands = Vec::with_capacity(NOISE_BANDS);
+    let mut start = 1;
+    for b in 1..=NOISE_BANDS {
+        let edge = 1 + ((half - 1) as f32 * (b as f32 / NOISE_BANDS as f32).powi(2)) as usize;
+        let end = edge.max(start + 1).min(half);
+        if start < end {
+            bands.push((start, end));
+            start = end;
+        }
     }
+    bands
 }
@@ -0,0 +1,133 @@
+/// Cosine similarity of two embeddings; zero if either is all zeros.
+pub fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm =
+        a.iter().map(|x| x * x).sum::<f32>().sqrt() * b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        dot / norm
+    } else {
+        0.0
+    }
+}
+
+/// Groups embeddings by speaker with average-linkage agglomerative
+/// clustering. Clusters keep merging until there are at most `max_speakers`
+/// of them and, beyond that, while the closest pair is still more similar
+/// than `same_speaker`, so one voice isn't split in two.
+///
+/// Returns one label per embedding, numbered by first appearance.
+pub fn cluster(embeddings: &[Vec<f32>], max_speakers: usize, same_speaker: f32) -> Vec<usize> {
+    let n = embeddings.len();
+    let mut similarity = vec![vec![0.0f32; n]; n];
+    for i in 0..n {
+        for j in i + 1..n {
+            let s = cosine(&embeddings[i], &embeddings[j]);
+            similarity[i][j] = s;
+            similarity[j][i] = s;
+        }
+    }
+
+    let mut clusters: Vec<Vec<usize>> = (0..n).map(|i| vec![i]).collect();
+    let linkage = |a: &[usize], b: &[usize]| {
+        let total: f32 = a
+            .iter()
+            .flat_map(|&i| b.iter().map(move |&j| (i, j)))
+            .map(|(i, j)| similarity[i][j])
+            .sum();
+        total / (a.len() * b.len()) as f32
+    };
+
+    while clusters.len() > 1 {
+        let mut best = (0, 1, f32::NEG_INFINITY);
+        for a in 0..clusters.len() {
+            for b in a + 1..clusters.len() {
+                let s = linkage(&clusters[a], &clusters[b]);
+                if s > best.2 {
+                    best = (a, b, s);
+                }
+            }
+        }
+
+        let (a, b, s) = best;
+        if clusters.len() <= max_speakers.max(1) && s < same_speaker {
+            break;
+        }
+        let merged = clusters.swap_remove(b);
+        clusters[a].extend(merged);
+    }
+
+    let mut labels = vec![0; n];
+    for (label, members) in clusters.iter().enumerate() {
+        for &i in members {
+            labels[i] = label;
+        }
+    }
+    renumber(&mut labels);
+    labels
+}
+
+/// Renames labels so they count up from zero in order of first appearance.
+pub fn renumber(labels: &mut [usize]) {
+    let mut order: Vec<usize> = Vec::new();
+    for label in labels.iter_mut() {
+        let index = match order.iter().position(|&l| l == *label) {
+            Some(index) => index,
+            None => {
+                order.push(*label);
+                order.len() - 1
+            }
+        };
+        *label = index;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Noisy copies of a few well separated voices.
+    fn voices(pattern: &[usize]) -> Vec<Vec<f32>> {
+        pattern
+            .iter()
+            .enumerate()
+            .map(|(n, &voice)| {
+                (0..16)
+                    .map(|d| {
+                        let base = if d % 4 == voice { 1.0 } else { 0.0 };
+                        base + 0.1 * ((n * 7 + d * 3) as f32).sin()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_two_speakers() {
+        let labels = cluster(&voices(&[1, 1, 2, 1, 2, 2]), 2, 0.8);
+        assert_eq!(labels, vec![0, 0, 1, 0, 1, 1]);
+    }
+
+    #[test]
+    fn test_capped_at_max_speakers() {
+        // Three voices but room for two: the closest pair shares a label
+        let labels = cluster(&voices(&[0, 1, 2, 0, 1, 2]), 2, 0.8);
+        assert_eq!(labels.iter().max(), Some(&1));
+        assert_eq!(labels[0], labels[3]);
+        assert_eq!(labels[1], labels[4]);
+        assert_eq!(labels[2], labels[5]);
+    }
+
+    #[test]
+    fn test_one_voice_stays_together() {
+        let labels = cluster(&voices(&[3, 3, 3, 3]), 2, 0.8);
+        assert_eq!(labels, vec![0, 0, 0, 0]);
+        assert!(cluster(&[], 2, 0.8).is_empty());
+    }
+
+    #[test]
+    fn test_renumber() {
+        let mut labels = vec![4, 4, 1, 4, 0];
+        renumber(&mut labels);
+        assert_eq!(labels, vec![0, 0, 1, 0, 2]);
+    }
+}
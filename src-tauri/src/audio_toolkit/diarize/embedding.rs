@@ -0,0 +1,71 @@
+use super::fbank::{Fbank, MEL_BINS};
+use anyhow::{anyhow, Result};
+use ndarray::Array3;
+use ort::inputs;
+use ort::session::builder::GraphOptimizationLevel;
+use ort::session::Session;
+use ort::value::TensorRef;
+use std::path::Path;
+
+/// Turns a stretch of 16 kHz speech into a fixed-size voice print. Prints of
+/// the same speaker point in roughly the same direction.
+pub trait SpeakerEmbedder: Send {
+    fn embed(&mut self, samples: &[f32]) -> Result<Vec<f32>>;
+}
+
+/// Speaker embeddings from a WeSpeaker-style ONNX model (ResNet or
+/// ECAPA-TDNN) that takes `[1, frames, 80]` filterbank features and returns
+/// one embedding per utterance.
+pub struct OnnxSpeakerEmbedder {
+    session: Session,
+    input_name: String,
+    output_name: String,
+    fbank: Fbank,
+}
+
+impl OnnxSpeakerEmbedder {
+    pub fn new<P: AsRef<Path>>(model_path: P) -> Result<Self> {
+        let session = Session::builder()?
+            .with_optimization_level(GraphOptimizationLevel::Level3)?
+            .with_intra_threads(1)?
+            .commit_from_file(model_path.as_ref())?;
+
+        let input_name = session
+            .inputs
+            .first()
+            .map(|input| input.name.clone())
+            .ok_or_else(|| anyhow!("Speaker model has no inputs"))?;
+        let output_name = session
+            .outputs
+            .first()
+            .map(|output| output.name.clone())
+            .ok_or_else(|| anyhow!("Speaker model has no outputs"))?;
+
+        Ok(Self {
+            session,
+            input_name,
+            output_name,
+            fbank: Fbank::new(),
+        })
+    }
+}
+
+impl SpeakerEmbedder for OnnxSpeakerEmbedder {
+    fn embed(&mut self, samples: &[f32]) -> Result<Vec<f32>> {
+        let frames = Fbank::frames(samples.len());
+        if frames == 0 {
+            return Err(anyhow!("Too little audio for a speaker embedding"));
+        }
+        let features = Array3::from_shape_vec((1, frames, MEL_BINS), self.fbank.compute(samples))?;
+
+        let outputs = self.session.run(inputs![
+            self.input_name.as_str() => TensorRef::from_array_view(features.view())?,
+        ])?;
+        let embedding = outputs
+            .get(self.output_name.as_str())
+            .ok_or_else(|| anyhow!("Speaker model output {} missing", self.output_name))?
+            .try_extract_array::<f32>()?;
+
+        Ok(embedding.iter().copied().collect())
+    }
+}
@@ -0,0 +1,186 @@
+use rustfft::{num_complex::Complex32, Fft, FftPlanner};
+use std::sync::Arc;
+
+const SAMPLE_RATE: f32 = 16000.0;
+/// 25 ms frames every 10 ms, as the speaker models were trained on
+const FRAME_LEN: usize = 400;
+const FRAME_SHIFT: usize = 160;
+const FFT_LEN: usize = 512;
+const PREEMPHASIS: f32 = 0.97;
+const LOW_FREQ: f32 = 20.0;
+
+pub const MEL_BINS: usize = 80;
+
+/// Kaldi-style log mel filterbank features for 16 kHz mono audio, with the
+/// per-utterance mean removed from each bin.
+pub struct Fbank {
+    fft: Arc<dyn Fft<f32>>,
+    window: Vec<f32>,
+    /// One (first bin, weights) pair per mel filter
+    filters: Vec<(usize, Vec<f32>)>,
+}
+
+fn mel(hz: f32) -> f32 {
+    1127.0 * (1.0 + hz / 700.0).ln()
+}
+
+impl Fbank {
+    pub fn new() -> Self {
+        let fft = FftPlanner::<f32>::new().plan_fft_forward(FFT_LEN);
+
+        // Povey window: a Hann window raised to 0.85
+        let window = (0..FRAME_LEN)
+            .map(|i| {
+                let hann = 0.5
+                    - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / (FRAME_LEN - 1) as f32).cos();
+                hann.powf(0.85)
+            })
+            .collect();
+
+        let mel_low = mel(LOW_FREQ);
+        let mel_high = mel(SAMPLE_RATE / 2.0);
+        let mel_step = (mel_high - mel_low) / (MEL_BINS + 1) as f32;
+        let bin_hz = SAMPLE_RATE / FFT_LEN as f32;
+
+        let filters = (0..MEL_BINS)
+            .map(|m| {
+                let left = mel_low + m as f32 * mel_step;
+                let centre = left + mel_step;
+                let right = centre + mel_step;
+
+                let weights: Vec<(usize, f32)> = (0..FFT_LEN / 2)
+                    .filter_map(|bin| {
+                        let freq = mel(bin as f32 * bin_hz);
+                        let weight = if freq > left && freq <= centre {
+                            (freq - left) / (centre - left)
+                        } else if freq > centre && freq < right {
+                            (right - freq) / (right - centre)
+                        } else {
+                            return None;
+                        };
+                        Some((bin, weight))
+                    })
+                    .collect();
+
+                let first = weights.first().map_or(0, |&(bin, _)| bin);
+                (first, weights.into_iter().map(|(_, w)| w).collect())
+            })
+            .collect();
+
+        Self {
+            fft,
+            window,
+            filters,
+        }
+    }
+
+    /// Number of feature frames produced for `samples` samples.
+    pub fn frames(samples: usize) -> usize {
+        if samples < FRAME_LEN {
+            0
+        } else {
+            1 + (samples - FRAME_LEN) / FRAME_SHIFT
+        }
+    }
+
+    /// Features for `samples`, `MEL_BINS` values per frame, row-major.
+    pub fn compute(&self, samples: &[f32]) -> Vec<f32> {
+        let frames = Self::frames(samples.len());
+        let mut features = Vec::with_capacity(frames * MEL_BINS);
+        let mut frame = [0.0f32; FRAME_LEN];
+        let mut spectrum = vec![Complex32::new(0.0, 0.0); FFT_LEN];
+
+        for f in 0..frames {
+            // The models expect 16-bit sample magnitudes
+            let start = f * FRAME_SHIFT;
+            for (dst, &src) in frame.iter_mut().zip(&samples[start..start + FRAME_LEN]) {
+                *dst = src * 32768.0;
+            }
+
+            let mean = frame.iter().sum::<f32>() / FRAME_LEN as f32;
+            frame.iter_mut().for_each(|s| *s -= mean);
+            for i in (1..FRAME_LEN).rev() {
+                frame[i] -= PREEMPHASIS * frame[i - 1];
+            }
+            frame[0] -= PREEMPHASIS * frame[0];
+
+            spectrum.fill(Complex32::new(0.0, 0.0));
+            for ((dst, &s), &w) in spectrum.iter_mut().zip(&frame).zip(&self.window) {
+                dst.re = s * w;
+            }
+            self.fft.process(&mut spectrum);
+
+            for (first, weights) in &self.filters {
+                let energy: f32 = weights
+                    .iter()
+                    .zip(&spectrum[*first..])
+                    .map(|(w, c)| w * c.norm_sqr())
+                    .sum();
+                features.push(energy.max(f32::EPSILON).ln());
+            }
+        }
+
+        // Cepstral mean normalisation, per bin over the whole input
+        if frames > 0 {
+            for bin in 0..MEL_BINS {
+                let mean = (0..frames)
+                    .map(|f| features[f * MEL_BINS + bin])
+                    .sum::<f32>()
+                    / frames as f32;
+                for f in 0..frames {
+                    features[f * MEL_BINS + bin] -= mean;
+                }
+            }
+        }
+
+        features
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(hz: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| 0.3 * (2.0 * std::f32::consts::PI * hz * i as f32 / SAMPLE_RATE).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_frame_count() {
+        assert_eq!(Fbank::frames(0), 0);
+        assert_eq!(Fbank::frames(399), 0);
+        assert_eq!(Fbank::frames(400), 1);
+        assert_eq!(Fbank::frames(16000), 98);
+        assert_eq!(Fbank::new().compute(&vec![0.0; 16000]).len(), 98 * MEL_BINS);
+    }
+
+    #[test]
+    fn test_mean_is_removed() {
+        let features = Fbank::new().compute(&tone(440.0, 8000));
+        let frames = features.len() / MEL_BINS;
+        for bin in 0..MEL_BINS {
+            let mean: f32 = (0..frames)
+                .map(|f| features[f * MEL_BINS + bin])
+                .sum::<f32>()
+                / frames as f32;
+            assert!(mean.abs() < 1e-3, "bin {bin}: {mean}");
+        }
+    }
+
+    #[test]
+    fn test_tone_lands_in_a_low_bin() {
+        // Without normalisation a single frame would be all offset, so
+        // compare a tone frame against a silent one instead
+        let fbank = Fbank::new();
+        let mut samples = vec![0.0; 4000];
+        samples.extend(tone(300.0, 4000));
+        let features = fbank.compute(&samples);
+        let last = &features[features.len() - MEL_BINS..];
+        let loudest = (0..MEL_BINS)
+            .max_by(|&a, &b| last[a].total_cmp(&last[b]))
+            .unwrap();
+        assert!(loudest < 20, "{loudest}");
+    }
+}
@@ -0,0 +1,413 @@
+//! Who spoke when, for recordings with a small known number of speakers
+//! (typically a clinician and a patient).
+//!
+//! Speech is cut into short overlapping windows, each window is turned into
+//! a voice print by a [`SpeakerEmbedder`], and the prints are clustered into
+//! at most N speakers. The resulting [`SpeakerTurn`]s are matched against
+//! transcript segments with [`attribute`], or against untimed text with
+//! [`spread`].
+
+mod cluster;
+mod embedding;
+mod fbank;
+
+pub use embedding::{OnnxSpeakerEmbedder, SpeakerEmbedder};
+
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+use crate::audio_toolkit::vad::VoiceActivityDetector;
+use anyhow::Result;
+use std::time::Duration;
+
+/// 30 ms at 16 kHz, the frame size every VAD in the toolkit takes
+const VAD_FRAME: usize = 480;
+
+#[derive(Debug, Clone)]
+pub struct DiarizationConfig {
+    /// Upper bound on the number of distinct speakers
+    pub max_speakers: usize,
+    /// Length of the audio behind each voice print
+    pub window: Duration,
+    /// Distance between consecutive windows, and so the time resolution
+    pub hop: Duration,
+    /// Speech stretches shorter than this are too short to tell apart and
+    /// are left to the surrounding turns
+    pub min_speech: Duration,
+    /// Clusters this similar are merged even below `max_speakers`
+    pub same_speaker_similarity: f32,
+}
+
+impl Default for DiarizationConfig {
+    fn default() -> Self {
+        Self {
+            max_speakers: 2,
+            window: Duration::from_millis(1500),
+            hop: Duration::from_millis(750),
+            min_speech: Duration::from_millis(300),
+            same_speaker_similarity: 0.6,
+        }
+    }
+}
+
+/// One speaker talking without interruption, in seconds from the start of
+/// the audio. Speakers are numbered from zero in order of first appearance.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpeakerTurn {
+    pub speaker: usize,
+    pub start: f32,
+    pub end: f32,
+}
+
+/// Transcript text with the speaker it was attributed to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttributedText {
+    pub speaker: usize,
+    pub start: f32,
+    pub end: f32,
+    pub text: String,
+}
+
+pub struct Diarizer {
+    embedder: Box<dyn SpeakerEmbedder>,
+    vad: Option<Box<dyn VoiceActivityDetector>>,
+    config: DiarizationConfig,
+}
+
+impl Diarizer {
+    pub fn new(embedder: Box<dyn SpeakerEmbedder>, config: DiarizationConfig) -> Self {
+        Self {
+            embedder,
+            vad: None,
+            config,
+        }
+    }
+
+    /// Skips non-speech before embedding. Without a VAD the whole input is
+    /// treated as speech, which suits audio that was trimmed while recording.
+    pub fn with_vad(mut self, vad: Box<dyn VoiceActivityDetector>) -> Self {
+        self.vad = Some(vad);
+        self
+    }
+
+    /// Speaker turns for 16 kHz mono `samples`.
+    pub fn diarize(&mut self, samples: &[f32]) -> Result<Vec<SpeakerTurn>> {
+        let regions = self.speech_regions(samples)?;
+        let windows = windows(&regions, &self.config);
+
+        let mut embeddings = Vec::with_capacity(windows.len());
+        for &(start, end) in &windows {
+            embeddings.push(self.embedder.embed(&samples[start..end])?);
+        }
+        let labels = cluster::cluster(
+            &embeddings,
+            self.config.max_speakers,
+            self.config.same_speaker_similarity,
+        );
+
+        let hop = to_samples(self.config.hop);
+        let mut turns: Vec<SpeakerTurn> = Vec::new();
+        for (i, (&(start, end), &speaker)) in windows.iter().zip(&labels).enumerate() {
+            // Overlapping windows each own the stretch up to the next one
+            let end = match windows.get(i + 1) {
+                Some(&(next, _)) if next < end && next <= start + hop => next,
+                _ => end,
+            };
+            let (start, end) = (to_secs(start), to_secs(end));
+            match turns.last_mut() {
+                Some(last) if last.speaker == speaker => last.end = end,
+                _ => turns.push(SpeakerTurn {
+                    speaker,
+                    start,
+                    end,
+                }),
+            }
+        }
+        Ok(turns)
+    }
+
+    /// Sample ranges the VAD considers speech.
+    fn speech_regions(&mut self, samples: &[f32]) -> Result<Vec<(usize, usize)>> {
+        let Some(vad) = self.vad.as_mut() else {
+            return Ok(vec![(0, samples.len())]);
+        };
+
+        vad.reset();
+        let mut regions: Vec<(usize, usize)> = Vec::new();
+        for (i, frame) in samples.chunks_exact(VAD_FRAME).enumerate() {
+            if !vad.is_voice(frame)? {
+                continue;
+            }
+            let start = i * VAD_FRAME;
+            match regions.last_mut() {
+                Some(last) if last.1 == start => last.1 = start + VAD_FRAME,
+                _ => regions.push((start, start + VAD_FRAME)),
+            }
+        }
+        Ok(regions)
+    }
+}
+
+fn to_samples(duration: Duration) -> usize {
+    (duration.as_secs_f64() * WHISPER_SAMPLE_RATE as f64) as usize
+}
+
+fn to_secs(samples: usize) -> f32 {
+    samples as f32 / WHISPER_SAMPLE_RATE as f32
+}
+
+/// Embedding windows over the speech regions. A region shorter than one
+/// window gets a single window of its own, and the last window of a region
+/// is pulled back so it ends with the region.
+fn windows(regions: &[(usize, usize)], config: &DiarizationConfig) -> Vec<(usize, usize)> {
+    let window = to_samples(config.window);
+    let hop = to_samples(config.hop).max(1);
+    let min_speech = to_samples(config.min_speech);
+
+    let mut out = Vec::new();
+    for &(start, end) in regions {
+        if end - start < min_speech {
+            continue;
+        }
+        if end - start <= window {
+            out.push((start, end));
+            continue;
+        }
+        let mut at = start;
+        loop {
+            if at + window >= end {
+                out.push((end - window, end));
+                break;
+            }
+            out.push((at, at + window));
+            at += hop;
+        }
+    }
+    out
+}
+
+/// Gives each transcript segment `(start, end, text)` to the speaker whose
+/// turns overlap it most, then joins consecutive segments of the same
+/// speaker. Segments no turn overlaps go to the nearest turn.
+pub fn attribute<'a>(
+    segments: impl IntoIterator<Item = (f32, f32, &'a str)>,
+    turns: &[SpeakerTurn],
+) -> Vec<AttributedText> {
+    let mut out: Vec<AttributedText> = Vec::new();
+
+    for (start, end, text) in segments {
+        let text = text.trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        let mut overlap = Vec::<f32>::new();
+        for turn in turns {
+            if overlap.len() <= turn.speaker {
+                overlap.resize(turn.speaker + 1, 0.0);
+            }
+            overlap[turn.speaker] += (end.min(turn.end) - start.max(turn.start)).max(0.0);
+        }
+        let best = overlap
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .filter(|(_, &o)| o > 0.0)
+            .map(|(speaker, _)| speaker);
+        let speaker = best
+            .or_else(|| nearest(turns, (start + end) / 2.0))
+            .unwrap_or(0);
+
+        match out.last_mut() {
+            Some(last) if last.speaker == speaker => {
+                last.end = end;
+                last.text.push(' ');
+                last.text.push_str(text);
+            }
+            _ => out.push(AttributedText {
+                speaker,
+                start,
+                end,
+                text: text.to_string(),
+            }),
+        }
+    }
+    out
+}
+
+/// Shares `text` out between `turns` in proportion to how long each one
+/// lasts, for engines that don't time their segments. Where the words
+/// change hands is an estimate, so it can be a few words off.
+pub fn spread(text: &str, turns: &[SpeakerTurn]) -> Vec<AttributedText> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words.is_empty() {
+        return Vec::new();
+    }
+    let total: f32 = turns.iter().map(|t| (t.end - t.start).max(0.0)).sum();
+    if total <= 0.0 {
+        return vec![AttributedText {
+            speaker: turns.first().map_or(0, |t| t.speaker),
+            start: turns.first().map_or(0.0, |t| t.start),
+            end: turns.last().map_or(0.0, |t| t.end),
+            text: words.join(" "),
+        }];
+    }
+
+    let mut out: Vec<AttributedText> = Vec::new();
+    let mut elapsed = 0.0;
+    let mut taken = 0;
+    for (i, turn) in turns.iter().enumerate() {
+        elapsed += (turn.end - turn.start).max(0.0);
+        let until = if i + 1 == turns.len() {
+            words.len()
+        } else {
+            ((elapsed / total * words.len() as f32).round() as usize).min(words.len())
+        };
+        if until <= taken {
+            continue;
+        }
+        let text = words[taken..until].join(" ");
+        taken = until;
+
+        match out.last_mut() {
+            Some(last) if last.speaker == turn.speaker => {
+                last.end = turn.end;
+                last.text.push(' ');
+                last.text.push_str(&text);
+            }
+            _ => out.push(AttributedText {
+                speaker: turn.speaker,
+                start: turn.start,
+                end: turn.end,
+                text,
+            }),
+        }
+    }
+    out
+}
+
+fn nearest(turns: &[SpeakerTurn], at: f32) -> Option<usize> {
+    let distance = |turn: &SpeakerTurn| (turn.start - at).max(at - turn.end).max(0.0);
+    turns
+        .iter()
+        .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+        .map(|turn| turn.speaker)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tells the synthetic voices apart by pitch: a voice print that's just
+    /// the zero-crossing rate.
+    struct PitchEmbedder;
+
+    impl SpeakerEmbedder for PitchEmbedder {
+        fn embed(&mut self, samples: &[f32]) -> Result<Vec<f32>> {
+            let crossings = samples
+                .windows(2)
+                .filter(|w| (w[0] < 0.0) != (w[1] < 0.0))
+                .count();
+            // 120 Hz points one way, 240 Hz at right angles to it
+            let rate = crossings as f32 / samples.len() as f32;
+            let angle = ((rate - 0.015) / 0.015).clamp(0.0, 1.0) * std::f32::consts::FRAC_PI_2;
+            Ok(vec![angle.cos(), angle.sin()])
+        }
+    }
+
+    fn voice(hz: f32, secs: f32) -> Vec<f32> {
+        let len = (secs * WHISPER_SAMPLE_RATE as f32) as usize;
+        (0..len)
+            .map(|i| 0.3 * (2.0 * std::f32::consts::PI * hz * i as f32 / 16000.0).sin())
+            .collect()
+    }
+
+    fn turn(speaker: usize, start: f32, end: f32) -> SpeakerTurn {
+        SpeakerTurn {
+            speaker,
+            start,
+            end,
+        }
+    }
+
+    #[test]
+    fn test_windows_cover_regions() {
+        let config = DiarizationConfig::default();
+        // 1 s, 0.2 s (too short) and 3 s of speech
+        let regions = [(0, 16000), (20000, 23200), (32000, 80000)];
+        let windows = windows(&regions, &config);
+        assert_eq!(windows[0], (0, 16000));
+        assert_eq!(windows[1], (32000, 56000));
+        assert_eq!(windows.last(), Some(&(56000, 80000)));
+        assert!(windows.iter().all(|&(s, e)| e - s <= 24000));
+    }
+
+    #[test]
+    fn test_two_voices_take_turns() {
+        let mut samples = voice(120.0, 3.0);
+        samples.extend(voice(240.0, 3.0));
+        samples.extend(voice(120.0, 3.0));
+
+        let mut diarizer = Diarizer::new(Box::new(PitchEmbedder), DiarizationConfig::default());
+        let turns = diarizer.diarize(&samples).unwrap();
+
+        assert_eq!(turns.len(), 3, "{turns:?}");
+        assert_eq!(
+            turns.iter().map(|t| t.speaker).collect::<Vec<_>>(),
+            vec![0, 1, 0]
+        );
+        assert!((turns[1].start - 3.0).abs() <= 0.75, "{turns:?}");
+        assert!((turns[2].start - 6.0).abs() <= 0.75, "{turns:?}");
+        assert_eq!(turns[2].end, 9.0);
+    }
+
+    #[test]
+    fn test_attribute_segments() {
+        let turns = [turn(0, 0.0, 3.2), turn(1, 3.2, 6.0), turn(0, 6.0, 9.0)];
+        let segments = [
+            (0.2, 1.5, "How long has it hurt?"),
+            (1.6, 3.4, "Since Monday."),
+            (3.5, 5.8, "Mostly at night."),
+            (6.1, 8.0, "Any fever?"),
+            (9.5, 9.9, "Okay."),
+        ];
+        let attributed = attribute(segments, &turns);
+
+        let summary: Vec<(usize, &str)> = attributed
+            .iter()
+            .map(|a| (a.speaker, a.text.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (0, "How long has it hurt? Since Monday."),
+                (1, "Mostly at night."),
+                // Past the last turn, so it goes to the nearest one
+                (0, "Any fever? Okay."),
+            ]
+        );
+        assert_eq!(attributed[2].end, 9.9);
+    }
+
+    #[test]
+    fn test_spread_untimed_text() {
+        let turns = [turn(0, 0.0, 2.0), turn(1, 2.0, 3.0), turn(0, 3.0, 4.0)];
+        let spread = spread("one two three four five six seven eight", &turns);
+
+        let summary: Vec<(usize, &str)> = spread
+            .iter()
+            .map(|a| (a.speaker, a.text.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (0, "one two three four"),
+                (1, "five six"),
+                (0, "seven eight"),
+            ]
+        );
+        assert_eq!(spread[1].start, 2.0);
+
+        // Nobody found speaking: it all goes to the first speaker
+        assert_eq!(super::spread("hello there", &[])[0].text, "hello there");
+        assert!(super::spread("  ", &turns).is_empty());
+    }
+}
@@ -1,11 +1,13 @@
 pub mod audio;
 pub mod constants;
+pub mod diarize;
 pub mod text;
 pub mod utils;
 pub mod vad;
 
 pub use audio::{
-    list_input_devices, list_output_devices, save_wav_file, AudioRecorder, CpalDeviceInfo,
+    list_input_devices, list_output_devices, save_wav_file, AudioRecorder, AudioSource,
+    CpalDeviceInfo, FileSource, Pacing, PcmFormat, PcmSource, SyntheticSource,
 };
 pub use text::{apply_custom_words, normalize_measurements, normalize_times, normalize_years};
 pub use utils::get_cpal_host;
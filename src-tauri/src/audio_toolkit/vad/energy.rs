@@ -0,0 +1,157 @@
+use anyhow::Result;
+
+use super::{VadFrame, VoiceActivityDetector};
+
+/// Quietest level the noise floor can settle at, so digital silence doesn't
+/// make every breath count as speech.
+const MIN_NOISE_FLOOR_DB: f32 = -70.0;
+/// Broadband noise (fans, rustling, clicks) crosses zero about every other
+/// sample; voiced speech far less often.
+const MAX_SPEECH_ZCR: f32 = 0.35;
+/// How quickly the floor follows the level in non-speech frames, and while
+/// speech goes on (so a noise source switching on is learned eventually).
+const FLOOR_RISE_NOISE: f32 = 0.05;
+const FLOOR_RISE_SPEECH: f32 = 0.002;
+
+/// Energy and zero-crossing detector. Much cruder than Silero but needs no
+/// model, so dictation keeps working when the ONNX model can't be loaded.
+pub struct EnergyVad {
+    /// dB above the noise floor a frame must reach to count as speech
+    margin_db: f32,
+    noise_floor_db: Option<f32>,
+    last_score: Option<f32>,
+}
+
+impl EnergyVad {
+    pub fn new(margin_db: f32) -> Self {
+        Self {
+            margin_db,
+            noise_floor_db: None,
+            last_score: None,
+        }
+    }
+}
+
+/// Level in dBFS and zero-crossing rate (crossings per sample) of a frame.
+fn frame_stats(frame: &[f32]) -> (f32, f32) {
+    if frame.is_empty() {
+        return (MIN_NOISE_FLOOR_DB, 0.0);
+    }
+    let mean_square = frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32;
+    let db = 10.0 * mean_square.max(1e-10).log10();
+    let crossings = frame
+        .windows(2)
+        .filter(|w| (w[0] >= 0.0) != (w[1] >= 0.0))
+        .count();
+    (db, crossings as f32 / frame.len() as f32)
+}
+
+impl VoiceActivityDetector for EnergyVad {
+    fn push_frame<'a>(&'a mut self, frame: &'a [f32]) -> Result<VadFrame<'a>> {
+        let (db, zcr) = frame_stats(frame);
+        let floor = *self
+            .noise_floor_db
+            .get_or_insert(db.max(MIN_NOISE_FLOOR_DB));
+
+        let above = db - floor;
+        let speech = above > self.margin_db && zcr < MAX_SPEECH_ZCR;
+        self.last_score = Some(above);
+
+        // Drop to quieter levels at once, rise slowly
+        let rate = if speech {
+            FLOOR_RISE_SPEECH
+        } else {
+            FLOOR_RISE_NOISE
+        };
+        let next = if db < floor {
+            db
+        } else {
+            floor + (db - floor) * rate
+        };
+        self.noise_floor_db = Some(next.max(MIN_NOISE_FLOOR_DB));
+
+        if speech {
+            Ok(VadFrame::Speech(frame))
+        } else {
+            Ok(VadFrame::Noise)
+        }
+    }
+
+    fn last_score(&self) -> Option<f32> {
+        self.last_score
+    }
+
+    // The noise floor describes the room, not the utterance, so it survives reset
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::PI;
+
+    const FRAME: usize = 480; // 30 ms at 16 kHz
+
+    fn tone(freq: f32, amplitude: f32) -> Vec<f32> {
+        (0..FRAME)
+            .map(|i| amplitude * (2.0 * PI * freq * i as f32 / 16000.0).sin())
+            .collect()
+    }
+
+    fn noise(amplitude: f32, seed: &mut u32) -> Vec<f32> {
+        (0..FRAME)
+            .map(|_| {
+                *seed ^= *seed << 13;
+                *seed ^= *seed >> 17;
+                *seed ^= *seed << 5;
+                amplitude * (*seed as f32 / u32::MAX as f32 * 2.0 - 1.0)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_voiced_tone_over_quiet_room_is_speech() {
+        let mut vad = EnergyVad::new(10.0);
+        let mut seed = 1;
+        for _ in 0..20 {
+            assert!(!vad.is_voice(&noise(0.002, &mut seed)).unwrap());
+        }
+        assert!(vad.is_voice(&tone(220.0, 0.3)).unwrap());
+        assert!(vad.last_score().unwrap() > 10.0);
+    }
+
+    #[test]
+    fn test_loud_broadband_noise_is_not_speech() {
+        let mut vad = EnergyVad::new(10.0);
+        let mut seed = 7;
+        for _ in 0..20 {
+            vad.is_voice(&noise(0.002, &mut seed)).unwrap();
+        }
+        // A burst of rustling is loud but crosses zero constantly
+        assert!(!vad.is_voice(&noise(0.3, &mut seed)).unwrap());
+    }
+
+    #[test]
+    fn test_floor_adapts_to_steady_hum() {
+        let mut vad = EnergyVad::new(10.0);
+        let mut seed = 3;
+        for _ in 0..20 {
+            vad.is_voice(&noise(0.002, &mut seed)).unwrap();
+        }
+
+        // A steady hum is detected at first, then learned as background
+        let hum = tone(120.0, 0.1);
+        let decisions: Vec<bool> = (0..2000).map(|_| vad.is_voice(&hum).unwrap()).collect();
+        assert!(decisions[0]);
+        assert!(!decisions.last().unwrap());
+    }
+
+    #[test]
+    fn test_frame_stats() {
+        let (db, zcr) = frame_stats(&[0.0; FRAME]);
+        assert!(db <= -99.0);
+        assert_eq!(zcr, 0.0);
+
+        let (db, _) = frame_stats(&[1.0; FRAME]);
+        assert!(db.abs() < 1e-3);
+    }
+}
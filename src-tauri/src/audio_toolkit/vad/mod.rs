@@ -22,11 +22,26 @@ pub trait VoiceActivityDetector: Send + Sync {
         Ok(self.push_frame(frame)?.is_speech())
     }
 
+    /// Raw score behind the last decision: speech probability for Silero,
+    /// dB above the noise floor for the energy detector.
+    fn last_score(&self) -> Option<f32> {
+        None
+    }
+
     fn reset(&mut self) {}
 }
 
+/// One VAD decision, reported to the recorder's VAD callback.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, specta::Type)]
+pub struct VadDecision {
+    pub speech: bool,
+    pub score: Option<f32>,
+}
+
+mod energy;
 mod silero;
 mod smoothed;
 
+pub use energy::EnergyVad;
 pub use silero::SileroVad;
 pub use smoothed::SmoothedVad;
@@ -103,7 +103,12 @@ impl VoiceActivityDetector for SmoothedVad {
         }
     }
 
+    fn last_score(&self) -> Option<f32> {
+        self.inner_vad.last_score()
+    }
+
     fn reset(&mut self) {
+        self.inner_vad.reset();
         self.frame_buffer.clear();
         self.hangover_counter = 0;
         self.onset_counter = 0;
@@ -0,0 +1,698 @@
+//! Headless front end to the dictation pipeline: the same recorder, VAD,
+//! models, normalisers and medical vocabulary as the desktop app, without a
+//! window or a running app.
+
+use anyhow::{anyhow, Context as _, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use handy_app_lib::audio_toolkit::{
+    audio::{AutoStopConfig, AutoStopReason, DspConfig},
+    constants::WHISPER_SAMPLE_RATE,
+    AudioRecorder, AudioSource, FileSource, Pacing, PcmFormat, PcmSource,
+};
+use handy_app_lib::host::{default_app_data_dir, HeadlessHost, Host};
+use handy_app_lib::managers::audio::build_vad;
+use handy_app_lib::managers::integrity::VerificationStatus;
+use handy_app_lib::managers::model::ModelManager;
+use handy_app_lib::managers::transcription::{
+    finalize_text, Transcript, TranscriptSegment, TranscriptionManager,
+};
+use handy_app_lib::medical_vocab::{check_custom_vocab_txt, MedicalVocabulary};
+use handy_app_lib::post_process::post_process_transcription;
+use handy_app_lib::subtitles::{self, SubtitleFormat};
+use handy_app_lib::validation::validate_custom_words;
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+
+const EXIT_FAILURE: i32 = 1;
+
+const VAD_MODEL: &str = "resources/models/silero_vad_v4.onnx";
+
+/// The recorder reports errors as non-`Send` boxes.
+fn boxed(e: Box<dyn std::error::Error>) -> anyhow::Error {
+    anyhow!(e.to_string())
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+#[derive(Parser)]
+#[command(name = "handy-cli", version, about, long_about = None)]
+struct Cli {
+    /// Model to use instead of the selected one
+    #[arg(long, global = true, value_name = "ID")]
+    model: Option<String>,
+    /// text, json, srt or vtt
+    #[arg(long, global = true, default_value = "text", value_parser = OutputFormat::parse)]
+    format: OutputFormat,
+    /// Write the result to FILE instead of stdout
+    #[arg(long, global = true, value_name = "FILE")]
+    output: Option<PathBuf>,
+    /// App data directory (default: the desktop app's)
+    #[arg(long, global = true, value_name = "DIR")]
+    data_dir: Option<PathBuf>,
+    /// Bundled resources directory
+    #[arg(long, global = true, value_name = "DIR")]
+    resources: Option<PathBuf>,
+    /// Log to stderr
+    #[arg(short, long, global = true)]
+    verbose: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Transcribe a WAV or FLAC file
+    Transcribe { file: PathBuf },
+    /// Record from the microphone until Enter, Ctrl-C or silence
+    Dictate(DictateArgs),
+    /// List, download or verify models
+    Models {
+        #[command(subcommand)]
+        action: ModelsCommand,
+    },
+    /// Check the custom medical vocabulary
+    Vocab {
+        #[command(subcommand)]
+        action: VocabCommand,
+    },
+}
+
+#[derive(clap::Args)]
+struct DictateArgs {
+    /// Dictate from a file, or raw PCM on stdin
+    #[arg(long, value_name = "FILE|-")]
+    input: Option<String>,
+    /// Stop after this much silence following speech
+    #[arg(long, value_name = "SECS", default_value_t = 2.0)]
+    silence: f32,
+    /// Sample rate of stdin PCM
+    #[arg(long, value_name = "HZ", default_value_t = 16000)]
+    rate: u32,
+    /// Channels of stdin PCM
+    #[arg(long, value_name = "N", default_value_t = 1)]
+    channels: u16,
+    /// Sample format of stdin PCM
+    #[arg(long, value_enum, default_value_t = PcmArg::S16le)]
+    pcm: PcmArg,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum PcmArg {
+    #[value(name = "s16le")]
+    S16le,
+    #[value(name = "f32le")]
+    F32le,
+}
+
+impl From<PcmArg> for PcmFormat {
+    fn from(pcm: PcmArg) -> Self {
+        match pcm {
+            PcmArg::S16le => PcmFormat::S16Le,
+            PcmArg::F32le => PcmFormat::F32Le,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum ModelsCommand {
+    /// List models and their install state
+    List {
+        #[arg(long)]
+        json: bool,
+    },
+    /// Download models
+    Download {
+        #[arg(value_name = "ID", required = true)]
+        ids: Vec<String>,
+    },
+    /// Check installed models (or just ID...) against their hashes
+    Verify {
+        #[arg(value_name = "ID")]
+        ids: Vec<String>,
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum VocabCommand {
+    /// Lint the vocabulary, optionally showing how TEXT would be corrected
+    Check {
+        text: Vec<String>,
+        /// Vocabulary file to check (default: the app's)
+        #[arg(long, value_name = "FILE")]
+        file: Option<PathBuf>,
+    },
+}
+
+#[derive(Clone)]
+enum OutputFormat {
+    Text,
+    Json,
+    Subtitles(SubtitleFormat),
+}
+
+impl OutputFormat {
+    fn parse(name: &str) -> std::result::Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "text" | "txt" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            other => SubtitleFormat::from_name(other)
+                .map(OutputFormat::Subtitles)
+                .ok_or_else(|| format!("unknown format {}", name)),
+        }
+    }
+}
+
+struct Output {
+    format: OutputFormat,
+    path: Option<PathBuf>,
+}
+
+impl Output {
+    fn write(&self, contents: &str) -> Result<()> {
+        match &self.path {
+            Some(path) => std::fs::write(path, contents)
+                .with_context(|| format!("Failed to write {}", path.display())),
+            None => {
+                let mut stdout = std::io::stdout().lock();
+                stdout.write_all(contents.as_bytes())?;
+                stdout.flush()?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+struct Context {
+    host: Arc<dyn Host>,
+    model_manager: Arc<ModelManager>,
+    model_override: Option<String>,
+}
+
+impl Context {
+    fn open(
+        data_dir: Option<PathBuf>,
+        resource_dir: Option<PathBuf>,
+        model: Option<String>,
+    ) -> Result<Self> {
+        let data_dir = data_dir
+            .or_else(default_app_data_dir)
+            .ok_or_else(|| anyhow!("Could not determine the app data directory"))?;
+        std::fs::create_dir_all(&data_dir)?;
+
+        let host: Arc<dyn Host> = Arc::new(
+            HeadlessHost::new(data_dir, resource_dir.unwrap_or_else(default_resource_dir))
+                .with_event_handler(report_event),
+        );
+        let model_manager = Arc::new(ModelManager::new(Arc::clone(&host))?);
+
+        Ok(Self {
+            host,
+            model_manager,
+            model_override: model,
+        })
+    }
+
+    fn model_id(&self) -> Result<String> {
+        let model_id = self
+            .model_override
+            .clone()
+            .unwrap_or_else(|| self.host.settings().selected_model);
+        if model_id.is_empty() {
+            return Err(anyhow!(
+                "No model selected; pass --model or see `handy-cli models list`"
+            ));
+        }
+        Ok(model_id)
+    }
+
+    /// A transcription manager with the model loading in the background,
+    /// so it is ready by the time recording finishes.
+    fn start_transcription(&self) -> Result<TranscriptionManager> {
+        let model_id = self.model_id()?;
+        let info = self
+            .model_manager
+            .get_model_info(&model_id)
+            .ok_or_else(|| anyhow!("Unknown model {}", model_id))?;
+        if !info.is_downloaded {
+            return Err(anyhow!(
+                "Model {} is not installed; run `handy-cli models download {}`",
+                model_id,
+                model_id
+            ));
+        }
+
+        let mut settings = self.host.settings();
+        settings.selected_model = model_id;
+        self.host.write_settings(settings);
+
+        let manager =
+            TranscriptionManager::new(Arc::clone(&self.host), Arc::clone(&self.model_manager))?;
+        manager.initiate_model_load();
+        Ok(manager)
+    }
+
+    fn transcribe(&self, manager: &TranscriptionManager, samples: Vec<f32>) -> Result<Transcript> {
+        let duration = samples.len() as f32 / WHISPER_SAMPLE_RATE as f32;
+        let mut transcript = manager.transcribe_detailed(samples)?;
+
+        let settings = self.host.settings();
+        transcript.text = post_process_transcription(&settings, &transcript.text);
+        for segment in &mut transcript.segments {
+            segment.text = post_process_transcription(&settings, &segment.text);
+        }
+
+        // Engines without timings still get one cue for the whole recording
+        if transcript.segments.is_empty() && !transcript.text.is_empty() {
+            transcript.segments.push(TranscriptSegment {
+                start: 0.0,
+                end: duration,
+                text: transcript.text.clone(),
+            });
+        }
+        Ok(transcript)
+    }
+}
+
+/// Bundled resources: next to an installed binary, or the source tree.
+fn default_resource_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os("HANDY_RESOURCE_DIR") {
+        return PathBuf::from(dir);
+    }
+    if let Some(exe_dir) = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+    {
+        let candidates = [
+            exe_dir.join("resources"),
+            // macOS app bundle: Contents/MacOS/handy-cli
+            exe_dir.join("../Resources/resources"),
+        ];
+        if let Some(dir) = candidates.into_iter().find(|d| d.is_dir()) {
+            return dir;
+        }
+    }
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("resources")
+}
+
+/// Download progress and failures go to stderr so stdout stays clean.
+fn report_event(event: &str, payload: &serde_json::Value) {
+    match event {
+        "model-download-progress" => {
+            if let (Some(id), Some(pct)) =
+                (payload["model_id"].as_str(), payload["percentage"].as_f64())
+            {
+                eprint!("\r{}: {:5.1}%", id, pct);
+                if pct >= 100.0 {
+                    eprintln!();
+                }
+            }
+        }
+        "model-download-retry" => eprintln!(
+            "\n{}: retrying (attempt {} of {})",
+            payload["model_id"].as_str().unwrap_or("?"),
+            payload["attempt"],
+            payload["max_attempts"]
+        ),
+        "model-verification-failed" | "model-extraction-failed" => eprintln!(
+            "\n{}: {}",
+            payload["model_id"].as_str().unwrap_or("?"),
+            payload["error"].as_str().unwrap_or("failed")
+        ),
+        "model-extraction-started" => eprintln!("Extracting {}...", payload),
+        _ => log::debug!("event {}: {}", event, payload),
+    }
+}
+
+fn render_transcript(transcript: &Transcript, model_id: &str, format: &OutputFormat) -> String {
+    match format {
+        OutputFormat::Text => format!("{}\n", transcript.text),
+        OutputFormat::Json => {
+            let json = serde_json::json!({
+                "model": model_id,
+                "text": transcript.text,
+                "segments": transcript.segments,
+            });
+            format!(
+                "{}\n",
+                serde_json::to_string_pretty(&json).unwrap_or_default()
+            )
+        }
+        OutputFormat::Subtitles(format) => subtitles::render(&transcript.segments, *format),
+    }
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+/// Runs `source` through the recorder and returns 16 kHz samples once it
+/// runs out.
+fn record_to_end(
+    mut recorder: AudioRecorder,
+    source: Box<dyn AudioSource>,
+    dsp: DspConfig,
+) -> Result<Vec<f32>> {
+    recorder.open_source(source).map_err(boxed)?;
+    recorder.set_dsp(dsp).map_err(boxed)?;
+    recorder.start().map_err(boxed)?;
+    let samples = recorder.finish().map_err(boxed)?;
+    recorder.close().map_err(boxed)?;
+    Ok(samples)
+}
+
+fn cmd_transcribe(ctx: &Context, output: &Output, file: &Path) -> Result<()> {
+    let manager = ctx.start_transcription()?;
+    // No VAD: subtitle timings must line up with the original audio
+    let samples = record_to_end(
+        AudioRecorder::new().map_err(boxed)?,
+        Box::new(FileSource::new(file, Pacing::Unpaced)),
+        ctx.host.settings().dsp,
+    )
+    .with_context(|| format!("Failed to read {}", file.display()))?;
+
+    let transcript = ctx.transcribe(&manager, samples)?;
+    output.write(&render_transcript(
+        &transcript,
+        &ctx.model_id()?,
+        &output.format,
+    ))?;
+    Ok(())
+}
+
+fn cmd_dictate(ctx: &Context, output: &Output, args: &DictateArgs) -> Result<()> {
+    let silence = Duration::from_secs_f32(args.silence.max(0.1));
+    let manager = ctx.start_transcription()?;
+
+    let vad_path = ctx.host.resource_path(VAD_MODEL)?;
+    let settings = ctx.host.settings();
+    let dsp = settings.dsp;
+    let (vad, load_error) = build_vad(&vad_path, &settings.vad);
+    if let Some(e) = load_error {
+        eprintln!("{}; using the energy VAD instead", e);
+    }
+    let (auto_stop_tx, auto_stop_rx) = mpsc::channel();
+    let recorder = AudioRecorder::new()
+        .map_err(boxed)?
+        .with_vad(vad)
+        .with_auto_stop_callback(move |reason| {
+            let _ = auto_stop_tx.send(reason);
+        });
+
+    let samples = match args.input.as_deref() {
+        Some("-") => record_to_end(
+            recorder,
+            Box::new(PcmSource::stdin(args.rate, args.channels, args.pcm.into())),
+            dsp,
+        )?,
+        Some(file) => record_to_end(
+            recorder,
+            Box::new(FileSource::new(file, Pacing::Unpaced)),
+            dsp,
+        )?,
+        None => record_microphone(recorder, auto_stop_rx, silence, dsp)?,
+    };
+
+    let transcript = ctx.transcribe(&manager, samples)?;
+    output.write(&render_transcript(
+        &transcript,
+        &ctx.model_id()?,
+        &output.format,
+    ))?;
+    Ok(())
+}
+
+/// Why a dictation from the microphone ended.
+enum StopReason {
+    Enter,
+    Signal,
+    Auto(AutoStopReason),
+}
+
+impl StopReason {
+    fn describe(&self, silence: Duration) -> String {
+        match self {
+            StopReason::Enter => "Stopped".to_string(),
+            StopReason::Signal => "Interrupted; transcribing what was recorded".to_string(),
+            StopReason::Auto(AutoStopReason::TrailingSilence) => {
+                format!("Stopped after {:.1}s of silence", silence.as_secs_f32())
+            }
+            StopReason::Auto(AutoStopReason::MaxDuration) => {
+                "Stopped at the maximum recording length".to_string()
+            }
+            StopReason::Auto(AutoStopReason::NoSpeech) => "Stopped: no speech heard".to_string(),
+        }
+    }
+}
+
+/// Set by SIGINT and SIGTERM, so an interrupted dictation is still
+/// transcribed. A second signal exits straight away.
+#[cfg(unix)]
+fn interrupt_flag() -> Result<Arc<AtomicBool>> {
+    use signal_hook::consts::{SIGINT, SIGTERM};
+    use signal_hook::flag;
+
+    let interrupted = Arc::new(AtomicBool::new(false));
+    for signal in [SIGINT, SIGTERM] {
+        flag::register_conditional_shutdown(signal, 130, Arc::clone(&interrupted))?;
+        flag::register(signal, Arc::clone(&interrupted))?;
+    }
+    Ok(interrupted)
+}
+
+/// Ctrl-C ends the process here, as it always has.
+#[cfg(not(unix))]
+fn interrupt_flag() -> Result<Arc<AtomicBool>> {
+    Ok(Arc::new(AtomicBool::new(false)))
+}
+
+fn record_microphone(
+    mut recorder: AudioRecorder,
+    auto_stop_rx: mpsc::Receiver<AutoStopReason>,
+    silence: Duration,
+    dsp: DspConfig,
+) -> Result<Vec<f32>> {
+    let interrupted = interrupt_flag()?;
+    recorder.open(None).map_err(boxed)?;
+    recorder.set_dsp(dsp).map_err(boxed)?;
+    recorder
+        .set_auto_stop(Some(AutoStopConfig {
+            trailing_silence: Some(silence),
+            ..Default::default()
+        }))
+        .map_err(boxed)?;
+    recorder.start().map_err(boxed)?;
+
+    // Enter only means something from a terminal; scripts that pipe or close
+    // stdin wait for the silence or a signal instead
+    let (enter_tx, enter_rx) = mpsc::channel();
+    if std::io::stdin().is_terminal() {
+        eprintln!("Listening... press Enter to stop");
+        std::thread::spawn(move || {
+            let mut line = String::new();
+            if matches!(std::io::stdin().lock().read_line(&mut line), Ok(n) if n > 0) {
+                let _ = enter_tx.send(());
+            }
+        });
+    } else {
+        eprintln!("Listening... stops after silence, SIGINT or SIGTERM");
+    }
+
+    let poll = Duration::from_millis(100);
+    let reason = loop {
+        match enter_rx.recv_timeout(poll) {
+            Ok(()) => break StopReason::Enter,
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            // No terminal, or it closed
+            Err(mpsc::RecvTimeoutError::Disconnected) => std::thread::sleep(poll),
+        }
+        if interrupted.load(Ordering::Relaxed) {
+            break StopReason::Signal;
+        }
+        if let Ok(reason) = auto_stop_rx.try_recv() {
+            break StopReason::Auto(reason);
+        }
+    };
+    eprintln!("{}", reason.describe(silence));
+
+    let samples = recorder.stop().map_err(boxed)?;
+    recorder.close().map_err(boxed)?;
+    Ok(samples)
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+fn cmd_models(ctx: &Context, action: &ModelsCommand) -> Result<()> {
+    match action {
+        ModelsCommand::List { json } => {
+            let mut models = ctx.model_manager.get_available_models();
+            models.sort_by(|a, b| a.id.cmp(&b.id));
+            if *json {
+                println!("{}", serde_json::to_string_pretty(&models)?);
+                return Ok(());
+            }
+            let selected = ctx.host.settings().selected_model;
+            for model in models {
+                println!(
+                    "{} {:<28} {:>6} MB  {}{}",
+                    if model.id == selected { "*" } else { " " },
+                    model.id,
+                    model.size_mb,
+                    model.name,
+                    if model.is_downloaded {
+                        ""
+                    } else {
+                        "  (not installed)"
+                    }
+                );
+            }
+            Ok(())
+        }
+        ModelsCommand::Download { ids } => {
+            let runtime = tokio::runtime::Runtime::new()?;
+            for id in ids {
+                runtime
+                    .block_on(ctx.model_manager.download_model(id))
+                    .with_context(|| format!("Failed to download {}", id))?;
+                eprintln!("{}: installed", id);
+            }
+            Ok(())
+        }
+        ModelsCommand::Verify { ids, json } => {
+            let ids: Vec<String> = if ids.is_empty() {
+                ctx.model_manager
+                    .get_available_models()
+                    .into_iter()
+                    .filter(|m| m.is_downloaded)
+                    .map(|m| m.id)
+                    .collect()
+            } else {
+                ids.to_vec()
+            };
+
+            let mut failed = false;
+            for id in ids {
+                let result = ctx.model_manager.verify_model(&id)?;
+                failed |= result.status == VerificationStatus::Mismatch;
+                if *json {
+                    println!("{}", serde_json::to_string(&result)?);
+                } else {
+                    println!(
+                        "{}: {:?}{}",
+                        result.model_id,
+                        result.status,
+                        result
+                            .detail
+                            .map(|d| format!(" ({})", d))
+                            .unwrap_or_default()
+                    );
+                }
+            }
+            if failed {
+                return Err(anyhow!("One or more models failed verification"));
+            }
+            Ok(())
+        }
+    }
+}
+
+fn cmd_vocab(ctx: &Context, action: &VocabCommand) -> Result<()> {
+    let VocabCommand::Check { text, file } = action;
+
+    let mut problems = 0;
+    let path = file
+        .clone()
+        .or_else(MedicalVocabulary::get_default_custom_vocab_path);
+    match &path {
+        Some(path) if path.exists() => {
+            let contents = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            for issue in check_custom_vocab_txt(&contents) {
+                println!("{}:{}: {}", path.display(), issue.line, issue.message);
+                problems += 1;
+            }
+        }
+        Some(path) if file.is_some() => {
+            return Err(anyhow!("{} does not exist", path.display()));
+        }
+        _ => eprintln!("No custom vocabulary file; checking built-in terms only"),
+    }
+
+    let settings = ctx.host.settings();
+    if let Err(e) = validate_custom_words(&settings.custom_words) {
+        println!("custom words: {}", e);
+        problems += 1;
+    }
+
+    if !text.is_empty() {
+        let mut vocab = match file {
+            Some(path) => MedicalVocabulary::with_custom_vocab(path.clone()),
+            None => MedicalVocabulary::new(),
+        };
+        let normalized = finalize_text(&text.join(" "), &settings);
+        println!("{}", vocab.process_text(&normalized));
+    }
+
+    if problems > 0 {
+        return Err(anyhow!("{} problem(s) found", problems));
+    }
+    Ok(())
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+struct StderrLogger;
+
+impl log::Log for StderrLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("[{}] {}", record.level(), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+fn run(cli: Cli) -> Result<()> {
+    if cli.verbose {
+        let _ = log::set_logger(&StderrLogger);
+        log::set_max_level(log::LevelFilter::Debug);
+    }
+
+    let ctx = Context::open(cli.data_dir, cli.resources, cli.model)?;
+    let output = Output {
+        format: cli.format,
+        path: cli.output,
+    };
+    match &cli.command {
+        Command::Transcribe { file } => cmd_transcribe(&ctx, &output, file),
+        Command::Dictate(args) => cmd_dictate(&ctx, &output, args),
+        Command::Models { action } => cmd_models(&ctx, action),
+        Command::Vocab { action } => cmd_vocab(&ctx, action),
+    }
+}
+
+fn main() {
+    // Usage errors exit with 2, help and --version with 0
+    let cli = Cli::try_parse().unwrap_or_else(|e| e.exit());
+    let code = match run(cli) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("handy-cli: {:#}", e);
+            EXIT_FAILURE
+        }
+    };
+    let _ = std::io::stdout().flush();
+
+    // Skip destructors: the model watcher thread would otherwise hold up
+    // exit for up to its polling interval
+    std::process::exit(code);
+}
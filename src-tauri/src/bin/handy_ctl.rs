@@ -0,0 +1,324 @@
+//! Sends commands to the running desktop app over its control API, for
+//! scripts, hotkey daemons and foot pedals. With the control API off, the
+//! recording commands fall back to signals on Unix.
+
+use base64::Engine as _;
+use clap::{Parser, Subcommand};
+use handy_app_lib::host::default_app_data_dir;
+use handy_app_lib::ipc::protocol::UNAUTHORIZED;
+use handy_app_lib::ipc::{ClientError, Endpoint, IpcClient};
+use serde_json::{json, Value};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const EXIT_CODES: &str = "\
+Exit codes:
+  0  Done
+  1  Handy refused or failed the command
+  2  Usage error
+  3  Handy isn't running, or its control API is turned off
+  4  The control API rejected the token
+  5  Nothing to do: start while recording, or stop while idle
+
+With the control API turned off, start, toggle and ping still work on Unix
+(and stop and cancel on Linux) by signalling the app, for the transcribe
+binding only. Signals can't report back, so start and stop then never exit 5.";
+
+const EXIT_FAILURE: i32 = 1;
+const EXIT_USAGE: i32 = 2;
+const EXIT_NOT_RUNNING: i32 = 3;
+const EXIT_UNAUTHORIZED: i32 = 4;
+const EXIT_UNCHANGED: i32 = 5;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+enum CtlError {
+    NotRunning(String),
+    Client(ClientError),
+    Failed(String),
+}
+
+impl From<ClientError> for CtlError {
+    fn from(e: ClientError) -> Self {
+        match e {
+            ClientError::Io(e)
+                if matches!(
+                    e.kind(),
+                    std::io::ErrorKind::NotFound | std::io::ErrorKind::ConnectionRefused
+                ) =>
+            {
+                CtlError::NotRunning(format!("Handy isn't running ({})", e))
+            }
+            e => CtlError::Client(e),
+        }
+    }
+}
+
+impl From<std::io::Error> for CtlError {
+    fn from(e: std::io::Error) -> Self {
+        CtlError::Failed(e.to_string())
+    }
+}
+
+type CtlResult<T = i32> = Result<T, CtlError>;
+
+/* ──────────────────────────────────────────────────────────────── */
+
+#[derive(Parser)]
+#[command(name = "handy-ctl", version, about, long_about = None, after_help = EXIT_CODES)]
+struct Cli {
+    /// Give up waiting for a reply; 0 waits forever (default 10, none for
+    /// transcribe and watch)
+    #[arg(long, global = true, value_name = "SECS", value_parser = parse_timeout)]
+    timeout: Option<f32>,
+    /// App data directory (default: the desktop app's)
+    #[arg(long, global = true, value_name = "DIR")]
+    data_dir: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Start recording (default binding: transcribe)
+    Start { binding: Option<String> },
+    /// Stop recording and transcribe
+    Stop { binding: Option<String> },
+    /// Start if idle, stop if recording
+    Toggle { binding: Option<String> },
+    /// Discard the recording in progress
+    Cancel,
+    /// Print recording, queue and model state as JSON
+    State,
+    /// Transcribe a WAV file with the running app
+    Transcribe {
+        #[arg(value_name = "FILE|-")]
+        file: String,
+        /// Also send the text to the binding's outputs, as if it had been
+        /// dictated
+        #[arg(long, value_name = "BINDING")]
+        deliver: Option<String>,
+    },
+    /// List models, or switch to a downloaded one
+    Models {
+        #[command(subcommand)]
+        action: ModelsCommand,
+    },
+    /// Print app events as JSON lines until interrupted
+    Watch {
+        #[arg(value_name = "EVENT")]
+        events: Vec<String>,
+    },
+    /// Check that Handy is running and reachable
+    Ping,
+}
+
+#[derive(Subcommand)]
+enum ModelsCommand {
+    /// List models as JSON
+    List,
+    /// Switch to a downloaded model
+    Select { id: String },
+}
+
+fn parse_timeout(secs: &str) -> Result<f32, String> {
+    secs.parse()
+        .ok()
+        .filter(|s: &f32| s.is_finite() && *s >= 0.0)
+        .ok_or_else(|| "expected a number of seconds".to_string())
+}
+
+/// Connects to the instance that owns `data_dir`.
+fn connect(data_dir: &Path) -> CtlResult<IpcClient> {
+    let (endpoint, token) = Endpoint::discover(data_dir).map_err(|_| {
+        CtlError::NotRunning(
+            "Handy isn't running, or its control API is turned off in the settings".to_string(),
+        )
+    })?;
+    Ok(IpcClient::connect(&endpoint, &token)?)
+}
+
+/// Sends `command` to the app as a signal, for when the control API is off.
+/// Gives back `unreachable` for commands that need the API.
+#[cfg(unix)]
+fn signal_app(data_dir: &Path, command: &Command, unreachable: CtlError) -> CtlResult {
+    use handy_app_lib::signal_handle;
+
+    // Only the transcribe binding has signals of its own
+    let transcribe_only =
+        |binding: &Option<String>| binding.as_deref().unwrap_or("transcribe") == "transcribe";
+    let signal = match command {
+        Command::Start { binding } if transcribe_only(binding) => Some(signal_handle::SIGNAL_START),
+        Command::Stop { binding } if transcribe_only(binding) => signal_handle::stop_signal(),
+        Command::Toggle { binding } if transcribe_only(binding) => {
+            Some(signal_handle::SIGNAL_TOGGLE)
+        }
+        Command::Cancel => signal_handle::cancel_signal(),
+        // Signal 0 only checks that the process is there
+        Command::Ping => Some(0),
+        _ => None,
+    };
+    let Some(signal) = signal else {
+        return Err(unreachable);
+    };
+    let Some(pid) = signal_handle::running_pid(data_dir)? else {
+        return Err(CtlError::NotRunning("Handy isn't running".to_string()));
+    };
+    if unsafe { libc::kill(pid, signal) } != 0 {
+        return Err(CtlError::Failed(format!(
+            "Failed to signal Handy (pid {}): {}",
+            pid,
+            std::io::Error::last_os_error()
+        )));
+    }
+    if matches!(command, Command::Toggle { .. }) {
+        // The app doesn't say which way it went
+        println!("toggled");
+    }
+    Ok(0)
+}
+
+#[cfg(not(unix))]
+fn signal_app(_: &Path, _: &Command, unreachable: CtlError) -> CtlResult {
+    Err(unreachable)
+}
+
+fn print_json(value: &Value) -> CtlResult {
+    let mut stdout = std::io::stdout().lock();
+    writeln!(
+        stdout,
+        "{}",
+        serde_json::to_string_pretty(value).unwrap_or_default()
+    )?;
+    Ok(0)
+}
+
+/// Exit code for a start or stop that reports whether anything changed.
+fn changed(result: Value) -> i32 {
+    if result == Value::Bool(true) {
+        0
+    } else {
+        EXIT_UNCHANGED
+    }
+}
+
+fn binding_param(binding: &Option<String>) -> Value {
+    match binding {
+        Some(binding) => json!({ "binding": binding }),
+        None => json!({}),
+    }
+}
+
+fn read_audio(path: &str) -> CtlResult<Vec<u8>> {
+    if path == "-" {
+        let mut bytes = Vec::new();
+        std::io::stdin().lock().read_to_end(&mut bytes)?;
+        return Ok(bytes);
+    }
+    std::fs::read(path).map_err(|e| CtlError::Failed(format!("Failed to read {}: {}", path, e)))
+}
+
+fn run(cli: Cli) -> CtlResult {
+    // Read any audio before touching the app
+    let (method, params) = match &cli.command {
+        Command::Start { binding } => ("recording.start", binding_param(binding)),
+        Command::Stop { binding } => ("recording.stop", binding_param(binding)),
+        Command::Toggle { binding } => ("recording.toggle", binding_param(binding)),
+        Command::Cancel => ("recording.cancel", Value::Null),
+        Command::State | Command::Ping => ("state", Value::Null),
+        Command::Transcribe { file, deliver } => {
+            let wav = base64::engine::general_purpose::STANDARD.encode(read_audio(file)?);
+            let mut params = json!({ "wav": wav });
+            if let Some(binding) = deliver {
+                params["deliver"] = json!(binding);
+            }
+            ("transcribe", params)
+        }
+        Command::Models {
+            action: ModelsCommand::List,
+        } => ("models.list", Value::Null),
+        Command::Models {
+            action: ModelsCommand::Select { id },
+        } => ("models.select", json!({ "id": id })),
+        Command::Watch { events } => ("events.subscribe", json!({ "events": events })),
+    };
+
+    let data_dir = cli
+        .data_dir
+        .or_else(default_app_data_dir)
+        .ok_or_else(|| CtlError::Failed("Can't find the app data directory".to_string()))?;
+    let mut client = match connect(&data_dir) {
+        Ok(client) => client,
+        Err(e @ CtlError::NotRunning(_)) => return signal_app(&data_dir, &cli.command, e),
+        Err(e) => return Err(e),
+    };
+    let timeout = match cli.timeout {
+        Some(secs) => (secs > 0.0).then(|| Duration::from_secs_f32(secs)),
+        None if matches!(
+            cli.command,
+            Command::Transcribe { .. } | Command::Watch { .. }
+        ) =>
+        {
+            None
+        }
+        None => Some(DEFAULT_TIMEOUT),
+    };
+    client.set_timeout(timeout)?;
+
+    let result = client.call(method, params)?;
+    match cli.command {
+        Command::Start { .. } | Command::Stop { .. } => Ok(changed(result)),
+        Command::Toggle { .. } => {
+            let recording = result == Value::Bool(true);
+            println!("{}", if recording { "recording" } else { "stopped" });
+            Ok(0)
+        }
+        Command::Cancel | Command::Ping => Ok(0),
+        Command::Transcribe { .. } => {
+            println!("{}", result["text"].as_str().unwrap_or_default());
+            Ok(0)
+        }
+        Command::Watch { .. } => loop {
+            let event = client.next_event()?;
+            let line = json!({ "event": event.event, "payload": event.payload });
+            let mut stdout = std::io::stdout().lock();
+            writeln!(stdout, "{}", line)?;
+            stdout.flush()?;
+        },
+        Command::State | Command::Models { .. } => print_json(&result),
+    }
+}
+
+fn main() {
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        // Help and --version go to stdout and exit 0
+        Err(e) if !e.use_stderr() => e.exit(),
+        Err(e) => {
+            let _ = e.print();
+            std::process::exit(EXIT_USAGE);
+        }
+    };
+    let code = match run(cli) {
+        Ok(code) => code,
+        Err(CtlError::NotRunning(message)) => {
+            eprintln!("handy-ctl: {}", message);
+            EXIT_NOT_RUNNING
+        }
+        Err(CtlError::Client(ClientError::Rpc(e))) if e.code == UNAUTHORIZED => {
+            eprintln!("handy-ctl: {}", e.message);
+            EXIT_UNAUTHORIZED
+        }
+        Err(CtlError::Client(e)) => {
+            eprintln!("handy-ctl: {}", e);
+            EXIT_FAILURE
+        }
+        Err(CtlError::Failed(message)) => {
+            eprintln!("handy-ctl: {}", message);
+            EXIT_FAILURE
+        }
+    };
+    let _ = std::io::stdout().flush();
+    std::process::exit(code);
+}
@@ -1,140 +1,432 @@
+use crate::helpers::clipboard_store::{
+    restore_unless_changed, ClipboardProvider, ClipboardSnapshot, SystemClipboard,
+};
+use crate::helpers::paste_timing::PasteTiming;
+use crate::helpers::screen_lock;
 use crate::input::{self, EnigoState};
-use crate::settings::{get_settings, ClipboardHandling, PasteMethod};
+use crate::settings::{get_settings, ClipboardExpiry, ClipboardHandling, PasteMethod, PasteTool};
 use enigo::Enigo;
-use log::info;
+use log::{debug, info, warn};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Manager};
-use tauri_plugin_clipboard_manager::ClipboardExt;
 
+#[cfg(target_os = "linux")]
+use crate::helpers::selection::{ClipboardOffer, ReadOutcome};
 #[cfg(target_os = "linux")]
 use crate::utils::is_wayland;
 #[cfg(target_os = "linux")]
-use std::process::Command;
+use std::io::Write;
+#[cfg(target_os = "linux")]
+use std::process::{Command, Stdio};
+
+/// Time for the clipboard write to reach other applications before the keystroke
+const CLIPBOARD_SETTLE: Duration = Duration::from_millis(50);
+
+/// Learnt from reads we can see, which is only on Linux; elsewhere the
+/// restore delay stays at its size-based default.
+static PASTE_TIMING: Lazy<Mutex<PasteTiming>> = Lazy::new(Default::default);
+
+/// How often a pending clipboard expiry checks the clock and the screen lock
+const EXPIRY_POLL: Duration = Duration::from_secs(2);
+/// Polls between checks that the clipboard still holds the copied text
+const OWNERSHIP_CHECK_TICKS: u32 = 15;
+
+/// Text we left on the clipboard with `CopyToClipboard`, to clear later
+struct ExpiringCopy {
+    text: String,
+    /// None when the text only goes on screen lock or quit
+    expires: Option<Instant>,
+}
+
+static EXPIRING_COPY: Lazy<Mutex<Option<ExpiringCopy>>> = Lazy::new(Default::default);
 
-/// Pastes text using the clipboard: saves current content, writes text, sends paste keystroke, restores clipboard.
+/// Why a clipboard paste failed.
+#[derive(Debug, PartialEq, Eq)]
+enum PasteError {
+    /// The keystroke never reached the target, so typing the text instead
+    /// can't paste it twice
+    NotSent(String),
+    /// The keystroke went out but the target didn't read the clipboard in
+    /// time; it may still act on it. Only Linux can tell
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    Unread(String),
+}
+
+impl std::fmt::Display for PasteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PasteError::NotSent(e) | PasteError::Unread(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// Pastes text using the clipboard: saves current content, writes text, sends
+/// paste keystroke, restores clipboard once the target has read it.
+///
+/// Every format on the clipboard is saved, not just text, so a copied image
+/// or file list survives the paste; an empty clipboard is left empty.
+///
+/// Fails if the keystroke can't be sent or, where we can tell, the target
+/// never read the clipboard; in both cases nothing was pasted and the
+/// previous contents are put straight back.
 fn paste_via_clipboard(
-    enigo: &mut Enigo,
+    clipboard: &mut impl ClipboardProvider,
     text: &str,
-    app_handle: &AppHandle,
-    paste_method: &PasteMethod,
-) -> Result<(), String> {
-    // Check for Wayland first
+    send_paste: impl FnOnce() -> Result<(), String>,
+) -> Result<(), PasteError> {
+    let saved = clipboard.snapshot().unwrap_or_else(|e| {
+        warn!("{}; only text will be restored", e);
+        let text = clipboard.read_text().ok().flatten();
+        ClipboardSnapshot {
+            formats: text
+                .map(|t| vec![("text/plain;charset=utf-8".to_string(), t.into_bytes())])
+                .unwrap_or_default(),
+        }
+    });
+
+    // On Linux we can see the target fetch the text, so the old contents go
+    // back as soon as it has, and never before
     #[cfg(target_os = "linux")]
-    if try_wayland_send_paste(paste_method)? {
-        return Ok(());
+    if let Some(offer) = clipboard.offer(text) {
+        return paste_with_offer(clipboard, offer, text, &saved, send_paste);
     }
 
-    let clipboard = app_handle.clipboard();
-    let clipboard_content = clipboard.read_text().unwrap_or_default();
+    clipboard.write_text(text).map_err(PasteError::NotSent)?;
 
-    clipboard
-        .write_text(text)
-        .map_err(|e| format!("Failed to write to clipboard: {}", e))?;
+    std::thread::sleep(CLIPBOARD_SETTLE);
+    if let Err(e) = send_paste() {
+        restore_clipboard(clipboard, text, &saved);
+        return Err(PasteError::NotSent(e));
+    }
 
-    std::thread::sleep(std::time::Duration::from_millis(50));
+    let delay = PASTE_TIMING.lock().restore_delay(text.len());
+    std::thread::sleep(delay);
+    restore_clipboard(clipboard, text, &saved);
 
-    match paste_method {
-        PasteMethod::CtrlV => input::send_paste_ctrl_v(enigo)?,
-        PasteMethod::CtrlShiftV => input::send_paste_ctrl_shift_v(enigo)?,
-        PasteMethod::ShiftInsert => input::send_paste_shift_insert(enigo)?,
-        _ => return Err("Invalid paste method for clipboard paste".into()),
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn paste_with_offer(
+    clipboard: &mut impl ClipboardProvider,
+    mut offer: ClipboardOffer,
+    text: &str,
+    saved: &ClipboardSnapshot,
+    send_paste: impl FnOnce() -> Result<(), String>,
+) -> Result<(), PasteError> {
+    let sent = Instant::now();
+    if let Err(e) = send_paste() {
+        drop(offer);
+        write_clipboard(clipboard, saved);
+        return Err(PasteError::NotSent(e));
+    }
+
+    let timeout = PASTE_TIMING.lock().read_timeout();
+    match offer.wait_for_read(sent, timeout) {
+        ReadOutcome::Read(latency) => {
+            debug!("Target read the clipboard after {:?}", latency);
+            PASTE_TIMING.lock().record_read(latency);
+            drop(offer);
+            write_clipboard(clipboard, saved);
+            Ok(())
+        }
+        ReadOutcome::Lost => {
+            // A clipboard manager is serving our text now; fall back to timing
+            drop(offer);
+            let delay = PASTE_TIMING.lock().restore_delay(text.len());
+            std::thread::sleep(delay.saturating_sub(sent.elapsed()));
+            restore_clipboard(clipboard, text, saved);
+            Ok(())
+        }
+        ReadOutcome::TimedOut => {
+            drop(offer);
+            write_clipboard(clipboard, saved);
+            Err(PasteError::Unread(format!(
+                "The target application didn't read the clipboard within {:?}",
+                timeout
+            )))
+        }
     }
+}
 
-    std::thread::sleep(std::time::Duration::from_millis(50));
+/// Puts the previous contents back, unless something else was copied since.
+fn restore_clipboard(
+    clipboard: &mut impl ClipboardProvider,
+    text: &str,
+    saved: &ClipboardSnapshot,
+) {
+    // The paste itself went through, so this isn't worth failing over
+    match restore_unless_changed(clipboard, text, saved) {
+        Ok(true) => {}
+        Ok(false) => debug!("Clipboard changed while pasting, leaving it alone"),
+        Err(e) => warn!("{}", e),
+    }
+}
 
-    clipboard
-        .write_text(&clipboard_content)
-        .map_err(|e| format!("Failed to restore clipboard: {}", e))?;
+#[cfg(target_os = "linux")]
+fn write_clipboard(clipboard: &mut impl ClipboardProvider, saved: &ClipboardSnapshot) {
+    if let Err(e) = clipboard.restore(saved) {
+        warn!("{}", e);
+    }
+}
 
+/* ──────────────────────────────────────────────────────────────── */
+
+/// Leaves `text` on the clipboard for the user, flagged as sensitive, and
+/// arranges for it to be cleared once it expires, the screen locks or Handy
+/// quits, whichever comes first.
+fn copy_to_clipboard(app: &AppHandle, text: &str, expiry: ClipboardExpiry) -> Result<(), String> {
+    SystemClipboard::new(app).write_sensitive(text)?;
+
+    let copy = ExpiringCopy {
+        text: text.to_string(),
+        expires: expiry
+            .to_seconds()
+            .map(|secs| Instant::now() + Duration::from_secs(secs)),
+    };
+    if EXPIRING_COPY.lock().replace(copy).is_none() {
+        let app = app.clone();
+        let spawned = std::thread::Builder::new()
+            .name("clipboard-expiry".to_string())
+            .spawn(move || watch_expiring_copy(&app));
+        if let Err(e) = spawned {
+            warn!("Failed to start clipboard expiry: {}", e);
+        }
+    }
     Ok(())
 }
 
-/// Attempts to paste using Wayland-specific tools (`wtype` or `dotool`).
-/// Returns `Ok(true)` if a Wayland tool handled the paste, `Ok(false)` if not applicable,
-/// or `Err` on failure from the underlying tool.
-#[cfg(target_os = "linux")]
-fn try_wayland_send_paste(paste_method: &PasteMethod) -> Result<bool, String> {
-    if is_wayland() {
-        if is_wtype_available() {
-            send_paste_via_wtype(paste_method)?;
-            return Ok(true);
-        } else if is_dotool_available() {
-            send_paste_via_dotool(paste_method)?;
-            return Ok(true);
+/// Waits for the copied text to expire or the screen to lock, then clears
+/// it. Ends early once something else has been copied.
+fn watch_expiring_copy(app: &AppHandle) {
+    let mut ticks = 0u32;
+    loop {
+        std::thread::sleep(EXPIRY_POLL);
+        ticks += 1;
+
+        let mut pending = EXPIRING_COPY.lock();
+        let Some(copy) = pending.as_ref() else {
+            return;
+        };
+
+        let expired = copy.expires.is_some_and(|at| Instant::now() >= at);
+        if expired || screen_lock::is_screen_locked() {
+            if let Some(copy) = pending.take() {
+                drop(pending);
+                debug!(
+                    "Clearing copied transcript ({})",
+                    if expired { "expired" } else { "screen locked" }
+                );
+                clear_copy(&mut SystemClipboard::new(app), &copy.text);
+            }
+            return;
+        }
+
+        if ticks % OWNERSHIP_CHECK_TICKS == 0 {
+            let current = SystemClipboard::new(app).read_text().ok().flatten();
+            if current.as_deref() != Some(copy.text.as_str()) {
+                pending.take();
+                return;
+            }
         }
     }
+}
 
-    Ok(false)
+/// Clears text left on the clipboard by `CopyToClipboard` straight away,
+/// for when Handy quits.
+pub fn clear_expiring_copy(app: &AppHandle) {
+    let copy = EXPIRING_COPY.lock().take();
+    if let Some(copy) = copy {
+        clear_copy(&mut SystemClipboard::new(app), &copy.text);
+    }
 }
 
-/// Check if wtype is available (Wayland text input tool)
-#[cfg(target_os = "linux")]
-fn is_wtype_available() -> bool {
-    Command::new("which")
-        .arg("wtype")
-        .output()
-        .map(|output| output.status.success())
-        .unwrap_or(false)
+/// Empties the clipboard if it still holds `text`; anything copied since,
+/// text or not, stays.
+fn clear_copy(clipboard: &mut impl ClipboardProvider, text: &str) {
+    let empty = ClipboardSnapshot::default();
+    match restore_unless_changed(clipboard, text, &empty) {
+        Ok(true) => {}
+        Ok(false) => debug!("Clipboard changed since copying, leaving it alone"),
+        Err(e) => warn!("Failed to clear copied transcript: {}", e),
+    }
+}
+
+/// Resolves `Auto` to the tool that suits this session.
+fn resolve_paste_tool(tool: PasteTool) -> PasteTool {
+    if tool != PasteTool::Auto {
+        return tool;
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let candidates: &[(&str, PasteTool)] = if is_wayland() {
+            &[
+                ("wtype", PasteTool::Wtype),
+                ("dotool", PasteTool::Dotool),
+                ("ydotool", PasteTool::Ydotool),
+            ]
+        } else {
+            &[("xdotool", PasteTool::Xdotool)]
+        };
+        for &(program, tool) in candidates {
+            if is_tool_available(program) {
+                return tool;
+            }
+        }
+    }
+
+    PasteTool::Enigo
 }
 
-/// Check if dotool is available (another Wayland text input tool)
+/// Sends the keystroke for `paste_method` with `tool`.
+fn send_paste_keystroke(
+    enigo: &mut Enigo,
+    paste_method: &PasteMethod,
+    tool: PasteTool,
+) -> Result<(), String> {
+    match tool {
+        #[cfg(target_os = "linux")]
+        PasteTool::Wtype => send_paste_via_wtype(paste_method),
+        #[cfg(target_os = "linux")]
+        PasteTool::Dotool => send_paste_via_dotool(paste_method),
+        #[cfg(target_os = "linux")]
+        PasteTool::Xdotool => send_paste_via_xdotool(paste_method),
+        #[cfg(target_os = "linux")]
+        PasteTool::Ydotool => send_paste_via_ydotool(paste_method),
+        _ => match paste_method {
+            PasteMethod::CtrlV => input::send_paste_ctrl_v(enigo),
+            PasteMethod::CtrlShiftV => input::send_paste_ctrl_shift_v(enigo),
+            PasteMethod::ShiftInsert => input::send_paste_shift_insert(enigo),
+            _ => Err("Invalid paste method for clipboard paste".into()),
+        },
+    }
+}
+
+/// Types `text` character by character with `tool`.
+fn type_text(enigo: &mut Enigo, text: &str, tool: PasteTool) -> Result<(), String> {
+    match tool {
+        #[cfg(target_os = "linux")]
+        PasteTool::Wtype => run_tool("wtype", &["--", text], None),
+        #[cfg(target_os = "linux")]
+        PasteTool::Dotool => {
+            // dotool reads one command per line
+            let script: Vec<String> = text
+                .split('\n')
+                .map(|line| format!("type {}", line))
+                .collect();
+            run_tool("dotool", &[], Some(&(script.join("\nkey enter\n") + "\n")))
+        }
+        #[cfg(target_os = "linux")]
+        PasteTool::Xdotool => run_tool(
+            "xdotool",
+            &["type", "--clearmodifiers", "--delay", "5", "--", text],
+            None,
+        ),
+        #[cfg(target_os = "linux")]
+        PasteTool::Ydotool => run_tool("ydotool", &["type", "--", text], None),
+        _ => input::paste_text_direct(enigo, text),
+    }
+}
+
+/// Check if a command-line input tool is installed
 #[cfg(target_os = "linux")]
-fn is_dotool_available() -> bool {
+fn is_tool_available(program: &str) -> bool {
     Command::new("which")
-        .arg("dotool")
+        .arg(program)
         .output()
         .map(|output| output.status.success())
         .unwrap_or(false)
 }
 
-/// Paste using wtype and return a friendly error on failure.
+/// Runs an input tool, optionally feeding it `stdin`, and returns a friendly
+/// error on failure.
 #[cfg(target_os = "linux")]
-fn send_paste_via_wtype(paste_method: &PasteMethod) -> Result<(), String> {
-    let args: Vec<&str> = match paste_method {
-        PasteMethod::CtrlV => vec!["-M", "ctrl", "-k", "v"],
-        PasteMethod::ShiftInsert => vec!["-M", "shift", "-k", "Insert"],
-        PasteMethod::CtrlShiftV => vec!["-M", "ctrl", "-M", "shift", "-k", "v"],
-        _ => return Err("Unsupported paste method".into()),
-    };
+fn run_tool(program: &str, args: &[&str], stdin: Option<&str>) -> Result<(), String> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to execute {}: {}", program, e))?;
 
-    let output = Command::new("wtype")
-        .args(&args)
-        .output()
-        .map_err(|e| format!("Failed to execute wtype: {}", e))?;
+    if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        pipe.write_all(input.as_bytes())
+            .map_err(|e| format!("Failed to write to {}: {}", program, e))?;
+    }
 
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Failed to execute {}: {}", program, e))?;
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("wtype failed: {}", stderr));
+        return Err(format!("{} failed: {}", program, stderr));
     }
 
     Ok(())
 }
 
+/// Paste using wtype and return a friendly error on failure.
+#[cfg(target_os = "linux")]
+fn send_paste_via_wtype(paste_method: &PasteMethod) -> Result<(), String> {
+    let args: &[&str] = match paste_method {
+        PasteMethod::CtrlV => &["-M", "ctrl", "-k", "v"],
+        PasteMethod::ShiftInsert => &["-M", "shift", "-k", "Insert"],
+        PasteMethod::CtrlShiftV => &["-M", "ctrl", "-M", "shift", "-k", "v"],
+        _ => return Err("Unsupported paste method".into()),
+    };
+    run_tool("wtype", args, None)
+}
+
 /// Paste using dotool and return a friendly error on failure.
 #[cfg(target_os = "linux")]
 fn send_paste_via_dotool(paste_method: &PasteMethod) -> Result<(), String> {
-    let command;
-    match paste_method {
-        PasteMethod::CtrlV => command = "echo key ctrl+v | dotool",
-        PasteMethod::ShiftInsert => command = "echo key shift+insert | dotool",
-        PasteMethod::CtrlShiftV => command = "echo key ctrl+shift+v | dotool",
+    let command = match paste_method {
+        PasteMethod::CtrlV => "key ctrl+v",
+        PasteMethod::ShiftInsert => "key shift+insert",
+        PasteMethod::CtrlShiftV => "key ctrl+shift+v",
         _ => return Err("Unsupported paste method".into()),
-    }
-    let output = Command::new("sh")
-        .arg("-c")
-        .arg(command)
-        .output()
-        .map_err(|e| format!("Failed to execute dotool: {}", e))?;
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("dotool failed: {}", stderr));
-    }
+    };
+    run_tool("dotool", &[], Some(&format!("{}\n", command)))
+}
 
-    Ok(())
+/// Paste using xdotool (X11) and return a friendly error on failure.
+#[cfg(target_os = "linux")]
+fn send_paste_via_xdotool(paste_method: &PasteMethod) -> Result<(), String> {
+    let keys = match paste_method {
+        PasteMethod::CtrlV => "ctrl+v",
+        PasteMethod::ShiftInsert => "shift+Insert",
+        PasteMethod::CtrlShiftV => "ctrl+shift+v",
+        _ => return Err("Unsupported paste method".into()),
+    };
+    run_tool("xdotool", &["key", "--clearmodifiers", keys], None)
+}
+
+/// Paste using ydotool (uinput, works on X11 and Wayland) and return a
+/// friendly error on failure. ydotool takes Linux key codes.
+#[cfg(target_os = "linux")]
+fn send_paste_via_ydotool(paste_method: &PasteMethod) -> Result<(), String> {
+    // KEY_LEFTCTRL 29, KEY_LEFTSHIFT 42, KEY_V 47, KEY_INSERT 110
+    let args: &[&str] = match paste_method {
+        PasteMethod::CtrlV => &["key", "29:1", "47:1", "47:0", "29:0"],
+        PasteMethod::ShiftInsert => &["key", "42:1", "110:1", "110:0", "42:0"],
+        PasteMethod::CtrlShiftV => &["key", "29:1", "42:1", "47:1", "47:0", "42:0", "29:0"],
+        _ => return Err("Unsupported paste method".into()),
+    };
+    run_tool("ydotool", args, None)
 }
 
 pub fn paste(text: String, app_handle: AppHandle) -> Result<(), String> {
     let settings = get_settings(&app_handle);
     let paste_method = settings.paste_method;
+    let tool = resolve_paste_tool(settings.paste_tool);
 
     // Append trailing space if setting is enabled
     let text = if settings.append_trailing_space {
@@ -143,7 +435,7 @@ pub fn paste(text: String, app_handle: AppHandle) -> Result<(), String> {
         text
     };
 
-    info!("Using paste method: {:?}", paste_method);
+    info!("Using paste method: {:?} via {:?}", paste_method, tool);
 
     // Get the managed Enigo instance, or initialize it lazily if not yet available
     // This handles the case where accessibility permissions were granted after app startup
@@ -170,19 +462,93 @@ pub fn paste(text: String, app_handle: AppHandle) -> Result<(), String> {
         PasteMethod::None => {
             info!("PasteMethod::None selected - skipping paste action");
         }
-        PasteMethod::Direct => input::paste_text_direct(&mut enigo, &text)?,
+        PasteMethod::Direct => type_text(&mut enigo, &text, tool)?,
         PasteMethod::CtrlV | PasteMethod::CtrlShiftV | PasteMethod::ShiftInsert => {
-            paste_via_clipboard(&mut enigo, &text, &app_handle, &paste_method)?
+            let mut clipboard = SystemClipboard::new(&app_handle);
+            let pasted = paste_via_clipboard(&mut clipboard, &text, || {
+                send_paste_keystroke(&mut enigo, &paste_method, tool)
+            });
+            match pasted {
+                Ok(()) => {}
+                // Typing after a keystroke the target may yet act on could
+                // enter the text twice
+                Err(PasteError::NotSent(e)) if settings.paste_fallback_to_typing => {
+                    warn!("Paste failed, typing the text instead: {}", e);
+                    type_text(&mut enigo, &text, tool)?;
+                }
+                Err(e) => return Err(e.to_string()),
+            }
         }
     }
 
     // After pasting, optionally copy to clipboard based on settings
     if settings.clipboard_handling == ClipboardHandling::CopyToClipboard {
-        let clipboard = app_handle.clipboard();
-        clipboard
-            .write_text(&text)
+        copy_to_clipboard(&app_handle, &text, settings.clipboard_expiry)
             .map_err(|e| format!("Failed to copy to clipboard: {}", e))?;
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::clipboard_store::mock::{copied_image, MockClipboard};
+
+    #[test]
+    fn test_every_format_survives_a_paste() {
+        let mut clipboard = copied_image();
+        let before = clipboard.formats();
+
+        let target = clipboard.clone();
+        let mut seen = None;
+        paste_via_clipboard(&mut clipboard, "BP 120/80", || {
+            seen = target.clone().read_text()?;
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(seen.as_deref(), Some("BP 120/80"));
+        assert_eq!(clipboard.formats(), before);
+    }
+
+    #[test]
+    fn test_empty_clipboard_stays_empty() {
+        let mut clipboard = MockClipboard::default();
+        paste_via_clipboard(&mut clipboard, "note", || Ok(())).unwrap();
+        assert!(clipboard.formats().is_empty());
+    }
+
+    #[test]
+    fn test_copy_during_paste_is_left_alone() {
+        let mut clipboard = copied_image();
+
+        // The user copies something else before we restore
+        let user = clipboard.clone();
+        paste_via_clipboard(&mut clipboard, "note", || user.clone().write_text("newer")).unwrap();
+        assert_eq!(clipboard.read_text().unwrap().as_deref(), Some("newer"));
+    }
+
+    #[test]
+    fn test_clear_copy() {
+        let mut clipboard = MockClipboard::default();
+        clipboard.write_text("BP 120/80").unwrap();
+        clear_copy(&mut clipboard, "BP 120/80");
+        assert!(clipboard.formats().is_empty());
+
+        // A screenshot taken since has no text, and must survive
+        let mut clipboard = copied_image();
+        let before = clipboard.formats();
+        clear_copy(&mut clipboard, "BP 120/80");
+        assert_eq!(clipboard.formats(), before);
+    }
+
+    #[test]
+    fn test_failed_keystroke_fails_the_paste() {
+        let mut clipboard = copied_image();
+        let before = clipboard.formats();
+        let result = paste_via_clipboard(&mut clipboard, "note", || Err("No keyboard".to_string()));
+        assert_eq!(result, Err(PasteError::NotSent("No keyboard".to_string())));
+        assert_eq!(clipboard.formats(), before);
+    }
+}
@@ -1,11 +1,15 @@
 use crate::audio_feedback;
-use crate::audio_toolkit::audio::{list_input_devices, list_output_devices};
+use crate::audio_toolkit::audio::{
+    list_input_devices, list_output_devices, ChannelPolicy, CpalDeviceInfo, CpalSource,
+    QualityReport,
+};
 use crate::managers::audio::{AudioRecordingManager, MicrophoneMode};
 use crate::settings::{get_settings, write_settings};
 use log::warn;
 use serde::{Deserialize, Serialize};
 use specta::Type;
 use std::sync::Arc;
+use std::time::Duration;
 use tauri::{AppHandle, Manager};
 
 #[derive(Serialize, Type)]
@@ -37,6 +41,33 @@ pub struct AudioDevice {
     pub index: String,
     pub name: String,
     pub is_default: bool,
+    /// Channel counts the device can be opened with
+    pub channels: Vec<u16>,
+    /// Common sample rates the device supports
+    pub sample_rates: Vec<u32>,
+}
+
+/// The "Default" entry first, describing whichever device is the system
+/// default, then every device.
+fn device_list(devices: Vec<CpalDeviceInfo>) -> Vec<AudioDevice> {
+    let default = devices.iter().find(|d| d.is_default);
+    let mut result = vec![AudioDevice {
+        index: "default".to_string(),
+        name: "Default".to_string(),
+        is_default: true,
+        channels: default.map(|d| d.channels.clone()).unwrap_or_default(),
+        sample_rates: default.map(|d| d.sample_rates.clone()).unwrap_or_default(),
+    }];
+
+    result.extend(devices.into_iter().map(|d| AudioDevice {
+        index: d.index,
+        name: d.name,
+        is_default: false, // The explicit default is handled separately
+        channels: d.channels,
+        sample_rates: d.sample_rates,
+    }));
+
+    result
 }
 
 #[tauri::command]
@@ -72,19 +103,7 @@ pub fn get_available_microphones() -> Result<Vec<AudioDevice>, String> {
     let devices =
         list_input_devices().map_err(|e| format!("Failed to list audio devices: {}", e))?;
 
-    let mut result = vec![AudioDevice {
-        index: "default".to_string(),
-        name: "Default".to_string(),
-        is_default: true,
-    }];
-
-    result.extend(devices.into_iter().map(|d| AudioDevice {
-        index: d.index,
-        name: d.name,
-        is_default: false, // The explicit default is handled separately
-    }));
-
-    Ok(result)
+    Ok(device_list(devices))
 }
 
 #[tauri::command]
@@ -106,6 +125,94 @@ pub fn set_selected_microphone(app: AppHandle, device_name: String) -> Result<()
     Ok(())
 }
 
+/// Sets how `device_name`'s channels are folded into mono. Fails if the
+/// device is connected and doesn't have the chosen channel. Takes effect
+/// straight away if the device is in use, or when the recording in
+/// progress ends.
+#[tauri::command]
+#[specta::specta]
+pub fn set_microphone_channel_policy(
+    app: AppHandle,
+    device_name: String,
+    policy: ChannelPolicy,
+) -> Result<(), String> {
+    if let ChannelPolicy::Channel { index } = policy {
+        check_channel_exists(&device_name, index)?;
+    }
+
+    let mut settings = get_settings(&app);
+    if policy == ChannelPolicy::Average {
+        settings.microphone_channels.remove(&device_name);
+    } else {
+        settings.microphone_channels.insert(device_name, policy);
+    }
+    write_settings(&app, settings);
+
+    let rm = app.state::<Arc<AudioRecordingManager>>();
+    rm.apply_channel_policy(&device_name)
+        .map_err(|e| format!("Failed to apply channel policy: {}", e))
+}
+
+/// Channels are numbered from 0 within the config the device is opened with.
+/// A device that isn't connected is checked when it is opened instead.
+fn check_channel_exists(device_name: &str, index: u16) -> Result<(), String> {
+    let devices =
+        list_input_devices().map_err(|e| format!("Failed to list audio devices: {}", e))?;
+    let Some(info) = devices.into_iter().find(|d| d.name == device_name) else {
+        return Ok(());
+    };
+    match CpalSource::opened_channels(&info.device) {
+        Ok(channels) if index >= channels => Err(format!(
+            "'{}' records {} channel(s), numbered from 0; there is no channel {}",
+            device_name, channels, index
+        )),
+        Ok(_) => Ok(()),
+        Err(e) => {
+            warn!("Can't tell how many channels '{}' has: {}", device_name, e);
+            Ok(())
+        }
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_microphone_channel_policy(app: AppHandle, device_name: String) -> ChannelPolicy {
+    get_settings(&app)
+        .microphone_channels
+        .get(&device_name)
+        .copied()
+        .unwrap_or_default()
+}
+
+/// Starts streaming `vad-calibration` events with the VAD's decision for
+/// every 30 ms frame, so thresholds can be tuned against the room.
+#[tauri::command]
+#[specta::specta]
+pub fn start_vad_calibration(app: AppHandle) -> Result<(), String> {
+    let rm = app.state::<Arc<AudioRecordingManager>>();
+    rm.start_vad_calibration()
+        .map_err(|e| format!("Failed to start VAD calibration: {}", e))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn stop_vad_calibration(app: AppHandle) {
+    let rm = app.state::<Arc<AudioRecordingManager>>();
+    rm.stop_vad_calibration();
+}
+
+/// Records five seconds from the current microphone and reports its levels,
+/// clipping and speech-to-noise ratio. Nothing is transcribed or kept.
+#[tauri::command]
+#[specta::specta]
+pub async fn test_microphone(app: AppHandle) -> Result<QualityReport, String> {
+    let rm = app.state::<Arc<AudioRecordingManager>>().inner().clone();
+    tauri::async_runtime::spawn_blocking(move || rm.test_microphone(Duration::from_secs(5)))
+        .await
+        .map_err(|e| format!("Microphone test failed: {}", e))?
+        .map_err(|e| format!("Microphone test failed: {}", e))
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn get_selected_microphone(app: AppHandle) -> Result<String, String> {
@@ -121,19 +228,7 @@ pub fn get_available_output_devices() -> Result<Vec<AudioDevice>, String> {
     let devices =
         list_output_devices().map_err(|e| format!("Failed to list output devices: {}", e))?;
 
-    let mut result = vec![AudioDevice {
-        index: "default".to_string(),
-        name: "Default".to_string(),
-        is_default: true,
-    }];
-
-    result.extend(devices.into_iter().map(|d| AudioDevice {
-        index: d.index,
-        name: d.name,
-        is_default: false, // The explicit default is handled separately
-    }));
-
-    Ok(result)
+    Ok(device_list(devices))
 }
 
 #[tauri::command]
@@ -1,8 +1,16 @@
-use crate::managers::model::{ModelInfo, ModelManager};
+use crate::managers::benchmark::{self, BenchmarkResult};
+use crate::managers::engine_options::{self, ParakeetCapabilities, ParakeetOptions};
+use crate::managers::integrity::ModelVerification;
+use crate::managers::model::{EngineType, ModelInfo, ModelManager};
+use crate::managers::model_pack::PackInstallResult;
 use crate::managers::transcription::TranscriptionManager;
 use crate::settings::{get_settings, write_settings};
+use serde::Serialize;
+use specta::Type;
+use std::path::PathBuf;
 use std::sync::Arc;
-use tauri::{AppHandle, State};
+use tauri::path::BaseDirectory;
+use tauri::{AppHandle, Emitter, Manager, State};
 
 #[tauri::command]
 #[specta::specta]
@@ -52,18 +60,21 @@ pub async fn delete_model(
 
 #[tauri::command]
 #[specta::specta]
-pub async fn set_active_model(
-    app_handle: AppHandle,
-    model_manager: State<'_, Arc<ModelManager>>,
-    transcription_manager: State<'_, Arc<TranscriptionManager>>,
-    model_id: String,
-) -> Result<(), String> {
+pub async fn set_active_model(app_handle: AppHandle, model_id: String) -> Result<(), String> {
+    select_model(&app_handle, &model_id)
+}
+
+/// Loads a downloaded model and makes it the selected one.
+pub fn select_model(app_handle: &AppHandle, model_id: &str) -> Result<(), String> {
+    let model_manager = app_handle.state::<Arc<ModelManager>>();
+    let transcription_manager = app_handle.state::<Arc<TranscriptionManager>>();
+
     // Validate model ID
-    crate::validation::validate_model_id(&model_id)?;
+    crate::validation::validate_model_id(model_id)?;
 
     // Check if model exists and is available
     let model_info = model_manager
-        .get_model_info(&model_id)
+        .get_model_info(model_id)
         .ok_or_else(|| format!("Model not found: {}", model_id))?;
 
     if !model_info.is_downloaded {
@@ -72,13 +83,13 @@ pub async fn set_active_model(
 
     // Load the model in the transcription manager
     transcription_manager
-        .load_model(&model_id)
+        .load_model(model_id)
         .map_err(|e| e.to_string())?;
 
     // Update settings
-    let mut settings = get_settings(&app_handle);
-    settings.selected_model = model_id.clone();
-    write_settings(&app_handle, settings);
+    let mut settings = get_settings(app_handle);
+    settings.selected_model = model_id.to_string();
+    write_settings(app_handle, settings);
 
     Ok(())
 }
@@ -140,7 +151,302 @@ pub async fn cancel_download(
 
 #[tauri::command]
 #[specta::specta]
-pub async fn get_recommended_first_model() -> Result<String, String> {
+pub async fn get_download_queue(
+    model_manager: State<'_, Arc<ModelManager>>,
+) -> Result<Vec<String>, String> {
+    Ok(model_manager.get_download_queue())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_recommended_first_model(
+    app_handle: AppHandle,
+    model_manager: State<'_, Arc<ModelManager>>,
+) -> Result<String, String> {
+    // Prefer what benchmarks on this machine say, including models benchmarked
+    // before they were deleted
+    let settings = get_settings(&app_handle);
+    let models = model_manager.get_available_models();
+    if let Some(model_id) = model_manager.recommend_model(
+        models.iter().map(|m| m.id.as_str()),
+        settings.model_latency_target_rtf,
+    ) {
+        return Ok(model_id);
+    }
+
     // Recommend Parakeet V3 model for first-time users - fastest and most accurate
     Ok("parakeet-tdt-0.6b-v3".to_string())
 }
+
+/// Runs each downloaded model (or just `model_ids`) on the bundled reference
+/// clip and stores the measurements for model recommendations.
+#[tauri::command]
+#[specta::specta]
+pub async fn benchmark_models(
+    app_handle: AppHandle,
+    model_manager: State<'_, Arc<ModelManager>>,
+    model_ids: Option<Vec<String>>,
+) -> Result<Vec<BenchmarkResult>, String> {
+    if let Some(model_ids) = &model_ids {
+        for model_id in model_ids {
+            crate::validation::validate_model_id(model_id)?;
+        }
+    }
+
+    let resolve = |path: &str| {
+        app_handle
+            .path()
+            .resolve(path, BaseDirectory::Resource)
+            .map_err(|e| format!("Failed to resolve {}: {}", path, e))
+    };
+    let clip_path = resolve(benchmark::REFERENCE_CLIP)?;
+    let text_path = resolve(benchmark::REFERENCE_TEXT)?;
+
+    let model_manager = Arc::clone(&model_manager);
+
+    // Each run loads a full model and transcribes; keep it off the async runtime
+    tauri::async_runtime::spawn_blocking(move || {
+        let (samples, reference) = benchmark::load_reference(&clip_path, &text_path)?;
+        let settings = get_settings(&app_handle);
+
+        let models: Vec<_> = model_manager
+            .get_available_models()
+            .into_iter()
+            .filter(|m| m.is_downloaded)
+            .filter(|m| model_ids.as_ref().is_none_or(|ids| ids.contains(&m.id)))
+            .collect();
+
+        let mut results = Vec::new();
+        for (index, model_info) in models.iter().enumerate() {
+            let _ = app_handle.emit(
+                "model-benchmark-progress",
+                &serde_json::json!({
+                    "model_id": model_info.id,
+                    "index": index,
+                    "total": models.len()
+                }),
+            );
+
+            match benchmark::benchmark_model(
+                &model_manager,
+                model_info,
+                &settings,
+                &samples,
+                &reference,
+            ) {
+                Ok(result) => results.push(result),
+                Err(e) => {
+                    log::warn!("Benchmark of model {} failed: {}", model_info.id, e);
+                    let _ = app_handle.emit(
+                        "model-benchmark-failed",
+                        &serde_json::json!({
+                            "model_id": model_info.id,
+                            "error": e.to_string()
+                        }),
+                    );
+                }
+            }
+        }
+
+        model_manager.record_benchmark_results(&results)?;
+        anyhow::Ok(results)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_benchmark_results(
+    model_manager: State<'_, Arc<ModelManager>>,
+) -> Result<Vec<BenchmarkResult>, String> {
+    Ok(model_manager.get_benchmark_results())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn refresh_model_catalog(
+    model_manager: State<'_, Arc<ModelManager>>,
+) -> Result<usize, String> {
+    model_manager
+        .refresh_catalog()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Re-checks installed models against their checksums. Checks every downloaded
+/// model when `model_id` is `None`.
+#[tauri::command]
+#[specta::specta]
+pub async fn verify_model(
+    model_manager: State<'_, Arc<ModelManager>>,
+    model_id: Option<String>,
+) -> Result<Vec<ModelVerification>, String> {
+    if let Some(model_id) = &model_id {
+        crate::validation::validate_model_id(model_id)?;
+    }
+
+    let model_manager = Arc::clone(&model_manager);
+
+    // Hashing a model takes a few seconds, keep it off the async runtime
+    tauri::async_runtime::spawn_blocking(move || {
+        let model_ids = match model_id {
+            Some(model_id) => vec![model_id],
+            None => model_manager
+                .get_available_models()
+                .into_iter()
+                .filter(|m| m.is_downloaded)
+                .map(|m| m.id)
+                .collect(),
+        };
+
+        model_ids
+            .iter()
+            .map(|id| model_manager.verify_model(id))
+            .collect::<anyhow::Result<Vec<_>>>()
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn import_model(
+    model_manager: State<'_, Arc<ModelManager>>,
+    path: String,
+    engine_type: EngineType,
+    display_name: String,
+) -> Result<ModelInfo, String> {
+    let model_manager = Arc::clone(&model_manager);
+
+    // Copying and test-loading can take a while
+    tauri::async_runtime::spawn_blocking(move || {
+        model_manager.import_model(std::path::Path::new(&path), engine_type, &display_name)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn install_model_pack(
+    model_manager: State<'_, Arc<ModelManager>>,
+    path: String,
+    allow_unknown: bool,
+) -> Result<Vec<PackInstallResult>, String> {
+    let model_manager = Arc::clone(&model_manager);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        model_manager.install_model_pack(std::path::Path::new(&path), allow_unknown)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn export_model_pack(
+    model_manager: State<'_, Arc<ModelManager>>,
+    model_ids: Vec<String>,
+    destination: String,
+) -> Result<(), String> {
+    for model_id in &model_ids {
+        crate::validation::validate_model_id(model_id)?;
+    }
+
+    let model_manager = Arc::clone(&model_manager);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        model_manager.export_model_pack(&model_ids, std::path::Path::new(&destination))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[derive(Serialize, Type)]
+pub struct ParakeetModelOptions {
+    options: ParakeetOptions,
+    capabilities: ParakeetCapabilities,
+}
+
+fn parakeet_model_path(model_manager: &ModelManager, model_id: &str) -> Result<PathBuf, String> {
+    let model_info = model_manager
+        .get_model_info(model_id)
+        .ok_or_else(|| format!("Model not found: {}", model_id))?;
+
+    if !matches!(model_info.engine_type, EngineType::Parakeet) {
+        return Err(format!("Model {} is not a Parakeet model", model_id));
+    }
+    if !model_info.is_downloaded {
+        return Err(format!("Model not downloaded: {}", model_id));
+    }
+
+    model_manager
+        .get_model_path(model_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_parakeet_model_options(
+    app_handle: AppHandle,
+    model_manager: State<'_, Arc<ModelManager>>,
+    model_id: String,
+) -> Result<ParakeetModelOptions, String> {
+    crate::validation::validate_model_id(&model_id)?;
+    let model_path = parakeet_model_path(&model_manager, &model_id)?;
+
+    Ok(ParakeetModelOptions {
+        options: engine_options::options_for(&get_settings(&app_handle), &model_id, &model_path),
+        capabilities: engine_options::capabilities(&model_path),
+    })
+}
+
+/// Saves engine options for a Parakeet model. If that model is loaded it is
+/// reloaded straight away so the options apply without a restart.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_parakeet_model_options(
+    app_handle: AppHandle,
+    model_manager: State<'_, Arc<ModelManager>>,
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+    model_id: String,
+    options: ParakeetOptions,
+) -> Result<(), String> {
+    crate::validation::validate_model_id(&model_id)?;
+    let model_path = parakeet_model_path(&model_manager, &model_id)?;
+    engine_options::validate(&model_path, &options)?;
+
+    let mut settings = get_settings(&app_handle);
+    let previous = settings
+        .parakeet_model_options
+        .insert(model_id.clone(), options);
+    write_settings(&app_handle, settings);
+
+    if transcription_manager.get_current_model().as_deref() != Some(model_id.as_str()) {
+        return Ok(());
+    }
+
+    let transcription_manager = Arc::clone(&transcription_manager);
+    let reloaded =
+        tauri::async_runtime::spawn_blocking(move || transcription_manager.reload_model())
+            .await
+            .map_err(|e| e.to_string())?;
+
+    if let Err(e) = reloaded {
+        // The model is still running with the old options; keep them saved
+        let mut settings = get_settings(&app_handle);
+        match previous {
+            Some(previous) => settings.parakeet_model_options.insert(model_id, previous),
+            None => settings.parakeet_model_options.remove(&model_id),
+        };
+        write_settings(&app_handle, settings);
+        return Err(e.to_string());
+    }
+    Ok(())
+}
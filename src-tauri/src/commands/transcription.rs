@@ -1,7 +1,9 @@
+use crate::managers::queue::{QueueState, TranscriptionQueue};
 use crate::managers::transcription::TranscriptionManager;
 use crate::settings::{get_settings, write_settings, ModelUnloadTimeout};
 use serde::Serialize;
 use specta::Type;
+use std::sync::Arc;
 use tauri::{AppHandle, State};
 
 #[derive(Serialize, Type)]
@@ -38,3 +40,11 @@ pub fn unload_model_manually(
         .unload_model()
         .map_err(|e| format!("Failed to unload model: {}", e))
 }
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_transcription_queue(
+    queue: State<'_, Arc<TranscriptionQueue>>,
+) -> Result<QueueState, String> {
+    Ok(queue.get_state())
+}
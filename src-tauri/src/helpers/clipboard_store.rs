@@ -0,0 +1,571 @@
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+/// Formats that hold plain text, most specific first: MIME types, X11
+/// target names, the macOS pasteboard type and the Windows Unicode text
+/// format.
+const TEXT_FORMATS: [&str; 5] = [
+    "text/plain;charset=utf-8",
+    "UTF8_STRING",
+    "text/plain",
+    "public.utf8-plain-text",
+    "13",
+];
+
+/// Everything that was on the clipboard: each format it was offered in,
+/// with the data. Format names are platform specific (MIME types, X11
+/// targets or Windows format ids) and only mean something to the provider
+/// that took the snapshot.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClipboardSnapshot {
+    pub formats: Vec<(String, Vec<u8>)>,
+}
+
+impl ClipboardSnapshot {
+    /// Nothing was on the clipboard, which is different from empty text.
+    pub fn is_empty(&self) -> bool {
+        self.formats.is_empty()
+    }
+
+    pub fn text(&self) -> Option<String> {
+        TEXT_FORMATS.iter().find_map(|name| {
+            let (_, data) = self.formats.iter().find(|(format, _)| format == name)?;
+            if *name == "13" {
+                // UTF-16 with a terminating null
+                let units: Vec<u16> = data
+                    .chunks_exact(2)
+                    .map(|b| u16::from_le_bytes([b[0], b[1]]))
+                    .take_while(|&u| u != 0)
+                    .collect();
+                Some(String::from_utf16_lossy(&units))
+            } else {
+                Some(String::from_utf8_lossy(data).into_owned())
+            }
+        })
+    }
+}
+
+/// The system clipboard as far as pasting needs it.
+pub trait ClipboardProvider {
+    fn snapshot(&mut self) -> Result<ClipboardSnapshot, String>;
+    /// Puts a snapshot back exactly, leaving the clipboard empty for an
+    /// empty snapshot.
+    fn restore(&mut self, snapshot: &ClipboardSnapshot) -> Result<(), String>;
+    fn read_text(&mut self) -> Result<Option<String>, String>;
+    fn write_text(&mut self, text: &str) -> Result<(), String>;
+
+    /// Offers `text` in a way that shows when the target reads it. None
+    /// means the caller writes it with `write_text` and goes by timing.
+    #[cfg(target_os = "linux")]
+    fn offer(&mut self, _text: &str) -> Option<crate::helpers::selection::ClipboardOffer> {
+        None
+    }
+}
+
+/// Puts `saved` back only while the clipboard still holds `ours`. Anything
+/// else, including contents without text such as a copied image, means the
+/// user copied something meanwhile. Returns whether it restored.
+pub fn restore_unless_changed(
+    clipboard: &mut dyn ClipboardProvider,
+    ours: &str,
+    saved: &ClipboardSnapshot,
+) -> Result<bool, String> {
+    match clipboard.read_text()? {
+        Some(current) if current == ours => clipboard.restore(saved).map(|()| true),
+        _ => Ok(false),
+    }
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+/// The real clipboard. Text goes through the clipboard plugin; snapshots use
+/// the platform's own API so images, HTML, file lists and the like survive.
+/// Linux without a reachable display server only keeps text and images, via
+/// the plugin.
+pub struct SystemClipboard {
+    app: AppHandle,
+}
+
+impl SystemClipboard {
+    pub fn new(app: &AppHandle) -> Self {
+        Self { app: app.clone() }
+    }
+
+    /// Text and image only, for platforms without a native snapshot.
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    fn plugin_snapshot(&self) -> Vec<(String, Vec<u8>)> {
+        let clipboard = self.app.clipboard();
+        let mut formats = Vec::new();
+        if let Ok(text) = clipboard.read_text() {
+            formats.push((TEXT_FORMATS[0].to_string(), text.into_bytes()));
+        }
+        if let Ok(image) = clipboard.read_image() {
+            let format = format!("image/x-rgba;{}x{}", image.width(), image.height());
+            formats.push((format, image.rgba().to_vec()));
+        }
+        formats
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    fn plugin_restore(&self, snapshot: &ClipboardSnapshot) -> Result<(), String> {
+        let clipboard = self.app.clipboard();
+        let image = snapshot.formats.iter().find_map(|(format, data)| {
+            let (width, height) = format.strip_prefix("image/x-rgba;")?.split_once('x')?;
+            Some((width.parse().ok()?, height.parse().ok()?, data))
+        });
+
+        let result = match (image, snapshot.text()) {
+            (Some((width, height, rgba)), _) => {
+                clipboard.write_image(&tauri::image::Image::new(rgba, width, height))
+            }
+            (None, Some(text)) => clipboard.write_text(text),
+            (None, None) => clipboard.clear(),
+        };
+        result.map_err(|e| format!("Failed to restore clipboard: {}", e))
+    }
+}
+
+impl SystemClipboard {
+    /// Writes text that shouldn't outlive its use, flagged so clipboard
+    /// managers and clipboard history leave it out where the platform has a
+    /// way to say so.
+    pub fn write_sensitive(&mut self, text: &str) -> Result<(), String> {
+        #[cfg(target_os = "linux")]
+        if let Err(e) = crate::helpers::selection::write_sensitive(text) {
+            log::debug!("{}; writing plain text", e);
+            return self.write_text(text);
+        }
+        #[cfg(target_os = "windows")]
+        if let Err(e) = windows_clipboard::write_sensitive(text) {
+            log::debug!("{}; writing plain text", e);
+            return self.write_text(text);
+        }
+        #[cfg(target_os = "macos")]
+        if let Err(e) = macos_pasteboard::write_sensitive(text) {
+            log::debug!("{}; writing plain text", e);
+            return self.write_text(text);
+        }
+        #[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+        self.write_text(text)?;
+
+        Ok(())
+    }
+}
+
+impl ClipboardProvider for SystemClipboard {
+    fn snapshot(&mut self) -> Result<ClipboardSnapshot, String> {
+        #[cfg(target_os = "linux")]
+        let formats = crate::helpers::selection::snapshot().unwrap_or_else(|e| {
+            log::debug!("{}; keeping only text and images", e);
+            self.plugin_snapshot()
+        });
+        #[cfg(target_os = "windows")]
+        let formats = windows_clipboard::snapshot()?;
+        #[cfg(target_os = "macos")]
+        let formats = macos_pasteboard::snapshot();
+        #[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+        let formats = self.plugin_snapshot();
+
+        Ok(ClipboardSnapshot { formats })
+    }
+
+    fn restore(&mut self, snapshot: &ClipboardSnapshot) -> Result<(), String> {
+        #[cfg(target_os = "linux")]
+        if let Err(e) = crate::helpers::selection::restore(snapshot.formats.clone()) {
+            log::debug!("{}; restoring only text and images", e);
+            self.plugin_restore(snapshot)?;
+        }
+        #[cfg(target_os = "windows")]
+        windows_clipboard::restore(&snapshot.formats)?;
+        #[cfg(target_os = "macos")]
+        macos_pasteboard::restore(&snapshot.formats)?;
+        #[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+        self.plugin_restore(snapshot)?;
+
+        Ok(())
+    }
+
+    fn read_text(&mut self) -> Result<Option<String>, String> {
+        // The plugin reports an empty or non-text clipboard as an error
+        Ok(self.app.clipboard().read_text().ok())
+    }
+
+    fn write_text(&mut self, text: &str) -> Result<(), String> {
+        self.app
+            .clipboard()
+            .write_text(text)
+            .map_err(|e| format!("Failed to write to clipboard: {}", e))
+    }
+
+    #[cfg(target_os = "linux")]
+    fn offer(&mut self, text: &str) -> Option<crate::helpers::selection::ClipboardOffer> {
+        match crate::helpers::selection::ClipboardOffer::new(text) {
+            Ok(mut offer) if offer.is_offered() => Some(offer),
+            Ok(_) => {
+                log::debug!("Clipboard taken over before pasting, writing it directly");
+                None
+            }
+            Err(e) => {
+                log::debug!("{}; writing the clipboard directly", e);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows_clipboard {
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::{HANDLE, HGLOBAL};
+    use windows::Win32::System::DataExchange::{
+        CloseClipboard, EmptyClipboard, EnumClipboardFormats, GetClipboardData, OpenClipboard,
+        RegisterClipboardFormatW, SetClipboardData,
+    };
+    use windows::Win32::System::Memory::{
+        GlobalAlloc, GlobalLock, GlobalSize, GlobalUnlock, GMEM_MOVEABLE,
+    };
+
+    const CF_UNICODETEXT: u32 = 13;
+
+    /// Registered formats that keep an entry out of clipboard history, cloud
+    /// clipboard sync and clipboard monitors
+    const SENSITIVE_HINTS: [(&str, u32); 3] = [
+        ("ExcludeClipboardContentFromMonitorProcessing", 0),
+        ("CanIncludeInClipboardHistory", 0),
+        ("CanUploadToCloudClipboard", 0),
+    ];
+
+    /// Formats held as GDI handles rather than memory; Windows synthesises
+    /// the bitmap ones from the DIB formats we do keep
+    const HANDLE_FORMATS: [u32; 8] = [2, 3, 9, 14, 0x80, 0x82, 0x83, 0x8E];
+
+    struct Open;
+
+    impl Open {
+        fn clipboard() -> Result<Self, String> {
+            unsafe { OpenClipboard(None) }
+                .map_err(|e| format!("Failed to open the clipboard: {}", e))?;
+            Ok(Self)
+        }
+    }
+
+    impl Drop for Open {
+        fn drop(&mut self) {
+            let _ = unsafe { CloseClipboard() };
+        }
+    }
+
+    pub fn snapshot() -> Result<Vec<(String, Vec<u8>)>, String> {
+        let _open = Open::clipboard()?;
+        let mut formats = Vec::new();
+        let mut format = 0;
+        loop {
+            format = unsafe { EnumClipboardFormats(format) };
+            if format == 0 {
+                break;
+            }
+            if HANDLE_FORMATS.contains(&format) {
+                continue;
+            }
+            let Ok(handle) = (unsafe { GetClipboardData(format) }) else {
+                continue;
+            };
+            let memory = HGLOBAL(handle.0);
+            unsafe {
+                let ptr = GlobalLock(memory) as *const u8;
+                if ptr.is_null() {
+                    continue;
+                }
+                let data = std::slice::from_raw_parts(ptr, GlobalSize(memory)).to_vec();
+                let _ = GlobalUnlock(memory);
+                formats.push((format.to_string(), data));
+            }
+        }
+        Ok(formats)
+    }
+
+    pub fn restore(formats: &[(String, Vec<u8>)]) -> Result<(), String> {
+        let _open = Open::clipboard()?;
+        unsafe { EmptyClipboard() }.map_err(|e| format!("Failed to empty the clipboard: {}", e))?;
+
+        for (format, data) in formats {
+            let Ok(format) = format.parse::<u32>() else {
+                continue;
+            };
+            if let Err(e) = set_data(format, data) {
+                log::warn!("Failed to restore clipboard format {}: {}", format, e);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn write_sensitive(text: &str) -> Result<(), String> {
+        let _open = Open::clipboard()?;
+        unsafe { EmptyClipboard() }.map_err(|e| format!("Failed to empty the clipboard: {}", e))?;
+
+        let utf16: Vec<u8> = text
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        set_data(CF_UNICODETEXT, &utf16)?;
+
+        for (name, value) in SENSITIVE_HINTS {
+            let wide: Vec<u16> = name.encode_utf16().chain(std::iter::once(0)).collect();
+            let format = unsafe { RegisterClipboardFormatW(PCWSTR(wide.as_ptr())) };
+            if format == 0 {
+                log::warn!("Failed to register clipboard format {}", name);
+                continue;
+            }
+            if let Err(e) = set_data(format, &value.to_le_bytes()) {
+                log::warn!("Failed to set clipboard format {}: {}", name, e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Copies `data` into global memory and hands it to the open clipboard.
+    fn set_data(format: u32, data: &[u8]) -> Result<(), String> {
+        unsafe {
+            let memory = GlobalAlloc(GMEM_MOVEABLE, data.len())
+                .map_err(|e| format!("Failed to allocate clipboard memory: {}", e))?;
+            let ptr = GlobalLock(memory) as *mut u8;
+            if ptr.is_null() {
+                return Err("Failed to lock clipboard memory".to_string());
+            }
+            std::ptr::copy_nonoverlapping(data.as_ptr(), ptr, data.len());
+            let _ = GlobalUnlock(memory);
+            // The clipboard owns the memory from here on
+            SetClipboardData(format, Some(HANDLE(memory.0)))
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos_pasteboard {
+    // Which of these methods are marked unsafe varies between objc2 releases
+    #![allow(unused_unsafe)]
+
+    use objc2::rc::Retained;
+    use objc2::runtime::ProtocolObject;
+    use objc2_app_kit::{NSPasteboard, NSPasteboardItem, NSPasteboardWriting};
+    use objc2_foundation::{NSArray, NSData, NSString};
+
+    const TEXT_TYPE: &str = "public.utf8-plain-text";
+    /// Markers from nspasteboard.org that clipboard managers such as Maccy,
+    /// Alfred and Paste honour: don't show it, and don't keep it
+    const SENSITIVE_TYPES: [&str; 2] = [
+        "org.nspasteboard.ConcealedType",
+        "org.nspasteboard.TransientType",
+    ];
+
+    /// Types of items after the first carry their index, e.g. `item2:public.file-url`,
+    /// so copying several files puts back several items
+    const ITEM_PREFIX: &str = "item";
+
+    fn format_name(index: usize, uti: &str) -> String {
+        if index == 0 {
+            uti.to_string()
+        } else {
+            format!("{}{}:{}", ITEM_PREFIX, index, uti)
+        }
+    }
+
+    fn parse_format(format: &str) -> (usize, &str) {
+        format
+            .strip_prefix(ITEM_PREFIX)
+            .and_then(|rest| rest.split_once(':'))
+            .and_then(|(index, uti)| Some((index.parse().ok()?, uti)))
+            .unwrap_or((0, format))
+    }
+
+    /// Every type of every pasteboard item, with its data.
+    pub fn snapshot() -> Vec<(String, Vec<u8>)> {
+        let mut formats = Vec::new();
+        unsafe {
+            let pasteboard = NSPasteboard::generalPasteboard();
+            let Some(items) = pasteboard.pasteboardItems() else {
+                return formats;
+            };
+            for (index, item) in items.iter().enumerate() {
+                for uti in item.types().iter() {
+                    if let Some(data) = item.dataForType(&uti) {
+                        formats.push((format_name(index, &uti.to_string()), data.to_vec()));
+                    }
+                }
+            }
+        }
+        formats
+    }
+
+    pub fn restore(formats: &[(String, Vec<u8>)]) -> Result<(), String> {
+        let mut items: Vec<Vec<(&str, &[u8])>> = Vec::new();
+        for (format, data) in formats {
+            let (index, uti) = parse_format(format);
+            if items.len() <= index {
+                items.resize_with(index + 1, Vec::new);
+            }
+            items[index].push((uti, data));
+        }
+        write_items(&items)
+    }
+
+    pub fn write_sensitive(text: &str) -> Result<(), String> {
+        let mut types = vec![(TEXT_TYPE, text.as_bytes())];
+        types.extend(SENSITIVE_TYPES.iter().map(|&marker| (marker, &[][..])));
+        write_items(&[types])
+    }
+
+    /// Replaces the pasteboard with one item per entry, leaving it empty
+    /// when there are none.
+    fn write_items(items: &[Vec<(&str, &[u8])>]) -> Result<(), String> {
+        unsafe {
+            let pasteboard = NSPasteboard::generalPasteboard();
+            pasteboard.clearContents();
+
+            let objects: Vec<Retained<ProtocolObject<dyn NSPasteboardWriting>>> = items
+                .iter()
+                .filter(|types| !types.is_empty())
+                .map(|types| {
+                    let item = NSPasteboardItem::new();
+                    for (uti, data) in types {
+                        item.setData_forType(&NSData::with_bytes(data), &NSString::from_str(uti));
+                    }
+                    ProtocolObject::from_retained(item)
+                })
+                .collect();
+            if !objects.is_empty()
+                && !pasteboard.writeObjects(&NSArray::from_retained_slice(&objects))
+            {
+                return Err("Failed to write to the pasteboard".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A clipboard in memory for tests. Clones share their contents, so a test
+/// can play the user or the target application while code under test holds
+/// the clipboard.
+#[cfg(test)]
+pub(crate) mod mock {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Behaves like the system clipboard: writing text replaces every format.
+    #[derive(Default, Clone)]
+    pub struct MockClipboard {
+        formats: Rc<RefCell<Vec<(String, Vec<u8>)>>>,
+    }
+
+    impl MockClipboard {
+        pub fn formats(&self) -> Vec<(String, Vec<u8>)> {
+            self.formats.borrow().clone()
+        }
+
+        pub fn set_formats(&self, formats: Vec<(String, Vec<u8>)>) {
+            *self.formats.borrow_mut() = formats;
+        }
+    }
+
+    impl ClipboardProvider for MockClipboard {
+        fn snapshot(&mut self) -> Result<ClipboardSnapshot, String> {
+            Ok(ClipboardSnapshot {
+                formats: self.formats(),
+            })
+        }
+
+        fn restore(&mut self, snapshot: &ClipboardSnapshot) -> Result<(), String> {
+            self.set_formats(snapshot.formats.clone());
+            Ok(())
+        }
+
+        fn read_text(&mut self) -> Result<Option<String>, String> {
+            self.snapshot().map(|s| s.text())
+        }
+
+        fn write_text(&mut self, text: &str) -> Result<(), String> {
+            self.set_formats(vec![(
+                TEXT_FORMATS[0].to_string(),
+                text.as_bytes().to_vec(),
+            )]);
+            Ok(())
+        }
+    }
+
+    /// A copied image, as a browser offers it.
+    pub fn copied_image() -> MockClipboard {
+        let clipboard = MockClipboard::default();
+        clipboard.set_formats(vec![
+            ("image/png".to_string(), vec![0x89, b'P', b'N', b'G']),
+            ("text/html".to_string(), b"<img src=\"scan.png\">".to_vec()),
+            (
+                "text/uri-list".to_string(),
+                b"file:///tmp/scan.png".to_vec(),
+            ),
+        ]);
+        clipboard
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use super::mock::{copied_image, MockClipboard};
+
+    #[test]
+    fn test_restores_while_ours() {
+        let mut clipboard = MockClipboard::default();
+        let saved = copied_image().snapshot().unwrap();
+        clipboard.write_text("note").unwrap();
+
+        assert!(restore_unless_changed(&mut clipboard, "note", &saved).unwrap());
+        assert_eq!(clipboard.formats(), saved.formats);
+    }
+
+    #[test]
+    fn test_newer_copy_is_left_alone() {
+        let mut clipboard = MockClipboard::default();
+        let saved = copied_image().snapshot().unwrap();
+
+        clipboard.write_text("something else").unwrap();
+        assert!(!restore_unless_changed(&mut clipboard, "note", &saved).unwrap());
+        assert_eq!(
+            clipboard.read_text().unwrap().as_deref(),
+            Some("something else")
+        );
+
+        // A screenshot has no text at all, but it's still a newer copy
+        let screenshot = copied_image().formats();
+        clipboard.set_formats(screenshot.clone());
+        assert!(!restore_unless_changed(&mut clipboard, "note", &saved).unwrap());
+        assert_eq!(clipboard.formats(), screenshot);
+    }
+
+    #[test]
+    fn test_snapshot_text() {
+        let snapshot = ClipboardSnapshot {
+            formats: vec![
+                ("text/html".to_string(), b"<b>hi</b>".to_vec()),
+                ("UTF8_STRING".to_string(), b"hi".to_vec()),
+            ],
+        };
+        assert_eq!(snapshot.text().as_deref(), Some("hi"));
+
+        // Windows CF_UNICODETEXT
+        let utf16: Vec<u8> = "hé\0"
+            .encode_utf16()
+            .flat_map(|u| u.to_le_bytes())
+            .collect();
+        let snapshot = ClipboardSnapshot {
+            formats: vec![("13".to_string(), utf16)],
+        };
+        assert_eq!(snapshot.text().as_deref(), Some("hé"));
+
+        assert_eq!(copied_image().snapshot().unwrap().text(), None);
+    }
+}
@@ -0,0 +1,135 @@
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+use std::process::Command;
+
+/// Opaque handle to the window (or application) that had keyboard focus
+/// when a recording started.
+///
+/// - macOS: the process id of the frontmost application
+/// - Windows: the raw `HWND` of the foreground window
+/// - Linux (X11 only): the X window id reported by `xdotool`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FocusedWindow(i64);
+
+/// Captures the currently focused window.
+///
+/// Returns `None` when the platform (e.g. Wayland) does not expose the focused
+/// window or the lookup fails.
+#[cfg(target_os = "macos")]
+pub fn capture_focused_window() -> Option<FocusedWindow> {
+    let output = Command::new("osascript")
+        .args([
+            "-e",
+            "tell application \"System Events\" to get unix id of first process whose frontmost is true",
+        ])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .map(FocusedWindow)
+}
+
+#[cfg(target_os = "windows")]
+pub fn capture_focused_window() -> Option<FocusedWindow> {
+    use windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
+
+    let hwnd = unsafe { GetForegroundWindow() };
+    if hwnd.0.is_null() {
+        None
+    } else {
+        Some(FocusedWindow(hwnd.0 as i64))
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn capture_focused_window() -> Option<FocusedWindow> {
+    // Wayland compositors don't let clients query or change focus
+    if crate::utils::is_wayland() {
+        return None;
+    }
+
+    let output = Command::new("xdotool")
+        .arg("getactivewindow")
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .map(FocusedWindow)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+pub fn capture_focused_window() -> Option<FocusedWindow> {
+    None
+}
+
+/// Brings a previously captured window back to the foreground so a paste
+/// lands where the user was working when the recording started.
+#[cfg(target_os = "macos")]
+pub fn restore_focus(target: &FocusedWindow) -> Result<(), String> {
+    let script = format!(
+        "tell application \"System Events\" to set frontmost of (first process whose unix id is {}) to true",
+        target.0
+    );
+    let output = Command::new("osascript")
+        .args(["-e", &script])
+        .output()
+        .map_err(|e| format!("Failed to execute osascript: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Failed to restore focus: {}", stderr));
+    }
+
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+pub fn restore_focus(target: &FocusedWindow) -> Result<(), String> {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::SetForegroundWindow;
+
+    let hwnd = HWND(target.0 as *mut std::ffi::c_void);
+    if unsafe { SetForegroundWindow(hwnd) }.as_bool() {
+        Ok(())
+    } else {
+        Err("SetForegroundWindow was refused".to_string())
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn restore_focus(target: &FocusedWindow) -> Result<(), String> {
+    let output = Command::new("xdotool")
+        .args(["windowactivate", "--sync", &target.0.to_string()])
+        .output()
+        .map_err(|e| format!("Failed to execute xdotool: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("xdotool failed: {}", stderr));
+    }
+
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+pub fn restore_focus(_target: &FocusedWindow) -> Result<(), String> {
+    Err("Restoring focus is not supported on this platform".to_string())
+}
+
+/// Returns true when the given window is still the focused one.
+pub fn is_focused(target: &FocusedWindow) -> bool {
+    capture_focused_window().as_ref() == Some(target)
+}
@@ -1 +1,7 @@
 pub mod clamshell;
+pub mod clipboard_store;
+pub mod focus;
+pub mod paste_timing;
+pub mod screen_lock;
+#[cfg(target_os = "linux")]
+pub mod selection;
@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+/// Shortest time our text stays on the clipboard after the paste keystroke
+const MIN_RESTORE_DELAY: Duration = Duration::from_millis(50);
+/// Longest we'll hold the user's clipboard hostage when we can't tell
+const MAX_RESTORE_DELAY: Duration = Duration::from_millis(1500);
+/// Extra time per KiB of text, for targets that copy large pastes slowly
+const PER_KIB: Duration = Duration::from_millis(10);
+/// How much slower than the slowest recent read we allow for
+const SAFETY_FACTOR: f32 = 2.0;
+/// How far each faster read pulls the estimate back down
+const DECAY: f32 = 0.2;
+
+/// How long we waited for a target to read before giving up on it
+const MIN_READ_TIMEOUT: Duration = Duration::from_millis(1000);
+const MAX_READ_TIMEOUT: Duration = Duration::from_millis(5000);
+
+/// Paste timing learnt from the targets we pasted into.
+///
+/// Where we can see the target read the clipboard (X11 and Wayland), we
+/// record how long that took. Where we can't, we wait a multiple of the
+/// recent read times before putting the previous clipboard back, so slow
+/// targets (Electron EMRs) don't end up pasting the old contents.
+///
+/// Only Linux reports reads, so the learning is Linux-only: on macOS and
+/// Windows nothing is recorded and the delay is the size-based default.
+#[derive(Debug, Default)]
+pub struct PasteTiming {
+    /// Estimated read latency in ms; jumps up to slow reads straight away
+    /// and drifts down again with fast ones
+    read_ms: Option<f32>,
+}
+
+impl PasteTiming {
+    pub fn record_read(&mut self, latency: Duration) {
+        let ms = latency.as_secs_f32() * 1000.0;
+        self.read_ms = Some(match self.read_ms {
+            Some(estimate) if ms < estimate => estimate + DECAY * (ms - estimate),
+            _ => ms,
+        });
+    }
+
+    fn scaled(&self, factor: f32) -> Duration {
+        self.read_ms.map_or(Duration::ZERO, |ms| {
+            Duration::from_millis((ms * factor).round() as u64)
+        })
+    }
+
+    /// Time to wait after the keystroke before restoring the clipboard,
+    /// when we can't see the target read it.
+    pub fn restore_delay(&self, text_len: usize) -> Duration {
+        let learnt = self.scaled(SAFETY_FACTOR);
+        let size = PER_KIB * (text_len / 1024) as u32;
+        (learnt.max(MIN_RESTORE_DELAY) + size).min(MAX_RESTORE_DELAY)
+    }
+
+    /// Time to wait for a target to read the clipboard before treating the
+    /// paste as failed.
+    pub fn read_timeout(&self) -> Duration {
+        let learnt = self.scaled(2.0 * SAFETY_FACTOR);
+        learnt.clamp(MIN_READ_TIMEOUT, MAX_READ_TIMEOUT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn test_defaults() {
+        let timing = PasteTiming::default();
+        assert_eq!(timing.restore_delay(10), MIN_RESTORE_DELAY);
+        assert_eq!(timing.restore_delay(10 * 1024), ms(150));
+        assert_eq!(timing.read_timeout(), MIN_READ_TIMEOUT);
+    }
+
+    #[test]
+    fn test_slow_reads_take_effect_at_once() {
+        let mut timing = PasteTiming::default();
+        timing.record_read(ms(20));
+        assert_eq!(timing.restore_delay(10), MIN_RESTORE_DELAY);
+
+        timing.record_read(ms(300));
+        assert_eq!(timing.restore_delay(10), ms(600));
+        assert_eq!(timing.read_timeout(), ms(1200));
+    }
+
+    #[test]
+    fn test_fast_reads_decay_slowly() {
+        let mut timing = PasteTiming::default();
+        timing.record_read(ms(300));
+        timing.record_read(ms(50));
+        // 300 + 0.2 * (50 - 300) = 250
+        assert_eq!(timing.restore_delay(10), ms(500));
+    }
+
+    #[test]
+    fn test_capped() {
+        let mut timing = PasteTiming::default();
+        timing.record_read(ms(10_000));
+        assert_eq!(timing.restore_delay(10), MAX_RESTORE_DELAY);
+        assert_eq!(timing.read_timeout(), MAX_READ_TIMEOUT);
+    }
+}
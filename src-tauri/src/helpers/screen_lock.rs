@@ -0,0 +1,177 @@
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+use std::process::Command;
+
+/// Checks whether the session's screen is locked.
+///
+/// Asks logind for the session's `LockedHint`, which GNOME, KDE and most
+/// screen lockers set. Returns false when logind isn't available.
+#[cfg(target_os = "linux")]
+pub fn is_screen_locked() -> bool {
+    let session = std::env::var("XDG_SESSION_ID").unwrap_or_else(|_| "self".to_string());
+    let output = Command::new("loginctl")
+        .args(["show-session", &session, "-p", "LockedHint", "--value"])
+        .output();
+
+    match output {
+        Ok(output) if output.status.success() => {
+            String::from_utf8_lossy(&output.stdout).trim() == "yes"
+        }
+        _ => false,
+    }
+}
+
+/// Checks whether the session's screen is locked.
+///
+/// Reads `CGSSessionScreenIsLocked` from the window server's session
+/// dictionary, which only has the key while the screen is locked. Without a
+/// window server session, falls back to the console users in the IORegistry.
+#[cfg(target_os = "macos")]
+pub fn is_screen_locked() -> bool {
+    macos_session::screen_is_locked().unwrap_or_else(|| {
+        match Command::new("ioreg")
+            .args(["-n", "Root", "-d", "1"])
+            .output()
+        {
+            Ok(output) => console_user_locked(&String::from_utf8_lossy(&output.stdout)),
+            Err(_) => false,
+        }
+    })
+}
+
+/// Finds the console user in `ioreg -n Root -d 1` output and whether their
+/// screen is locked. The users are a list of dictionaries on one line:
+///
+/// ```text
+/// "IOConsoleUsers" = ({"kCGSSessionOnConsoleKey"=Yes,...,"CGSSessionScreenIsLocked"=Yes,...})
+/// ```
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn console_user_locked(ioreg: &str) -> bool {
+    let Some(users) = ioreg
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("\"IOConsoleUsers\""))
+    else {
+        return false;
+    };
+    let users: String = users.chars().filter(|c| !c.is_whitespace()).collect();
+    users.split('}').any(|user| {
+        user.contains("\"kCGSSessionOnConsoleKey\"=Yes")
+            && user.contains("\"CGSSessionScreenIsLocked\"=Yes")
+    })
+}
+
+#[cfg(target_os = "macos")]
+mod macos_session {
+    use std::ffi::{c_char, c_void};
+
+    type CFTypeRef = *const c_void;
+
+    const K_CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGSessionCopyCurrentDictionary() -> CFTypeRef;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFStringCreateWithCString(
+            alloc: CFTypeRef,
+            c_str: *const c_char,
+            encoding: u32,
+        ) -> CFTypeRef;
+        fn CFDictionaryGetValue(dict: CFTypeRef, key: CFTypeRef) -> CFTypeRef;
+        fn CFGetTypeID(cf: CFTypeRef) -> usize;
+        fn CFBooleanGetTypeID() -> usize;
+        fn CFBooleanGetValue(boolean: CFTypeRef) -> u8;
+        fn CFRelease(cf: CFTypeRef);
+    }
+
+    /// None when this process has no window server session to ask.
+    pub fn screen_is_locked() -> Option<bool> {
+        unsafe {
+            let session = CGSessionCopyCurrentDictionary();
+            if session.is_null() {
+                return None;
+            }
+            let key = CFStringCreateWithCString(
+                std::ptr::null(),
+                c"CGSSessionScreenIsLocked".as_ptr(),
+                K_CF_STRING_ENCODING_UTF8,
+            );
+            let value = if key.is_null() {
+                std::ptr::null()
+            } else {
+                CFDictionaryGetValue(session, key)
+            };
+            let locked = !value.is_null()
+                && CFGetTypeID(value) == CFBooleanGetTypeID()
+                && CFBooleanGetValue(value) != 0;
+            if !key.is_null() {
+                CFRelease(key);
+            }
+            CFRelease(session);
+            Some(locked)
+        }
+    }
+}
+
+/// Checks whether the session's screen is locked.
+///
+/// While it is, the input desktop is the secure Winlogon one, which we can't
+/// open or switch to.
+#[cfg(target_os = "windows")]
+pub fn is_screen_locked() -> bool {
+    use windows::Win32::System::StationsAndDesktops::{
+        CloseDesktop, OpenInputDesktop, SwitchDesktop, DESKTOP_CONTROL_FLAGS, DESKTOP_SWITCHDESKTOP,
+    };
+
+    unsafe {
+        match OpenInputDesktop(DESKTOP_CONTROL_FLAGS(0), false, DESKTOP_SWITCHDESKTOP) {
+            Ok(desktop) => {
+                let locked = SwitchDesktop(desktop).is_err();
+                let _ = CloseDesktop(desktop);
+                locked
+            }
+            Err(_) => true,
+        }
+    }
+}
+
+/// Stub implementation for other platforms
+/// Always returns false since there's no way to tell
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+pub fn is_screen_locked() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Abridged `ioreg -n Root -d 1` output with two users logged in
+    const IOREG_LOCKED: &str = r#"+-o Root  <class IORegistryEntry, id 0x100000100, retain 28>
+    {
+      "IOKitBuildVersion" = "Darwin Kernel Version 23.4.0: Fri Mar 15 00:10:42 PDT 2024; root:xnu-10063.101.17~1/RELEASE_ARM64_T6000"
+      "IOConsoleLocks" = ()
+      "IOConsoleUsers" = ({"kCGSSessionOnConsoleKey"=No,"kSCSecuritySessionID"=100021,"kCGSSessionUserNameKey"="guest","kCGSSessionUserIDKey"=502,"kCGSessionLoginDoneKey"=Yes},{"kCGSSessionOnConsoleKey"=Yes,"kSCSecuritySessionID"=100016,"kCGSSessionSecureInputPID"=0,"kCGSSessionGroupIDKey"=20,"kCGSSessionAuditIDKey"=100016,"kCGSessionLoginDoneKey"=Yes,"kCGSSessionSystemSafeBoot"=No,"kCGSSessionUserNameKey"="jane","CGSSessionScreenIsLocked"=Yes,"kCGSessionLongUserNameKey"="Jane Appleseed","kCGSSessionUserIDKey"=501,"kCGSSessionConsoleSetKey"=0,"kCGSSessionLoginwindowSafeLogin"=No})
+      "IORegistryPlanes" = {"IOService"="IOService","IOPower"="IOPower","IODeviceTree"="IODeviceTree"}
+    }
+"#;
+
+    #[test]
+    fn test_console_user_locked() {
+        assert!(console_user_locked(IOREG_LOCKED));
+
+        let unlocked = IOREG_LOCKED.replace(r#""CGSSessionScreenIsLocked"=Yes,"#, "");
+        assert!(!console_user_locked(&unlocked));
+
+        // Only the user at the console counts
+        let background = IOREG_LOCKED.replace(
+            r#""kCGSSessionOnConsoleKey"=Yes"#,
+            r#""kCGSSessionOnConsoleKey"=No"#,
+        );
+        assert!(!console_user_locked(&background));
+
+        assert!(!console_user_locked(""));
+    }
+}
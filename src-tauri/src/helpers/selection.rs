@@ -0,0 +1,745 @@
+//! Direct access to the Linux clipboard, for what the clipboard plugin can't
+//! do: seeing when another application reads the text we pasted, and saving
+//! and restoring every format on the clipboard rather than just its text.
+//!
+//! - X11: we become the owner of the `CLIPBOARD` selection ourselves and
+//!   serve conversion requests.
+//! - Wayland: `wl-copy --paste-once` serves a single paste and then exits;
+//!   snapshots go through the data-control protocol, and through XWayland on
+//!   compositors without it.
+
+use std::io::{Read, Write};
+use std::os::fd::AsRawFd;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use x11rb::connection::{Connection, RequestConnection as _};
+use x11rb::protocol::xproto::{
+    Atom, AtomEnum, ConnectionExt as _, CreateWindowAux, EventMask, PropMode, Property,
+    SelectionNotifyEvent, SelectionRequestEvent, Window, WindowClass, SELECTION_NOTIFY_EVENT,
+};
+use x11rb::protocol::Event;
+use x11rb::rust_connection::RustConnection;
+use x11rb::wrapper::ConnectionExt as _;
+use x11rb::{COPY_DEPTH_FROM_PARENT, COPY_FROM_PARENT, CURRENT_TIME, NONE};
+
+/// How often the X11 server thread checks for requests and cancellation
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+/// Text targets offered while pasting, preferred first
+const TEXT_TARGETS: [&str; 5] = [
+    "UTF8_STRING",
+    "text/plain;charset=utf-8",
+    "text/plain",
+    "TEXT",
+    "STRING",
+];
+/// Asks clipboard managers (Klipper, CopyQ, GPaste and others) to keep an
+/// entry out of their history
+const SENSITIVE_HINT: (&str, &[u8]) = ("x-kde-passwordManagerHint", b"secret");
+/// Time `wl-copy` gets to take the selection before we send the keystroke
+const WL_COPY_SETTLE: Duration = Duration::from_millis(100);
+
+/// What became of the text after the paste keystroke.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadOutcome {
+    /// An application fetched it, this long after the keystroke
+    Read(Duration),
+    /// Another client (typically a clipboard manager) took over the
+    /// clipboard, so we can't see reads any more
+    Lost,
+    /// Nobody fetched it in time
+    TimedOut,
+}
+
+enum Backend {
+    X11 {
+        events_rx: mpsc::Receiver<OfferEvent>,
+        stop: Arc<AtomicBool>,
+        handle: Option<thread::JoinHandle<()>>,
+    },
+    Wayland(Child),
+}
+
+enum OfferEvent {
+    Read(Instant),
+    Lost,
+}
+
+/// Text offered on the clipboard until it has been read or dropped.
+pub struct ClipboardOffer {
+    backend: Backend,
+    lost: bool,
+}
+
+impl ClipboardOffer {
+    /// Offers `text` on the clipboard. Fails if neither a display server
+    /// connection nor `wl-copy` is available, so callers can fall back to
+    /// writing the clipboard blind.
+    pub fn new(text: &str) -> Result<Self, String> {
+        let backend = if crate::utils::is_wayland() {
+            Self::wayland(text)?
+        } else {
+            Self::x11(text)?
+        };
+        Ok(Self {
+            backend,
+            lost: false,
+        })
+    }
+
+    fn wayland(text: &str) -> Result<Backend, String> {
+        let mut child = Command::new("wl-copy")
+            .args([
+                "--foreground",
+                "--paste-once",
+                "--type",
+                "text/plain;charset=utf-8",
+            ])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("Failed to execute wl-copy: {}", e))?;
+
+        let written = child
+            .stdin
+            .take()
+            .map(|mut stdin| stdin.write_all(text.as_bytes()));
+        if !matches!(written, Some(Ok(()))) {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err("Failed to hand text to wl-copy".into());
+        }
+
+        thread::sleep(WL_COPY_SETTLE);
+        if let Ok(Some(status)) = child.try_wait() {
+            return Err(format!("wl-copy exited early: {}", status));
+        }
+        Ok(Backend::Wayland(child))
+    }
+
+    fn x11(text: &str) -> Result<Backend, String> {
+        let (ready_tx, ready_rx) = mpsc::channel();
+        let (events_tx, events_rx) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let handle = {
+            let text = text.to_string();
+            let stop = stop.clone();
+            thread::Builder::new()
+                .name("clipboard-offer".to_string())
+                .spawn(move || {
+                    let server = X11Client::connect().and_then(|client| {
+                        let formats = TEXT_TARGETS
+                            .iter()
+                            .map(|name| Ok((client.atom(name)?, text.clone().into_bytes())))
+                            .collect::<X11Result<Vec<_>>>()?;
+                        X11Server::new(client, formats)
+                    });
+                    let result = server.and_then(|server| {
+                        let _ = ready_tx.send(Ok(()));
+                        server.run(&stop, Some(&events_tx))
+                    });
+                    if let Err(e) = result {
+                        // Ignored once we're past the ready message
+                        let _ = ready_tx.send(Err(e.to_string()));
+                        log::debug!("Clipboard offer ended: {}", e);
+                    }
+                })
+                .map_err(|e| format!("Failed to start clipboard offer: {}", e))?
+        };
+
+        match ready_rx.recv() {
+            Ok(Ok(())) => Ok(Backend::X11 {
+                events_rx,
+                stop,
+                handle: Some(handle),
+            }),
+            Ok(Err(e)) => Err(format!("Failed to own the X11 clipboard: {}", e)),
+            Err(_) => Err("Clipboard offer thread exited".into()),
+        }
+    }
+
+    /// Whether the text is still on offer. Clipboard managers read (and on
+    /// X11 often take over) new clipboard contents as soon as they appear;
+    /// reads before the paste keystroke don't count.
+    pub fn is_offered(&mut self) -> bool {
+        match &mut self.backend {
+            Backend::X11 { events_rx, .. } => {
+                while let Ok(event) = events_rx.try_recv() {
+                    if let OfferEvent::Lost = event {
+                        self.lost = true;
+                    }
+                }
+            }
+            Backend::Wayland(child) => {
+                // A paste-once offer is gone after any read
+                if !matches!(child.try_wait(), Ok(None)) {
+                    self.lost = true;
+                }
+            }
+        }
+        !self.lost
+    }
+
+    /// Waits for an application to fetch the text after the paste
+    /// keystroke sent at `since`.
+    pub fn wait_for_read(&mut self, since: Instant, timeout: Duration) -> ReadOutcome {
+        if self.lost {
+            return ReadOutcome::Lost;
+        }
+        let deadline = since + timeout;
+
+        match &mut self.backend {
+            Backend::X11 { events_rx, .. } => loop {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                match events_rx.recv_timeout(remaining) {
+                    Ok(OfferEvent::Read(at)) if at >= since => {
+                        return ReadOutcome::Read(at - since)
+                    }
+                    Ok(OfferEvent::Read(_)) => {}
+                    Ok(OfferEvent::Lost) => {
+                        self.lost = true;
+                        return ReadOutcome::Lost;
+                    }
+                    Err(mpsc::RecvTimeoutError::Timeout) => return ReadOutcome::TimedOut,
+                    Err(mpsc::RecvTimeoutError::Disconnected) => return ReadOutcome::Lost,
+                }
+            },
+            Backend::Wayland(child) => {
+                while Instant::now() < deadline {
+                    match child.try_wait() {
+                        Ok(Some(_)) => return ReadOutcome::Read(since.elapsed()),
+                        Ok(None) => thread::sleep(POLL_INTERVAL),
+                        Err(_) => return ReadOutcome::Lost,
+                    }
+                }
+                ReadOutcome::TimedOut
+            }
+        }
+    }
+}
+
+impl Drop for ClipboardOffer {
+    /// Withdraws the text from the clipboard if it's still ours.
+    fn drop(&mut self) {
+        match &mut self.backend {
+            Backend::X11 { stop, handle, .. } => {
+                stop.store(true, Ordering::Relaxed);
+                if let Some(handle) = handle.take() {
+                    let _ = handle.join();
+                }
+            }
+            Backend::Wayland(child) => {
+                let _ = child.kill();
+                let _ = child.wait();
+            }
+        }
+    }
+}
+
+/// Every format on the clipboard with its data, by MIME type (Wayland) or
+/// target name (X11). Empty when the clipboard is.
+pub fn snapshot() -> Result<Vec<(String, Vec<u8>)>, String> {
+    if crate::utils::is_wayland() {
+        match wayland_snapshot() {
+            Ok(formats) => return Ok(formats),
+            Err(e) => log::debug!("Wayland clipboard unavailable, trying XWayland: {}", e),
+        }
+    }
+    x11_snapshot().map_err(|e| format!("Failed to read the clipboard: {}", e))
+}
+
+/// Puts `formats` (from [`snapshot`]) back on the clipboard, or empties it
+/// if there are none.
+pub fn restore(formats: Vec<(String, Vec<u8>)>) -> Result<(), String> {
+    if crate::utils::is_wayland() {
+        match wayland_restore(formats.clone()) {
+            Ok(()) => return Ok(()),
+            Err(e) => log::debug!("Wayland clipboard unavailable, trying XWayland: {}", e),
+        }
+    }
+    x11_restore(formats).map_err(|e| format!("Failed to restore the clipboard: {}", e))
+}
+
+/// Puts `text` on the clipboard flagged as sensitive, so clipboard managers
+/// that honour the hint don't record it.
+pub fn write_sensitive(text: &str) -> Result<(), String> {
+    let mut formats: Vec<_> = TEXT_TARGETS
+        .iter()
+        .map(|name| (name.to_string(), text.as_bytes().to_vec()))
+        .collect();
+    formats.push((SENSITIVE_HINT.0.to_string(), SENSITIVE_HINT.1.to_vec()));
+    restore(formats)
+}
+
+fn wayland_snapshot() -> Result<Vec<(String, Vec<u8>)>, Box<dyn std::error::Error>> {
+    use wl_clipboard_rs::paste::{
+        get_contents, get_mime_types, ClipboardType, Error, MimeType, Seat,
+    };
+
+    let mut types: Vec<String> = match get_mime_types(ClipboardType::Regular, Seat::Unspecified) {
+        Ok(types) => types.into_iter().collect(),
+        Err(Error::ClipboardEmpty) => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    types.sort();
+
+    let mut formats = Vec::new();
+    let mut total = 0;
+    for mime in types {
+        let (mut pipe, _) = get_contents(
+            ClipboardType::Regular,
+            Seat::Unspecified,
+            MimeType::Specific(&mime),
+        )?;
+        match read_transfer(&mut pipe)? {
+            Some(data) if total + data.len() <= MAX_SNAPSHOT_BYTES => {
+                total += data.len();
+                formats.push((mime, data));
+            }
+            Some(_) => log::warn!("Clipboard too large to keep everything, skipping {}", mime),
+            None => log::debug!("Clipboard source didn't send {} in time", mime),
+        }
+    }
+    Ok(formats)
+}
+
+/// Reads one Wayland clipboard transfer to the end. Like an X11 conversion,
+/// it gives up (`None`) once the source has been silent for
+/// `CONVERT_TIMEOUT` or sent more than `MAX_SNAPSHOT_BYTES`; dropping the
+/// pipe then tells the source to stop.
+fn read_transfer(pipe: &mut (impl Read + AsRawFd)) -> std::io::Result<Option<Vec<u8>>> {
+    let mut data = Vec::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut deadline = Instant::now() + CONVERT_TIMEOUT;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let mut pollfd = libc::pollfd {
+            fd: pipe.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let ready = unsafe { libc::poll(&mut pollfd, 1, remaining.as_millis() as libc::c_int) };
+        if ready < 0 {
+            let e = std::io::Error::last_os_error();
+            if e.kind() == std::io::ErrorKind::Interrupted {
+                continue;
+            }
+            return Err(e);
+        }
+        if ready == 0 {
+            return Ok(None);
+        }
+
+        // Readable or hung up, so this doesn't block
+        let read = pipe.read(&mut buf)?;
+        if read == 0 {
+            return Ok(Some(data));
+        }
+        data.extend_from_slice(&buf[..read]);
+        if data.len() > MAX_SNAPSHOT_BYTES {
+            return Ok(None);
+        }
+        deadline = Instant::now() + CONVERT_TIMEOUT;
+    }
+}
+
+fn wayland_restore(formats: Vec<(String, Vec<u8>)>) -> Result<(), Box<dyn std::error::Error>> {
+    use wl_clipboard_rs::copy::{self, ClipboardType, MimeSource, MimeType, Options, Seat, Source};
+
+    if formats.is_empty() {
+        copy::clear(ClipboardType::Regular, Seat::All)?;
+        return Ok(());
+    }
+
+    let sources = formats
+        .into_iter()
+        .map(|(mime, data)| MimeSource {
+            source: Source::Bytes(data.into_boxed_slice()),
+            mime_type: MimeType::Specific(mime),
+        })
+        .collect();
+    // Served from a background thread until something else is copied
+    Options::new().copy_multi(sources)?;
+    Ok(())
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+/// Targets that describe or act on the selection rather than hold data
+const META_TARGETS: [&str; 7] = [
+    "TARGETS",
+    "MULTIPLE",
+    "TIMESTAMP",
+    "SAVE_TARGETS",
+    "DELETE",
+    "INSERT_SELECTION",
+    "INSERT_PROPERTY",
+];
+/// How long the owner gets to answer one conversion request
+const CONVERT_TIMEOUT: Duration = Duration::from_secs(1);
+/// Snapshots stop collecting formats past this size
+const MAX_SNAPSHOT_BYTES: usize = 32 * 1024 * 1024;
+/// Bytes of a ChangeProperty request that aren't the data
+const CHANGE_PROPERTY_HEADER: usize = 24;
+
+type X11Result<T> = Result<T, Box<dyn std::error::Error>>;
+
+/// A display connection with a tiny unmapped window of our own.
+struct X11Client {
+    conn: RustConnection,
+    window: Window,
+    clipboard: Atom,
+    targets: Atom,
+}
+
+impl X11Client {
+    fn connect() -> X11Result<Self> {
+        let (conn, screen_num) = x11rb::connect(None)?;
+        let root = conn.setup().roots[screen_num].root;
+        let window = conn.generate_id()?;
+        conn.create_window(
+            COPY_DEPTH_FROM_PARENT,
+            window,
+            root,
+            0,
+            0,
+            1,
+            1,
+            0,
+            WindowClass::INPUT_OUTPUT,
+            COPY_FROM_PARENT,
+            &CreateWindowAux::new().event_mask(EventMask::PROPERTY_CHANGE),
+        )?;
+
+        let clipboard = conn.intern_atom(false, b"CLIPBOARD")?.reply()?.atom;
+        let targets = conn.intern_atom(false, b"TARGETS")?.reply()?.atom;
+        Ok(Self {
+            conn,
+            window,
+            clipboard,
+            targets,
+        })
+    }
+
+    fn atom(&self, name: &str) -> X11Result<Atom> {
+        Ok(self.conn.intern_atom(false, name.as_bytes())?.reply()?.atom)
+    }
+
+    fn atom_name(&self, atom: Atom) -> X11Result<String> {
+        let name = self.conn.get_atom_name(atom)?.reply()?.name;
+        Ok(String::from_utf8_lossy(&name).into_owned())
+    }
+
+    fn has_owner(&self) -> X11Result<bool> {
+        Ok(self
+            .conn
+            .get_selection_owner(self.clipboard)?
+            .reply()?
+            .owner
+            != NONE)
+    }
+
+    fn close(&self) -> X11Result<()> {
+        self.conn.destroy_window(self.window)?;
+        self.conn.flush()?;
+        Ok(())
+    }
+
+    /// Asks the owner for the clipboard as `target`, following the INCR
+    /// protocol for large transfers. `None` if the owner refused.
+    fn convert(&self, target: Atom) -> X11Result<Option<Vec<u8>>> {
+        let property = self.atom("HANDY_CLIPBOARD")?;
+        self.conn
+            .convert_selection(self.window, self.clipboard, target, property, CURRENT_TIME)?;
+        self.conn.flush()?;
+
+        let deadline = Instant::now() + CONVERT_TIMEOUT;
+        let notify = loop {
+            match self.conn.poll_for_event()? {
+                Some(Event::SelectionNotify(notify)) if notify.target == target => break notify,
+                Some(_) => {}
+                None if Instant::now() > deadline => return Ok(None),
+                None => thread::sleep(POLL_INTERVAL),
+            }
+        };
+        if notify.property == NONE {
+            return Ok(None);
+        }
+
+        let reply = self
+            .conn
+            .get_property(true, self.window, property, AtomEnum::ANY, 0, u32::MAX)?
+            .reply()?;
+        if reply.type_ != self.atom("INCR")? {
+            return Ok(Some(reply.value));
+        }
+
+        // Deleting the INCR property (above) asks for the first chunk; an
+        // empty chunk ends the transfer
+        let mut data = Vec::new();
+        let mut deadline = Instant::now() + CONVERT_TIMEOUT;
+        loop {
+            match self.conn.poll_for_event()? {
+                Some(Event::PropertyNotify(event))
+                    if event.atom == property && event.state == Property::NEW_VALUE =>
+                {
+                    let chunk = self
+                        .conn
+                        .get_property(true, self.window, property, AtomEnum::ANY, 0, u32::MAX)?
+                        .reply()?;
+                    if chunk.value.is_empty() {
+                        return Ok(Some(data));
+                    }
+                    data.extend_from_slice(&chunk.value);
+                    if data.len() > MAX_SNAPSHOT_BYTES {
+                        return Ok(None);
+                    }
+                    deadline = Instant::now() + CONVERT_TIMEOUT;
+                }
+                Some(_) => {}
+                None if Instant::now() > deadline => return Ok(None),
+                None => thread::sleep(POLL_INTERVAL),
+            }
+        }
+    }
+}
+
+/// Every format on the X11 clipboard, by target name. Empty if the
+/// clipboard has no owner.
+fn x11_snapshot() -> X11Result<Vec<(String, Vec<u8>)>> {
+    let client = X11Client::connect()?;
+    let mut formats = Vec::new();
+    if !client.has_owner()? {
+        client.close()?;
+        return Ok(formats);
+    }
+
+    let targets: Vec<Atom> = match client.convert(client.targets)? {
+        // TARGETS is a list of 32-bit atoms in the client's byte order
+        Some(list) => list
+            .chunks_exact(4)
+            .map(|b| u32::from_ne_bytes([b[0], b[1], b[2], b[3]]))
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let mut total = 0;
+    for target in targets {
+        let name = client.atom_name(target)?;
+        if META_TARGETS.contains(&name.as_str()) || formats.iter().any(|(n, _)| *n == name) {
+            continue;
+        }
+        match client.convert(target)? {
+            Some(data) if total + data.len() <= MAX_SNAPSHOT_BYTES => {
+                total += data.len();
+                formats.push((name, data));
+            }
+            Some(_) => log::warn!("Clipboard too large to keep everything, skipping {}", name),
+            None => log::debug!("Clipboard owner refused {}", name),
+        }
+    }
+
+    client.close()?;
+    Ok(formats)
+}
+
+/// Makes `formats` the X11 clipboard, served from a background thread until
+/// another client takes the clipboard. No formats leaves it empty. Formats
+/// bigger than the server's maximum request size are skipped.
+fn x11_restore(formats: Vec<(String, Vec<u8>)>) -> X11Result<()> {
+    if formats.is_empty() {
+        let client = X11Client::connect()?;
+        client
+            .conn
+            .set_selection_owner(NONE, client.clipboard, CURRENT_TIME)?;
+        return client.close();
+    }
+
+    let (ready_tx, ready_rx) = mpsc::channel();
+    thread::Builder::new()
+        .name("clipboard-restore".to_string())
+        .spawn(move || {
+            let server = X11Client::connect().and_then(|client| {
+                let formats = formats
+                    .into_iter()
+                    .map(|(name, data)| Ok((client.atom(&name)?, data)))
+                    .collect::<X11Result<Vec<_>>>()?;
+                X11Server::new(client, formats)
+            });
+            let result = server.and_then(|server| {
+                let _ = ready_tx.send(Ok(()));
+                server.run(&AtomicBool::new(false), None)
+            });
+            if let Err(e) = result {
+                let _ = ready_tx.send(Err(e.to_string()));
+                log::debug!("Restored clipboard no longer served: {}", e);
+            }
+        })?;
+
+    match ready_rx.recv() {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(e)) => Err(e.into()),
+        Err(_) => Err("clipboard restore thread exited".into()),
+    }
+}
+
+/// Serves a fixed set of formats as the owner of the X11 clipboard.
+struct X11Server {
+    client: X11Client,
+    formats: Vec<(Atom, Vec<u8>)>,
+}
+
+impl X11Server {
+    /// Formats too big for a single request would need the INCR protocol,
+    /// which we don't serve, so they're left out.
+    fn new(client: X11Client, mut formats: Vec<(Atom, Vec<u8>)>) -> X11Result<Self> {
+        let conn = &client.conn;
+        let max_data = conn
+            .maximum_request_bytes()
+            .saturating_sub(CHANGE_PROPERTY_HEADER);
+        let mut skipped = Vec::new();
+        formats.retain(|(target, data)| {
+            let fits = data.len() <= max_data;
+            if !fits {
+                skipped.push((*target, data.len()));
+            }
+            fits
+        });
+        for (target, len) in skipped {
+            log::warn!(
+                "Not restoring clipboard format {} ({} bytes): larger than one X11 request",
+                client.atom_name(target)?,
+                len
+            );
+        }
+
+        conn.set_selection_owner(client.window, client.clipboard, CURRENT_TIME)?;
+        if conn.get_selection_owner(client.clipboard)?.reply()?.owner != client.window {
+            return Err("another client kept the selection".into());
+        }
+        conn.flush()?;
+        Ok(Self { client, formats })
+    }
+
+    /// Serves requests until told to stop or someone else takes the
+    /// clipboard, reporting each time a format is fetched.
+    fn run(self, stop: &AtomicBool, events_tx: Option<&mpsc::Sender<OfferEvent>>) -> X11Result<()> {
+        let notify = |event: OfferEvent| {
+            if let Some(tx) = events_tx {
+                let _ = tx.send(event);
+            }
+        };
+        let client = &self.client;
+
+        while !stop.load(Ordering::Relaxed) {
+            match client.conn.poll_for_event()? {
+                Some(Event::SelectionRequest(request)) => {
+                    if self.answer(&request)? {
+                        notify(OfferEvent::Read(Instant::now()));
+                    }
+                }
+                Some(Event::SelectionClear(clear)) if clear.selection == client.clipboard => {
+                    notify(OfferEvent::Lost);
+                    return client.close();
+                }
+                Some(_) => {}
+                None => thread::sleep(POLL_INTERVAL),
+            }
+        }
+
+        client
+            .conn
+            .set_selection_owner(NONE, client.clipboard, CURRENT_TIME)?;
+        client.close()
+    }
+
+    /// Converts the selection for one request; true if it fetched data.
+    fn answer(&self, request: &SelectionRequestEvent) -> X11Result<bool> {
+        let client = &self.client;
+        // Obsolete clients leave the property empty and expect the target
+        let property = if request.property == NONE {
+            request.target
+        } else {
+            request.property
+        };
+
+        let data = self
+            .formats
+            .iter()
+            .find(|(target, _)| *target == request.target);
+        let served = if request.selection != client.clipboard {
+            None
+        } else if request.target == client.targets {
+            let mut targets = vec![client.targets];
+            targets.extend(self.formats.iter().map(|(target, _)| *target));
+            client.conn.change_property32(
+                PropMode::REPLACE,
+                request.requestor,
+                property,
+                AtomEnum::ATOM,
+                &targets,
+            )?;
+            Some(false)
+        } else if let Some((target, data)) = data {
+            client.conn.change_property8(
+                PropMode::REPLACE,
+                request.requestor,
+                property,
+                *target,
+                data,
+            )?;
+            Some(true)
+        } else {
+            None
+        };
+
+        let notify = SelectionNotifyEvent {
+            response_type: SELECTION_NOTIFY_EVENT,
+            sequence: 0,
+            time: request.time,
+            requestor: request.requestor,
+            selection: request.selection,
+            target: request.target,
+            property: if served.is_some() { property } else { NONE },
+        };
+        client
+            .conn
+            .send_event(false, request.requestor, EventMask::NO_EVENT, notify)?;
+        client.conn.flush()?;
+
+        Ok(served == Some(true))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixStream;
+
+    #[test]
+    fn test_read_transfer_to_end() {
+        let (mut source, mut pipe) = UnixStream::pair().unwrap();
+        let writer = thread::spawn(move || {
+            source.write_all(b"<b>hi</b>").unwrap();
+        });
+        writer.join().unwrap();
+        assert_eq!(
+            read_transfer(&mut pipe).unwrap().as_deref(),
+            Some(&b"<b>hi</b>"[..])
+        );
+    }
+
+    #[test]
+    fn test_read_transfer_gives_up_on_a_silent_source() {
+        // The source keeps its end open and never writes
+        let (_source, mut pipe) = UnixStream::pair().unwrap();
+        let started = Instant::now();
+        assert_eq!(read_transfer(&mut pipe).unwrap(), None);
+        assert!(started.elapsed() < CONVERT_TIMEOUT * 3);
+    }
+}
@@ -0,0 +1,209 @@
+//! What the model and transcription managers need from their surroundings:
+//! settings, directories and a place to send events. The desktop app
+//! provides these through its `AppHandle`; `handy-cli` runs the same
+//! managers on a [`HeadlessHost`].
+
+use crate::settings::{self, AppSettings, SETTINGS_STORE_PATH};
+use anyhow::{anyhow, Result};
+use log::warn;
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Bundle identifier; the data directory is named after it.
+pub const APP_IDENTIFIER: &str = "com.pais.handy";
+
+pub trait Host: Send + Sync + 'static {
+    fn settings(&self) -> AppSettings;
+    fn write_settings(&self, settings: AppSettings);
+    fn app_data_dir(&self) -> Result<PathBuf>;
+    /// Absolute path of a bundled file such as `resources/models/silero_vad_v4.onnx`.
+    fn resource_path(&self, relative: &str) -> Result<PathBuf>;
+    fn emit_json(&self, event: &str, payload: serde_json::Value);
+}
+
+impl dyn Host {
+    pub fn emit<S: Serialize>(&self, event: &str, payload: S) {
+        match serde_json::to_value(payload) {
+            Ok(value) => self.emit_json(event, value),
+            Err(e) => warn!("Failed to serialize {} event: {}", event, e),
+        }
+    }
+}
+
+impl Host for AppHandle {
+    fn settings(&self) -> AppSettings {
+        settings::get_settings(self)
+    }
+
+    fn write_settings(&self, settings: AppSettings) {
+        settings::write_settings(self, settings)
+    }
+
+    fn app_data_dir(&self) -> Result<PathBuf> {
+        self.path()
+            .app_data_dir()
+            .map_err(|e| anyhow!("Failed to get app data dir: {}", e))
+    }
+
+    fn resource_path(&self, relative: &str) -> Result<PathBuf> {
+        self.path()
+            .resolve(relative, tauri::path::BaseDirectory::Resource)
+            .map_err(|e| anyhow!("Failed to resolve resource {}: {}", relative, e))
+    }
+
+    fn emit_json(&self, event: &str, payload: serde_json::Value) {
+        let _ = Emitter::emit(self, event, payload);
+    }
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+type EventHandler = Box<dyn Fn(&str, &serde_json::Value) + Send + Sync>;
+
+/// A host without a running app. Settings are read from the app's store
+/// once; changes stay in memory so a CLI run never rewrites the desktop
+/// app's settings underneath it.
+pub struct HeadlessHost {
+    data_dir: PathBuf,
+    resource_dir: PathBuf,
+    settings: RwLock<AppSettings>,
+    on_event: Option<EventHandler>,
+}
+
+impl HeadlessHost {
+    pub fn new(data_dir: PathBuf, resource_dir: PathBuf) -> Self {
+        let settings = load_store_settings(&data_dir);
+        Self {
+            data_dir,
+            resource_dir,
+            settings: RwLock::new(settings),
+            on_event: None,
+        }
+    }
+
+    pub fn with_event_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&str, &serde_json::Value) + Send + Sync + 'static,
+    {
+        self.on_event = Some(Box::new(handler));
+        self
+    }
+}
+
+impl Host for HeadlessHost {
+    fn settings(&self) -> AppSettings {
+        self.settings.read().clone()
+    }
+
+    fn write_settings(&self, settings: AppSettings) {
+        *self.settings.write() = settings;
+    }
+
+    fn app_data_dir(&self) -> Result<PathBuf> {
+        Ok(self.data_dir.clone())
+    }
+
+    fn resource_path(&self, relative: &str) -> Result<PathBuf> {
+        // Tauri resources keep their `resources/` prefix inside the bundle
+        let relative = relative.strip_prefix("resources/").unwrap_or(relative);
+        Ok(self.resource_dir.join(relative))
+    }
+
+    fn emit_json(&self, event: &str, payload: serde_json::Value) {
+        if let Some(handler) = &self.on_event {
+            handler(event, &payload);
+        }
+    }
+}
+
+/// Where the desktop app keeps its data on this platform.
+pub fn default_app_data_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "macos")]
+    {
+        let home = std::env::var_os("HOME")?;
+        Some(
+            PathBuf::from(home)
+                .join("Library/Application Support")
+                .join(APP_IDENTIFIER),
+        )
+    }
+    #[cfg(target_os = "windows")]
+    {
+        let appdata = std::env::var_os("APPDATA")?;
+        Some(PathBuf::from(appdata).join(APP_IDENTIFIER))
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let data_home = std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .filter(|p| p.is_absolute())
+            .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".local/share")))?;
+        Some(data_home.join(APP_IDENTIFIER))
+    }
+}
+
+/// Settings from the app's store file, or the defaults when there is none.
+pub fn load_store_settings(data_dir: &Path) -> AppSettings {
+    let path = data_dir.join(SETTINGS_STORE_PATH);
+    let parsed = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|json| serde_json::from_str::<serde_json::Value>(&json).ok())
+        .and_then(|mut store| store.get_mut("settings").map(serde_json::Value::take))
+        .map(serde_json::from_value::<AppSettings>);
+
+    match parsed {
+        Some(Ok(settings)) => settings,
+        Some(Err(e)) => {
+            warn!("Ignoring unreadable settings in {}: {}", path.display(), e);
+            settings::get_default_settings()
+        }
+        None => settings::get_default_settings(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_headless_host_reads_store_settings() {
+        let dir = tempfile::tempdir().unwrap();
+
+        // No store yet: defaults
+        let host = HeadlessHost::new(dir.path().to_path_buf(), PathBuf::from("/res"));
+        assert_eq!(
+            host.settings().selected_model,
+            settings::get_default_settings().selected_model
+        );
+
+        let mut stored = settings::get_default_settings();
+        stored.selected_model = "parakeet-tdt-0.6b-v3".to_string();
+        let store = serde_json::json!({ "settings": stored });
+        std::fs::write(
+            dir.path().join(SETTINGS_STORE_PATH),
+            serde_json::to_vec(&store).unwrap(),
+        )
+        .unwrap();
+
+        let host = HeadlessHost::new(dir.path().to_path_buf(), PathBuf::from("/res"));
+        assert_eq!(host.settings().selected_model, "parakeet-tdt-0.6b-v3");
+
+        // Writes stay in memory
+        let mut changed = host.settings();
+        changed.selected_model = "small".to_string();
+        host.write_settings(changed);
+        assert_eq!(host.settings().selected_model, "small");
+        assert_eq!(
+            load_store_settings(dir.path()).selected_model,
+            "parakeet-tdt-0.6b-v3"
+        );
+
+        assert_eq!(
+            host.resource_path("resources/models/silero_vad_v4.onnx")
+                .unwrap(),
+            PathBuf::from("/res/models/silero_vad_v4.onnx")
+        );
+    }
+}
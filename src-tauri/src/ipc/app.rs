@@ -0,0 +1,234 @@
+//! Connects the control server to the running app.
+
+use super::{
+    write_private_file, AudioBuffer, ControlApi, Endpoint, IpcServer, PORT_FILE, TOKEN_FILE,
+};
+use crate::actions;
+use crate::audio_toolkit::{
+    audio::decode_wav_bytes, AudioRecorder, AudioSource, Pacing, PcmSource, SyntheticSource,
+};
+use crate::managers::audio::{build_vad, AudioRecordingManager};
+use crate::managers::model::ModelManager;
+use crate::managers::queue::{self, TranscriptionQueue};
+use crate::managers::transcription::TranscriptionManager;
+use crate::output;
+use crate::settings::get_settings;
+use crate::utils;
+use log::{debug, info, warn};
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::io::Cursor;
+use std::sync::Arc;
+use tauri::{AppHandle, EventId, Listener, Manager};
+
+/// App events passed on to subscribed clients. The mic level is left out;
+/// it fires many times a second and is only meant for the overlay.
+const FORWARDED_EVENTS: &[&str] = &[
+    "transcription-queue-changed",
+    "transcription-failed",
+    "recording-auto-stopped",
+    "recording-quality",
+    "microphone-disconnected",
+    "microphone-stalled",
+    "model-state-changed",
+    "model-download-progress",
+    "model-download-complete",
+    "model-download-queue-changed",
+    "output-failed",
+    "diarization-failed",
+    "vad-fallback",
+];
+
+const VAD_MODEL: &str = "resources/models/silero_vad_v4.onnx";
+
+struct Running {
+    server: IpcServer,
+    listeners: Vec<EventId>,
+}
+
+/// The running server, if the control API is enabled.
+#[derive(Default)]
+pub struct IpcState(Mutex<Option<Running>>);
+
+/// Starts or stops the server to match `ipc_enabled`.
+pub(crate) fn apply_setting(app: &AppHandle) -> Result<(), String> {
+    let enabled = get_settings(app).ipc_enabled;
+    let state = app.state::<IpcState>();
+    let mut running = state.0.lock();
+
+    if !enabled {
+        if let Some(running) = running.take() {
+            shut_down(app, running);
+        }
+        return Ok(());
+    }
+    if running.is_some() {
+        return Ok(());
+    }
+
+    let started = start(app).map_err(|e| format!("Failed to start the control API: {}", e))?;
+    *running = Some(started);
+    Ok(())
+}
+
+/// Stops the server and removes its socket, e.g. when the app exits.
+pub(crate) fn stop(app: &AppHandle) {
+    if let Some(running) = app.state::<IpcState>().0.lock().take() {
+        shut_down(app, running);
+    }
+}
+
+fn start(app: &AppHandle) -> std::io::Result<Running> {
+    let data_dir = app.path().app_data_dir().map_err(std::io::Error::other)?;
+    std::fs::create_dir_all(&data_dir)?;
+
+    let token = super::generate_token()?;
+    let api = Arc::new(AppControl { app: app.clone() });
+    let server = IpcServer::start(Endpoint::for_data_dir(&data_dir), token.clone(), api)?;
+    // Written after binding so a client never pairs a new token with an old server
+    write_private_file(&data_dir.join(TOKEN_FILE), &token)?;
+    if let Endpoint::Tcp(addr) = server.endpoint() {
+        write_private_file(&data_dir.join(PORT_FILE), &addr.port().to_string())?;
+    }
+
+    let listeners = FORWARDED_EVENTS
+        .iter()
+        .map(|&name| {
+            let publisher = server.publisher();
+            app.listen_any(name, move |event| {
+                let payload = serde_json::from_str(event.payload()).unwrap_or(Value::Null);
+                publisher.publish(name, payload);
+            })
+        })
+        .collect();
+
+    info!("Control API listening on {}", server.endpoint());
+    Ok(Running { server, listeners })
+}
+
+fn shut_down(app: &AppHandle, running: Running) {
+    for id in running.listeners {
+        app.unlisten(id);
+    }
+    if let Ok(data_dir) = app.path().app_data_dir() {
+        let _ = std::fs::remove_file(data_dir.join(TOKEN_FILE));
+        let _ = std::fs::remove_file(data_dir.join(PORT_FILE));
+    }
+    info!("Control API on {} stopped", running.server.endpoint());
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+struct AppControl {
+    app: AppHandle,
+}
+
+impl AppControl {
+    /// Runs a buffer through the recorder the way the microphone's audio
+    /// goes: resampling, DSP and VAD trimming. Returns 16 kHz mono.
+    fn record(&self, audio: AudioBuffer) -> Result<Vec<f32>, String> {
+        let settings = get_settings(&self.app);
+        let vad_path = self
+            .app
+            .path()
+            .resolve(VAD_MODEL, tauri::path::BaseDirectory::Resource)
+            .map_err(|e| format!("Failed to resolve VAD path: {}", e))?;
+        let (vad, load_error) = build_vad(&vad_path, &settings.vad);
+        if let Some(e) = load_error {
+            warn!("{}; using the energy VAD for this request", e);
+        }
+
+        let source: Box<dyn AudioSource> = match audio {
+            AudioBuffer::Wav(bytes) => {
+                let (samples, sample_rate) =
+                    decode_wav_bytes(&bytes).map_err(|e| format!("Invalid WAV data: {}", e))?;
+                Box::new(SyntheticSource::new(sample_rate, Pacing::Unpaced).samples(&samples))
+            }
+            AudioBuffer::Pcm {
+                data,
+                sample_rate,
+                channels,
+                format,
+            } => Box::new(PcmSource::new(
+                Cursor::new(data),
+                sample_rate,
+                channels,
+                format,
+            )),
+        };
+
+        let mut recorder = AudioRecorder::new()
+            .map_err(|e| e.to_string())?
+            .with_vad(vad);
+        recorder.open_source(source).map_err(|e| e.to_string())?;
+        recorder.set_dsp(settings.dsp).map_err(|e| e.to_string())?;
+        recorder.start().map_err(|e| e.to_string())?;
+        let samples = recorder.finish().map_err(|e| e.to_string())?;
+        recorder.close().map_err(|e| e.to_string())?;
+        Ok(samples)
+    }
+}
+
+impl ControlApi for AppControl {
+    fn start_recording(&self, binding_id: &str) -> Result<bool, String> {
+        actions::set_binding_active(&self.app, binding_id, true, "control API")
+    }
+
+    fn stop_recording(&self, binding_id: &str) -> Result<bool, String> {
+        actions::set_binding_active(&self.app, binding_id, false, "control API")
+    }
+
+    fn toggle_recording(&self, binding_id: &str) -> Result<bool, String> {
+        actions::toggle_binding(&self.app, binding_id, "control API")
+    }
+
+    fn cancel(&self) -> Result<(), String> {
+        utils::cancel_current_operation(&self.app);
+        Ok(())
+    }
+
+    fn state(&self) -> Result<Value, String> {
+        let rm = self.app.state::<Arc<AudioRecordingManager>>();
+        let queue = self.app.state::<Arc<TranscriptionQueue>>();
+        let tm = self.app.state::<Arc<TranscriptionManager>>();
+        Ok(json!({
+            "recording": rm.is_recording(),
+            "queue": queue.get_state(),
+            "model": {
+                "selected": get_settings(&self.app).selected_model,
+                "loaded": tm.get_current_model(),
+            },
+        }))
+    }
+
+    fn transcribe(&self, audio: AudioBuffer, deliver_to: Option<&str>) -> Result<String, String> {
+        // Start loading while the audio is prepared; transcription waits for it
+        let tm = self.app.state::<Arc<TranscriptionManager>>();
+        tm.initiate_model_load();
+
+        let samples = self.record(audio)?;
+        debug!("Control API transcription of {} samples", samples.len());
+        let settings = get_settings(&self.app);
+        let text = queue::transcribe_recording(&self.app, samples, &settings)
+            .map_err(|e| e.to_string())?;
+
+        if let Some(binding_id) = deliver_to {
+            if !text.is_empty() {
+                output::deliver(&self.app, &settings, 0, binding_id, &text, None);
+            }
+        }
+        Ok(text)
+    }
+
+    fn list_models(&self) -> Result<Value, String> {
+        let models = self.app.state::<Arc<ModelManager>>().get_available_models();
+        Ok(json!({
+            "selected": get_settings(&self.app).selected_model,
+            "models": models,
+        }))
+    }
+
+    fn select_model(&self, model_id: &str) -> Result<(), String> {
+        crate::commands::models::select_model(&self.app, model_id)
+    }
+}
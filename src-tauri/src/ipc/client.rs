@@ -0,0 +1,114 @@
+use super::protocol::{self, EventParams, Request, RpcError, ServerMessage, MAX_LINE_BYTES};
+use super::{Endpoint, Stream};
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::io::{self, BufReader, Write};
+use std::time::Duration;
+
+#[derive(Debug)]
+pub enum ClientError {
+    /// Couldn't reach the server, or the connection dropped
+    Io(io::Error),
+    /// The server answered with an error
+    Rpc(RpcError),
+    /// The server sent something that isn't a valid reply
+    Protocol(String),
+}
+
+impl std::fmt::Display for ClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClientError::Io(e) => write!(f, "{}", e),
+            ClientError::Rpc(e) => write!(f, "{}", e),
+            ClientError::Protocol(message) => write!(f, "Invalid reply: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<io::Error> for ClientError {
+    fn from(e: io::Error) -> Self {
+        ClientError::Io(e)
+    }
+}
+
+/// A blocking connection to the control server, authenticated on connect.
+pub struct IpcClient {
+    reader: BufReader<Box<dyn Stream>>,
+    writer: Box<dyn Stream>,
+    next_id: u64,
+    /// Events that arrived while waiting for a response
+    events: VecDeque<EventParams>,
+}
+
+impl IpcClient {
+    pub fn connect(endpoint: &Endpoint, token: &str) -> Result<Self, ClientError> {
+        let stream = super::connect(endpoint)?;
+        let writer = stream.try_clone_stream()?;
+        let mut client = Self {
+            reader: BufReader::new(stream),
+            writer,
+            next_id: 1,
+            events: VecDeque::new(),
+        };
+        client.call("auth", json!({ "token": token }))?;
+        Ok(client)
+    }
+
+    /// Gives up on a reply after `timeout`; None waits forever.
+    pub fn set_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.writer.set_read_timeout(timeout)?;
+        self.writer.set_write_timeout(timeout)
+    }
+
+    pub fn call(&mut self, method: &str, params: Value) -> Result<Value, ClientError> {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.writer
+            .write_all(&protocol::to_line(&Request::new(id, method, params)))?;
+
+        loop {
+            match self.read_message()? {
+                ServerMessage::Response(response) if response.id == id => {
+                    return match (response.result, response.error) {
+                        (_, Some(error)) => Err(ClientError::Rpc(error)),
+                        (Some(result), None) => Ok(result),
+                        (None, None) => Err(ClientError::Protocol(
+                            "response without result or error".to_string(),
+                        )),
+                    };
+                }
+                ServerMessage::Response(response) => {
+                    // Only a parse error goes unmatched, and we don't send those
+                    if let Some(error) = response.error {
+                        return Err(ClientError::Rpc(error));
+                    }
+                }
+                ServerMessage::Event { params, .. } => self.events.push_back(params),
+            }
+        }
+    }
+
+    /// Waits for the next event; call `events.subscribe` first.
+    pub fn next_event(&mut self) -> Result<EventParams, ClientError> {
+        if let Some(event) = self.events.pop_front() {
+            return Ok(event);
+        }
+        loop {
+            if let ServerMessage::Event { params, .. } = self.read_message()? {
+                return Ok(params);
+            }
+        }
+    }
+
+    fn read_message(&mut self) -> Result<ServerMessage, ClientError> {
+        let line = protocol::read_line(&mut self.reader, MAX_LINE_BYTES)?.ok_or_else(|| {
+            ClientError::Io(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "The server closed the connection",
+            ))
+        })?;
+        serde_json::from_str(&line).map_err(|e| ClientError::Protocol(e.to_string()))
+    }
+}
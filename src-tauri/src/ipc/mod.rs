@@ -0,0 +1,219 @@
+//! Local control API for scripts and other applications on the same machine.
+//!
+//! JSON-RPC 2.0, one message per line, over a Unix domain socket in the app
+//! data directory (or, where there are none, a TCP port on 127.0.0.1 written
+//! next to it). Every connection starts with an `auth` call carrying the
+//! token from the token file, which only the user can read.
+//!
+//! | Method               | Params                                          | Result |
+//! |----------------------|-------------------------------------------------|--------|
+//! | `auth`               | `{token}`                                       | `true` |
+//! | `recording.start`    | `{binding?}` (default `"transcribe"`)           | whether it started |
+//! | `recording.stop`     | `{binding?}`                                    | whether it stopped |
+//! | `recording.toggle`   | `{binding?}`                                    | whether it's now recording |
+//! | `recording.cancel`   |                                                 | `null` |
+//! | `state`              |                                                 | recording, queue and model state |
+//! | `transcribe`         | `{wav}` or `{pcm, rate, channels?, format?}`, base64, plus `{deliver?}` | `{text}` |
+//! | `models.list`        |                                                 | models and which is selected |
+//! | `models.select`      | `{id}`                                          | `null` |
+//! | `events.subscribe`   | `{events?}` (default: all)                      | `true` |
+//! | `events.unsubscribe` |                                                 | `true` |
+//!
+//! Subscribed clients receive `{"method": "event", "params": {event, payload}}`
+//! notifications with the same names and payloads the frontend gets.
+
+pub(crate) mod app;
+mod client;
+pub mod protocol;
+mod server;
+
+pub use client::{ClientError, IpcClient};
+pub use server::{EventPublisher, IpcServer};
+
+use crate::audio_toolkit::PcmFormat;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::net::SocketAddr;
+use std::net::TcpStream;
+use std::path::Path;
+use std::time::Duration;
+
+/// Token file, rewritten with a fresh token every time the server starts
+pub const TOKEN_FILE: &str = "ipc-token";
+#[cfg(unix)]
+pub const SOCKET_FILE: &str = "handy.sock";
+/// Port of the loopback listener, on platforms without Unix sockets
+pub const PORT_FILE: &str = "ipc-port";
+
+/// What the server controls. The app implements this over its managers;
+/// tests use a stand-in.
+pub trait ControlApi: Send + Sync + 'static {
+    /// Starts recording for a binding; false if it already was.
+    fn start_recording(&self, binding_id: &str) -> Result<bool, String>;
+    /// Stops recording for a binding and queues the transcription; false if
+    /// it wasn't recording.
+    fn stop_recording(&self, binding_id: &str) -> Result<bool, String>;
+    /// Starts or stops, whichever a shortcut press would; true if it's now
+    /// recording.
+    fn toggle_recording(&self, binding_id: &str) -> Result<bool, String>;
+    fn cancel(&self) -> Result<(), String>;
+    fn state(&self) -> Result<serde_json::Value, String>;
+    /// Runs audio through the same pipeline as a recording and returns the
+    /// final text, also delivering it to a binding's outputs if given.
+    fn transcribe(&self, audio: AudioBuffer, deliver_to: Option<&str>) -> Result<String, String>;
+    fn list_models(&self) -> Result<serde_json::Value, String>;
+    fn select_model(&self, model_id: &str) -> Result<(), String>;
+}
+
+/// Audio handed over for transcription.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AudioBuffer {
+    /// A complete WAV file
+    Wav(Vec<u8>),
+    /// Raw interleaved samples
+    Pcm {
+        data: Vec<u8>,
+        sample_rate: u32,
+        channels: u16,
+        format: PcmFormat,
+    },
+}
+
+/// Where the server listens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Endpoint {
+    #[cfg(unix)]
+    Unix(std::path::PathBuf),
+    Tcp(SocketAddr),
+}
+
+impl Endpoint {
+    /// The endpoint the app uses for `data_dir`, before it's bound.
+    #[cfg(unix)]
+    pub fn for_data_dir(data_dir: &Path) -> Self {
+        Endpoint::Unix(data_dir.join(SOCKET_FILE))
+    }
+
+    /// The endpoint the app uses, before it's bound; the port is picked then.
+    #[cfg(not(unix))]
+    pub fn for_data_dir(_data_dir: &Path) -> Self {
+        Endpoint::Tcp(SocketAddr::from(([127, 0, 0, 1], 0)))
+    }
+
+    /// The endpoint of the instance running for `data_dir`, and its token.
+    pub fn discover(data_dir: &Path) -> io::Result<(Self, String)> {
+        let token = fs::read_to_string(data_dir.join(TOKEN_FILE))?
+            .trim()
+            .to_string();
+
+        #[cfg(unix)]
+        let endpoint = Endpoint::Unix(data_dir.join(SOCKET_FILE));
+        #[cfg(not(unix))]
+        let endpoint = {
+            let port = fs::read_to_string(data_dir.join(PORT_FILE))?;
+            let port = port
+                .trim()
+                .parse::<u16>()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            Endpoint::Tcp(SocketAddr::from(([127, 0, 0, 1], port)))
+        };
+
+        Ok((endpoint, token))
+    }
+}
+
+impl std::fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            #[cfg(unix)]
+            Endpoint::Unix(path) => write!(f, "{}", path.display()),
+            Endpoint::Tcp(addr) => write!(f, "{}", addr),
+        }
+    }
+}
+
+/// A new random token, hex encoded.
+pub fn generate_token() -> io::Result<String> {
+    let mut bytes = [0u8; 32];
+    getrandom::fill(&mut bytes).map_err(|e| io::Error::other(e.to_string()))?;
+    Ok(hex::encode(bytes))
+}
+
+/// Writes `contents` to a file only the user can read, replacing it.
+pub(crate) fn write_private_file(path: &Path, contents: &str) -> io::Result<()> {
+    let _ = fs::remove_file(path);
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)?.write_all(contents.as_bytes())
+}
+
+/// Compares tokens in time that doesn't depend on where they differ.
+pub(crate) fn token_matches(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected
+            .bytes()
+            .zip(given.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+/// A connected socket of either kind.
+pub(crate) trait Stream: Read + Write + Send {
+    fn try_clone_stream(&self) -> io::Result<Box<dyn Stream>>;
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()>;
+    fn set_write_timeout(&self, timeout: Option<Duration>) -> io::Result<()>;
+    fn shutdown(&self);
+}
+
+#[cfg(unix)]
+impl Stream for std::os::unix::net::UnixStream {
+    fn try_clone_stream(&self) -> io::Result<Box<dyn Stream>> {
+        Ok(Box::new(self.try_clone()?))
+    }
+
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        std::os::unix::net::UnixStream::set_read_timeout(self, timeout)
+    }
+
+    fn set_write_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        std::os::unix::net::UnixStream::set_write_timeout(self, timeout)
+    }
+
+    fn shutdown(&self) {
+        let _ = std::os::unix::net::UnixStream::shutdown(self, std::net::Shutdown::Both);
+    }
+}
+
+impl Stream for TcpStream {
+    fn try_clone_stream(&self) -> io::Result<Box<dyn Stream>> {
+        Ok(Box::new(self.try_clone()?))
+    }
+
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        TcpStream::set_read_timeout(self, timeout)
+    }
+
+    fn set_write_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        TcpStream::set_write_timeout(self, timeout)
+    }
+
+    fn shutdown(&self) {
+        let _ = TcpStream::shutdown(self, std::net::Shutdown::Both);
+    }
+}
+
+pub(crate) fn connect(endpoint: &Endpoint) -> io::Result<Box<dyn Stream>> {
+    match endpoint {
+        #[cfg(unix)]
+        Endpoint::Unix(path) => Ok(Box::new(std::os::unix::net::UnixStream::connect(path)?)),
+        Endpoint::Tcp(addr) => {
+            let stream = TcpStream::connect(addr)?;
+            stream.set_nodelay(true)?;
+            Ok(Box::new(stream))
+        }
+    }
+}
@@ -0,0 +1,158 @@
+//! JSON-RPC 2.0 messages, one per line in each direction.
+
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use std::io::{self, BufRead, Read};
+
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const INTERNAL_ERROR: i64 = -32603;
+/// The app couldn't do what was asked, e.g. no model is loaded
+pub const APP_ERROR: i64 = -32000;
+/// Missing or wrong token
+pub const UNAUTHORIZED: i64 = -32001;
+
+/// Longest line either side sends; room for a few minutes of base64 audio
+pub const MAX_LINE_BYTES: usize = 64 * 1024 * 1024;
+
+/// Longest line the server reads before the client has authenticated, so an
+/// unknown local process can't make it buffer a huge line
+pub const MAX_AUTH_LINE_BYTES: usize = 4 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Request {
+    pub jsonrpc: String,
+    /// Absent for notifications, which get no response
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<Value>,
+    pub method: String,
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub params: Value,
+}
+
+impl Request {
+    pub fn new(id: u64, method: &str, params: Value) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id: Some(Value::from(id)),
+            method: method.to_string(),
+            params,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn invalid_params(message: impl Into<String>) -> Self {
+        Self::new(INVALID_PARAMS, message)
+    }
+
+    pub fn app(message: impl Into<String>) -> Self {
+        Self::new(APP_ERROR, message)
+    }
+}
+
+impl std::fmt::Display for RpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.message, self.code)
+    }
+}
+
+impl std::error::Error for RpcError {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Response {
+    pub jsonrpc: String,
+    pub id: Value,
+    #[serde(
+        default,
+        deserialize_with = "present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+impl Response {
+    pub fn new(id: Value, result: Result<Value, RpcError>) -> Self {
+        let (result, error) = match result {
+            Ok(value) => (Some(value), None),
+            Err(e) => (None, Some(e)),
+        };
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result,
+            error,
+        }
+    }
+}
+
+/// A `null` result is still a result; a plain `Option` would read it as
+/// missing.
+fn present<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Value>, D::Error> {
+    Value::deserialize(deserializer).map(Some)
+}
+
+/// An event pushed to a subscribed client, sent as a JSON-RPC notification
+/// with method `"event"`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventParams {
+    pub event: String,
+    pub payload: Value,
+}
+
+/// Anything the server sends: a response to a request, or an event.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum ServerMessage {
+    Response(Response),
+    Event { method: String, params: EventParams },
+}
+
+/// Reads one message line of at most `max_bytes`, without the newline. None
+/// at end of stream.
+pub(crate) fn read_line(reader: &mut impl BufRead, max_bytes: usize) -> io::Result<Option<String>> {
+    let mut buf = Vec::new();
+    let read = reader
+        .by_ref()
+        .take(max_bytes as u64 + 1)
+        .read_until(b'\n', &mut buf)?;
+    if read == 0 {
+        return Ok(None);
+    }
+    if buf.len() > max_bytes {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Message too long",
+        ));
+    }
+    if buf.last() == Some(&b'\n') {
+        buf.pop();
+    }
+    String::from_utf8(buf)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Serialises a message as one line.
+pub(crate) fn to_line(message: &impl Serialize) -> Vec<u8> {
+    let mut line = serde_json::to_vec(message).unwrap_or_default();
+    line.push(b'\n');
+    line
+}
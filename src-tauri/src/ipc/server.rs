@@ -0,0 +1,591 @@
+use super::protocol::{
+    self, EventParams, Request, Response, RpcError, INTERNAL_ERROR, INVALID_REQUEST,
+    MAX_AUTH_LINE_BYTES, MAX_LINE_BYTES, METHOD_NOT_FOUND, PARSE_ERROR, UNAUTHORIZED,
+};
+use super::{token_matches, AudioBuffer, ControlApi, Endpoint, Stream};
+use crate::audio_toolkit::PcmFormat;
+use base64::Engine as _;
+use log::{debug, info, warn};
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::io::{self, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Authenticated clients at once; more are refused at `auth`
+const MAX_CLIENTS: usize = 16;
+/// Connections still authenticating at once; more are turned away
+const MAX_PENDING: usize = 8;
+/// A client that stops reading its events is dropped after this long
+const WRITE_TIMEOUT: Duration = Duration::from_secs(2);
+/// Time a new connection gets to authenticate, however slowly it sends
+const AUTH_TIMEOUT: Duration = Duration::from_secs(5);
+/// Messages waiting for a client's writer; a client this far behind is dropped
+const OUTBOX_SIZE: usize = 256;
+
+struct Client {
+    /// Feeds the client's writer thread, so publishing never blocks on it
+    outbox: SyncSender<Vec<u8>>,
+    /// Kept to hang up on the client
+    stream: Box<dyn Stream>,
+    /// Events this client wants; None until it subscribes, empty for all
+    events: Option<HashSet<String>>,
+}
+
+struct Shared {
+    api: Arc<dyn ControlApi>,
+    token: String,
+    stopping: AtomicBool,
+    next_client: AtomicU64,
+    /// Authenticated clients only
+    clients: Mutex<HashMap<u64, Client>>,
+    /// Connections that haven't authenticated yet
+    pending: AtomicUsize,
+}
+
+enum Listener {
+    #[cfg(unix)]
+    Unix(std::os::unix::net::UnixListener),
+    Tcp(TcpListener),
+}
+
+impl Listener {
+    fn accept(&self) -> io::Result<Box<dyn Stream>> {
+        match self {
+            #[cfg(unix)]
+            Listener::Unix(listener) => Ok(Box::new(listener.accept()?.0)),
+            Listener::Tcp(listener) => {
+                let (stream, _) = listener.accept()?;
+                stream.set_nodelay(true)?;
+                Ok(Box::new(stream))
+            }
+        }
+    }
+}
+
+/// The local control server. Stops, and removes its socket, when dropped.
+pub struct IpcServer {
+    endpoint: Endpoint,
+    shared: Arc<Shared>,
+    accept_thread: Option<thread::JoinHandle<()>>,
+}
+
+impl IpcServer {
+    /// Listens on `endpoint`, which must be a Unix socket path or a loopback
+    /// address (port 0 picks a free one), and serves clients that present
+    /// `token`.
+    pub fn start(endpoint: Endpoint, token: String, api: Arc<dyn ControlApi>) -> io::Result<Self> {
+        let (listener, endpoint) = bind(endpoint)?;
+        let shared = Arc::new(Shared {
+            api,
+            token,
+            stopping: AtomicBool::new(false),
+            next_client: AtomicU64::new(1),
+            clients: Mutex::new(HashMap::new()),
+            pending: AtomicUsize::new(0),
+        });
+
+        let accept_thread = {
+            let shared = shared.clone();
+            thread::Builder::new()
+                .name("ipc-accept".to_string())
+                .spawn(move || accept_loop(listener, shared))?
+        };
+        info!("IPC server listening on {}", endpoint);
+
+        Ok(Self {
+            endpoint,
+            shared,
+            accept_thread: Some(accept_thread),
+        })
+    }
+
+    /// Where clients connect, with the port filled in for TCP.
+    pub fn endpoint(&self) -> &Endpoint {
+        &self.endpoint
+    }
+
+    pub fn publisher(&self) -> EventPublisher {
+        EventPublisher {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl Drop for IpcServer {
+    fn drop(&mut self) {
+        self.shared.stopping.store(true, Ordering::SeqCst);
+        // Wake the accept loop so it sees the flag
+        let _ = super::connect(&self.endpoint);
+        if let Some(handle) = self.accept_thread.take() {
+            let _ = handle.join();
+        }
+        for (_, client) in self.shared.clients.lock().drain() {
+            client.stream.shutdown();
+        }
+        #[cfg(unix)]
+        if let Endpoint::Unix(path) = &self.endpoint {
+            let _ = std::fs::remove_file(path);
+        }
+        debug!("IPC server stopped");
+    }
+}
+
+/// Sends events to subscribed clients; cheap to clone.
+#[derive(Clone)]
+pub struct EventPublisher {
+    shared: Arc<Shared>,
+}
+
+impl EventPublisher {
+    pub fn publish(&self, event: &str, payload: Value) {
+        let line = protocol::to_line(&json!({
+            "jsonrpc": "2.0",
+            "method": "event",
+            "params": EventParams {
+                event: event.to_string(),
+                payload,
+            },
+        }));
+
+        let mut clients = self.shared.clients.lock();
+        for (id, client) in clients.iter_mut() {
+            let wanted = match &client.events {
+                Some(events) => events.is_empty() || events.contains(event),
+                None => false,
+            };
+            if wanted && client.outbox.try_send(line.clone()).is_err() {
+                // Too far behind or gone; its connection thread cleans up
+                debug!("Dropping IPC client {} that stopped reading", id);
+                client.events = None;
+                client.stream.shutdown();
+            }
+        }
+    }
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+fn bind(endpoint: Endpoint) -> io::Result<(Listener, Endpoint)> {
+    match endpoint {
+        #[cfg(unix)]
+        Endpoint::Unix(path) => {
+            use std::os::unix::fs::PermissionsExt;
+            use std::os::unix::net::{UnixListener, UnixStream};
+
+            if path.exists() {
+                if UnixStream::connect(&path).is_ok() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::AddrInUse,
+                        format!("Another instance is listening on {}", path.display()),
+                    ));
+                }
+                // Left behind by an instance that didn't shut down cleanly
+                std::fs::remove_file(&path)?;
+            }
+            let listener = UnixListener::bind(&path)?;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+            Ok((Listener::Unix(listener), Endpoint::Unix(path)))
+        }
+        Endpoint::Tcp(addr) => {
+            if !addr.ip().is_loopback() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Refusing to listen on non-loopback address {}", addr),
+                ));
+            }
+            let listener = TcpListener::bind(addr)?;
+            let bound = listener.local_addr()?;
+            Ok((Listener::Tcp(listener), Endpoint::Tcp(bound)))
+        }
+    }
+}
+
+fn accept_loop(listener: Listener, shared: Arc<Shared>) {
+    loop {
+        let accepted = listener.accept();
+        if shared.stopping.load(Ordering::SeqCst) {
+            return;
+        }
+        let stream = match accepted {
+            Ok(stream) => stream,
+            Err(e) => {
+                warn!("IPC accept failed: {}", e);
+                thread::sleep(Duration::from_millis(100));
+                continue;
+            }
+        };
+
+        let Some(pending) = PendingSlot::take(&shared) else {
+            warn!("Too many IPC connections authenticating, turning one away");
+            stream.shutdown();
+            continue;
+        };
+
+        let id = shared.next_client.fetch_add(1, Ordering::Relaxed);
+        let spawned = thread::Builder::new()
+            .name("ipc-client".to_string())
+            .spawn(move || {
+                let shared = pending.shared.clone();
+                if let Err(e) = serve(&shared, id, stream, pending) {
+                    debug!("IPC client {} disconnected: {}", id, e);
+                }
+                shared.clients.lock().remove(&id);
+            });
+        if let Err(e) = spawned {
+            warn!("Failed to start IPC client thread: {}", e);
+        }
+    }
+}
+
+/// A place among the connections still authenticating, given up on
+/// authentication or when the connection ends.
+struct PendingSlot {
+    shared: Arc<Shared>,
+    held: bool,
+}
+
+impl PendingSlot {
+    fn take(shared: &Arc<Shared>) -> Option<Self> {
+        if shared.pending.fetch_add(1, Ordering::SeqCst) >= MAX_PENDING {
+            shared.pending.fetch_sub(1, Ordering::SeqCst);
+            return None;
+        }
+        Some(Self {
+            shared: shared.clone(),
+            held: true,
+        })
+    }
+
+    fn release(&mut self) {
+        if std::mem::take(&mut self.held) {
+            self.shared.pending.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+}
+
+impl Drop for PendingSlot {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+/// Reads a connection that has to authenticate by a deadline, so a client
+/// trickling in a byte at a time can't hold its place indefinitely.
+struct DeadlineReader {
+    stream: Box<dyn Stream>,
+    deadline: Option<Instant>,
+}
+
+impl DeadlineReader {
+    /// Authenticated clients may sit idle between requests.
+    fn clear_deadline(&mut self) -> io::Result<()> {
+        self.deadline = None;
+        self.stream.set_read_timeout(None)
+    }
+}
+
+impl Read for DeadlineReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if let Some(deadline) = self.deadline {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "Didn't authenticate in time",
+                ));
+            }
+            self.stream.set_read_timeout(Some(remaining))?;
+        }
+        self.stream.read(buf)
+    }
+}
+
+/// Sends a client its responses and events in order. Ends when the client
+/// is gone from the table and its connection thread has finished.
+fn write_loop(id: u64, mut stream: Box<dyn Stream>, outbox: Receiver<Vec<u8>>) {
+    for line in outbox {
+        if let Err(e) = stream.write_all(&line) {
+            debug!("IPC client {} stopped reading: {}", id, e);
+            // Wakes the connection thread so it cleans up
+            stream.shutdown();
+            return;
+        }
+    }
+}
+
+fn serve(
+    shared: &Shared,
+    id: u64,
+    stream: Box<dyn Stream>,
+    mut pending: PendingSlot,
+) -> io::Result<()> {
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    let (outbox, queue) = mpsc::sync_channel(OUTBOX_SIZE);
+    {
+        let stream = stream.try_clone_stream()?;
+        thread::Builder::new()
+            .name("ipc-writer".to_string())
+            .spawn(move || write_loop(id, stream, queue))?;
+    }
+
+    let control = stream.try_clone_stream()?;
+    let mut reader = BufReader::new(DeadlineReader {
+        stream,
+        deadline: Some(Instant::now() + AUTH_TIMEOUT),
+    });
+    let mut authenticated = false;
+    loop {
+        // Only a client holding the token may send anything large
+        let max_bytes = if authenticated {
+            MAX_LINE_BYTES
+        } else {
+            MAX_AUTH_LINE_BYTES
+        };
+        let Some(line) = protocol::read_line(&mut reader, max_bytes)? else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let (id_value, result) = match parse_request(&line) {
+            Ok(request) if authenticated => (request.id.clone(), dispatch(shared, id, &request)),
+            Ok(request) => {
+                let result = authenticate(shared, &request).and_then(|value| {
+                    let stream = control
+                        .try_clone_stream()
+                        .map_err(|e| RpcError::new(INTERNAL_ERROR, e.to_string()))?;
+                    register(shared, id, outbox.clone(), stream)?;
+                    Ok(value)
+                });
+                if result.is_ok() {
+                    authenticated = true;
+                    pending.release();
+                    reader.get_mut().clear_deadline()?;
+                }
+                (request.id, result)
+            }
+            Err((id_value, error)) => (Some(id_value), Err(error)),
+        };
+
+        if let Some(id_value) = id_value {
+            let response = protocol::to_line(&Response::new(id_value, result));
+            // Waits for room: a client only holds up its own requests
+            outbox
+                .send(response)
+                .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "Client stopped reading"))?;
+        }
+        if !authenticated {
+            // One chance; anything but the right token ends the connection
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn parse_request(line: &str) -> Result<Request, (Value, RpcError)> {
+    let value: Value = serde_json::from_str(line)
+        .map_err(|e| (Value::Null, RpcError::new(PARSE_ERROR, e.to_string())))?;
+    let id = value.get("id").cloned().unwrap_or(Value::Null);
+    let request: Request = serde_json::from_value(value)
+        .map_err(|e| (id.clone(), RpcError::new(INVALID_REQUEST, e.to_string())))?;
+    if request.jsonrpc != "2.0" {
+        return Err((id, RpcError::new(INVALID_REQUEST, "Expected jsonrpc 2.0")));
+    }
+    Ok(request)
+}
+
+fn register(
+    shared: &Shared,
+    id: u64,
+    outbox: SyncSender<Vec<u8>>,
+    stream: Box<dyn Stream>,
+) -> Result<(), RpcError> {
+    let mut clients = shared.clients.lock();
+    if clients.len() >= MAX_CLIENTS {
+        return Err(RpcError::app("Too many clients connected"));
+    }
+    clients.insert(
+        id,
+        Client {
+            outbox,
+            stream,
+            events: None,
+        },
+    );
+    Ok(())
+}
+
+fn authenticate(shared: &Shared, request: &Request) -> Result<Value, RpcError> {
+    #[derive(Deserialize, Default)]
+    struct AuthParams {
+        token: String,
+    }
+
+    if request.method != "auth" {
+        return Err(RpcError::new(UNAUTHORIZED, "Call auth first"));
+    }
+    let params: AuthParams = params(&request.params)?;
+    if !token_matches(&shared.token, &params.token) {
+        return Err(RpcError::new(UNAUTHORIZED, "Wrong token"));
+    }
+    Ok(Value::Bool(true))
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct BindingParams {
+    binding: Option<String>,
+}
+
+impl BindingParams {
+    fn binding(&self) -> &str {
+        self.binding.as_deref().unwrap_or("transcribe")
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct TranscribeParams {
+    /// Base64 WAV file
+    wav: Option<String>,
+    /// Base64 raw PCM
+    pcm: Option<String>,
+    rate: Option<u32>,
+    channels: Option<u16>,
+    /// "s16le" (default) or "f32le"
+    format: Option<String>,
+    /// Binding whose outputs also get the text
+    deliver: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct SubscribeParams {
+    events: Vec<String>,
+}
+
+#[derive(Deserialize, Default)]
+struct ModelParams {
+    id: String,
+}
+
+fn params<T: DeserializeOwned + Default>(value: &Value) -> Result<T, RpcError> {
+    if value.is_null() {
+        return Ok(T::default());
+    }
+    T::deserialize(value).map_err(|e| RpcError::invalid_params(e.to_string()))
+}
+
+fn dispatch(shared: &Shared, client: u64, request: &Request) -> Result<Value, RpcError> {
+    let api = &shared.api;
+    match request.method.as_str() {
+        "auth" => Ok(Value::Bool(true)),
+        "recording.start" => {
+            let p: BindingParams = params(&request.params)?;
+            api.start_recording(p.binding())
+                .map(Value::Bool)
+                .map_err(RpcError::app)
+        }
+        "recording.stop" => {
+            let p: BindingParams = params(&request.params)?;
+            api.stop_recording(p.binding())
+                .map(Value::Bool)
+                .map_err(RpcError::app)
+        }
+        "recording.toggle" => {
+            let p: BindingParams = params(&request.params)?;
+            api.toggle_recording(p.binding())
+                .map(Value::Bool)
+                .map_err(RpcError::app)
+        }
+        "recording.cancel" => api.cancel().map(|()| Value::Null).map_err(RpcError::app),
+        "state" => api.state().map_err(RpcError::app),
+        "transcribe" => {
+            let p: TranscribeParams = params(&request.params)?;
+            let audio = audio_buffer(&p)?;
+            api.transcribe(audio, p.deliver.as_deref())
+                .map(|text| json!({ "text": text }))
+                .map_err(RpcError::app)
+        }
+        "models.list" => api.list_models().map_err(RpcError::app),
+        "models.select" => {
+            let p: ModelParams = params(&request.params)?;
+            if p.id.is_empty() {
+                return Err(RpcError::invalid_params("Missing model id"));
+            }
+            api.select_model(&p.id)
+                .map(|()| Value::Null)
+                .map_err(RpcError::app)
+        }
+        "events.subscribe" => {
+            let p: SubscribeParams = params(&request.params)?;
+            match shared.clients.lock().get_mut(&client) {
+                Some(c) => c.events = Some(p.events.into_iter().collect()),
+                None => return Err(RpcError::new(INTERNAL_ERROR, "Client not registered")),
+            }
+            Ok(Value::Bool(true))
+        }
+        "events.unsubscribe" => {
+            if let Some(c) = shared.clients.lock().get_mut(&client) {
+                c.events = None;
+            }
+            Ok(Value::Bool(true))
+        }
+        other => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("Unknown method {}", other),
+        )),
+    }
+}
+
+fn audio_buffer(p: &TranscribeParams) -> Result<AudioBuffer, RpcError> {
+    let decode = |data: &str| {
+        base64::engine::general_purpose::STANDARD
+            .decode(data)
+            .map_err(|e| RpcError::invalid_params(format!("Audio isn't valid base64: {}", e)))
+    };
+
+    match (&p.wav, &p.pcm) {
+        (Some(wav), None) => Ok(AudioBuffer::Wav(decode(wav)?)),
+        (None, Some(pcm)) => {
+            let sample_rate = p
+                .rate
+                .filter(|rate| (1000..=384_000).contains(rate))
+                .ok_or_else(|| {
+                    RpcError::invalid_params("PCM needs a rate between 1000 and 384000")
+                })?;
+            let channels = p.channels.unwrap_or(1);
+            if !(1..=32).contains(&channels) {
+                return Err(RpcError::invalid_params("PCM needs 1 to 32 channels"));
+            }
+            let format = match p.format.as_deref().unwrap_or("s16le") {
+                "s16le" => PcmFormat::S16Le,
+                "f32le" => PcmFormat::F32Le,
+                other => {
+                    return Err(RpcError::invalid_params(format!(
+                        "Unknown PCM format {} (expected s16le or f32le)",
+                        other
+                    )))
+                }
+            };
+            Ok(AudioBuffer::Pcm {
+                data: decode(pcm)?,
+                sample_rate,
+                channels,
+                format,
+            })
+        }
+        _ => Err(RpcError::invalid_params("Pass exactly one of wav or pcm")),
+    }
+}
@@ -6,30 +6,37 @@ pub mod audio_toolkit;
 mod clipboard;
 mod commands;
 mod helpers;
+pub mod host;
 mod input;
-mod managers;
-mod medical_vocab;
+pub mod ipc;
+pub mod managers;
+pub mod medical_vocab;
+mod output;
 mod overlay;
-mod settings;
+pub mod post_process;
+pub mod settings;
 mod shortcut;
-mod signal_handle;
+#[cfg(unix)]
+pub mod signal_handle;
+pub mod subtitles;
 mod tray;
 mod tray_i18n;
 mod utils;
-mod validation;
+pub mod validation;
 #[cfg(debug_assertions)]
 use specta_typescript::{BigIntExportBehavior, Typescript};
 use tauri_specta::{collect_commands, Builder};
 
 use env_filter::Builder as EnvFilterBuilder;
+use host::Host;
 use managers::audio::AudioRecordingManager;
+use managers::diarization::DiarizationManager;
 // EPHEMERAL MODE: History manager disabled - transcriptions not saved to disk
 // use managers::history::HistoryManager;
 use managers::model::ModelManager;
+use managers::queue::TranscriptionQueue;
 use managers::transcription::TranscriptionManager;
 #[cfg(unix)]
-use signal_hook::consts::SIGUSR2;
-#[cfg(unix)]
 use signal_hook::iterator::Signals;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU8, Ordering};
@@ -131,12 +138,17 @@ fn initialize_core_logic(app_handle: &AppHandle) {
     let recording_manager = Arc::new(
         AudioRecordingManager::new(app_handle).expect("Failed to initialize recording manager"),
     );
+    let host: Arc<dyn Host> = Arc::new(app_handle.clone());
     let model_manager =
-        Arc::new(ModelManager::new(app_handle).expect("Failed to initialize model manager"));
+        Arc::new(ModelManager::new(Arc::clone(&host)).expect("Failed to initialize model manager"));
     let transcription_manager = Arc::new(
-        TranscriptionManager::new(app_handle, model_manager.clone())
+        TranscriptionManager::new(host, model_manager.clone())
             .expect("Failed to initialize transcription manager"),
     );
+    let transcription_queue = Arc::new(
+        TranscriptionQueue::new(app_handle).expect("Failed to initialize transcription queue"),
+    );
+    let diarization_manager = Arc::new(DiarizationManager::default());
 
     // EPHEMERAL MODE: History manager disabled for privacy compliance
     // Transcriptions are processed in-memory only and not persisted to disk
@@ -148,22 +160,69 @@ fn initialize_core_logic(app_handle: &AppHandle) {
     app_handle.manage(recording_manager.clone());
     app_handle.manage(model_manager.clone());
     app_handle.manage(transcription_manager.clone());
+    app_handle.manage(transcription_queue.clone());
+    app_handle.manage(diarization_manager.clone());
     // app_handle.manage(history_manager.clone());
 
+    // Pick up catalog changes from the configured mirror in the background;
+    // the bundled or cached catalog is already in use if this fails
+    if settings::get_settings(app_handle)
+        .model_catalog_url
+        .is_some()
+    {
+        let model_manager = model_manager.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = model_manager.refresh_catalog().await {
+                log::warn!("Failed to refresh model catalog: {}", e);
+            }
+        });
+    }
+
+    // Load the selected model up front when the user opted in, so the first
+    // dictation after startup (or login, with autostart) doesn't wait for it
+    if settings::get_settings(app_handle).preload_model_on_startup {
+        transcription_manager
+            .initiate_model_load_with_reason(managers::model_policy::ModelStateReason::Preload);
+    }
+
+    // Pick up model downloads that were interrupted when the app last exited
+    {
+        let model_manager = model_manager.clone();
+        tauri::async_runtime::spawn(async move {
+            model_manager.resume_download_queue().await;
+        });
+    }
+
     // Initialize the shortcuts
     shortcut::init_shortcuts(app_handle);
 
+    // Start the control API if the user turned it on
+    if let Err(e) = ipc::app::apply_setting(app_handle) {
+        log::error!("{}", e);
+    }
+
     #[cfg(unix)]
     {
-        match Signals::new([SIGUSR2]) {
+        match Signals::new(signal_handle::handled_signals()) {
             Ok(signals) => {
-                // Set up SIGUSR2 signal handler for toggling transcription
+                // Let hotkey daemons and scripts drive recording with signals
                 signal_handle::setup_signal_handler(app_handle.clone(), signals);
             }
             Err(e) => {
                 log::error!("Failed to initialize signal handler: {}", e);
             }
         }
+
+        // Lets handy-ctl find us when the control API is off
+        match app_handle.path().app_data_dir() {
+            Ok(dir) => match signal_handle::lock_pid_file(&dir) {
+                Ok(lock) => {
+                    app_handle.manage(lock);
+                }
+                Err(e) => log::warn!("Failed to lock {}: {}", signal_handle::PID_FILE, e),
+            },
+            Err(e) => log::warn!("Failed to get app data dir: {}", e),
+        }
     }
 
     // Apply macOS Accessory policy if starting hidden
@@ -265,6 +324,12 @@ fn trigger_update_check(app: AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+/// Runs the model probe when the process was started by `import_model` to
+/// test-load a model. Returns `None` for a normal launch.
+pub fn run_model_probe() -> Option<i32> {
+    managers::model_import::probe_from_args()
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // Parse console logging directives from RUST_LOG, falling back to info-level logging
@@ -287,6 +352,12 @@ pub fn run() {
         shortcut::change_word_correction_threshold_setting,
         shortcut::change_paste_method_setting,
         shortcut::change_clipboard_handling_setting,
+        shortcut::change_clipboard_expiry_setting,
+        shortcut::change_binding_outputs_setting,
+        shortcut::change_ipc_enabled_setting,
+        shortcut::change_signal_bindings_setting,
+        shortcut::change_paste_tool_setting,
+        shortcut::change_paste_fallback_setting,
         shortcut::update_custom_words,
         shortcut::suspend_binding,
         shortcut::resume_binding,
@@ -296,6 +367,18 @@ pub fn run() {
         shortcut::mark_setup_completed,
         shortcut::change_medical_mode_setting,
         shortcut::change_hide_privacy_notice_setting,
+        shortcut::change_paste_into_original_window_setting,
+        shortcut::change_model_catalog_url_setting,
+        shortcut::change_model_signing_public_key_setting,
+        shortcut::change_model_latency_target_setting,
+        shortcut::change_preload_model_on_startup_setting,
+        shortcut::change_low_memory_unload_threshold_setting,
+        shortcut::change_clinic_hours_setting,
+        shortcut::change_auto_stop_setting,
+        shortcut::change_pre_roll_setting,
+        shortcut::change_dsp_settings,
+        shortcut::change_vad_settings,
+        shortcut::change_diarization_settings,
         shortcut::change_update_checks_setting,
         trigger_update_check,
         commands::cancel_operation,
@@ -317,6 +400,7 @@ pub fn run() {
         commands::medical::open_custom_vocab_file,
         commands::models::delete_model,
         commands::models::cancel_download,
+        commands::models::get_download_queue,
         commands::models::set_active_model,
         commands::models::get_current_model,
         commands::models::get_transcription_model_status,
@@ -324,11 +408,25 @@ pub fn run() {
         commands::models::has_any_models_available,
         commands::models::has_any_models_or_downloads,
         commands::models::get_recommended_first_model,
+        commands::models::benchmark_models,
+        commands::models::get_benchmark_results,
+        commands::models::get_parakeet_model_options,
+        commands::models::set_parakeet_model_options,
+        commands::models::refresh_model_catalog,
+        commands::models::verify_model,
+        commands::models::import_model,
+        commands::models::install_model_pack,
+        commands::models::export_model_pack,
         commands::audio::update_microphone_mode,
         commands::audio::get_microphone_mode,
         commands::audio::get_available_microphones,
         commands::audio::set_selected_microphone,
         commands::audio::get_selected_microphone,
+        commands::audio::set_microphone_channel_policy,
+        commands::audio::get_microphone_channel_policy,
+        commands::audio::start_vad_calibration,
+        commands::audio::stop_vad_calibration,
+        commands::audio::test_microphone,
         commands::audio::get_available_output_devices,
         commands::audio::set_selected_output_device,
         commands::audio::get_selected_output_device,
@@ -340,6 +438,7 @@ pub fn run() {
         commands::transcription::set_model_unload_timeout,
         commands::transcription::get_model_load_status,
         commands::transcription::unload_model_manually,
+        commands::transcription::get_transcription_queue,
         // EPHEMERAL MODE: History commands disabled - no persistent storage
         // commands::history::get_history_entries,
         // commands::history::toggle_history_entry_saved,
@@ -404,6 +503,7 @@ pub fn run() {
             Some(vec![]),
         ))
         .manage(Mutex::new(ShortcutToggleStates::default()))
+        .manage(ipc::app::IpcState::default())
         .setup(move |app| {
             // Remove quarantine attribute from the app on first launch (macOS only)
             #[cfg(target_os = "macos")]
@@ -479,6 +579,13 @@ pub fn run() {
             _ => {}
         })
         .invoke_handler(specta_builder.invoke_handler())
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                // Don't leave a copied transcript behind on the clipboard
+                clipboard::clear_expiring_copy(app);
+                ipc::app::stop(app);
+            }
+        });
 }
@@ -12,5 +12,10 @@ fn main() {
         }
     }
 
+    // Model import re-launches the app to test-load a model in isolation
+    if let Some(exit_code) = handy_app_lib::run_model_probe() {
+        std::process::exit(exit_code);
+    }
+
     handy_app_lib::run()
 }
@@ -1,11 +1,20 @@
-use crate::audio_toolkit::{list_input_devices, vad::SmoothedVad, AudioRecorder, SileroVad};
+use crate::audio_toolkit::{
+    audio::{
+        list_input_device_names, AutoStopConfig, AutoStopReason, ChannelPolicy, QualityReport,
+    },
+    list_input_devices,
+    vad::{EnergyVad, SmoothedVad},
+    AudioRecorder, CpalDeviceInfo, SileroVad, VoiceActivityDetector,
+};
 use crate::helpers::clamshell;
-use crate::settings::{get_settings, AppSettings};
+use crate::settings::{get_settings, AppSettings, AutoStopSettings, VadBackend, VadSettings};
 use crate::utils;
 use log::{debug, error, info, warn};
 use parking_lot::Mutex;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tauri::{Emitter, Manager};
 
 /// Synchronous implementation of mute operation - runs on blocking thread pool
@@ -108,6 +117,12 @@ fn set_mute(mute: bool) {
 
 const WHISPER_SAMPLE_RATE: usize = 16000;
 
+/// Holds the recording state while a microphone test runs
+const MIC_TEST_BINDING: &str = "microphone-test";
+
+/// How often an open microphone is checked for unplugged or returning devices
+const DEVICE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
 /* ──────────────────────────────────────────────────────────────── */
 
 #[derive(Clone, Debug)]
@@ -124,25 +139,112 @@ pub enum MicrophoneMode {
 
 /* ──────────────────────────────────────────────────────────────── */
 
+/// Builds the configured VAD. When Silero can't be loaded the energy
+/// detector is used instead and the load error is returned alongside it, so
+/// dictation keeps working and the caller can tell the user.
+pub fn build_vad(
+    vad_path: &Path,
+    settings: &VadSettings,
+) -> (Box<dyn VoiceActivityDetector>, Option<anyhow::Error>) {
+    let (inner, load_error): (Box<dyn VoiceActivityDetector>, _) = match settings.backend {
+        VadBackend::Silero => match SileroVad::new(vad_path, settings.threshold) {
+            Ok(silero) => (Box::new(silero), None),
+            Err(e) => (
+                Box::new(EnergyVad::new(settings.energy_margin_db)),
+                Some(anyhow::anyhow!("Failed to create SileroVad: {}", e)),
+            ),
+        },
+        VadBackend::Energy => (Box::new(EnergyVad::new(settings.energy_margin_db)), None),
+    };
+
+    let smoothed_vad = SmoothedVad::new(
+        inner,
+        settings.prefill_frames as usize,
+        settings.hangover_frames as usize,
+        settings.onset_frames as usize,
+    );
+    (Box::new(smoothed_vad), load_error)
+}
+
+fn vad_for_app(vad_path: &Path, app_handle: &tauri::AppHandle) -> Box<dyn VoiceActivityDetector> {
+    let (vad, load_error) = build_vad(vad_path, &get_settings(app_handle).vad);
+    if let Some(e) = load_error {
+        warn!("{}; falling back to the energy VAD", e);
+        let _ = app_handle.emit(
+            "vad-fallback",
+            serde_json::json!({
+                "error": e.to_string(),
+                "fallback": "energy"
+            }),
+        );
+    }
+    vad
+}
+
+/// A VAD built from the current settings, for audio that isn't coming from
+/// the live stream.
+pub fn vad_from_settings(app_handle: &tauri::AppHandle) -> Box<dyn VoiceActivityDetector> {
+    // Without the bundled model this falls back to the energy VAD
+    let vad_path = resolve_vad_path(app_handle).unwrap_or_default();
+    vad_for_app(&vad_path, app_handle)
+}
+
+fn resolve_vad_path(app_handle: &tauri::AppHandle) -> Result<std::path::PathBuf, anyhow::Error> {
+    app_handle
+        .path()
+        .resolve(
+            "resources/models/silero_vad_v4.onnx",
+            tauri::path::BaseDirectory::Resource,
+        )
+        .map_err(|e| anyhow::anyhow!("Failed to resolve VAD path: {}", e))
+}
+
+fn auto_stop_config(settings: &AutoStopSettings) -> Option<AutoStopConfig> {
+    let nonzero = |d: Duration| (!d.is_zero()).then_some(d);
+    settings.enabled.then(|| AutoStopConfig {
+        trailing_silence: nonzero(Duration::from_millis(settings.trailing_silence_ms.into())),
+        max_duration: nonzero(Duration::from_secs(settings.max_duration_secs.into())),
+        no_speech_timeout: nonzero(Duration::from_secs(settings.no_speech_timeout_secs.into())),
+    })
+}
+
+fn channel_policy(settings: &AppSettings, device_name: Option<&str>) -> ChannelPolicy {
+    device_name
+        .and_then(|name| settings.microphone_channels.get(name))
+        .copied()
+        .unwrap_or_default()
+}
+
 fn create_audio_recorder(
-    vad_path: &str,
+    vad_path: &Path,
     app_handle: &tauri::AppHandle,
+    calibrating: Arc<AtomicBool>,
+    audio_seen: Arc<AtomicBool>,
+    on_auto_stop: impl Fn(AutoStopReason) + Send + Sync + 'static,
+    on_stream_error: impl Fn(String) + Send + Sync + 'static,
 ) -> Result<AudioRecorder, anyhow::Error> {
-    let silero = SileroVad::new(vad_path, 0.3)
-        .map_err(|e| anyhow::anyhow!("Failed to create SileroVad: {}", e))?;
-    let smoothed_vad = SmoothedVad::new(Box::new(silero), 15, 15, 2);
-
     // Recorder with VAD plus a spectrum-level callback that forwards updates to
-    // the frontend.
+    // the frontend, and VAD decisions while calibrating.
     let recorder = AudioRecorder::new()
         .map_err(|e| anyhow::anyhow!("Failed to create AudioRecorder: {}", e))?
-        .with_vad(Box::new(smoothed_vad))
+        .with_vad(vad_for_app(vad_path, app_handle))
         .with_level_callback({
             let app_handle = app_handle.clone();
             move |levels| {
+                audio_seen.store(true, Ordering::Relaxed);
                 utils::emit_levels(&app_handle, &levels);
             }
-        });
+        })
+        .with_vad_callback({
+            let app_handle = app_handle.clone();
+            move |decision| {
+                if calibrating.load(Ordering::Relaxed) {
+                    let _ = app_handle.emit("vad-calibration", decision);
+                }
+            }
+        })
+        .with_auto_stop_callback(on_auto_stop)
+        .with_error_callback(on_stream_error);
 
     Ok(recorder)
 }
@@ -157,6 +259,8 @@ struct MicrophoneStreamState {
     is_recording: bool,
     /// Whether system mute was applied by this manager
     did_mute: bool,
+    /// Whether VAD calibration opened the stream (so stopping it closes it)
+    opened_for_calibration: bool,
 }
 
 impl MicrophoneStreamState {
@@ -165,6 +269,7 @@ impl MicrophoneStreamState {
             is_open: false,
             is_recording: false,
             did_mute: false,
+            opened_for_calibration: false,
         }
     }
 }
@@ -178,6 +283,19 @@ pub struct AudioRecordingManager {
     recorder: Arc<Mutex<Option<AudioRecorder>>>,
     /// Consolidated stream state to prevent race conditions between flags
     stream_state: Arc<Mutex<MicrophoneStreamState>>,
+    /// Forward VAD decisions to the frontend
+    calibrating: Arc<AtomicBool>,
+    /// Name of the device the open stream is reading from
+    active_device: Arc<Mutex<Option<String>>>,
+    /// The open stream failed or its device went away; the watcher reopens it
+    stream_lost: Arc<AtomicBool>,
+    /// Set by the level callback; cleared by each device check
+    audio_seen: Arc<AtomicBool>,
+    /// Input devices seen by the last device check
+    known_devices: Arc<Mutex<Vec<String>>>,
+    /// The active device's channel policy changed mid-recording; the stream
+    /// is reopened once the recording ends
+    restart_pending: Arc<AtomicBool>,
 }
 
 impl AudioRecordingManager {
@@ -198,6 +316,12 @@ impl AudioRecordingManager {
 
             recorder: Arc::new(Mutex::new(None)),
             stream_state: Arc::new(Mutex::new(MicrophoneStreamState::new())),
+            calibrating: Arc::new(AtomicBool::new(false)),
+            active_device: Arc::new(Mutex::new(None)),
+            stream_lost: Arc::new(AtomicBool::new(false)),
+            audio_seen: Arc::new(AtomicBool::new(false)),
+            known_devices: Arc::new(Mutex::new(Vec::new())),
+            restart_pending: Arc::new(AtomicBool::new(false)),
         };
 
         // Always-on?  Open immediately.
@@ -205,12 +329,19 @@ impl AudioRecordingManager {
             manager.start_microphone_stream()?;
         }
 
+        manager.spawn_device_watcher();
+
         Ok(manager)
     }
 
     /* ---------- helper methods --------------------------------------------- */
 
-    fn get_effective_microphone_device(&self, settings: &AppSettings) -> Option<cpal::Device> {
+    fn vad_path(&self) -> Result<std::path::PathBuf, anyhow::Error> {
+        resolve_vad_path(&self.app_handle)
+    }
+
+    /// The microphone the user asked for, considering clamshell mode.
+    fn preferred_microphone(&self, settings: &AppSettings) -> Option<String> {
         // Check if we're in clamshell mode and have a clamshell microphone configured
         let use_clamshell_mic = if let Ok(is_clamshell) = clamshell::is_clamshell() {
             is_clamshell && settings.clamshell_microphone.is_some()
@@ -218,25 +349,53 @@ impl AudioRecordingManager {
             false
         };
 
-        let device_name = if use_clamshell_mic {
-            settings.clamshell_microphone.as_ref()?
+        if use_clamshell_mic {
+            settings.clamshell_microphone.clone()
         } else {
-            settings.selected_microphone.as_ref()?
-        };
+            settings.selected_microphone.clone()
+        }
+    }
+
+    /// Picks the input device to open: the preferred microphone, then the
+    /// selected microphone, then the system default, then whatever is left.
+    /// Emits `microphone-fallback` when the preferred one isn't available.
+    fn choose_microphone(
+        &self,
+        settings: &AppSettings,
+        devices: Vec<CpalDeviceInfo>,
+    ) -> Option<CpalDeviceInfo> {
+        let preferred = self.preferred_microphone(settings);
+        let candidates = [preferred.as_ref(), settings.selected_microphone.as_ref()];
+
+        let position = candidates
+            .into_iter()
+            .flatten()
+            .find_map(|name| devices.iter().position(|d| d.name == *name))
+            .or_else(|| devices.iter().position(|d| d.is_default))
+            .or((!devices.is_empty()).then_some(0))?;
+        let chosen = devices.into_iter().nth(position)?;
+
+        if let Some(preferred) = preferred.filter(|p| *p != chosen.name) {
+            warn!(
+                "Selected device '{}' not found, using '{}'",
+                preferred, chosen.name
+            );
+            let _ = self.app_handle.emit(
+                "microphone-fallback",
+                serde_json::json!({
+                    "requested": preferred,
+                    "device": chosen.name
+                }),
+            );
+        }
+        Some(chosen)
+    }
 
-        // Find the device by name
+    /// The device a newly opened stream should use, or `None` for the
+    /// system default when devices can't be listed.
+    fn get_effective_microphone_device(&self, settings: &AppSettings) -> Option<CpalDeviceInfo> {
         match list_input_devices() {
-            Ok(devices) => {
-                let device = devices
-                    .into_iter()
-                    .find(|d| d.name == *device_name)
-                    .map(|d| d.device);
-
-                if device.is_none() {
-                    warn!("Selected device '{}' not found, using default", device_name);
-                }
-                device
-            }
+            Ok(devices) => self.choose_microphone(settings, devices),
             Err(e) => {
                 warn!(
                     "Failed to enumerate audio devices, using default device: {}",
@@ -291,30 +450,40 @@ impl AudioRecordingManager {
         // Don't mute immediately - caller will handle muting after audio feedback
         stream.did_mute = false;
 
-        let vad_path = self
-            .app_handle
-            .path()
-            .resolve(
-                "resources/models/silero_vad_v4.onnx",
-                tauri::path::BaseDirectory::Resource,
-            )
-            .map_err(|e| anyhow::anyhow!("Failed to resolve VAD path: {}", e))?;
+        let vad_path = self.vad_path()?;
         let mut recorder_opt = self.recorder.lock();
 
         if recorder_opt.is_none() {
-            let vad_path_str = vad_path
-                .to_str()
-                .ok_or_else(|| anyhow::anyhow!("Invalid VAD path: contains invalid UTF-8"))?;
-            *recorder_opt = Some(create_audio_recorder(vad_path_str, &self.app_handle)?);
+            *recorder_opt = Some(create_audio_recorder(
+                &vad_path,
+                &self.app_handle,
+                self.calibrating.clone(),
+                self.audio_seen.clone(),
+                self.auto_stop_handler(),
+                self.stream_error_handler(),
+            )?);
         }
 
         // Get the selected device from settings, considering clamshell mode
         let settings = get_settings(&self.app_handle);
-        let selected_device = self.get_effective_microphone_device(&settings);
+        let (device_name, selected_device) = match self.get_effective_microphone_device(&settings) {
+            Some(info) => (Some(info.name), Some(info.device)),
+            None => (None, None),
+        };
 
         if let Some(rec) = recorder_opt.as_mut() {
+            rec.set_channel_policy(channel_policy(&settings, device_name.as_deref()));
             rec.open(selected_device)
                 .map_err(|e| anyhow::anyhow!("Failed to open recorder: {}", e))?;
+            *self.active_device.lock() = device_name;
+            self.stream_lost.store(false, Ordering::Relaxed);
+            self.audio_seen.store(true, Ordering::Relaxed);
+            if let Err(e) = rec.set_pre_roll(self.pre_roll_duration(&settings)) {
+                warn!("Failed to set pre-roll: {}", e);
+            }
+            if let Err(e) = rec.set_dsp(settings.dsp) {
+                warn!("Failed to set audio processing: {}", e);
+            }
         }
 
         stream.is_open = true;
@@ -348,10 +517,241 @@ impl AudioRecordingManager {
         }
 
         stream.is_open = false;
+        stream.opened_for_calibration = false;
+        self.calibrating.store(false, Ordering::Relaxed);
+        *self.active_device.lock() = None;
+        self.stream_lost.store(false, Ordering::Relaxed);
+        // The next open picks up the current settings anyway
+        self.restart_pending.store(false, Ordering::Relaxed);
         drop(stream); // Release lock before logging
         debug!("Microphone stream stopped");
     }
 
+    /// Runs on the audio thread, which the stop path waits on, so the stop
+    /// itself happens on another thread.
+    fn auto_stop_handler(&self) -> impl Fn(AutoStopReason) + Send + Sync + 'static {
+        let state = Arc::clone(&self.state);
+        let app_handle = self.app_handle.clone();
+        move |reason| {
+            let binding_id = match &*state.lock() {
+                RecordingState::Recording { binding_id } => binding_id.clone(),
+                RecordingState::Idle => return,
+            };
+            let app_handle = app_handle.clone();
+            std::thread::spawn(move || {
+                crate::actions::auto_stop_recording(&app_handle, &binding_id, reason);
+            });
+        }
+    }
+
+    /// Runs on the audio thread (or cpal's) when the stream fails. Recovery
+    /// is left to the device watcher so the audio thread never waits on it.
+    fn stream_error_handler(&self) -> impl Fn(String) + Send + Sync + 'static {
+        let app_handle = self.app_handle.clone();
+        let active_device = Arc::clone(&self.active_device);
+        let stream_lost = Arc::clone(&self.stream_lost);
+        move |error| {
+            stream_lost.store(true, Ordering::Relaxed);
+            let _ = app_handle.emit(
+                "audio-stream-error",
+                serde_json::json!({
+                    "error": error,
+                    "device": active_device.lock().clone()
+                }),
+            );
+        }
+    }
+
+    /* ---------- hot-plug ----------------------------------------------------- */
+
+    fn spawn_device_watcher(&self) {
+        let manager = self.clone();
+        let spawned = std::thread::Builder::new()
+            .name("microphone-watcher".into())
+            .spawn(move || loop {
+                std::thread::sleep(DEVICE_POLL_INTERVAL);
+                manager.check_devices();
+            });
+        if let Err(e) = spawned {
+            warn!("Failed to start the microphone watcher: {}", e);
+        }
+    }
+
+    /// Notices devices coming and going while the microphone is open. A
+    /// stream whose device vanished or failed is moved to the next device
+    /// in line, and back to the preferred one once it returns. A recording
+    /// in progress carries on across the switch.
+    fn check_devices(&self) {
+        if !self.stream_state.lock().is_open {
+            return;
+        }
+
+        let names = match list_input_device_names() {
+            Ok(names) => names,
+            Err(e) => {
+                debug!("Failed to enumerate audio devices: {}", e);
+                return;
+            }
+        };
+
+        {
+            let mut known = self.known_devices.lock();
+            if *known != names {
+                let _ = self.app_handle.emit("audio-devices-changed", &names);
+                *known = names.clone();
+            }
+        }
+
+        // Device lists aren't a reliable sign of life (some backends leave
+        // out devices that are already open), so a stream counts as lost
+        // when it stops delivering audio.
+        let audio_flowing = self.audio_seen.swap(false, Ordering::Relaxed);
+        let active = self.active_device.lock().clone();
+        if !audio_flowing && !self.stream_lost.load(Ordering::Relaxed) {
+            let unplugged = active.as_ref().is_some_and(|a| !names.contains(a));
+            warn!(
+                "Microphone {:?} stopped delivering audio{}",
+                active,
+                if unplugged { " and was unplugged" } else { "" }
+            );
+            let payload = serde_json::json!({
+                "device": active,
+                "recording": self.is_recording()
+            });
+            let _ = if unplugged {
+                self.app_handle.emit("microphone-disconnected", payload)
+            } else {
+                self.app_handle.emit("microphone-stalled", payload)
+            };
+            *self.active_device.lock() = None;
+            self.stream_lost.store(true, Ordering::Relaxed);
+        }
+
+        let settings = get_settings(&self.app_handle);
+        let preferred_returned = self
+            .preferred_microphone(&settings)
+            .is_some_and(|p| active.as_ref() != Some(&p) && names.contains(&p));
+
+        if self.stream_lost.load(Ordering::Relaxed) || preferred_returned {
+            self.reopen_stream(&settings);
+        }
+    }
+
+    fn reopen_stream(&self, settings: &AppSettings) {
+        let devices = match list_input_devices() {
+            Ok(devices) => devices,
+            Err(e) => {
+                warn!("Failed to enumerate audio devices: {}", e);
+                return;
+            }
+        };
+        // Nothing to fall back to; try again when a device shows up
+        let Some(info) = self.choose_microphone(settings, devices) else {
+            return;
+        };
+
+        let stream = self.stream_state.lock();
+        if !stream.is_open {
+            return;
+        }
+
+        let mut recorder = self.recorder.lock();
+        let Some(rec) = recorder.as_mut() else {
+            return;
+        };
+        rec.set_channel_policy(channel_policy(settings, Some(&info.name)));
+        match rec.switch_device(Some(info.device)) {
+            Ok(()) => {
+                info!("Microphone stream moved to '{}'", info.name);
+                let preferred = self.preferred_microphone(settings);
+                let _ = self.app_handle.emit(
+                    "microphone-switched",
+                    serde_json::json!({
+                        "device": info.name,
+                        "preferred": preferred.is_none_or(|p| p == info.name)
+                    }),
+                );
+                *self.active_device.lock() = Some(info.name);
+                self.stream_lost.store(false, Ordering::Relaxed);
+                self.audio_seen.store(true, Ordering::Relaxed);
+            }
+            Err(e) => warn!("Failed to reopen microphone '{}': {}", info.name, e),
+        }
+    }
+
+    /// Pre-roll only makes sense while the microphone stays open between
+    /// recordings; in on-demand mode there is nothing before the start.
+    fn pre_roll_duration(&self, settings: &AppSettings) -> Duration {
+        match *self.mode.lock() {
+            MicrophoneMode::AlwaysOn => Duration::from_millis(settings.pre_roll_ms.into()),
+            MicrophoneMode::OnDemand => Duration::ZERO,
+        }
+    }
+
+    /// Applies the pre-roll setting and microphone mode to an open stream.
+    /// Turning it off wipes whatever was buffered.
+    pub fn apply_pre_roll(&self) {
+        let settings = get_settings(&self.app_handle);
+        if let Some(rec) = self.recorder.lock().as_ref() {
+            if let Err(e) = rec.set_pre_roll(self.pre_roll_duration(&settings)) {
+                warn!("Failed to set pre-roll: {}", e);
+            }
+        }
+    }
+
+    pub fn apply_dsp_settings(&self) {
+        let settings = get_settings(&self.app_handle);
+        if let Some(rec) = self.recorder.lock().as_ref() {
+            if let Err(e) = rec.set_dsp(settings.dsp) {
+                warn!("Failed to set audio processing: {}", e);
+            }
+        }
+    }
+
+    /* ---------- VAD tuning --------------------------------------------------- */
+
+    /// Rebuilds the VAD from the current settings. An open stream switches
+    /// over from its next frame.
+    pub fn apply_vad_settings(&self) -> Result<(), anyhow::Error> {
+        let vad_path = self.vad_path()?;
+        if let Some(rec) = self.recorder.lock().as_mut() {
+            rec.set_vad(vad_for_app(&vad_path, &self.app_handle));
+        }
+        Ok(())
+    }
+
+    /// Streams the VAD's speech/noise decisions to the frontend as
+    /// `vad-calibration` events, opening the microphone if needed.
+    pub fn start_vad_calibration(&self) -> Result<(), anyhow::Error> {
+        let was_open = self.stream_state.lock().is_open;
+        if !was_open {
+            self.start_microphone_stream()?;
+            self.stream_state.lock().opened_for_calibration = true;
+        }
+
+        if let Some(rec) = self.recorder.lock().as_ref() {
+            rec.set_calibrating(true)
+                .map_err(|e| anyhow::anyhow!("Failed to start VAD calibration: {}", e))?;
+        }
+        self.calibrating.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    pub fn stop_vad_calibration(&self) {
+        self.calibrating.store(false, Ordering::Relaxed);
+        if let Some(rec) = self.recorder.lock().as_ref() {
+            let _ = rec.set_calibrating(false);
+        }
+
+        let opened_for_calibration = self.stream_state.lock().opened_for_calibration;
+        if opened_for_calibration
+            && matches!(*self.mode.lock(), MicrophoneMode::OnDemand)
+            && !self.is_recording()
+        {
+            self.stop_microphone_stream();
+        }
+    }
+
     /* ---------- mode switching --------------------------------------------- */
 
     pub fn update_mode(&self, new_mode: MicrophoneMode) -> Result<(), anyhow::Error> {
@@ -373,6 +773,7 @@ impl AudioRecordingManager {
         }
 
         *self.mode.lock() = new_mode;
+        self.apply_pre_roll();
         Ok(())
     }
 
@@ -389,6 +790,9 @@ impl AudioRecordingManager {
             }
 
             if let Some(rec) = self.recorder.lock().as_ref() {
+                let settings = get_settings(&self.app_handle);
+                rec.set_auto_stop(auto_stop_config(&settings.auto_stop))
+                    .map_err(|e| format!("Failed to start recording: {}", e))?;
                 rec.start()
                     .map_err(|e| format!("Failed to start recording: {}", e))?;
 
@@ -415,6 +819,38 @@ impl AudioRecordingManager {
         Ok(())
     }
 
+    /// Reopens the stream after `device_name`'s channel policy changed, if
+    /// it is the device in use. A recording in progress isn't interrupted:
+    /// the stream is reopened once it ends.
+    pub fn apply_channel_policy(&self, device_name: &str) -> Result<(), anyhow::Error> {
+        if self.active_device.lock().as_deref() != Some(device_name) {
+            // Picked up whenever the device is next opened
+            return Ok(());
+        }
+        if self.is_recording() {
+            debug!(
+                "Channel policy for '{}' applies after this recording",
+                device_name
+            );
+            self.restart_pending.store(true, Ordering::Relaxed);
+            return Ok(());
+        }
+        self.update_selected_device()
+    }
+
+    /// Carries out a restart [`apply_channel_policy`](Self::apply_channel_policy)
+    /// put off until the recording ended.
+    fn restart_if_pending(&self) {
+        if self.restart_pending.swap(false, Ordering::Relaxed) {
+            if let Err(e) = self.update_selected_device() {
+                warn!(
+                    "Failed to reopen the microphone with its new channel policy: {}",
+                    e
+                );
+            }
+        }
+    }
+
     pub fn stop_recording(&self, binding_id: &str) -> Option<Vec<f32>> {
         let mut state = self.state.lock();
 
@@ -426,8 +862,11 @@ impl AudioRecordingManager {
                 drop(state);
 
                 let samples = if let Some(rec) = self.recorder.lock().as_ref() {
-                    match rec.stop() {
-                        Ok(buf) => buf,
+                    match rec.stop_with_report() {
+                        Ok((buf, report)) => {
+                            self.report_quality(&report);
+                            buf
+                        }
                         Err(e) => {
                             error!("stop() failed: {e}");
                             Vec::new()
@@ -444,6 +883,7 @@ impl AudioRecordingManager {
                 if matches!(*self.mode.lock(), MicrophoneMode::OnDemand) {
                     self.stop_microphone_stream();
                 }
+                self.restart_if_pending();
 
                 // Pad if very short
                 let s_len = samples.len();
@@ -461,6 +901,71 @@ impl AudioRecordingManager {
             _ => None,
         }
     }
+
+    /// Sends the input levels of a finished recording to the frontend as a
+    /// `recording-quality` event, and logs anything wrong with them.
+    fn report_quality(&self, report: &QualityReport) {
+        if !report.warnings.is_empty() {
+            warn!(
+                "Input quality: {:?} (peak {:.1} dBFS, clipping {:.2}%)",
+                report.warnings,
+                report.peak_db,
+                report.clipping_ratio * 100.0
+            );
+        }
+        let _ = self.app_handle.emit("recording-quality", report);
+    }
+
+    /// Records `duration` from the microphone and reports its levels
+    /// without transcribing anything. Fails if a recording is in progress.
+    pub fn test_microphone(&self, duration: Duration) -> Result<QualityReport, anyhow::Error> {
+        {
+            let mut state = self.state.lock();
+            if !matches!(*state, RecordingState::Idle) {
+                return Err(anyhow::anyhow!("Recording already in progress"));
+            }
+            // Keeps shortcuts from starting a recording meanwhile
+            *state = RecordingState::Recording {
+                binding_id: MIC_TEST_BINDING.to_string(),
+            };
+        }
+
+        let was_open = self.stream_state.lock().is_open;
+        let result = self.run_microphone_test(duration);
+
+        if !was_open {
+            self.stop_microphone_stream();
+        }
+        *self.state.lock() = RecordingState::Idle;
+        self.restart_if_pending();
+        result
+    }
+
+    fn run_microphone_test(&self, duration: Duration) -> Result<QualityReport, anyhow::Error> {
+        self.start_microphone_stream()?;
+        {
+            let recorder = self.recorder.lock();
+            let rec = recorder
+                .as_ref()
+                .ok_or_else(|| anyhow::anyhow!("Recorder not initialized"))?;
+            rec.set_auto_stop(None)
+                .and_then(|_| rec.start())
+                .map_err(|e| anyhow::anyhow!("Failed to start the microphone test: {}", e))?;
+        }
+
+        std::thread::sleep(duration);
+
+        let recorder = self.recorder.lock();
+        let rec = recorder
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Recorder not initialized"))?;
+        let (_, report) = rec
+            .stop_with_report()
+            .map_err(|e| anyhow::anyhow!("Failed to stop the microphone test: {}", e))?;
+        info!("Microphone test: {:?}", report);
+        Ok(report)
+    }
+
     pub fn is_recording(&self) -> bool {
         matches!(*self.state.lock(), RecordingState::Recording { .. })
     }
@@ -483,6 +988,7 @@ impl AudioRecordingManager {
             if matches!(*self.mode.lock(), MicrophoneMode::OnDemand) {
                 self.stop_microphone_stream();
             }
+            self.restart_if_pending();
         }
     }
 }
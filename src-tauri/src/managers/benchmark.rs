@@ -0,0 +1,416 @@
+use crate::managers::engine_options;
+use crate::managers::model::{ModelInfo, ModelManager};
+use crate::managers::transcription::{load_engine, LoadedEngine};
+use crate::settings::AppSettings;
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use transcribe_rs::{
+    engines::{
+        parakeet::{ParakeetInferenceParams, TimestampGranularity},
+        whisper::WhisperInferenceParams,
+    },
+    TranscriptionEngine,
+};
+
+/// Reference recording and its transcript, bundled under `resources/`.
+pub const REFERENCE_CLIP: &str = "resources/benchmark/reference.wav";
+pub const REFERENCE_TEXT: &str = "resources/benchmark/reference.txt";
+
+/// Measurements from previous runs, in the app data directory.
+const RESULTS_FILE: &str = "benchmark_results.json";
+
+const SAMPLE_RATE: f64 = 16_000.0;
+const MEMORY_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// How one model performed on this machine.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct BenchmarkResult {
+    pub model_id: String,
+    pub load_ms: u64,
+    pub transcribe_ms: u64,
+    pub audio_ms: u64,
+    /// Processing time divided by audio duration; below 1.0 is faster than real time
+    pub real_time_factor: f64,
+    /// Resident memory added while loading and running the model, when the
+    /// platform can report it
+    pub peak_memory_mb: Option<u64>,
+    /// Word error rate against the reference transcript, 0.0 is a perfect match
+    pub word_error_rate: f64,
+    pub transcript: String,
+    /// Seconds since the Unix epoch
+    pub measured_at: u64,
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+/// Lower-cases and strips punctuation so only the words themselves are compared.
+fn normalize_words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric() || *c == '\'')
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// Word-level edit distance divided by the reference length.
+pub fn word_error_rate(reference: &str, hypothesis: &str) -> f64 {
+    let reference = normalize_words(reference);
+    let hypothesis = normalize_words(hypothesis);
+
+    if reference.is_empty() {
+        return if hypothesis.is_empty() { 0.0 } else { 1.0 };
+    }
+
+    let mut previous: Vec<usize> = (0..=hypothesis.len()).collect();
+    let mut current = vec![0; hypothesis.len() + 1];
+    for (i, ref_word) in reference.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, hyp_word) in hypothesis.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ref_word != hyp_word);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[hypothesis.len()] as f64 / reference.len() as f64
+}
+
+/// Picks the most accurate candidate whose real-time factor is within
+/// `max_rtf`. When none is fast enough, the fastest one wins. Candidates
+/// without a benchmark result are ignored.
+pub fn recommend_model<'a>(
+    results: &BTreeMap<String, BenchmarkResult>,
+    candidates: impl IntoIterator<Item = &'a str>,
+    max_rtf: f64,
+) -> Option<String> {
+    let measured: Vec<&BenchmarkResult> = candidates
+        .into_iter()
+        .filter_map(|id| results.get(id))
+        .collect();
+
+    let within_target = measured
+        .iter()
+        .filter(|r| r.real_time_factor <= max_rtf)
+        .min_by(|a, b| {
+            a.word_error_rate
+                .total_cmp(&b.word_error_rate)
+                .then(a.real_time_factor.total_cmp(&b.real_time_factor))
+        });
+
+    within_target
+        .or_else(|| {
+            measured
+                .iter()
+                .min_by(|a, b| a.real_time_factor.total_cmp(&b.real_time_factor))
+        })
+        .map(|r| r.model_id.clone())
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+fn results_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(RESULTS_FILE)
+}
+
+pub fn load_results(data_dir: &Path) -> BTreeMap<String, BenchmarkResult> {
+    let path = results_path(data_dir);
+    if !path.exists() {
+        return BTreeMap::new();
+    }
+
+    match fs::read_to_string(&path)
+        .map_err(anyhow::Error::from)
+        .and_then(|json| serde_json::from_str(&json).map_err(Into::into))
+    {
+        Ok(results) => results,
+        Err(e) => {
+            warn!("Ignoring unreadable benchmark results: {}", e);
+            BTreeMap::new()
+        }
+    }
+}
+
+pub fn save_results(data_dir: &Path, results: &BTreeMap<String, BenchmarkResult>) -> Result<()> {
+    fs::write(results_path(data_dir), serde_json::to_vec_pretty(results)?)?;
+    Ok(())
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+/// Current resident set size of this process.
+#[cfg(target_os = "linux")]
+fn resident_memory() -> Option<u64> {
+    let statm = fs::read_to_string("/proc/self/statm").ok()?;
+    let pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    (page_size > 0).then(|| pages * page_size as u64)
+}
+
+#[cfg(target_os = "macos")]
+fn resident_memory() -> Option<u64> {
+    let mut info: libc::proc_taskinfo = unsafe { std::mem::zeroed() };
+    let size = std::mem::size_of::<libc::proc_taskinfo>() as libc::c_int;
+    let written = unsafe {
+        libc::proc_pidinfo(
+            std::process::id() as libc::c_int,
+            libc::PROC_PIDTASKINFO,
+            0,
+            &mut info as *mut _ as *mut libc::c_void,
+            size,
+        )
+    };
+    (written == size).then_some(info.pti_resident_size)
+}
+
+#[cfg(windows)]
+fn resident_memory() -> Option<u64> {
+    use windows::Win32::System::ProcessStatus::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS};
+    use windows::Win32::System::Threading::GetCurrentProcess;
+
+    let mut counters = PROCESS_MEMORY_COUNTERS::default();
+    let size = std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32;
+    unsafe { GetProcessMemoryInfo(GetCurrentProcess(), &mut counters, size) }.ok()?;
+    Some(counters.WorkingSetSize as u64)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn resident_memory() -> Option<u64> {
+    None
+}
+
+/// Polls resident memory on a background thread and reports the highest
+/// value seen above the level at which sampling started.
+struct MemorySampler {
+    baseline: u64,
+    peak: Arc<AtomicU64>,
+    stop: Arc<AtomicBool>,
+    handle: thread::JoinHandle<()>,
+}
+
+impl MemorySampler {
+    fn start() -> Option<Self> {
+        let baseline = resident_memory()?;
+        let peak = Arc::new(AtomicU64::new(baseline));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let handle = {
+            let peak = peak.clone();
+            let stop = stop.clone();
+            thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    if let Some(rss) = resident_memory() {
+                        peak.fetch_max(rss, Ordering::Relaxed);
+                    }
+                    thread::sleep(MEMORY_POLL_INTERVAL);
+                }
+            })
+        };
+
+        Some(Self {
+            baseline,
+            peak,
+            stop,
+            handle,
+        })
+    }
+
+    /// Peak growth over the baseline, in megabytes.
+    fn finish(self) -> u64 {
+        self.stop.store(true, Ordering::Relaxed);
+        let _ = self.handle.join();
+        if let Some(rss) = resident_memory() {
+            self.peak.fetch_max(rss, Ordering::Relaxed);
+        }
+        self.peak
+            .load(Ordering::Relaxed)
+            .saturating_sub(self.baseline)
+            / (1024 * 1024)
+    }
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+/// The bundled reference clip as 16 kHz mono samples, plus its transcript.
+pub fn load_reference(clip_path: &Path, text_path: &Path) -> Result<(Vec<f32>, String)> {
+    let samples = transcribe_rs::audio::read_wav_samples(clip_path)
+        .map_err(|e| anyhow!("Failed to read benchmark clip: {}", e))?;
+    let reference = fs::read_to_string(text_path)
+        .map_err(|e| anyhow!("Failed to read benchmark transcript: {}", e))?;
+    Ok((samples, reference.trim().to_string()))
+}
+
+fn transcribe_reference(engine: &mut LoadedEngine, samples: Vec<f32>) -> Result<String> {
+    let result = match engine {
+        LoadedEngine::Whisper(whisper_engine) => {
+            // The reference clip is English; skipping detection keeps runs comparable
+            let params = WhisperInferenceParams {
+                language: Some("en".to_string()),
+                ..Default::default()
+            };
+            whisper_engine
+                .transcribe_samples(samples, Some(params))
+                .map_err(|e| anyhow!("Whisper transcription failed: {}", e))?
+        }
+        LoadedEngine::Parakeet(parakeet_engine) => {
+            let params = ParakeetInferenceParams {
+                timestamp_granularity: TimestampGranularity::Segment,
+            };
+            parakeet_engine
+                .transcribe_samples(samples, Some(params))
+                .map_err(|e| anyhow!("Parakeet transcription failed: {}", e))?
+        }
+    };
+    Ok(result.text.trim().to_string())
+}
+
+/// Loads one downloaded model into a separate engine, transcribes the
+/// reference clip and drops it again. The active model is left alone.
+/// Parakeet models run with the user's saved engine options.
+pub fn benchmark_model(
+    model_manager: &ModelManager,
+    model_info: &ModelInfo,
+    settings: &AppSettings,
+    samples: &[f32],
+    reference: &str,
+) -> Result<BenchmarkResult> {
+    if !model_info.is_downloaded {
+        return Err(anyhow!("Model not downloaded: {}", model_info.id));
+    }
+    let model_path = model_manager.get_model_path(&model_info.id)?;
+    let parakeet_options = engine_options::options_for(settings, &model_info.id, &model_path);
+
+    let sampler = MemorySampler::start();
+
+    let load_start = Instant::now();
+    let mut engine = load_engine(model_info, &model_path, &parakeet_options)?;
+    let load_ms = load_start.elapsed().as_millis() as u64;
+
+    let transcribe_start = Instant::now();
+    let transcript = transcribe_reference(&mut engine, samples.to_vec());
+    let transcribe_ms = transcribe_start.elapsed().as_millis() as u64;
+
+    let peak_memory_mb = sampler.map(MemorySampler::finish);
+    drop(engine);
+    let transcript = transcript?;
+
+    let audio_secs = samples.len() as f64 / SAMPLE_RATE;
+    let result = BenchmarkResult {
+        model_id: model_info.id.clone(),
+        load_ms,
+        transcribe_ms,
+        audio_ms: (audio_secs * 1000.0) as u64,
+        real_time_factor: if audio_secs > 0.0 {
+            transcribe_ms as f64 / 1000.0 / audio_secs
+        } else {
+            0.0
+        },
+        peak_memory_mb,
+        word_error_rate: word_error_rate(reference, &transcript),
+        transcript,
+        measured_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+    };
+
+    info!(
+        "Benchmarked {}: load {}ms, RTF {:.3}, WER {:.3}, peak {:?} MB",
+        result.model_id,
+        result.load_ms,
+        result.real_time_factor,
+        result.word_error_rate,
+        result.peak_memory_mb
+    );
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(id: &str, rtf: f64, wer: f64) -> BenchmarkResult {
+        BenchmarkResult {
+            model_id: id.to_string(),
+            load_ms: 0,
+            transcribe_ms: 0,
+            audio_ms: 0,
+            real_time_factor: rtf,
+            peak_memory_mb: None,
+            word_error_rate: wer,
+            transcript: String::new(),
+            measured_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_word_error_rate() {
+        let reference = "And so my fellow Americans, ask not what your country can do for you";
+        assert_eq!(word_error_rate(reference, reference), 0.0);
+        assert_eq!(
+            word_error_rate(
+                reference,
+                "and so my fellow americans ask not what your country can do for you."
+            ),
+            0.0
+        );
+
+        // One substitution and one deletion over 14 words
+        let wer = word_error_rate(
+            reference,
+            "And so my fellow Americans, ask what our country can do for you",
+        );
+        assert!((wer - 2.0 / 14.0).abs() < 1e-9);
+
+        assert_eq!(word_error_rate("", ""), 0.0);
+        assert_eq!(word_error_rate("hello", ""), 1.0);
+    }
+
+    #[test]
+    fn test_recommend_model_prefers_accuracy_within_target() {
+        let results = BTreeMap::from([
+            ("fast".to_string(), result("fast", 0.05, 0.10)),
+            ("accurate".to_string(), result("accurate", 0.25, 0.02)),
+            ("slow".to_string(), result("slow", 1.50, 0.00)),
+        ]);
+
+        assert_eq!(
+            recommend_model(&results, ["fast", "accurate", "slow"], 0.3),
+            Some("accurate".to_string())
+        );
+        assert_eq!(
+            recommend_model(&results, ["fast", "accurate", "slow"], 0.1),
+            Some("fast".to_string())
+        );
+        // Nothing meets the target: fall back to the fastest
+        assert_eq!(
+            recommend_model(&results, ["accurate", "slow"], 0.01),
+            Some("accurate".to_string())
+        );
+        assert_eq!(recommend_model(&results, ["unmeasured"], 0.3), None);
+    }
+
+    #[test]
+    fn test_results_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(load_results(dir.path()).is_empty());
+
+        let results = BTreeMap::from([("small".to_string(), result("small", 0.4, 0.05))]);
+        save_results(dir.path(), &results).unwrap();
+        let loaded = load_results(dir.path());
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded["small"].real_time_factor, 0.4);
+    }
+}
@@ -0,0 +1,468 @@
+use crate::managers::model::{EngineType, ModelInfo};
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Manifest schema this build understands. Manifests with a different
+/// `schema_version` are rejected and the previous catalog stays in use.
+/// Publishers may add optional fields without bumping it, since unknown
+/// fields are ignored; anything older builds would misread needs a bump.
+pub const CATALOG_SCHEMA_VERSION: u32 = 1;
+
+/// Catalog shipped with the app, used whenever no valid cached catalog exists.
+const BUNDLED_CATALOG: &str = include_str!("../../resources/model_catalog.json");
+
+/// Name of the refreshed catalog cached in the app data directory.
+const CACHED_CATALOG_FILE: &str = "model_catalog.json";
+
+/// Refuse remote manifests larger than this; a catalog is a few KB at most.
+const MAX_CATALOG_BYTES: usize = 1024 * 1024;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogManifest {
+    pub schema_version: u32,
+    /// Bumped by whoever publishes the catalog; a cached catalog older than
+    /// the bundled one is ignored after an app update.
+    pub catalog_version: u64,
+    pub models: Vec<CatalogEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogEntry {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    /// Per-OS description overrides, keyed by `std::env::consts::OS`
+    #[serde(default)]
+    pub platform_descriptions: HashMap<String, String>,
+    pub engine: EngineType,
+    /// File name (or directory name for directory-based models) inside models_dir
+    pub filename: String,
+    #[serde(default)]
+    pub is_directory: bool,
+    /// Download locations, tried in order
+    pub urls: Vec<String>,
+    pub size_mb: u64,
+    /// Hex-encoded SHA-256 of the downloaded file or archive
+    #[serde(default)]
+    pub sha256: Option<String>,
+    #[serde(default)]
+    pub languages: Vec<String>,
+    #[serde(default)]
+    pub license: Option<String>,
+    /// Oldest app version able to run this model, e.g. "0.6.0"
+    #[serde(default)]
+    pub min_app_version: Option<String>,
+    pub accuracy_score: f32, // 0.0 to 1.0, higher is more accurate
+    pub speed_score: f32,    // 0.0 to 1.0, higher is faster
+    /// Platforms (`std::env::consts::OS`) this model is recommended for
+    #[serde(default)]
+    pub recommended_platforms: Vec<String>,
+}
+
+impl CatalogEntry {
+    pub fn to_model_info(&self) -> ModelInfo {
+        let os = std::env::consts::OS;
+        ModelInfo {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            description: self
+                .platform_descriptions
+                .get(os)
+                .unwrap_or(&self.description)
+                .clone(),
+            filename: self.filename.clone(),
+            url: self.urls.first().cloned(),
+            urls: self.urls.clone(),
+            size_mb: self.size_mb,
+            sha256: self.sha256.as_ref().map(|h| h.to_lowercase()),
+            languages: self.languages.clone(),
+            license: self.license.clone(),
+            is_downloaded: false,
+            is_downloading: false,
+            partial_size: 0,
+            is_directory: self.is_directory,
+            engine_type: self.engine.clone(),
+            accuracy_score: self.accuracy_score,
+            speed_score: self.speed_score,
+            platform_recommended: self.recommended_platforms.iter().any(|p| p == os),
+        }
+    }
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+/// Parses and validates a catalog manifest.
+pub fn parse_catalog(json: &str) -> Result<CatalogManifest> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| anyhow!("Invalid model catalog: {}", e))?;
+
+    // Check the schema before the fields: a manifest from a newer schema may
+    // not fit these types, and should say so rather than fail on some field
+    let schema_version = value
+        .get("schema_version")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| anyhow!("Invalid model catalog: missing schema_version"))?;
+    check_schema_version(schema_version)?;
+
+    let manifest: CatalogManifest =
+        serde_json::from_value(value).map_err(|e| anyhow!("Invalid model catalog: {}", e))?;
+    validate_catalog(&manifest)?;
+    Ok(manifest)
+}
+
+fn check_schema_version(version: u64) -> Result<()> {
+    if version != u64::from(CATALOG_SCHEMA_VERSION) {
+        return Err(anyhow!(
+            "Unsupported model catalog schema version {} (expected {})",
+            version,
+            CATALOG_SCHEMA_VERSION
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_catalog(manifest: &CatalogManifest) -> Result<()> {
+    check_schema_version(manifest.schema_version.into())?;
+
+    if manifest.models.is_empty() {
+        return Err(anyhow!("Model catalog contains no models"));
+    }
+
+    let mut ids = HashSet::new();
+    let mut filenames = HashSet::new();
+
+    for entry in &manifest.models {
+        crate::validation::validate_model_id(&entry.id)
+            .map_err(|e| anyhow!("Catalog entry '{}': {}", entry.id, e))?;
+
+        if !ids.insert(entry.id.as_str()) {
+            return Err(anyhow!("Duplicate model id in catalog: {}", entry.id));
+        }
+
+        if entry.name.trim().is_empty() {
+            return Err(anyhow!("Catalog entry '{}' has no name", entry.id));
+        }
+
+        if !is_plain_file_name(&entry.filename) {
+            return Err(anyhow!(
+                "Catalog entry '{}' has an invalid filename: {}",
+                entry.id,
+                entry.filename
+            ));
+        }
+
+        if !filenames.insert(entry.filename.as_str()) {
+            return Err(anyhow!(
+                "Catalog entry '{}' reuses filename {}",
+                entry.id,
+                entry.filename
+            ));
+        }
+
+        if entry.urls.is_empty() {
+            return Err(anyhow!("Catalog entry '{}' has no download URL", entry.id));
+        }
+
+        for url in &entry.urls {
+            validate_catalog_url(url)
+                .map_err(|e| anyhow!("Catalog entry '{}': {}", entry.id, e))?;
+        }
+
+        if let Some(hash) = &entry.sha256 {
+            if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(anyhow!(
+                    "Catalog entry '{}' has a malformed sha256",
+                    entry.id
+                ));
+            }
+        }
+
+        if let Some(version) = &entry.min_app_version {
+            if parse_version(version).is_none() {
+                return Err(anyhow!(
+                    "Catalog entry '{}' has an invalid min_app_version: {}",
+                    entry.id,
+                    version
+                ));
+            }
+        }
+
+        for score in [entry.accuracy_score, entry.speed_score] {
+            if !(0.0..=1.0).contains(&score) {
+                return Err(anyhow!(
+                    "Catalog entry '{}' has a score outside 0.0..=1.0",
+                    entry.id
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Accepts http(s) URLs only, so a catalog can't point downloads at local files.
+pub fn validate_catalog_url(url: &str) -> Result<()> {
+    let parsed = reqwest::Url::parse(url).map_err(|e| anyhow!("Invalid URL '{}': {}", url, e))?;
+    match parsed.scheme() {
+        "https" | "http" if parsed.host_str().is_some() => Ok(()),
+        _ => Err(anyhow!("Unsupported URL '{}': expected http(s)", url)),
+    }
+}
+
+/// A bare file or directory name for inside models_dir, which can't escape it
+/// or collide with the files the model manager keeps next to models.
+pub fn is_plain_file_name(name: &str) -> bool {
+    !name.is_empty()
+        && name != "."
+        && name != ".."
+        && !name.contains(['/', '\\', '\0'])
+        && !name.ends_with(".partial")
+        && !name.ends_with(".extracting")
+        && !name.ends_with(".previous")
+        && !name.ends_with(".importing")
+        && !name.ends_with(".integrity.json")
+}
+
+/// Parses "major.minor.patch", ignoring any pre-release suffix.
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let core = version.trim().split(['-', '+']).next()?;
+    let mut parts = core.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().unwrap_or("0").parse().ok()?;
+    let patch = parts.next().unwrap_or("0").parse().ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((major, minor, patch))
+}
+
+/// True if this build is new enough to run the entry.
+pub fn is_compatible(entry: &CatalogEntry, app_version: &str) -> bool {
+    match (&entry.min_app_version, parse_version(app_version)) {
+        (Some(min), Some(current)) => parse_version(min).is_some_and(|min| current >= min),
+        _ => true,
+    }
+}
+
+/// Converts the catalog into the model table used by `ModelManager`, dropping
+/// entries that need a newer app.
+pub fn catalog_models(manifest: &CatalogManifest, app_version: &str) -> HashMap<String, ModelInfo> {
+    manifest
+        .models
+        .iter()
+        .filter(|entry| {
+            let compatible = is_compatible(entry, app_version);
+            if !compatible {
+                info!(
+                    "Skipping model {}: requires app version {}",
+                    entry.id,
+                    entry.min_app_version.as_deref().unwrap_or_default()
+                );
+            }
+            compatible
+        })
+        .map(|entry| (entry.id.clone(), entry.to_model_info()))
+        .collect()
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+pub fn bundled_catalog() -> Result<CatalogManifest> {
+    parse_catalog(BUNDLED_CATALOG)
+}
+
+fn cached_catalog_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(CACHED_CATALOG_FILE)
+}
+
+/// Loads the catalog to use at startup: the cached refresh if it is valid and
+/// not older than the bundled catalog, otherwise the bundled one.
+pub fn load_catalog(data_dir: &Path) -> Result<CatalogManifest> {
+    let bundled = bundled_catalog()?;
+    let cache_path = cached_catalog_path(data_dir);
+
+    if !cache_path.exists() {
+        return Ok(bundled);
+    }
+
+    match fs::read_to_string(&cache_path)
+        .map_err(anyhow::Error::from)
+        .and_then(|json| parse_catalog(&json))
+    {
+        Ok(cached) if cached.catalog_version >= bundled.catalog_version => {
+            info!(
+                "Using cached model catalog (version {})",
+                cached.catalog_version
+            );
+            Ok(cached)
+        }
+        Ok(cached) => {
+            info!(
+                "Cached model catalog (version {}) is older than bundled (version {}), ignoring",
+                cached.catalog_version, bundled.catalog_version
+            );
+            Ok(bundled)
+        }
+        Err(e) => {
+            warn!("Ignoring cached model catalog: {}", e);
+            Ok(bundled)
+        }
+    }
+}
+
+/// Downloads and validates a catalog from `url`.
+pub async fn fetch_catalog(url: &str) -> Result<CatalogManifest> {
+    validate_catalog_url(url)?;
+
+    let client = reqwest::Client::builder().timeout(FETCH_TIMEOUT).build()?;
+    let response = client.get(url).send().await?;
+
+    if !response.status().is_success() {
+        return Err(anyhow!(
+            "Failed to fetch model catalog: HTTP {}",
+            response.status()
+        ));
+    }
+
+    if response
+        .content_length()
+        .is_some_and(|len| len as usize > MAX_CATALOG_BYTES)
+    {
+        return Err(anyhow!("Model catalog is too large"));
+    }
+
+    let body = response.bytes().await?;
+    if body.len() > MAX_CATALOG_BYTES {
+        return Err(anyhow!("Model catalog is too large"));
+    }
+
+    let json = std::str::from_utf8(&body).map_err(|_| anyhow!("Model catalog is not UTF-8"))?;
+    parse_catalog(json)
+}
+
+/// Persists a refreshed catalog so it is used on the next start, even offline.
+pub fn save_cached_catalog(data_dir: &Path, manifest: &CatalogManifest) -> Result<()> {
+    let cache_path = cached_catalog_path(data_dir);
+    let tmp_path = cache_path.with_extension("json.tmp");
+    fs::write(&tmp_path, serde_json::to_vec_pretty(manifest)?)?;
+    fs::rename(&tmp_path, &cache_path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str) -> CatalogEntry {
+        CatalogEntry {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            platform_descriptions: HashMap::new(),
+            engine: EngineType::Whisper,
+            filename: format!("{}.bin", id),
+            is_directory: false,
+            urls: vec![format!("https://example.com/{}.bin", id)],
+            size_mb: 1,
+            sha256: None,
+            languages: vec![],
+            license: None,
+            min_app_version: None,
+            accuracy_score: 0.5,
+            speed_score: 0.5,
+            recommended_platforms: vec![],
+        }
+    }
+
+    fn manifest(models: Vec<CatalogEntry>) -> CatalogManifest {
+        CatalogManifest {
+            schema_version: CATALOG_SCHEMA_VERSION,
+            catalog_version: 1,
+            models,
+        }
+    }
+
+    #[test]
+    fn test_bundled_catalog_is_valid() {
+        let catalog = bundled_catalog().expect("bundled catalog must be valid");
+        assert!(catalog
+            .models
+            .iter()
+            .any(|m| m.id == "parakeet-tdt-0.6b-v3"));
+    }
+
+    #[test]
+    fn test_validate_catalog() {
+        assert!(validate_catalog(&manifest(vec![entry("a"), entry("b")])).is_ok());
+
+        // Duplicate ids
+        assert!(validate_catalog(&manifest(vec![entry("a"), entry("a")])).is_err());
+
+        // Unsupported schema
+        let mut m = manifest(vec![entry("a")]);
+        m.schema_version = CATALOG_SCHEMA_VERSION + 1;
+        assert!(validate_catalog(&m).is_err());
+
+        // Path traversal in filename
+        let mut e = entry("a");
+        e.filename = "../evil.bin".to_string();
+        assert!(validate_catalog(&manifest(vec![e])).is_err());
+
+        // Non-http URL
+        let mut e = entry("a");
+        e.urls = vec!["file:///etc/passwd".to_string()];
+        assert!(validate_catalog(&manifest(vec![e])).is_err());
+
+        // Malformed hash
+        let mut e = entry("a");
+        e.sha256 = Some("abc".to_string());
+        assert!(validate_catalog(&manifest(vec![e])).is_err());
+
+        // Score out of range
+        let mut e = entry("a");
+        e.accuracy_score = 1.5;
+        assert!(validate_catalog(&manifest(vec![e])).is_err());
+    }
+
+    #[test]
+    fn test_parse_catalog_ignores_unknown_fields() {
+        let mut json = serde_json::to_value(manifest(vec![entry("a")])).unwrap();
+        json["published_by"] = "example".into();
+        json["models"][0]["checksum_algorithm"] = "sha256".into();
+
+        let parsed = parse_catalog(&json.to_string()).unwrap();
+        assert_eq!(parsed.models[0].id, "a");
+    }
+
+    #[test]
+    fn test_parse_catalog_checks_schema_first() {
+        // A future schema that renamed `models` fails on its version
+        let json = r#"{"schema_version":2,"catalog_version":1,"entries":[]}"#;
+        let err = parse_catalog(json).unwrap_err().to_string();
+        assert!(err.contains("schema version 2"), "{}", err);
+
+        assert!(parse_catalog(r#"{"catalog_version":1,"models":[]}"#).is_err());
+    }
+
+    #[test]
+    fn test_min_app_version() {
+        let mut e = entry("a");
+        assert!(is_compatible(&e, "0.5.0"));
+
+        e.min_app_version = Some("0.6.0".to_string());
+        assert!(!is_compatible(&e, "0.5.9"));
+        assert!(is_compatible(&e, "0.6.0"));
+        assert!(is_compatible(&e, "1.0.0-beta.1"));
+
+        let models = catalog_models(&manifest(vec![e, entry("b")]), "0.5.0");
+        assert!(!models.contains_key("a"));
+        assert!(models.contains_key("b"));
+    }
+}
@@ -0,0 +1,112 @@
+use crate::audio_toolkit::diarize::{self, DiarizationConfig, Diarizer, OnnxSpeakerEmbedder};
+use crate::audio_toolkit::VoiceActivityDetector;
+use crate::managers::transcription::Transcript;
+use crate::settings::DiarizationSettings;
+use anyhow::{anyhow, Result};
+use log::{debug, info};
+use parking_lot::Mutex;
+use std::time::Instant;
+
+/// Text one speaker said without interruption, with that speaker's label.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LabelledTurn {
+    pub label: String,
+    pub text: String,
+}
+
+struct Loaded {
+    model_path: String,
+    speakers: usize,
+    diarizer: Diarizer,
+}
+
+/// Splits transcripts into speaker turns. The speaker model is loaded on
+/// first use and kept until the diarisation or VAD settings change.
+#[derive(Default)]
+pub struct DiarizationManager {
+    loaded: Mutex<Option<Loaded>>,
+}
+
+impl DiarizationManager {
+    /// Attributes the segments of `transcript` to speakers found in
+    /// `samples`, the same 16 kHz audio the transcript was made from. `vad`
+    /// is called when the speaker model is loaded, for the VAD that keeps
+    /// pauses and noise out of the voice prints.
+    pub fn label(
+        &self,
+        samples: &[f32],
+        transcript: &Transcript,
+        settings: &DiarizationSettings,
+        vad: impl FnOnce() -> Box<dyn VoiceActivityDetector>,
+    ) -> Result<Vec<LabelledTurn>> {
+        let model_path = settings
+            .model_path
+            .as_deref()
+            .ok_or_else(|| anyhow!("No speaker model configured"))?;
+        let speakers = settings.speaker_labels.len();
+
+        let mut loaded = self.loaded.lock();
+        if !loaded
+            .as_ref()
+            .is_some_and(|l| l.model_path == model_path && l.speakers == speakers)
+        {
+            let load_start = Instant::now();
+            let embedder = OnnxSpeakerEmbedder::new(model_path)?;
+            let config = DiarizationConfig {
+                max_speakers: speakers,
+                ..Default::default()
+            };
+            info!(
+                "Speaker model {} loaded in {:?}",
+                model_path,
+                load_start.elapsed()
+            );
+            *loaded = Some(Loaded {
+                model_path: model_path.to_string(),
+                speakers,
+                diarizer: Diarizer::new(Box::new(embedder), config).with_vad(vad()),
+            });
+        }
+        let diarizer = &mut loaded.as_mut().expect("loaded above").diarizer;
+
+        let started = Instant::now();
+        let turns = diarizer.diarize(samples)?;
+        debug!(
+            "Diarised {} samples into {} turns in {:?}",
+            samples.len(),
+            turns.len(),
+            started.elapsed()
+        );
+
+        let attributed = if transcript.segments.is_empty() {
+            // Engines without timings give nothing to line the turns up with
+            debug!("No segment timings; sharing the text out by turn length");
+            diarize::spread(&transcript.text, &turns)
+        } else {
+            let segments = transcript
+                .segments
+                .iter()
+                .map(|s| (s.start, s.end, s.text.as_str()));
+            diarize::attribute(segments, &turns)
+        };
+
+        Ok(attributed
+            .into_iter()
+            .map(|a| LabelledTurn {
+                label: settings
+                    .speaker_labels
+                    .get(a.speaker)
+                    .cloned()
+                    .unwrap_or_else(|| format!("Speaker {}", a.speaker + 1)),
+                text: a.text,
+            })
+            .collect())
+    }
+
+    /// Frees the speaker model.
+    pub fn unload(&self) {
+        if self.loaded.lock().take().is_some() {
+            debug!("Speaker model unloaded");
+        }
+    }
+}
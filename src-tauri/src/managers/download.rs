@@ -0,0 +1,236 @@
+use anyhow::{anyhow, Result};
+use log::warn;
+use reqwest::StatusCode;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Attempts per model before a download is reported as failed. Attempts
+/// rotate through the catalog's mirror URLs.
+pub const MAX_ATTEMPTS: u32 = 6;
+
+const BASE_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Headroom kept free on top of what the model needs, so a download doesn't
+/// leave the disk completely full.
+const DISK_SPACE_MARGIN: u64 = 200 * 1024 * 1024;
+
+/// Pending downloads, persisted in the app data directory.
+const QUEUE_FILE: &str = "download_queue.json";
+
+/// Outcome of a single download attempt.
+pub enum FetchError {
+    /// Network hiccup, server error or truncated body; worth another try
+    Retryable(anyhow::Error),
+    /// This URL won't serve the model (404, bad redirect, ...), but another
+    /// mirror may
+    Mirror(anyhow::Error),
+    /// Nothing will help (disk full, cancelled, ...)
+    Fatal(anyhow::Error),
+}
+
+impl From<reqwest::Error> for FetchError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_builder() || e.is_redirect() {
+            FetchError::Mirror(e.into())
+        } else {
+            FetchError::Retryable(e.into())
+        }
+    }
+}
+
+impl From<std::io::Error> for FetchError {
+    fn from(e: std::io::Error) -> Self {
+        // Local file errors (permissions, disk full) won't fix themselves
+        FetchError::Fatal(e.into())
+    }
+}
+
+/// Delay before retry number `attempt` (0-based): 1s, 2s, 4s, ... capped at a minute.
+pub fn backoff_delay(attempt: u32) -> Duration {
+    BASE_BACKOFF
+        .checked_mul(1u32 << attempt.min(16))
+        .unwrap_or(MAX_BACKOFF)
+        .min(MAX_BACKOFF)
+}
+
+pub fn is_retryable_status(status: StatusCode) -> bool {
+    status.is_server_error()
+        || status == StatusCode::REQUEST_TIMEOUT
+        || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Total size from a `Content-Range: bytes start-end/total` header.
+pub fn parse_content_range_total(value: &str) -> Option<u64> {
+    let (unit, range) = value.trim().split_once(' ')?;
+    if unit != "bytes" {
+        return None;
+    }
+    range.split_once('/')?.1.parse().ok()
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+/// Fails with a readable message when `dir` can't hold `needed` more bytes.
+pub fn check_disk_space(dir: &Path, needed: u64) -> Result<()> {
+    let available = match available_space(dir) {
+        Ok(available) => available,
+        Err(e) => {
+            // Don't block downloads on platforms where the query fails
+            warn!("Could not determine free disk space: {}", e);
+            return Ok(());
+        }
+    };
+
+    let required = needed.saturating_add(DISK_SPACE_MARGIN);
+    if available < required {
+        return Err(anyhow!(
+            "Not enough disk space: {} needed, {} available in {}",
+            format_bytes(required),
+            format_bytes(available),
+            dir.display()
+        ));
+    }
+    Ok(())
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const GB: f64 = 1024.0 * 1024.0 * 1024.0;
+    const MB: f64 = 1024.0 * 1024.0;
+    let bytes = bytes as f64;
+    if bytes >= GB {
+        format!("{:.1} GB", bytes / GB)
+    } else {
+        format!("{:.0} MB", bytes / MB)
+    }
+}
+
+#[cfg(unix)]
+fn available_space(dir: &Path) -> Result<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = CString::new(dir.as_os_str().as_bytes())?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    #[allow(clippy::unnecessary_cast)]
+    Ok(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+#[cfg(windows)]
+fn available_space(dir: &Path) -> Result<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows::core::PCWSTR;
+    use windows::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide: Vec<u16> = dir.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut available = 0u64;
+    unsafe {
+        GetDiskFreeSpaceExW(
+            PCWSTR(wide.as_ptr()),
+            Some(&mut available as *mut u64),
+            None,
+            None,
+        )?
+    };
+    Ok(available)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn available_space(_dir: &Path) -> Result<u64> {
+    Err(anyhow!("Free space query not supported on this platform"))
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+fn queue_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(QUEUE_FILE)
+}
+
+pub fn load_queue(data_dir: &Path) -> Vec<String> {
+    let path = queue_path(data_dir);
+    if !path.exists() {
+        return Vec::new();
+    }
+
+    match fs::read_to_string(&path)
+        .map_err(anyhow::Error::from)
+        .and_then(|json| serde_json::from_str::<Vec<String>>(&json).map_err(Into::into))
+    {
+        Ok(queue) => queue,
+        Err(e) => {
+            warn!("Ignoring unreadable download queue: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+pub fn save_queue(data_dir: &Path, queue: &[String]) {
+    let path = queue_path(data_dir);
+    let result = if queue.is_empty() {
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    } else {
+        serde_json::to_vec(queue)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| fs::write(&path, json).map_err(Into::into))
+    };
+
+    if let Err(e) = result {
+        warn!("Failed to persist download queue: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_delay() {
+        assert_eq!(backoff_delay(0), Duration::from_secs(1));
+        assert_eq!(backoff_delay(1), Duration::from_secs(2));
+        assert_eq!(backoff_delay(3), Duration::from_secs(8));
+        assert_eq!(backoff_delay(10), MAX_BACKOFF);
+        assert_eq!(backoff_delay(u32::MAX), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn test_retryable_status() {
+        assert!(is_retryable_status(StatusCode::BAD_GATEWAY));
+        assert!(is_retryable_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(!is_retryable_status(StatusCode::NOT_FOUND));
+        assert!(!is_retryable_status(StatusCode::FORBIDDEN));
+    }
+
+    #[test]
+    fn test_parse_content_range_total() {
+        assert_eq!(parse_content_range_total("bytes 100-199/1000"), Some(1000));
+        assert_eq!(parse_content_range_total("bytes 0-0/*"), None);
+        assert_eq!(parse_content_range_total("items 0-1/2"), None);
+    }
+
+    #[test]
+    fn test_queue_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(load_queue(dir.path()).is_empty());
+
+        let queue = vec!["turbo".to_string(), "small".to_string()];
+        save_queue(dir.path(), &queue);
+        assert_eq!(load_queue(dir.path()), queue);
+
+        save_queue(dir.path(), &[]);
+        assert!(!queue_path(dir.path()).exists());
+    }
+
+    #[test]
+    fn test_disk_space_preflight() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(check_disk_space(dir.path(), 0).is_ok());
+        assert!(check_disk_space(dir.path(), u64::MAX / 2).is_err());
+    }
+}
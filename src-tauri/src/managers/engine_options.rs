@@ -0,0 +1,258 @@
+use crate::settings::AppSettings;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::Path;
+use transcribe_rs::engines::parakeet::{ExecutionProvider, ParakeetModelParams, QuantizationType};
+
+/// Parakeet ONNX graphs that ship in both precisions. The preprocessor
+/// (`nemo128.onnx`) is always full precision.
+const QUANTIZABLE_GRAPHS: [&str; 2] = ["encoder-model", "decoder_joint-model"];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ParakeetQuantization {
+    /// Full precision: most accurate, slower and larger in memory
+    Fp32,
+    #[default]
+    Int8,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ParakeetExecutionProvider {
+    #[default]
+    Cpu,
+    CoreMl,
+    Cuda,
+    DirectMl,
+}
+
+/// Engine options for one Parakeet model, persisted per model id.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default, Type)]
+pub struct ParakeetOptions {
+    #[serde(default)]
+    pub quantization: ParakeetQuantization,
+    /// Intra-op threads; `None` uses every core. A lower cap keeps other
+    /// applications responsive while a transcription runs.
+    #[serde(default)]
+    pub threads: Option<u32>,
+    #[serde(default)]
+    pub execution_provider: ParakeetExecutionProvider,
+}
+
+/// What a model directory and this machine can run, for the settings UI.
+#[derive(Serialize, Debug, Clone, Type)]
+pub struct ParakeetCapabilities {
+    pub quantizations: Vec<ParakeetQuantization>,
+    pub execution_providers: Vec<ParakeetExecutionProvider>,
+    pub max_threads: u32,
+}
+
+/// The saved options for `model_id`. Without saved options this is the
+/// default, switched to fp32 when the model only ships full-precision weights.
+pub fn options_for(settings: &AppSettings, model_id: &str, model_dir: &Path) -> ParakeetOptions {
+    if let Some(options) = settings.parakeet_model_options.get(model_id) {
+        return options.clone();
+    }
+
+    let mut options = ParakeetOptions::default();
+    let available = available_quantizations(model_dir);
+    if !available.contains(&options.quantization) && !available.is_empty() {
+        options.quantization = available[0];
+    }
+    options
+}
+
+pub fn max_threads() -> u32 {
+    std::thread::available_parallelism()
+        .map(|n| n.get() as u32)
+        .unwrap_or(4) // Fallback to 4 threads if detection fails
+}
+
+/// Execution providers that can exist on this platform. Whether the bundled
+/// ONNX Runtime was built with them is only known at load time; unavailable
+/// providers fall back to the CPU.
+pub fn platform_execution_providers() -> Vec<ParakeetExecutionProvider> {
+    let mut providers = vec![ParakeetExecutionProvider::Cpu];
+    if cfg!(target_os = "macos") {
+        providers.push(ParakeetExecutionProvider::CoreMl);
+    }
+    if cfg!(any(target_os = "linux", target_os = "windows")) {
+        providers.push(ParakeetExecutionProvider::Cuda);
+    }
+    if cfg!(target_os = "windows") {
+        providers.push(ParakeetExecutionProvider::DirectMl);
+    }
+    providers
+}
+
+fn graph_filename(graph: &str, quantization: ParakeetQuantization) -> String {
+    match quantization {
+        ParakeetQuantization::Fp32 => format!("{}.onnx", graph),
+        ParakeetQuantization::Int8 => format!("{}.int8.onnx", graph),
+    }
+}
+
+/// Precisions whose graphs are all present in `model_dir`.
+pub fn available_quantizations(model_dir: &Path) -> Vec<ParakeetQuantization> {
+    [ParakeetQuantization::Fp32, ParakeetQuantization::Int8]
+        .into_iter()
+        .filter(|q| {
+            QUANTIZABLE_GRAPHS
+                .iter()
+                .all(|graph| model_dir.join(graph_filename(graph, *q)).is_file())
+        })
+        .collect()
+}
+
+pub fn capabilities(model_dir: &Path) -> ParakeetCapabilities {
+    ParakeetCapabilities {
+        quantizations: available_quantizations(model_dir),
+        execution_providers: platform_execution_providers(),
+        max_threads: max_threads(),
+    }
+}
+
+/// Checks options against the files in `model_dir` and this machine, so a
+/// missing fp32 graph is reported instead of silently loading int8.
+pub fn validate(model_dir: &Path, options: &ParakeetOptions) -> Result<(), String> {
+    for graph in QUANTIZABLE_GRAPHS {
+        let filename = graph_filename(graph, options.quantization);
+        if !model_dir.join(&filename).is_file() {
+            return Err(format!(
+                "{:?} weights are not installed for this model (missing {})",
+                options.quantization, filename
+            ));
+        }
+    }
+
+    if let Some(threads) = options.threads {
+        let max = max_threads();
+        if threads == 0 || threads > max {
+            return Err(format!("Thread count must be between 1 and {}", max));
+        }
+    }
+
+    if !platform_execution_providers().contains(&options.execution_provider) {
+        return Err(format!(
+            "Execution provider {:?} is not supported on this platform",
+            options.execution_provider
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn to_model_params(options: &ParakeetOptions) -> ParakeetModelParams {
+    let quantization = match options.quantization {
+        ParakeetQuantization::Fp32 => QuantizationType::FP32,
+        ParakeetQuantization::Int8 => QuantizationType::Int8,
+    };
+    let provider = match options.execution_provider {
+        ParakeetExecutionProvider::Cpu => ExecutionProvider::Cpu,
+        ParakeetExecutionProvider::CoreMl => ExecutionProvider::CoreML,
+        ParakeetExecutionProvider::Cuda => ExecutionProvider::Cuda,
+        ParakeetExecutionProvider::DirectMl => ExecutionProvider::DirectML,
+    };
+    let threads = options.threads.unwrap_or_else(max_threads) as usize;
+
+    ParakeetModelParams::quantized(quantization)
+        .with_threads(Some(threads))
+        .with_execution_provider(provider)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn model_dir(files: &[&str]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for file in files {
+            fs::write(dir.path().join(file), b"onnx").unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_available_quantizations() {
+        let int8_only = model_dir(&[
+            "encoder-model.int8.onnx",
+            "decoder_joint-model.int8.onnx",
+            "nemo128.onnx",
+        ]);
+        assert_eq!(
+            available_quantizations(int8_only.path()),
+            vec![ParakeetQuantization::Int8]
+        );
+
+        // Half an fp32 set doesn't count
+        let mixed = model_dir(&[
+            "encoder-model.onnx",
+            "encoder-model.int8.onnx",
+            "decoder_joint-model.int8.onnx",
+        ]);
+        assert_eq!(
+            available_quantizations(mixed.path()),
+            vec![ParakeetQuantization::Int8]
+        );
+    }
+
+    #[test]
+    fn test_validate_options() {
+        let dir = model_dir(&["encoder-model.int8.onnx", "decoder_joint-model.int8.onnx"]);
+
+        assert!(validate(dir.path(), &ParakeetOptions::default()).is_ok());
+
+        let fp32 = ParakeetOptions {
+            quantization: ParakeetQuantization::Fp32,
+            ..Default::default()
+        };
+        assert!(validate(dir.path(), &fp32).is_err());
+
+        let zero_threads = ParakeetOptions {
+            threads: Some(0),
+            ..Default::default()
+        };
+        assert!(validate(dir.path(), &zero_threads).is_err());
+
+        let one_thread = ParakeetOptions {
+            threads: Some(1),
+            ..Default::default()
+        };
+        assert!(validate(dir.path(), &one_thread).is_ok());
+    }
+
+    #[test]
+    fn test_default_options_follow_installed_weights() {
+        let settings = crate::settings::get_default_settings();
+
+        let fp32_only = model_dir(&["encoder-model.onnx", "decoder_joint-model.onnx"]);
+        let options = options_for(&settings, "user-parakeet", fp32_only.path());
+        assert_eq!(options.quantization, ParakeetQuantization::Fp32);
+        assert!(validate(fp32_only.path(), &options).is_ok());
+
+        let both = model_dir(&[
+            "encoder-model.onnx",
+            "decoder_joint-model.onnx",
+            "encoder-model.int8.onnx",
+            "decoder_joint-model.int8.onnx",
+        ]);
+        assert_eq!(
+            options_for(&settings, "parakeet", both.path()).quantization,
+            ParakeetQuantization::Int8
+        );
+    }
+
+    #[test]
+    fn test_to_model_params() {
+        let params = to_model_params(&ParakeetOptions {
+            quantization: ParakeetQuantization::Fp32,
+            threads: Some(2),
+            execution_provider: ParakeetExecutionProvider::Cuda,
+        });
+        assert_eq!(params.quantization, QuantizationType::FP32);
+        assert_eq!(params.intra_threads, Some(2));
+        assert_eq!(params.execution_provider, ExecutionProvider::Cuda);
+    }
+}
@@ -0,0 +1,464 @@
+use anyhow::{anyhow, Result};
+use minisign_verify::{PublicKey, Signature};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use specta::Type;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Detached signatures are expected next to the artifact, minisign style.
+const SIGNATURE_SUFFIX: &str = ".minisig";
+
+/// A minisign signature file is a few hundred bytes.
+const MAX_SIGNATURE_BYTES: usize = 4096;
+
+const READ_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// What was installed for a model, written next to it at install time so the
+/// files can be re-checked later even though the downloaded archive is gone.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityRecord {
+    /// SHA-256 of the downloaded file or archive
+    pub source_sha256: String,
+    /// Whether the download matched a catalog checksum or a signature when it
+    /// was installed. Without that, `source_sha256` is only what arrived.
+    #[serde(default)]
+    pub source_verified: bool,
+    /// SHA-256 of every installed file, keyed by path relative to the model
+    pub files: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationStatus {
+    /// Files match the catalog hash or the record written at install time
+    Verified,
+    /// At least one file is missing, extra or modified
+    Mismatch,
+    /// The model isn't downloaded
+    NotInstalled,
+    /// Nothing trusted to compare against: no catalog hash, and no record or
+    /// only one written for a download that was never checked
+    Unverifiable,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ModelVerification {
+    pub model_id: String,
+    pub status: VerificationStatus,
+    pub detail: Option<String>,
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; READ_BUFFER_SIZE];
+
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Hashes `path` and compares it with the expected hex digest.
+/// Returns the actual digest on success.
+pub fn verify_sha256(path: &Path, expected: &str) -> Result<String> {
+    let actual = sha256_file(path)?;
+    if actual.eq_ignore_ascii_case(expected) {
+        Ok(actual)
+    } else {
+        Err(anyhow!(
+            "Checksum mismatch for {}: expected {}, got {}",
+            path.display(),
+            expected.to_lowercase(),
+            actual
+        ))
+    }
+}
+
+/// Accepts either the bare base64 key or the full contents of a minisign `.pub` file.
+pub fn parse_public_key(key: &str) -> Result<PublicKey> {
+    let key = key.trim();
+    let parsed = if key.contains('\n') {
+        PublicKey::decode(key)
+    } else {
+        PublicKey::from_base64(key)
+    };
+    parsed.map_err(|e| anyhow!("Invalid signing public key: {}", e))
+}
+
+/// Verifies a minisign detached signature over the file at `path`.
+pub fn verify_signature(path: &Path, signature: &str, public_key: &str) -> Result<()> {
+    let public_key = parse_public_key(public_key)?;
+    let signature =
+        Signature::decode(signature).map_err(|e| anyhow!("Invalid model signature: {}", e))?;
+
+    let mut verifier = public_key
+        .verify_stream(&signature)
+        .map_err(|e| anyhow!("Unsupported model signature: {}", e))?;
+
+    let mut file = File::open(path)?;
+    let mut buffer = vec![0u8; READ_BUFFER_SIZE];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        verifier.update(&buffer[..read]);
+    }
+
+    verifier.finalize().map_err(|e| {
+        anyhow!(
+            "Signature verification failed for {}: {}",
+            path.display(),
+            e
+        )
+    })
+}
+
+pub fn signature_url(url: &str) -> String {
+    format!("{}{}", url, SIGNATURE_SUFFIX)
+}
+
+/// Downloads the detached signature published alongside `url`.
+pub async fn fetch_signature(client: &reqwest::Client, url: &str) -> Result<String> {
+    let sig_url = signature_url(url);
+    let response = client.get(&sig_url).send().await?;
+
+    if !response.status().is_success() {
+        return Err(anyhow!(
+            "Failed to fetch model signature from {}: HTTP {}",
+            sig_url,
+            response.status()
+        ));
+    }
+
+    let body = response.bytes().await?;
+    if body.len() > MAX_SIGNATURE_BYTES {
+        return Err(anyhow!("Model signature at {} is too large", sig_url));
+    }
+
+    String::from_utf8(body.to_vec()).map_err(|_| anyhow!("Model signature is not UTF-8"))
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+/// Hashes every regular file under `root`. Keys use `/` separators on all platforms.
+pub fn hash_tree(root: &Path) -> Result<BTreeMap<String, String>> {
+    let mut files = BTreeMap::new();
+    let mut pending = vec![root.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            let path = entry.path();
+
+            if file_type.is_dir() {
+                pending.push(path);
+            } else if file_type.is_file() {
+                let relative = path
+                    .strip_prefix(root)?
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                files.insert(relative, sha256_file(&path)?);
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// Single digest over a set of file hashes, used as the source hash of models
+/// that weren't installed from one archive.
+pub fn tree_digest(files: &BTreeMap<String, String>) -> String {
+    let mut hasher = Sha256::new();
+    for (path, hash) in files {
+        hasher.update(path.as_bytes());
+        hasher.update(b"\0");
+        hasher.update(hash.as_bytes());
+        hasher.update(b"\n");
+    }
+    hex::encode(hasher.finalize())
+}
+
+pub fn record_path(models_dir: &Path, filename: &str) -> PathBuf {
+    models_dir.join(format!("{}.integrity.json", filename))
+}
+
+pub fn write_record(path: &Path, record: &IntegrityRecord) -> Result<()> {
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, serde_json::to_vec_pretty(record)?)?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+pub fn read_record(path: &Path) -> Option<IntegrityRecord> {
+    let json = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&json).ok()
+}
+
+/// Re-checks an installed model.
+///
+/// Single-file models are compared with the catalog hash when there is one.
+/// Directory models (and files without a catalog hash) are compared with the
+/// record written when they were installed, which only counts as verified if
+/// the download itself was checked.
+pub fn verify_installed(
+    model_path: &Path,
+    is_directory: bool,
+    catalog_sha256: Option<&str>,
+    record: Option<&IntegrityRecord>,
+) -> Result<(VerificationStatus, Option<String>)> {
+    let installed = if is_directory {
+        model_path.is_dir()
+    } else {
+        model_path.is_file()
+    };
+    if !installed {
+        return Ok((VerificationStatus::NotInstalled, None));
+    }
+
+    if !is_directory {
+        if let Some(expected) = catalog_sha256 {
+            return Ok(match verify_sha256(model_path, expected) {
+                Ok(_) => (VerificationStatus::Verified, None),
+                Err(e) => (VerificationStatus::Mismatch, Some(e.to_string())),
+            });
+        }
+    }
+
+    let Some(record) = record else {
+        return Ok((
+            VerificationStatus::Unverifiable,
+            Some("No checksum in the catalog and no install record".to_string()),
+        ));
+    };
+
+    let actual = if is_directory {
+        hash_tree(model_path)?
+    } else {
+        let name = model_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        BTreeMap::from([(name, sha256_file(model_path)?)])
+    };
+
+    if let Some(detail) = diff_files(&record.files, &actual) {
+        return Ok((VerificationStatus::Mismatch, Some(detail)));
+    }
+
+    // Files are intact, but the catalog may now describe a different artifact
+    if let Some(expected) = catalog_sha256 {
+        if !record.source_sha256.eq_ignore_ascii_case(expected) {
+            return Ok((
+                VerificationStatus::Mismatch,
+                Some(
+                    "Installed files come from a different release than the catalog lists"
+                        .to_string(),
+                ),
+            ));
+        }
+    }
+    if !record.source_verified {
+        return Ok((
+            VerificationStatus::Unverifiable,
+            Some(
+                "Files match what was installed, but their source was never checked against a checksum or signature"
+                    .to_string(),
+            ),
+        ));
+    }
+
+    Ok((VerificationStatus::Verified, None))
+}
+
+fn diff_files(
+    expected: &BTreeMap<String, String>,
+    actual: &BTreeMap<String, String>,
+) -> Option<String> {
+    for (path, hash) in expected {
+        match actual.get(path) {
+            None => return Some(format!("Missing file: {}", path)),
+            Some(actual_hash) if actual_hash != hash => {
+                return Some(format!("Modified file: {}", path))
+            }
+            _ => {}
+        }
+    }
+
+    actual
+        .keys()
+        .find(|path| !expected.contains_key(*path))
+        .map(|path| format!("Unexpected file: {}", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    // Test vector from the minisign-verify crate (prehashed signature of b"test")
+    const PUBLIC_KEY: &str = "RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3";
+    const SIGNATURE: &str = "untrusted comment: signature from minisign secret key
+RUQf6LRCGA9i559r3g7V1qNyJDApGip8MfqcadIgT9CuhV3EMhHoN1mGTkUidF/z7SrlQgXdy8ofjb7bNJJylDOocrCo8KLzZwo=
+trusted comment: timestamp:1556193335\tfile:test
+y/rUw2y8/hOUYjZU71eHp/Wo1KZ40fGy2VJEDl34XMJM+TX48Ss/17u3IvIfbVR1FkZZSNCisQbuQY+bHwhEBg==";
+
+    // sha256("test")
+    const TEST_SHA256: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+
+    /// Serves `files` over plain HTTP/1.1 on an ephemeral localhost port.
+    fn serve(files: Vec<(&'static str, Vec<u8>)>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { break };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut request_line = String::new();
+                if reader.read_line(&mut request_line).is_err() {
+                    continue;
+                }
+                // Drain the headers
+                let mut line = String::new();
+                while reader.read_line(&mut line).is_ok() && line != "\r\n" && !line.is_empty() {
+                    line.clear();
+                }
+
+                let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+                let response = match files.iter().find(|(p, _)| *p == path) {
+                    Some((_, body)) => {
+                        let mut r = format!(
+                            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                            body.len()
+                        )
+                        .into_bytes();
+                        r.extend_from_slice(body);
+                        r
+                    }
+                    None => {
+                        b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                            .to_vec()
+                    }
+                };
+                let _ = stream.write_all(&response);
+            }
+        });
+
+        format!("http://{}", addr)
+    }
+
+    fn download(url: &str, dest: &Path) {
+        let bytes = reqwest::blocking::get(url).unwrap().bytes().unwrap();
+        fs::write(dest, bytes).unwrap();
+    }
+
+    #[test]
+    fn test_sha256_over_local_server() {
+        let base = serve(vec![
+            ("/model.bin", b"test".to_vec()),
+            ("/tampered.bin", b"Test".to_vec()),
+        ]);
+        let dir = tempfile::tempdir().unwrap();
+
+        let good = dir.path().join("model.bin");
+        download(&format!("{}/model.bin", base), &good);
+        assert_eq!(verify_sha256(&good, TEST_SHA256).unwrap(), TEST_SHA256);
+        assert!(verify_sha256(&good, &TEST_SHA256.to_uppercase()).is_ok());
+
+        let bad = dir.path().join("tampered.bin");
+        download(&format!("{}/tampered.bin", base), &bad);
+        assert!(verify_sha256(&bad, TEST_SHA256).is_err());
+    }
+
+    #[test]
+    fn test_detached_signature_over_local_server() {
+        let base = serve(vec![
+            ("/model.bin", b"test".to_vec()),
+            ("/model.bin.minisig", SIGNATURE.as_bytes().to_vec()),
+            ("/unsigned.bin", b"test".to_vec()),
+        ]);
+        let dir = tempfile::tempdir().unwrap();
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let client = reqwest::Client::new();
+
+        let model = dir.path().join("model.bin");
+        let url = format!("{}/model.bin", base);
+        download(&url, &model);
+        let signature = runtime.block_on(fetch_signature(&client, &url)).unwrap();
+        assert!(verify_signature(&model, &signature, PUBLIC_KEY).is_ok());
+
+        // Same signature over different content must fail
+        fs::write(&model, b"Test").unwrap();
+        assert!(verify_signature(&model, &signature, PUBLIC_KEY).is_err());
+
+        // Missing signature is an error, not a silent pass
+        let unsigned = format!("{}/unsigned.bin", base);
+        assert!(runtime
+            .block_on(fetch_signature(&client, &unsigned))
+            .is_err());
+    }
+
+    #[test]
+    fn test_verify_installed_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let model_dir = dir.path().join("parakeet");
+        fs::create_dir_all(model_dir.join("sub")).unwrap();
+        fs::write(model_dir.join("vocab.txt"), b"a b c").unwrap();
+        fs::write(model_dir.join("sub/encoder.onnx"), b"weights").unwrap();
+
+        let record = IntegrityRecord {
+            source_sha256: TEST_SHA256.to_string(),
+            source_verified: true,
+            files: hash_tree(&model_dir).unwrap(),
+        };
+        assert!(record.files.contains_key("sub/encoder.onnx"));
+
+        let (status, _) =
+            verify_installed(&model_dir, true, Some(TEST_SHA256), Some(&record)).unwrap();
+        assert_eq!(status, VerificationStatus::Verified);
+        let (status, _) = verify_installed(&model_dir, true, None, Some(&record)).unwrap();
+        assert_eq!(status, VerificationStatus::Verified);
+
+        // Intact files from a source nobody checked prove nothing, even when
+        // the record claims the catalog's hash
+        let unchecked = IntegrityRecord {
+            source_verified: false,
+            ..record.clone()
+        };
+        for catalog in [None, Some(TEST_SHA256)] {
+            let (status, _) =
+                verify_installed(&model_dir, true, catalog, Some(&unchecked)).unwrap();
+            assert_eq!(status, VerificationStatus::Unverifiable);
+        }
+
+        fs::write(model_dir.join("vocab.txt"), b"a b d").unwrap();
+        let (status, detail) = verify_installed(&model_dir, true, None, Some(&record)).unwrap();
+        assert_eq!(status, VerificationStatus::Mismatch);
+        assert_eq!(detail.as_deref(), Some("Modified file: vocab.txt"));
+
+        let (status, _) = verify_installed(&model_dir, true, None, None).unwrap();
+        assert_eq!(status, VerificationStatus::Unverifiable);
+
+        let (status, _) =
+            verify_installed(&dir.path().join("missing"), true, None, Some(&record)).unwrap();
+        assert_eq!(status, VerificationStatus::NotInstalled);
+    }
+}
@@ -1,4 +1,14 @@
 pub mod audio;
+pub mod benchmark;
+pub mod catalog;
+pub mod diarization;
+pub mod download;
+pub mod engine_options;
 pub mod history;
+pub mod integrity;
 pub mod model;
+pub mod model_import;
+pub mod model_pack;
+pub mod model_policy;
+pub mod queue;
 pub mod transcription;
@@ -1,18 +1,28 @@
-use crate::settings::{get_settings, write_settings};
+use crate::host::Host;
+use crate::managers::benchmark::{self, BenchmarkResult};
+use crate::managers::catalog;
+use crate::managers::download::{self, FetchError};
+use crate::managers::integrity::{self, IntegrityRecord, ModelVerification, VerificationStatus};
+use crate::managers::model_import::{self, UserModelEntry};
+use crate::managers::model_pack::{
+    self, PackInstallResult, PackInstallStatus, PackManifest, PackModel,
+};
 use anyhow::Result;
 use flate2::read::GzDecoder;
 use futures_util::StreamExt;
 use log::{debug, info, warn};
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
+use reqwest::header::{CONTENT_RANGE, RANGE};
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use specta::Type;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::fs::File;
 use std::io::Write;
-use std::path::{Component, PathBuf};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
 use tar::Archive;
-use tauri::{AppHandle, Emitter, Manager};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub enum EngineType {
@@ -27,7 +37,11 @@ pub struct ModelInfo {
     pub description: String,
     pub filename: String,
     pub url: Option<String>,
+    pub urls: Vec<String>,
     pub size_mb: u64,
+    pub sha256: Option<String>,
+    pub languages: Vec<String>,
+    pub license: Option<String>,
     pub is_downloaded: bool,
     pub is_downloading: bool,
     pub partial_size: u64,
@@ -47,166 +61,58 @@ pub struct DownloadProgress {
 }
 
 pub struct ModelManager {
-    app_handle: AppHandle,
+    host: Arc<dyn Host>,
+    data_dir: PathBuf,
     models_dir: PathBuf,
     available_models: RwLock<HashMap<String, ModelInfo>>,
+    /// Held for the duration of a download so only one runs at a time
+    download_slot: tokio::sync::Mutex<()>,
+    download_queue: Mutex<Vec<String>>,
+    cancelled_downloads: Mutex<HashSet<String>>,
+    benchmark_results: RwLock<BTreeMap<String, BenchmarkResult>>,
 }
 
 impl ModelManager {
-    pub fn new(app_handle: &AppHandle) -> Result<Self> {
+    pub fn new(host: Arc<dyn Host>) -> Result<Self> {
         // Create models directory in app data
-        let models_dir = app_handle
-            .path()
-            .app_data_dir()
-            .map_err(|e| anyhow::anyhow!("Failed to get app data dir: {}", e))?
-            .join("models");
+        let data_dir = host.app_data_dir()?;
+        let models_dir = data_dir.join("models");
 
         if !models_dir.exists() {
             fs::create_dir_all(&models_dir)?;
         }
 
-        let mut available_models = HashMap::new();
-
-        // TODO this should be read from a JSON file or something..
-        available_models.insert(
-            "small".to_string(),
-            ModelInfo {
-                id: "small".to_string(),
-                name: "Whisper Small".to_string(),
-                description: "Fast and fairly accurate.".to_string(),
-                filename: "ggml-small.bin".to_string(),
-                url: Some("https://blob.handy.computer/ggml-small.bin".to_string()),
-                size_mb: 487,
-                is_downloaded: false,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: false,
-                engine_type: EngineType::Whisper,
-                accuracy_score: 0.60,
-                speed_score: 0.85,
-                platform_recommended: false,
-            },
-        );
-
-        // Add downloadable models
-        available_models.insert(
-            "medium".to_string(),
-            ModelInfo {
-                id: "medium".to_string(),
-                name: "Whisper Medium".to_string(),
-                description: "Good accuracy, medium speed".to_string(),
-                filename: "whisper-medium-q4_1.bin".to_string(),
-                url: Some("https://blob.handy.computer/whisper-medium-q4_1.bin".to_string()),
-                size_mb: 492, // Approximate size
-                is_downloaded: false,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: false,
-                engine_type: EngineType::Whisper,
-                accuracy_score: 0.75,
-                speed_score: 0.60,
-                platform_recommended: false,
-            },
-        );
-
-        available_models.insert(
-            "turbo".to_string(),
-            ModelInfo {
-                id: "turbo".to_string(),
-                name: "Whisper Turbo".to_string(),
-                description: "Balanced accuracy and speed.".to_string(),
-                filename: "ggml-large-v3-turbo.bin".to_string(),
-                url: Some("https://blob.handy.computer/ggml-large-v3-turbo.bin".to_string()),
-                size_mb: 1600, // Approximate size
-                is_downloaded: false,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: false,
-                engine_type: EngineType::Whisper,
-                accuracy_score: 0.80,
-                speed_score: 0.40,
-                platform_recommended: false,
-            },
-        );
-
-        available_models.insert(
-            "large".to_string(),
-            ModelInfo {
-                id: "large".to_string(),
-                name: "Whisper Large".to_string(),
-                description: "Good accuracy, but slow.".to_string(),
-                filename: "ggml-large-v3-q5_0.bin".to_string(),
-                url: Some("https://blob.handy.computer/ggml-large-v3-q5_0.bin".to_string()),
-                size_mb: 1100, // Approximate size
-                is_downloaded: false,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: false,
-                engine_type: EngineType::Whisper,
-                accuracy_score: 0.85,
-                speed_score: 0.30,
-                platform_recommended: false,
-            },
-        );
+        // Models come from the catalog manifest (bundled, or a cached refresh)
+        let catalog = catalog::load_catalog(&data_dir)?;
+        let mut available_models = catalog::catalog_models(&catalog, env!("CARGO_PKG_VERSION"));
 
-        // Add NVIDIA Parakeet models (directory-based)
-        // Note: Parakeet is especially recommended for Windows since GPU backends are disabled
-        available_models.insert(
-            "parakeet-tdt-0.6b-v2".to_string(),
-            ModelInfo {
-                id: "parakeet-tdt-0.6b-v2".to_string(),
-                name: "Parakeet V2".to_string(),
-                #[cfg(target_os = "windows")]
-                description: "English only. Recommended for Windows - fast and accurate without GPU requirements.".to_string(),
-                #[cfg(not(target_os = "windows"))]
-                description: "English only. The best model for English speakers.".to_string(),
-                filename: "parakeet-tdt-0.6b-v2-int8".to_string(), // Directory name
-                url: Some("https://blob.handy.computer/parakeet-v2-int8.tar.gz".to_string()),
-                size_mb: 473, // Approximate size for int8 quantized model
-                is_downloaded: false,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: true,
-                engine_type: EngineType::Parakeet,
-                accuracy_score: 0.85,
-                speed_score: 0.85,
-                #[cfg(target_os = "windows")]
-                platform_recommended: true,
-                #[cfg(not(target_os = "windows"))]
-                platform_recommended: false,
-            },
-        );
+        // Models the user imported from disk
+        for entry in model_import::load_user_models(&data_dir) {
+            if available_models.contains_key(&entry.id) {
+                warn!(
+                    "Imported model {} clashes with a catalog model, skipping",
+                    entry.id
+                );
+                continue;
+            }
+            let mut model_info = entry.to_model_info();
+            model_info.size_mb = model_size_mb(&models_dir.join(&entry.filename));
+            available_models.insert(entry.id.clone(), model_info);
+        }
 
-        available_models.insert(
-            "parakeet-tdt-0.6b-v3".to_string(),
-            ModelInfo {
-                id: "parakeet-tdt-0.6b-v3".to_string(),
-                name: "Parakeet V3".to_string(),
-                #[cfg(target_os = "windows")]
-                description: "English only. Recommended for Windows - optimized with Int8 quantization for 2x faster inference.".to_string(),
-                #[cfg(not(target_os = "windows"))]
-                description: "Fast and accurate with Int8 quantization".to_string(),
-                filename: "parakeet-tdt-0.6b-v3-int8".to_string(), // Directory name
-                url: Some("https://blob.handy.computer/parakeet-v3-int8.tar.gz".to_string()),
-                size_mb: 478, // Approximate size for int8 quantized model
-                is_downloaded: false,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: true,
-                engine_type: EngineType::Parakeet,
-                accuracy_score: 0.80,
-                speed_score: 0.85,
-                #[cfg(target_os = "windows")]
-                platform_recommended: true,
-                #[cfg(not(target_os = "windows"))]
-                platform_recommended: false,
-            },
-        );
+        // Downloads still pending when the app last exited
+        let download_queue = download::load_queue(&data_dir);
+        let benchmark_results = benchmark::load_results(&data_dir);
 
         let manager = Self {
-            app_handle: app_handle.clone(),
+            host,
+            data_dir,
             models_dir,
             available_models: RwLock::new(available_models),
+            download_slot: tokio::sync::Mutex::new(()),
+            download_queue: Mutex::new(download_queue),
+            cancelled_downloads: Mutex::new(HashSet::new()),
+            benchmark_results: RwLock::new(benchmark_results),
         };
 
         // Migrate any bundled models to user directory
@@ -231,15 +137,71 @@ impl ModelManager {
         models.get(model_id).cloned()
     }
 
+    /// Fetches the catalog from the configured `model_catalog_url`, caches it and
+    /// swaps it in. On any failure the current catalog stays in place.
+    pub async fn refresh_catalog(&self) -> Result<usize> {
+        let url = self
+            .host
+            .settings()
+            .model_catalog_url
+            .ok_or_else(|| anyhow::anyhow!("No model catalog URL configured"))?;
+
+        info!("Refreshing model catalog from {}", url);
+        let manifest = catalog::fetch_catalog(&url).await?;
+        catalog::save_cached_catalog(&self.data_dir, &manifest)?;
+
+        let count = self.apply_catalog(&manifest)?;
+        info!(
+            "Model catalog refreshed to version {} ({} models)",
+            manifest.catalog_version, count
+        );
+        self.host.emit("model-catalog-updated", count);
+
+        Ok(count)
+    }
+
+    fn apply_catalog(&self, manifest: &catalog::CatalogManifest) -> Result<usize> {
+        let mut new_models = catalog::catalog_models(manifest, env!("CARGO_PKG_VERSION"));
+        let downloading: Vec<String> = {
+            let mut models = self.available_models.write();
+
+            // Retired models that are still on disk (or mid-download) stay listed
+            // so the user's selection keeps working
+            for (id, model) in models.iter() {
+                if (model.is_downloaded || model.is_downloading) && !new_models.contains_key(id) {
+                    new_models.insert(id.clone(), model.clone());
+                }
+            }
+
+            let downloading = models
+                .values()
+                .filter(|model| model.is_downloading)
+                .map(|model| model.id.clone())
+                .collect();
+            *models = new_models;
+            downloading
+        };
+
+        self.update_download_status()?;
+
+        let mut models = self.available_models.write();
+        for id in downloading {
+            if let Some(model) = models.get_mut(&id) {
+                model.is_downloading = true;
+            }
+        }
+
+        Ok(models.len())
+    }
+
     fn migrate_bundled_models(&self) -> Result<()> {
         // Check for bundled models and copy them to user directory
         let bundled_models = ["ggml-small.bin"]; // Add other bundled models here if any
 
         for filename in &bundled_models {
-            let bundled_path = self.app_handle.path().resolve(
-                format!("resources/models/{}", filename),
-                tauri::path::BaseDirectory::Resource,
-            );
+            let bundled_path = self
+                .host
+                .resource_path(&format!("resources/models/{}", filename));
 
             if let Ok(bundled_path) = bundled_path {
                 if bundled_path.exists() {
@@ -276,6 +238,20 @@ impl ModelManager {
                     let _ = fs::remove_dir_all(&extracting_path);
                 }
 
+                // An interrupted swap leaves the previous copy behind; put it back
+                // if the new one never made it into place
+                let previous_path = self
+                    .models_dir
+                    .join(format!("{}.previous", &model.filename));
+                if previous_path.exists() {
+                    if model_path.exists() {
+                        let _ = fs::remove_dir_all(&previous_path);
+                    } else {
+                        warn!("Restoring previous install of model: {}", model.id);
+                        let _ = fs::rename(&previous_path, &model_path);
+                    }
+                }
+
                 model.is_downloaded = model_path.exists() && model_path.is_dir();
                 model.is_downloading = false;
 
@@ -305,16 +281,47 @@ impl ModelManager {
         Ok(())
     }
 
+    pub fn get_benchmark_results(&self) -> Vec<BenchmarkResult> {
+        self.benchmark_results.read().values().cloned().collect()
+    }
+
+    /// Stores fresh measurements, replacing earlier runs of the same models.
+    pub fn record_benchmark_results(&self, results: &[BenchmarkResult]) -> Result<()> {
+        let mut stored = self.benchmark_results.write();
+        for result in results {
+            stored.insert(result.model_id.clone(), result.clone());
+        }
+        benchmark::save_results(&self.data_dir, &stored)
+    }
+
+    /// Best benchmarked candidate for the latency target, if any were measured.
+    pub fn recommend_model<'a>(
+        &self,
+        candidates: impl IntoIterator<Item = &'a str>,
+        max_rtf: f64,
+    ) -> Option<String> {
+        benchmark::recommend_model(&self.benchmark_results.read(), candidates, max_rtf)
+    }
+
     fn auto_select_model_if_needed(&self) -> Result<()> {
         // Check if we have a selected model in settings
-        let settings = get_settings(&self.app_handle);
+        let settings = self.host.settings();
 
         // If no model is selected or selected model is empty
         if settings.selected_model.is_empty() {
             let models = self.available_models.read();
 
+            // Measurements from this machine beat the static priorities below
+            let benchmarked = self.recommend_model(
+                models
+                    .values()
+                    .filter(|model| model.is_downloaded)
+                    .map(|model| model.id.as_str()),
+                settings.model_latency_target_rtf,
+            );
+
             // Platform-aware model selection priority
-            let available_model = {
+            let available_model = benchmarked.and_then(|id| models.get(&id)).or({
                 #[cfg(target_os = "windows")]
                 {
                     // On Windows, prioritize Parakeet models due to disabled GPU backends
@@ -339,7 +346,7 @@ impl ModelManager {
                     // On other platforms, just find the first available downloaded model
                     models.values().find(|model| model.is_downloaded)
                 }
-            };
+            });
 
             if let Some(available_model) = available_model {
                 info!(
@@ -352,7 +359,7 @@ impl ModelManager {
                 // Update settings with the selected model
                 let mut updated_settings = settings;
                 updated_settings.selected_model = available_model.id.clone();
-                write_settings(&self.app_handle, updated_settings);
+                self.host.write_settings(updated_settings);
 
                 info!("Successfully auto-selected model: {}", available_model.id);
             }
@@ -361,6 +368,9 @@ impl ModelManager {
         Ok(())
     }
 
+    /// Downloads a model, waiting behind any downloads already in progress.
+    /// The request is persisted so an interrupted queue picks up again on the
+    /// next launch; transient failures are retried with backoff.
     pub async fn download_model(&self, model_id: &str) -> Result<()> {
         let model_info = {
             let models = self.available_models.read();
@@ -370,14 +380,52 @@ impl ModelManager {
         let model_info =
             model_info.ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
 
-        let url = model_info
-            .url
-            .ok_or_else(|| anyhow::anyhow!("No download URL for model"))?;
+        // Mirrors are tried in catalog order, one per attempt
+        let urls = if model_info.urls.is_empty() {
+            model_info.url.iter().cloned().collect::<Vec<_>>()
+        } else {
+            model_info.urls.clone()
+        };
+        if urls.is_empty() {
+            return Err(anyhow::anyhow!("No download URL for model"));
+        }
+
+        self.cancelled_downloads.lock().remove(model_id);
+        self.enqueue_download(model_id);
+
+        let result = self.download_queued(&model_info, &urls).await;
+
+        self.dequeue_download(model_id);
+        self.cancelled_downloads.lock().remove(model_id);
+
+        if result.is_err() {
+            let partial_path = self
+                .models_dir
+                .join(format!("{}.partial", &model_info.filename));
+            let mut models = self.available_models.write();
+            if let Some(model) = models.get_mut(model_id) {
+                model.is_downloading = false;
+                model.partial_size = partial_path.metadata().map(|m| m.len()).unwrap_or(0);
+            }
+        }
+
+        result
+    }
+
+    async fn download_queued(&self, model_info: &ModelInfo, urls: &[String]) -> Result<()> {
+        let model_id = model_info.id.as_str();
         let model_path = self.models_dir.join(&model_info.filename);
         let partial_path = self
             .models_dir
             .join(format!("{}.partial", &model_info.filename));
 
+        // Only one download runs at a time; the lock hands out turns in request order
+        let _slot = self.download_slot.lock().await;
+
+        if self.is_download_cancelled(model_id) {
+            return Err(anyhow::anyhow!("Download cancelled"));
+        }
+
         // Don't download if complete version already exists
         if model_path.exists() {
             // Clean up any partial file that might exist
@@ -388,139 +436,294 @@ impl ModelManager {
             return Ok(());
         }
 
-        // Check if we have a partial download to resume
-        let mut resume_from = if partial_path.exists() {
-            let size = partial_path.metadata()?.len();
-            info!("Resuming download of model {} from byte {}", model_id, size);
-            size
+        // Rough preflight from the catalog size before any bytes are written;
+        // archives need room for the extracted copy as well
+        let partial_size = partial_path.metadata().map(|m| m.len()).unwrap_or(0);
+        let expected_bytes = model_info.size_mb * 1024 * 1024;
+        let needed = if model_info.is_directory {
+            expected_bytes * 2
         } else {
-            info!("Starting fresh download of model {} from {}", model_id, url);
-            0
+            expected_bytes
         };
+        download::check_disk_space(&self.models_dir, needed.saturating_sub(partial_size))?;
+
+        // Mirrors take turns; one that can't serve the model at all is dropped,
+        // and the download only fails once none are left
+        let mut mirrors: Vec<&String> = urls.iter().collect();
+        let mut current = 0;
+        let mut attempt = 0;
+        let url = loop {
+            let url = mirrors[current];
+            match self.fetch_to_partial(model_info, url, &partial_path).await {
+                Ok(()) => break url.clone(),
+                Err(FetchError::Fatal(e)) => return Err(e),
+                Err(FetchError::Mirror(e)) => {
+                    mirrors.remove(current);
+                    if mirrors.is_empty() {
+                        return Err(e.context(format!("No mirror could serve model {}", model_id)));
+                    }
+                    warn!(
+                        "Download of model {} from {} failed, trying the next mirror: {}",
+                        model_id, url, e
+                    );
+                    current %= mirrors.len();
+                }
+                Err(FetchError::Retryable(e)) => {
+                    current = (current + 1) % mirrors.len();
+                    attempt += 1;
+                    if attempt >= download::MAX_ATTEMPTS {
+                        return Err(e.context(format!(
+                            "Download of model {} failed after {} attempts",
+                            model_id, attempt
+                        )));
+                    }
+
+                    let delay = download::backoff_delay(attempt - 1);
+                    warn!(
+                        "Download of model {} failed (attempt {}/{}), retrying in {:?}: {}",
+                        model_id,
+                        attempt,
+                        download::MAX_ATTEMPTS,
+                        delay,
+                        e
+                    );
+                    self.host.emit(
+                        "model-download-retry",
+                        &serde_json::json!({
+                            "model_id": model_id,
+                            "attempt": attempt,
+                            "max_attempts": download::MAX_ATTEMPTS,
+                            "delay_ms": delay.as_millis() as u64,
+                            "error": e.to_string()
+                        }),
+                    );
+
+                    tokio::time::sleep(delay).await;
+                    if self.is_download_cancelled(model_id) {
+                        return Err(anyhow::anyhow!("Download cancelled"));
+                    }
+                }
+            }
+        };
+
+        // Check the download against the catalog (and a signature, if a signing
+        // key is configured) before anything is moved into place
+        let (source_sha256, source_verified) =
+            match self.verify_download(model_info, &url, &partial_path).await {
+                Ok(checked) => checked,
+                Err(e) => {
+                    let _ = fs::remove_file(&partial_path);
+                    self.host.emit(
+                        "model-verification-failed",
+                        &serde_json::json!({
+                            "model_id": model_id,
+                            "error": e.to_string()
+                        }),
+                    );
+                    return Err(e);
+                }
+            };
+
+        // Handle directory-based models (extract tar.gz) vs file-based models
+        if model_info.is_directory {
+            // Emit extraction started event
+            self.host.emit("model-extraction-started", model_id);
+            info!("Extracting archive for directory-based model: {}", model_id);
 
-        // Mark as downloading
+            if let Err(e) =
+                self.install_archive(model_info, &partial_path, &source_sha256, source_verified)
+            {
+                self.host.emit(
+                    "model-extraction-failed",
+                    &serde_json::json!({
+                        "model_id": model_id,
+                        "error": e.to_string()
+                    }),
+                );
+                return Err(e);
+            }
+
+            info!("Successfully extracted archive for model: {}", model_id);
+            // Emit extraction completed event
+            self.host.emit("model-extraction-completed", model_id);
+
+            // Remove the downloaded tar.gz file
+            let _ = fs::remove_file(&partial_path);
+        } else {
+            // Move partial file to final location for file-based models
+            fs::rename(&partial_path, &model_path)?;
+            self.write_integrity_record(
+                &model_info.filename,
+                BTreeMap::from([(model_info.filename.clone(), source_sha256.clone())]),
+                &source_sha256,
+                source_verified,
+            )?;
+        }
+
+        // Update download status
         {
             let mut models = self.available_models.write();
             if let Some(model) = models.get_mut(model_id) {
-                model.is_downloading = true;
+                model.is_downloading = false;
+                model.is_downloaded = true;
+                model.partial_size = 0;
             }
         }
 
-        // Create HTTP client with range request for resuming
-        let client = reqwest::Client::new();
-        let mut request = client.get(&url);
+        // Emit completion event
+        self.host.emit("model-download-complete", model_id);
 
-        if resume_from > 0 {
-            request = request.header("Range", format!("bytes={}-", resume_from));
-        }
+        info!(
+            "Successfully downloaded model {} to {:?}",
+            model_id, model_path
+        );
 
-        let mut response = request.send().await?;
+        Ok(())
+    }
 
-        // If we tried to resume but server returned 200 (not 206 Partial Content),
-        // the server doesn't support range requests. Delete partial file and restart
-        // fresh to avoid file corruption (appending full file to partial).
-        if resume_from > 0 && response.status() == reqwest::StatusCode::OK {
-            warn!(
-                "Server doesn't support range requests for model {}, restarting download",
-                model_id
-            );
-            drop(response);
-            let _ = fs::remove_file(&partial_path);
+    /// One attempt at bringing `partial_path` up to the full size of `url`,
+    /// resuming from whatever an earlier attempt left behind.
+    async fn fetch_to_partial(
+        &self,
+        model_info: &ModelInfo,
+        url: &str,
+        partial_path: &Path,
+    ) -> std::result::Result<(), FetchError> {
+        let model_id = model_info.id.as_str();
+
+        // Check if we have a partial download to resume
+        let resume_from = if partial_path.exists() {
+            let size = partial_path.metadata()?.len();
+            info!("Resuming download of model {} from byte {}", model_id, size);
+            size
+        } else {
+            info!("Starting fresh download of model {} from {}", model_id, url);
+            0
+        };
 
-            // Reset resume_from since we're starting fresh
-            resume_from = 0;
+        // Create HTTP client with range request for resuming
+        let client = reqwest::Client::builder()
+            .connect_timeout(std::time::Duration::from_secs(30))
+            .build()?;
+        let mut request = client.get(url);
 
-            // Restart download without range header
-            response = client.get(&url).send().await?;
+        if resume_from > 0 {
+            request = request.header(RANGE, format!("bytes={}-", resume_from));
         }
 
-        // Check for success or partial content status
-        if !response.status().is_success()
-            && response.status() != reqwest::StatusCode::PARTIAL_CONTENT
-        {
-            // Mark as not downloading on error
-            {
-                let mut models = self.available_models.write();
-                if let Some(model) = models.get_mut(model_id) {
-                    model.is_downloading = false;
-                }
+        let response = request.send().await?;
+        let status = response.status();
+
+        // The server can't serve anything past our partial file: either it is
+        // already complete, or it's larger than the file and must be discarded
+        if resume_from > 0 && status == StatusCode::RANGE_NOT_SATISFIABLE {
+            let total = response
+                .headers()
+                .get(CONTENT_RANGE)
+                .and_then(|v| v.to_str().ok())
+                .and_then(download::parse_content_range_total);
+            if total == Some(resume_from) {
+                return Ok(());
             }
-            return Err(anyhow::anyhow!(
-                "Failed to download model: HTTP {}",
-                response.status()
-            ));
+            let _ = fs::remove_file(partial_path);
+            return Err(FetchError::Retryable(anyhow::anyhow!(
+                "Server rejected resume of model {} at byte {}",
+                model_id,
+                resume_from
+            )));
         }
 
-        let total_size = if resume_from > 0 {
-            // For resumed downloads, add the resume point to content length
-            resume_from + response.content_length().unwrap_or(0)
-        } else {
-            response.content_length().unwrap_or(0)
-        };
+        // Check for success or partial content status
+        if !status.is_success() {
+            let e = anyhow::anyhow!("Failed to download model: HTTP {}", status);
+            return Err(if download::is_retryable_status(status) {
+                FetchError::Retryable(e)
+            } else {
+                FetchError::Mirror(e)
+            });
+        }
 
-        let mut downloaded = resume_from;
-        let mut stream = response.bytes_stream();
+        let (mut downloaded, total_size, mut file) =
+            if resume_from > 0 && status == StatusCode::PARTIAL_CONTENT {
+                // For resumed downloads the full size comes from Content-Range
+                let total = response
+                    .headers()
+                    .get(CONTENT_RANGE)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(download::parse_content_range_total)
+                    .or_else(|| response.content_length().map(|len| resume_from + len));
+                let file = fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(partial_path)?;
+                (resume_from, total, file)
+            } else {
+                // If we tried to resume but the server sent the whole file, it
+                // doesn't support range requests. Start over rather than
+                // appending the full file to the partial.
+                if resume_from > 0 {
+                    warn!(
+                        "Server doesn't support range requests for model {}, restarting download",
+                        model_id
+                    );
+                }
+                (0, response.content_length(), File::create(partial_path)?)
+            };
 
-        // Open file for appending if resuming, or create new if starting fresh
-        let mut file = if resume_from > 0 {
-            std::fs::OpenOptions::new()
-                .create(true)
-                .append(true)
-                .open(&partial_path)?
-        } else {
-            std::fs::File::create(&partial_path)?
+        // Now that the exact size is known, make sure the rest of it fits
+        if let Some(total) = total_size {
+            let extracted = if model_info.is_directory { total } else { 0 };
+            download::check_disk_space(
+                &self.models_dir,
+                total.saturating_sub(downloaded) + extracted,
+            )
+            .map_err(FetchError::Fatal)?;
+        }
+
+        let progress = |downloaded: u64| {
+            let total = total_size.unwrap_or(0);
+            DownloadProgress {
+                model_id: model_id.to_string(),
+                downloaded,
+                total,
+                percentage: if total > 0 {
+                    (downloaded as f64 / total as f64) * 100.0
+                } else {
+                    0.0
+                },
+            }
         };
 
         // Emit initial progress
-        let initial_progress = DownloadProgress {
-            model_id: model_id.to_string(),
-            downloaded,
-            total: total_size,
-            percentage: if total_size > 0 {
-                (downloaded as f64 / total_size as f64) * 100.0
-            } else {
-                0.0
-            },
-        };
-        let _ = self
-            .app_handle
-            .emit("model-download-progress", &initial_progress);
+        self.host
+            .emit("model-download-progress", &progress(downloaded));
 
         // Download with progress (throttled to reduce IPC overhead)
         let mut last_progress_emit = std::time::Instant::now();
         const PROGRESS_THROTTLE_MS: u128 = 100; // Emit max 10 times per second
 
+        let mut stream = response.bytes_stream();
         while let Some(chunk) = stream.next().await {
-            let chunk = chunk.inspect_err(|_e| {
-                // Mark as not downloading on error
-                {
-                    let mut models = self.available_models.write();
-                    if let Some(model) = models.get_mut(model_id) {
-                        model.is_downloading = false;
-                    }
-                }
-            })?;
+            // The partial file is kept so the next attempt can resume
+            let chunk = chunk?;
 
             file.write_all(&chunk)?;
             downloaded += chunk.len() as u64;
 
-            let percentage = if total_size > 0 {
-                (downloaded as f64 / total_size as f64) * 100.0
-            } else {
-                0.0
-            };
+            if self.is_download_cancelled(model_id) {
+                file.flush()?;
+                info!(
+                    "Download of model {} stopped at byte {}",
+                    model_id, downloaded
+                );
+                return Err(FetchError::Fatal(anyhow::anyhow!("Download cancelled")));
+            }
 
             // Throttle progress events to reduce IPC overhead
             let now = std::time::Instant::now();
             if now.duration_since(last_progress_emit).as_millis() >= PROGRESS_THROTTLE_MS {
-                let progress = DownloadProgress {
-                    model_id: model_id.to_string(),
-                    downloaded,
-                    total: total_size,
-                    percentage,
-                };
-
-                let _ = self.app_handle.emit("model-download-progress", &progress);
+                self.host
+                    .emit("model-download-progress", &progress(downloaded));
                 last_progress_emit = now;
             }
         }
@@ -528,157 +731,664 @@ impl ModelManager {
         file.flush()?;
         drop(file); // Ensure file is closed before moving
 
-        // Emit final 100% progress to ensure UI updates
-        let final_progress = DownloadProgress {
-            model_id: model_id.to_string(),
-            downloaded,
-            total: total_size,
-            percentage: if total_size > 0 { 100.0 } else { 0.0 },
-        };
-        let _ = self
-            .app_handle
-            .emit("model-download-progress", &final_progress);
-
-        // Verify downloaded file size matches expected size
-        if total_size > 0 {
+        // Verify downloaded file size matches Content-Length
+        if let Some(total) = total_size {
             let actual_size = partial_path.metadata()?.len();
-            if actual_size != total_size {
-                // Download is incomplete/corrupted - delete partial and return error
-                let _ = fs::remove_file(&partial_path);
-                {
-                    let mut models = self.available_models.write();
-                    if let Some(model) = models.get_mut(model_id) {
-                        model.is_downloading = false;
-                    }
+            if actual_size != total {
+                // A short file is resumed on the next attempt; anything longer
+                // can't be trusted and starts over
+                if actual_size > total {
+                    let _ = fs::remove_file(partial_path);
                 }
-                return Err(anyhow::anyhow!(
+                return Err(FetchError::Retryable(anyhow::anyhow!(
                     "Download incomplete: expected {} bytes, got {} bytes",
-                    total_size,
+                    total,
                     actual_size
-                ));
+                )));
             }
         }
 
-        // Handle directory-based models (extract tar.gz) vs file-based models
-        if model_info.is_directory {
-            // Emit extraction started event
-            let _ = self.app_handle.emit("model-extraction-started", model_id);
-            info!("Extracting archive for directory-based model: {}", model_id);
+        // Emit final 100% progress to ensure UI updates
+        let mut final_progress = progress(downloaded);
+        if final_progress.total > 0 {
+            final_progress.percentage = 100.0;
+        }
+        self.host.emit("model-download-progress", &final_progress);
 
-            // Use a temporary extraction directory to ensure atomic operations
-            let temp_extract_dir = self
-                .models_dir
-                .join(format!("{}.extracting", &model_info.filename));
-            let final_model_dir = self.models_dir.join(&model_info.filename);
+        Ok(())
+    }
 
-            // Clean up any previous incomplete extraction
-            if temp_extract_dir.exists() {
-                let _ = fs::remove_dir_all(&temp_extract_dir);
+    /// Model ids waiting for or in the middle of a download, in order.
+    pub fn get_download_queue(&self) -> Vec<String> {
+        self.download_queue.lock().clone()
+    }
+
+    /// Restarts downloads that were still queued when the app last exited.
+    pub async fn resume_download_queue(&self) {
+        let pending = self.get_download_queue();
+        if pending.is_empty() {
+            return;
+        }
+
+        info!("Resuming {} queued model download(s)", pending.len());
+        for model_id in pending {
+            // Skip anything cancelled or finished since startup
+            if !self.download_queue.lock().contains(&model_id) {
+                continue;
+            }
+            if let Err(e) = self.download_model(&model_id).await {
+                warn!("Queued download of model {} failed: {}", model_id, e);
+                self.dequeue_download(&model_id);
             }
+        }
+    }
 
-            // Create temporary extraction directory
-            fs::create_dir_all(&temp_extract_dir)?;
+    fn enqueue_download(&self, model_id: &str) {
+        {
+            let mut queue = self.download_queue.lock();
+            if !queue.iter().any(|id| id == model_id) {
+                queue.push(model_id.to_string());
+            }
+            download::save_queue(&self.data_dir, &queue);
+        }
 
-            // Open the downloaded tar.gz file
-            let tar_gz = File::open(&partial_path)?;
-            let tar = GzDecoder::new(tar_gz);
-            let mut archive = Archive::new(tar);
+        {
+            let mut models = self.available_models.write();
+            if let Some(model) = models.get_mut(model_id) {
+                model.is_downloading = true;
+            }
+        }
 
-            // Validate archive entries for security (prevent path traversal attacks)
-            for entry_result in archive.entries()? {
-                let entry = entry_result?;
-                let entry_path = entry.path()?;
+        self.emit_download_queue();
+    }
 
-                // Check for absolute paths or parent directory traversal
-                if entry_path.is_absolute() {
-                    return Err(anyhow::anyhow!(
-                        "Malicious archive: contains absolute path '{}'",
-                        entry_path.display()
-                    ));
-                }
+    fn dequeue_download(&self, model_id: &str) {
+        {
+            let mut queue = self.download_queue.lock();
+            queue.retain(|id| id != model_id);
+            download::save_queue(&self.data_dir, &queue);
+        }
+        self.emit_download_queue();
+    }
 
-                for component in entry_path.components() {
-                    if component == Component::ParentDir {
-                        return Err(anyhow::anyhow!(
-                            "Malicious archive: contains parent directory traversal in '{}'",
-                            entry_path.display()
-                        ));
-                    }
-                }
+    fn emit_download_queue(&self) {
+        self.host
+            .emit("model-download-queue-changed", &self.get_download_queue());
+    }
+
+    fn is_download_cancelled(&self, model_id: &str) -> bool {
+        self.cancelled_downloads.lock().contains(model_id)
+    }
+
+    /// Hashes a finished download and checks it against the catalog entry.
+    /// When a signing key is configured, the detached signature published
+    /// next to `url` must verify as well. Returns the download's SHA-256 and
+    /// whether either check actually vouched for it.
+    async fn verify_download(
+        &self,
+        model_info: &ModelInfo,
+        url: &str,
+        path: &Path,
+    ) -> Result<(String, bool)> {
+        let (source_sha256, mut verified) = match &model_info.sha256 {
+            Some(expected) => {
+                let hash = integrity::verify_sha256(path, expected)?;
+                info!("Checksum verified for model {}", model_info.id);
+                (hash, true)
             }
+            None => (integrity::sha256_file(path)?, false),
+        };
 
-            // Re-open the archive for extraction (entries iterator consumed above)
-            let tar_gz = File::open(&partial_path)?;
-            let tar = GzDecoder::new(tar_gz);
-            let mut archive = Archive::new(tar);
+        if let Some(public_key) = self.host.settings().model_signing_public_key {
+            let client = reqwest::Client::new();
+            let signature = integrity::fetch_signature(&client, url).await?;
+            integrity::verify_signature(path, &signature, &public_key)?;
+            info!("Signature verified for model {}", model_info.id);
+            verified = true;
+        }
 
-            // Extract to the temporary directory first
-            archive.unpack(&temp_extract_dir).map_err(|e| {
-                let error_msg = format!("Failed to extract archive: {}", e);
-                // Clean up failed extraction
-                let _ = fs::remove_dir_all(&temp_extract_dir);
-                let _ = self.app_handle.emit(
-                    "model-extraction-failed",
-                    &serde_json::json!({
-                        "model_id": model_id,
-                        "error": error_msg
-                    }),
-                );
-                anyhow::anyhow!(error_msg)
-            })?;
+        if !verified {
+            warn!(
+                "No checksum in the catalog and no signing key for model {}; installing it as unverified",
+                model_info.id
+            );
+        }
+        Ok((source_sha256, verified))
+    }
 
-            // Find the actual extracted directory (archive might have a nested structure)
-            let extracted_dirs: Vec<_> = fs::read_dir(&temp_extract_dir)?
-                .filter_map(|entry| entry.ok())
-                .filter(|entry| entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false))
-                .collect();
+    /// Extracts a verified archive into a temporary directory and swaps it into
+    /// place only once extraction and hashing have both succeeded.
+    fn install_archive(
+        &self,
+        model_info: &ModelInfo,
+        archive_path: &Path,
+        source_sha256: &str,
+        source_verified: bool,
+    ) -> Result<()> {
+        let temp_extract_dir = self
+            .models_dir
+            .join(format!("{}.extracting", &model_info.filename));
 
-            if extracted_dirs.len() == 1 {
-                // Single directory extracted, move it to the final location
-                let source_dir = extracted_dirs[0].path();
-                if final_model_dir.exists() {
-                    fs::remove_dir_all(&final_model_dir)?;
-                }
-                fs::rename(&source_dir, &final_model_dir)?;
-                // Clean up temp directory
+        // Clean up any previous incomplete extraction
+        if temp_extract_dir.exists() {
+            let _ = fs::remove_dir_all(&temp_extract_dir);
+        }
+        fs::create_dir_all(&temp_extract_dir)?;
+
+        let extracted = extract_archive(archive_path, &temp_extract_dir).and_then(|root| {
+            let files = integrity::hash_tree(&root)?;
+            Ok((root, files))
+        });
+        let (extracted_root, files) = match extracted {
+            Ok(extracted) => extracted,
+            Err(e) => {
                 let _ = fs::remove_dir_all(&temp_extract_dir);
+                return Err(e);
+            }
+        };
+
+        if let Err(e) = self.swap_into_place(&extracted_root, &model_info.filename) {
+            let _ = fs::remove_dir_all(&temp_extract_dir);
+            return Err(e);
+        }
+
+        if temp_extract_dir.exists() {
+            let _ = fs::remove_dir_all(&temp_extract_dir);
+        }
+
+        self.write_integrity_record(&model_info.filename, files, source_sha256, source_verified)
+    }
+
+    /// Moves a fully prepared file or directory to `models_dir/filename`,
+    /// keeping the previous copy until the new one is in place.
+    fn swap_into_place(&self, staged: &Path, filename: &str) -> Result<()> {
+        let final_path = self.models_dir.join(filename);
+
+        // Renaming a file over another replaces it atomically
+        if staged.is_file() {
+            fs::rename(staged, &final_path)?;
+            return Ok(());
+        }
+
+        let backup_dir = self.models_dir.join(format!("{}.previous", filename));
+        if final_path.exists() {
+            if backup_dir.exists() {
+                fs::remove_dir_all(&backup_dir)?;
+            }
+            fs::rename(&final_path, &backup_dir)?;
+        }
+
+        if let Err(e) = fs::rename(staged, &final_path) {
+            if backup_dir.exists() {
+                let _ = fs::rename(&backup_dir, &final_path);
+            }
+            return Err(e.into());
+        }
+
+        let _ = fs::remove_dir_all(&backup_dir);
+        Ok(())
+    }
+
+    fn write_integrity_record(
+        &self,
+        filename: &str,
+        files: BTreeMap<String, String>,
+        source_sha256: &str,
+        source_verified: bool,
+    ) -> Result<()> {
+        let record = IntegrityRecord {
+            source_sha256: source_sha256.to_string(),
+            source_verified,
+            files,
+        };
+        integrity::write_record(&integrity::record_path(&self.models_dir, filename), &record)
+    }
+
+    /// Re-hashes an installed model and compares it with the catalog checksum
+    /// or the record written when it was installed.
+    pub fn verify_model(&self, model_id: &str) -> Result<ModelVerification> {
+        let model_info = self
+            .get_model_info(model_id)
+            .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+
+        if model_info.is_downloading {
+            return Err(anyhow::anyhow!(
+                "Model is currently downloading: {}",
+                model_id
+            ));
+        }
+
+        let model_path = self.models_dir.join(&model_info.filename);
+        let record = integrity::read_record(&integrity::record_path(
+            &self.models_dir,
+            &model_info.filename,
+        ));
+
+        let (status, detail) = integrity::verify_installed(
+            &model_path,
+            model_info.is_directory,
+            model_info.sha256.as_deref(),
+            record.as_ref(),
+        )?;
+
+        match status {
+            VerificationStatus::Mismatch => warn!(
+                "Model {} failed verification: {}",
+                model_id,
+                detail.as_deref().unwrap_or_default()
+            ),
+            _ => info!("Model {} verification: {:?}", model_id, status),
+        }
+
+        Ok(ModelVerification {
+            model_id: model_id.to_string(),
+            status,
+            detail,
+        })
+    }
+
+    /// Registers a Whisper GGML file or Parakeet directory from disk.
+    ///
+    /// The model is validated and test-loaded in a separate process before it
+    /// is linked (or copied) into models_dir and added to the user catalog.
+    pub fn import_model(
+        &self,
+        source: &Path,
+        engine_type: EngineType,
+        display_name: &str,
+    ) -> Result<ModelInfo> {
+        let display_name = display_name.trim();
+        if display_name.is_empty() || display_name.len() > 100 {
+            return Err(anyhow::anyhow!(
+                "Display name must be between 1 and 100 characters"
+            ));
+        }
+
+        let source = source
+            .canonicalize()
+            .map_err(|e| anyhow::anyhow!("Cannot read {}: {}", source.display(), e))?;
+        if source.starts_with(&self.models_dir) {
+            return Err(anyhow::anyhow!(
+                "Model is already inside the models directory"
+            ));
+        }
+
+        model_import::validate_layout(&source, &engine_type)?;
+        info!("Test-loading imported model from {}", source.display());
+        model_import::probe_in_sandbox(&source, &engine_type)?;
+
+        let model_id = {
+            let models = self.available_models.read();
+            model_import::model_id_for(display_name, |id| {
+                models.contains_key(id)
+                    || self.models_dir.join(id).exists()
+                    || self.models_dir.join(format!("{}.bin", id)).exists()
+            })
+        };
+        let is_directory = matches!(engine_type, EngineType::Parakeet);
+        let filename = if is_directory {
+            model_id.clone()
+        } else {
+            format!("{}.bin", model_id)
+        };
+
+        // Stage next to the final location so the rename is atomic
+        let staging_path = self.models_dir.join(format!("{}.importing", filename));
+        let final_path = self.models_dir.join(&filename);
+        let staged = model_import::copy_model(&source, &staging_path).and_then(|_| {
+            if is_directory {
+                integrity::hash_tree(&staging_path)
             } else {
-                // Multiple items or no directories, rename the temp directory itself
-                if final_model_dir.exists() {
-                    fs::remove_dir_all(&final_model_dir)?;
-                }
-                fs::rename(&temp_extract_dir, &final_model_dir)?;
+                Ok(BTreeMap::from([(
+                    filename.clone(),
+                    integrity::sha256_file(&staging_path)?,
+                )]))
+            }
+        });
+        let files = match staged {
+            Ok(files) => files,
+            Err(e) => {
+                let _ = fs::remove_dir_all(&staging_path);
+                let _ = fs::remove_file(&staging_path);
+                return Err(e);
             }
+        };
+        fs::rename(&staging_path, &final_path)?;
+
+        let entry = UserModelEntry {
+            id: model_id.clone(),
+            name: display_name.to_string(),
+            engine: engine_type,
+            filename,
+            is_directory,
+            source_path: source.to_string_lossy().to_string(),
+            imported_at: unix_now(),
+        };
 
-            info!("Successfully extracted archive for model: {}", model_id);
-            // Emit extraction completed event
-            let _ = self.app_handle.emit("model-extraction-completed", model_id);
+        let mut model_info = entry.to_model_info();
+        let source_sha256 = integrity::tree_digest(&files);
+        // Whatever was on disk; nothing vouches for where it came from
+        self.write_integrity_record(&model_info.filename, files, &source_sha256, false)?;
 
-            // Remove the downloaded tar.gz file
-            let _ = fs::remove_file(&partial_path);
+        let mut user_models = model_import::load_user_models(&self.data_dir);
+        user_models.push(entry);
+        model_import::save_user_models(&self.data_dir, &user_models)?;
+
+        model_info.is_downloaded = true;
+        model_info.size_mb = model_size_mb(&final_path);
+        self.available_models
+            .write()
+            .insert(model_id.clone(), model_info.clone());
+
+        info!("Imported model {} as {}", source.display(), model_id);
+        self.host.emit("model-imported", &model_id);
+
+        Ok(model_info)
+    }
+
+    /// Installs the models in an offline model pack, either a directory or a
+    /// `.tar.gz` with a `handy-model-pack.json` manifest at its root.
+    ///
+    /// Every model is hashed against the manifest (and the catalog checksum
+    /// where one applies) before it is moved into place. A bad model is
+    /// reported and skipped without stopping the rest of the pack. With a
+    /// signing key configured the manifest must carry a valid signature, and
+    /// models the catalog doesn't list are refused unless `allow_unknown`, in
+    /// which case they are test-loaded like an import before installing.
+    pub fn install_model_pack(
+        &self,
+        pack_path: &Path,
+        allow_unknown: bool,
+    ) -> Result<Vec<PackInstallResult>> {
+        let results = if model_pack::is_archive(pack_path) {
+            let temp_dir = self
+                .models_dir
+                .join(format!(".pack-{}.extracting", unix_now()));
+            fs::create_dir_all(&temp_dir)?;
+            let results = extract_archive(pack_path, &temp_dir)
+                .and_then(|pack_dir| self.install_pack_dir(&pack_dir, allow_unknown));
+            let _ = fs::remove_dir_all(&temp_dir);
+            results?
+        } else if pack_path.is_dir() {
+            self.install_pack_dir(pack_path, allow_unknown)?
         } else {
-            // Move partial file to final location for file-based models
-            fs::rename(&partial_path, &model_path)?;
+            return Err(anyhow::anyhow!(
+                "Model pack must be a directory or a .tar.gz archive"
+            ));
+        };
+
+        self.update_download_status()?;
+        self.host.emit("model-pack-installed", &results);
+
+        Ok(results)
+    }
+
+    fn install_pack_dir(
+        &self,
+        pack_dir: &Path,
+        allow_unknown: bool,
+    ) -> Result<Vec<PackInstallResult>> {
+        let manifest = model_pack::read_manifest(pack_dir)?;
+        let signed = match self.host.settings().model_signing_public_key {
+            Some(public_key) => {
+                model_pack::verify_manifest_signature(pack_dir, &public_key)?;
+                info!("Model pack signature verified");
+                true
+            }
+            None => false,
+        };
+        info!(
+            "Installing model pack with {} models (exported by Handy {})",
+            manifest.models.len(),
+            manifest.app_version
+        );
+
+        Ok(manifest
+            .models
+            .iter()
+            .map(|model| {
+                let (status, error) =
+                    match self.install_pack_model(pack_dir, model, signed, allow_unknown) {
+                        Ok(status) => {
+                            info!("Model pack: {} {:?}", model.id, status);
+                            (status, None)
+                        }
+                        Err(e) => {
+                            warn!("Model pack: failed to install {}: {}", model.id, e);
+                            (PackInstallStatus::Failed, Some(e.to_string()))
+                        }
+                    };
+                PackInstallResult {
+                    model_id: model.id.clone(),
+                    status,
+                    error,
+                }
+            })
+            .collect())
+    }
+
+    /// `signed` means the manifest, and so every hash in it, carried a valid
+    /// signature from the configured key.
+    fn install_pack_model(
+        &self,
+        pack_dir: &Path,
+        model: &PackModel,
+        signed: bool,
+        allow_unknown: bool,
+    ) -> Result<PackInstallStatus> {
+        let known = self.get_model_info(&model.id);
+        let in_catalog = known.is_some()
+            && !model_import::load_user_models(&self.data_dir)
+                .iter()
+                .any(|m| m.id == model.id);
+        if !in_catalog && !allow_unknown {
+            return Err(anyhow::anyhow!(
+                "{} isn't in the model catalog; allow unknown models to install it",
+                model.id
+            ));
         }
 
-        // Update download status
+        let mut catalog_checked = false;
+        match &known {
+            Some(info) => {
+                if info.filename != model.filename || info.is_directory != model.is_directory {
+                    return Err(anyhow::anyhow!(
+                        "Pack entry doesn't match the installed catalog"
+                    ));
+                }
+                if info.is_downloading {
+                    return Err(anyhow::anyhow!("Model is currently downloading"));
+                }
+                match (&info.sha256, info.is_directory) {
+                    // Single-file downloads can be checked against the catalog itself
+                    (Some(expected), false) => {
+                        if !model
+                            .files
+                            .get(&model.filename)
+                            .is_some_and(|hash| hash.eq_ignore_ascii_case(expected))
+                        {
+                            return Err(anyhow::anyhow!("Checksum doesn't match the catalog"));
+                        }
+                        catalog_checked = true;
+                    }
+                    // Directories can only claim the archive they came from
+                    (Some(expected), true) => {
+                        if !model.source_sha256.eq_ignore_ascii_case(expected) {
+                            return Err(anyhow::anyhow!(
+                                "Pack model comes from a different release than the catalog lists"
+                            ));
+                        }
+                    }
+                    (None, _) => {}
+                }
+            }
+            None => {
+                // Unknown here, e.g. a model imported on the exporting machine
+                if self.models_dir.join(&model.filename).exists() {
+                    return Err(anyhow::anyhow!(
+                        "{} already exists in the models directory",
+                        model.filename
+                    ));
+                }
+                model_import::validate_layout(
+                    &model_pack::model_path(pack_dir, model),
+                    &model.engine,
+                )?;
+            }
+        }
+
+        model_pack::verify_pack_model(pack_dir, model)?;
+
+        let final_path = self.models_dir.join(&model.filename);
+        let record =
+            integrity::read_record(&integrity::record_path(&self.models_dir, &model.filename));
+        if final_path.exists() && record.is_some_and(|r| r.files == model.files) {
+            return Ok(PackInstallStatus::AlreadyInstalled);
+        }
+
+        // Matching the manifest only says the files arrived intact; nothing
+        // vouches for a model outside the catalog actually loading
+        if !in_catalog {
+            info!("Test-loading pack model {}", model.id);
+            model_import::probe_in_sandbox(
+                &model_pack::model_path(pack_dir, model),
+                &model.engine,
+            )?;
+        }
+
+        // Stage next to the final location so the swap is a rename
+        let staging_path = self
+            .models_dir
+            .join(format!("{}.importing", model.filename));
+        let _ = fs::remove_dir_all(&staging_path);
+        let _ = fs::remove_file(&staging_path);
+        if let Err(e) =
+            model_import::copy_model(&model_pack::model_path(pack_dir, model), &staging_path)
+                .and_then(|_| self.swap_into_place(&staging_path, &model.filename))
         {
-            let mut models = self.available_models.write();
-            if let Some(model) = models.get_mut(model_id) {
-                model.is_downloading = false;
-                model.is_downloaded = true;
-                model.partial_size = 0;
+            let _ = fs::remove_dir_all(&staging_path);
+            let _ = fs::remove_file(&staging_path);
+            return Err(e);
+        }
+
+        let source_sha256 = if model.source_sha256.is_empty() {
+            integrity::tree_digest(&model.files)
+        } else {
+            model.source_sha256.clone()
+        };
+        self.write_integrity_record(
+            &model.filename,
+            model.files.clone(),
+            &source_sha256,
+            signed || catalog_checked,
+        )?;
+
+        if known.is_none() {
+            let entry = UserModelEntry {
+                id: model.id.clone(),
+                name: model.name.clone(),
+                engine: model.engine.clone(),
+                filename: model.filename.clone(),
+                is_directory: model.is_directory,
+                source_path: pack_dir.to_string_lossy().to_string(),
+                imported_at: unix_now(),
+            };
+            let mut model_info = entry.to_model_info();
+            model_info.size_mb = model_size_mb(&final_path);
+
+            let mut user_models = model_import::load_user_models(&self.data_dir);
+            user_models.push(entry);
+            model_import::save_user_models(&self.data_dir, &user_models)?;
+            self.available_models
+                .write()
+                .insert(model.id.clone(), model_info);
+        }
+
+        Ok(PackInstallStatus::Installed)
+    }
+
+    /// Writes installed models into a model pack for offline machines. A
+    /// destination ending in `.tar.gz` produces an archive, anything else a
+    /// directory. Models that no longer match their checksums are refused.
+    pub fn export_model_pack(&self, model_ids: &[String], dest: &Path) -> Result<()> {
+        if model_ids.is_empty() {
+            return Err(anyhow::anyhow!("No models selected for export"));
+        }
+
+        let mut models = Vec::new();
+        let mut paths = Vec::new();
+
+        for model_id in model_ids {
+            let model_info = self
+                .get_model_info(model_id)
+                .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+            let model_path = self.get_model_path(model_id)?;
+
+            let files = if model_info.is_directory {
+                integrity::hash_tree(&model_path)?
+            } else {
+                BTreeMap::from([(
+                    model_info.filename.clone(),
+                    integrity::sha256_file(&model_path)?,
+                )])
+            };
+
+            // Don't spread a damaged model to other machines
+            let record = integrity::read_record(&integrity::record_path(
+                &self.models_dir,
+                &model_info.filename,
+            ));
+            if record.as_ref().is_some_and(|r| r.files != files) {
+                return Err(anyhow::anyhow!(
+                    "Model {} no longer matches its install record; re-download it before exporting",
+                    model_id
+                ));
+            }
+            if let (false, Some(expected)) = (model_info.is_directory, &model_info.sha256) {
+                if files.get(&model_info.filename) != Some(expected) {
+                    return Err(anyhow::anyhow!(
+                        "Model {} doesn't match the catalog checksum",
+                        model_id
+                    ));
+                }
             }
+
+            let source_sha256 = record
+                .map(|r| r.source_sha256)
+                .or_else(|| model_info.sha256.clone())
+                .unwrap_or_else(|| integrity::tree_digest(&files));
+
+            models.push(PackModel {
+                id: model_info.id.clone(),
+                name: model_info.name.clone(),
+                engine: model_info.engine_type.clone(),
+                filename: model_info.filename.clone(),
+                is_directory: model_info.is_directory,
+                source_sha256,
+                files,
+            });
+            paths.push(model_path);
         }
 
-        // Emit completion event
-        let _ = self.app_handle.emit("model-download-complete", model_id);
+        let manifest = PackManifest {
+            format_version: model_pack::PACK_FORMAT_VERSION,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            created_at: unix_now(),
+            models,
+        };
+        let sources: Vec<_> = paths.into_iter().zip(manifest.models.iter()).collect();
+
+        if model_pack::is_archive(dest) {
+            model_pack::write_pack_archive(dest, &manifest, &sources)?;
+        } else {
+            model_pack::write_pack_dir(dest, &manifest, &sources)?;
+        }
 
         info!(
-            "Successfully downloaded model {} to {:?}",
-            model_id, model_path
+            "Exported {} models to model pack {}",
+            manifest.models.len(),
+            dest.display()
         );
-
         Ok(())
     }
 
@@ -734,6 +1444,19 @@ impl ModelManager {
             return Err(anyhow::anyhow!("No model files found to delete"));
         }
 
+        let _ = fs::remove_file(integrity::record_path(
+            &self.models_dir,
+            &model_info.filename,
+        ));
+
+        // Imported models have nothing to download again, so drop them entirely
+        let mut user_models = model_import::load_user_models(&self.data_dir);
+        if user_models.iter().any(|m| m.id == model_id) {
+            user_models.retain(|m| m.id != model_id);
+            model_import::save_user_models(&self.data_dir, &user_models)?;
+            self.available_models.write().remove(model_id);
+        }
+
         // Update download status
         self.update_download_status()?;
         debug!("ModelManager: download status updated");
@@ -789,13 +1512,14 @@ impl ModelManager {
     pub fn cancel_download(&self, model_id: &str) -> Result<()> {
         debug!("ModelManager: cancel_download called for: {}", model_id);
 
-        let _model_info = {
-            let models = self.available_models.read();
-            models.get(model_id).cloned()
-        };
+        if self.get_model_info(model_id).is_none() {
+            return Err(anyhow::anyhow!("Model not found: {}", model_id));
+        }
 
-        let _model_info =
-            _model_info.ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+        // The download task stops at its next chunk (or when its turn in the
+        // queue comes up). The partial file is kept so it can be resumed later.
+        self.cancelled_downloads.lock().insert(model_id.to_string());
+        self.dequeue_download(model_id);
 
         // Mark as not downloading
         {
@@ -805,14 +1529,81 @@ impl ModelManager {
             }
         }
 
-        // Note: The actual download cancellation would need to be handled
-        // by the download task itself. This just updates the state.
-        // The partial file is kept so the download can be resumed later.
-
-        // Update download status to reflect current state
-        self.update_download_status()?;
-
         info!("Download cancelled for: {}", model_id);
         Ok(())
     }
 }
+
+/// Unpacks a `.tar.gz` into `dest` after rejecting absolute paths and `..`
+/// entries. Returns the model root: the single top-level directory if the
+/// archive has one, otherwise `dest` itself.
+fn extract_archive(archive_path: &Path, dest: &Path) -> Result<PathBuf> {
+    // Validate archive entries for security (prevent path traversal attacks)
+    let mut archive = Archive::new(GzDecoder::new(File::open(archive_path)?));
+    for entry_result in archive.entries()? {
+        let entry = entry_result?;
+        let entry_path = entry.path()?;
+
+        // Check for absolute paths or parent directory traversal
+        if entry_path.is_absolute() {
+            return Err(anyhow::anyhow!(
+                "Malicious archive: contains absolute path '{}'",
+                entry_path.display()
+            ));
+        }
+
+        for component in entry_path.components() {
+            if component == Component::ParentDir {
+                return Err(anyhow::anyhow!(
+                    "Malicious archive: contains parent directory traversal in '{}'",
+                    entry_path.display()
+                ));
+            }
+        }
+    }
+
+    // Re-open the archive for extraction (entries iterator consumed above)
+    let mut archive = Archive::new(GzDecoder::new(File::open(archive_path)?));
+    archive
+        .unpack(dest)
+        .map_err(|e| anyhow::anyhow!("Failed to extract archive: {}", e))?;
+
+    // Find the actual extracted directory (archive might have a nested structure)
+    let entries: Vec<_> = fs::read_dir(dest)?.filter_map(|entry| entry.ok()).collect();
+    if entries.len() == 1
+        && entries[0]
+            .file_type()
+            .map(|ft| ft.is_dir())
+            .unwrap_or(false)
+    {
+        Ok(entries[0].path())
+    } else {
+        Ok(dest.to_path_buf())
+    }
+}
+
+/// Size on disk in MB, for models that don't come with a catalog size.
+fn model_size_mb(path: &Path) -> u64 {
+    fn size(path: &Path) -> u64 {
+        match fs::metadata(path) {
+            Ok(meta) if meta.is_dir() => fs::read_dir(path)
+                .map(|entries| {
+                    entries
+                        .filter_map(|entry| entry.ok())
+                        .map(|entry| size(&entry.path()))
+                        .sum()
+                })
+                .unwrap_or(0),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        }
+    }
+    size(path) / (1024 * 1024)
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
@@ -0,0 +1,427 @@
+use crate::managers::model::{EngineType, ModelInfo};
+use anyhow::{anyhow, Result};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+use transcribe_rs::{
+    engines::{
+        parakeet::{ParakeetEngine, ParakeetModelParams},
+        whisper::WhisperEngine,
+    },
+    TranscriptionEngine,
+};
+
+/// Argument that turns the app binary into a one-shot model probe.
+pub const PROBE_ARG: &str = "--probe-model";
+
+/// Large models on slow disks can take a while to load; anything past this is
+/// treated as a hang.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(180);
+
+/// Exit code the probe uses for a model that failed to load or transcribe.
+const PROBE_FAILED: i32 = 3;
+
+/// `ggml` magic as written by whisper.cpp (little-endian u32 0x67676d6c)
+const GGML_MAGIC: &[u8; 4] = b"lmgg";
+
+/// Imported models in the user catalog, persisted in the app data directory.
+const USER_CATALOG_FILE: &str = "user_models.json";
+
+const PARAKEET_REQUIRED_FILES: [&str; 2] = ["vocab.txt", "nemo128.onnx"];
+const PARAKEET_WEIGHTS: [&str; 2] = ["encoder-model", "decoder_joint-model"];
+
+/// A model the user imported from disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserModelEntry {
+    pub id: String,
+    pub name: String,
+    pub engine: EngineType,
+    /// File or directory name inside models_dir
+    pub filename: String,
+    pub is_directory: bool,
+    /// Where the model was imported from, for reference only
+    pub source_path: String,
+    /// Seconds since the Unix epoch
+    pub imported_at: u64,
+}
+
+impl UserModelEntry {
+    pub fn to_model_info(&self) -> ModelInfo {
+        ModelInfo {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            description: "Imported model".to_string(),
+            filename: self.filename.clone(),
+            url: None,
+            urls: Vec::new(),
+            size_mb: 0,
+            sha256: None,
+            languages: Vec::new(),
+            license: None,
+            is_downloaded: false,
+            is_downloading: false,
+            partial_size: 0,
+            is_directory: self.is_directory,
+            engine_type: self.engine.clone(),
+            accuracy_score: 0.0,
+            speed_score: 0.0,
+            platform_recommended: false,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct UserCatalog {
+    models: Vec<UserModelEntry>,
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+/// Checks that `path` looks like a model the given engine can load, before
+/// anything is copied or loaded.
+pub fn validate_layout(path: &Path, engine: &EngineType) -> Result<()> {
+    match engine {
+        EngineType::Whisper => {
+            if !path.is_file() {
+                return Err(anyhow!("Whisper models must be a single GGML .bin file"));
+            }
+
+            let mut magic = [0u8; 4];
+            File::open(path)?
+                .read_exact(&mut magic)
+                .map_err(|_| anyhow!("File is too small to be a Whisper model"))?;
+            if &magic != GGML_MAGIC {
+                return Err(anyhow!("Not a GGML Whisper model (bad file header)"));
+            }
+        }
+        EngineType::Parakeet => {
+            if !path.is_dir() {
+                return Err(anyhow!("Parakeet models must be a directory"));
+            }
+
+            for name in PARAKEET_REQUIRED_FILES {
+                if !path.join(name).is_file() {
+                    return Err(anyhow!("Missing {} in model directory", name));
+                }
+            }
+
+            for name in PARAKEET_WEIGHTS {
+                let int8 = path.join(format!("{}.int8.onnx", name));
+                let fp32 = path.join(format!("{}.onnx", name));
+                if !int8.is_file() && !fp32.is_file() {
+                    return Err(anyhow!(
+                        "Missing {}.int8.onnx or {}.onnx in model directory",
+                        name,
+                        name
+                    ));
+                }
+            }
+
+            let vocab = fs::read_to_string(path.join("vocab.txt"))?;
+            if !vocab.lines().any(|line| line.starts_with("<blk>")) {
+                return Err(anyhow!("vocab.txt has no <blk> token"));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Turns a display name into a model id that can't clash with catalog ids.
+pub fn model_id_for(display_name: &str, taken: impl Fn(&str) -> bool) -> String {
+    let mut slug = String::new();
+    for c in display_name.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_matches('-');
+    let base = if slug.is_empty() {
+        "user-model".to_string()
+    } else {
+        format!("user-{}", &slug[..slug.len().min(60)])
+            .trim_end_matches('-')
+            .to_string()
+    };
+
+    let mut id = base.clone();
+    let mut n = 2;
+    while taken(&id) {
+        id = format!("{}-{}", base, n);
+        n += 1;
+    }
+    id
+}
+
+/// Copies `source` to `dest`, recursively for directories. Never hard-links:
+/// the copy is hashed and verified, and a shared inode would let later edits
+/// to the user's original change the installed model behind our back.
+/// `fs::copy` already clones extents on filesystems that support it
+/// (copy_file_range on Linux, clonefile on macOS), so this stays cheap there.
+pub fn copy_model(source: &Path, dest: &Path) -> Result<()> {
+    if source.is_dir() {
+        fs::create_dir_all(dest)?;
+        for entry in fs::read_dir(source)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            if file_type.is_symlink() {
+                return Err(anyhow!(
+                    "Symbolic links are not supported in model directories: {}",
+                    entry.path().display()
+                ));
+            }
+            copy_model(&entry.path(), &dest.join(entry.file_name()))?;
+        }
+    } else {
+        fs::copy(source, dest)?;
+    }
+    Ok(())
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+/// Test-loads a model in a child process and runs a short transcription, so
+/// a malformed file that crashes the inference runtime can't take the app down.
+pub fn probe_in_sandbox(path: &Path, engine: &EngineType) -> Result<()> {
+    let exe = std::env::current_exe()?;
+    let engine_arg = match engine {
+        EngineType::Whisper => "whisper",
+        EngineType::Parakeet => "parakeet",
+    };
+
+    let mut child = Command::new(exe)
+        .arg(PROBE_ARG)
+        .arg(engine_arg)
+        .arg(path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow!("Failed to start model probe: {}", e))?;
+
+    // Inference runtimes log heavily to stderr; drain it so the child can't
+    // block on a full pipe
+    let stderr_reader = child.stderr.take().map(|mut stderr| {
+        thread::spawn(move || {
+            let mut output = Vec::new();
+            let _ = stderr.read_to_end(&mut output);
+            String::from_utf8_lossy(&output).to_string()
+        })
+    });
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if started.elapsed() > PROBE_TIMEOUT {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(anyhow!("Model took too long to load"));
+        }
+        thread::sleep(Duration::from_millis(100));
+    };
+
+    let stderr = stderr_reader
+        .and_then(|handle| handle.join().ok())
+        .unwrap_or_default();
+    debug!(
+        "Model probe for {} exited with {} after {:?}",
+        path.display(),
+        status,
+        started.elapsed()
+    );
+
+    if status.success() {
+        return Ok(());
+    }
+
+    let reason = stderr
+        .lines()
+        .rev()
+        .find(|line| line.starts_with("probe error: "))
+        .map(|line| line.trim_start_matches("probe error: ").to_string())
+        .unwrap_or_else(|| match status.code() {
+            Some(code) => format!("probe exited with code {}", code),
+            None => "probe crashed".to_string(),
+        });
+    Err(anyhow!("Model failed to load: {}", reason))
+}
+
+/// Entry point of the probe child process. Returns the exit code when the
+/// process was started as a probe, `None` for a normal app launch.
+pub fn probe_from_args() -> Option<i32> {
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) != Some(PROBE_ARG) {
+        return None;
+    }
+
+    let (Some(engine), Some(path)) = (args.get(2), args.get(3)) else {
+        eprintln!(
+            "probe error: usage: {} <whisper|parakeet> <path>",
+            PROBE_ARG
+        );
+        return Some(PROBE_FAILED);
+    };
+
+    match run_probe(engine, Path::new(path)) {
+        Ok(()) => Some(0),
+        Err(e) => {
+            eprintln!("probe error: {}", e);
+            Some(PROBE_FAILED)
+        }
+    }
+}
+
+fn run_probe(engine: &str, path: &Path) -> Result<()> {
+    // One second of silence is enough to exercise the whole pipeline
+    let samples = vec![0.0f32; 16000];
+
+    match engine {
+        "whisper" => {
+            let mut engine = WhisperEngine::new();
+            engine.load_model(path).map_err(|e| anyhow!("{}", e))?;
+            engine
+                .transcribe_samples(samples, None)
+                .map_err(|e| anyhow!("{}", e))?;
+        }
+        "parakeet" => {
+            let mut engine = ParakeetEngine::new();
+            engine
+                .load_model_with_params(path, ParakeetModelParams::int8())
+                .map_err(|e| anyhow!("{}", e))?;
+            engine
+                .transcribe_samples(samples, None)
+                .map_err(|e| anyhow!("{}", e))?;
+        }
+        other => return Err(anyhow!("Unknown engine: {}", other)),
+    }
+
+    Ok(())
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+fn user_catalog_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(USER_CATALOG_FILE)
+}
+
+pub fn load_user_models(data_dir: &Path) -> Vec<UserModelEntry> {
+    let path = user_catalog_path(data_dir);
+    if !path.exists() {
+        return Vec::new();
+    }
+
+    match fs::read_to_string(&path)
+        .map_err(anyhow::Error::from)
+        .and_then(|json| serde_json::from_str::<UserCatalog>(&json).map_err(Into::into))
+    {
+        Ok(catalog) => catalog.models,
+        Err(e) => {
+            warn!("Ignoring unreadable user model catalog: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+pub fn save_user_models(data_dir: &Path, models: &[UserModelEntry]) -> Result<()> {
+    let path = user_catalog_path(data_dir);
+    let tmp_path = path.with_extension("json.tmp");
+    let catalog = UserCatalog {
+        models: models.to_vec(),
+    };
+    fs::write(&tmp_path, serde_json::to_vec_pretty(&catalog)?)?;
+    fs::rename(&tmp_path, &path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parakeet_dir(root: &Path) -> PathBuf {
+        let dir = root.join("parakeet-custom");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("vocab.txt"), "<unk> 0\n<blk> 1\n").unwrap();
+        fs::write(dir.join("nemo128.onnx"), b"onnx").unwrap();
+        fs::write(dir.join("encoder-model.int8.onnx"), b"onnx").unwrap();
+        fs::write(dir.join("decoder_joint-model.onnx"), b"onnx").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_validate_parakeet_layout() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = parakeet_dir(root.path());
+        assert!(validate_layout(&dir, &EngineType::Parakeet).is_ok());
+
+        fs::remove_file(dir.join("encoder-model.int8.onnx")).unwrap();
+        assert!(validate_layout(&dir, &EngineType::Parakeet).is_err());
+
+        fs::write(dir.join("encoder-model.onnx"), b"onnx").unwrap();
+        fs::write(dir.join("vocab.txt"), "<unk> 0\n").unwrap();
+        assert!(validate_layout(&dir, &EngineType::Parakeet).is_err());
+
+        // A directory is never a Whisper model
+        assert!(validate_layout(&dir, &EngineType::Whisper).is_err());
+    }
+
+    #[test]
+    fn test_validate_whisper_layout() {
+        let root = tempfile::tempdir().unwrap();
+        let good = root.path().join("ggml-custom.bin");
+        fs::write(&good, b"lmgg\x00\x00").unwrap();
+        assert!(validate_layout(&good, &EngineType::Whisper).is_ok());
+
+        let bad = root.path().join("model.onnx");
+        fs::write(&bad, b"\x08\x07onnx").unwrap();
+        assert!(validate_layout(&bad, &EngineType::Whisper).is_err());
+
+        let tiny = root.path().join("tiny.bin");
+        fs::write(&tiny, b"lm").unwrap();
+        assert!(validate_layout(&tiny, &EngineType::Whisper).is_err());
+    }
+
+    #[test]
+    fn test_model_id_for() {
+        assert_eq!(
+            model_id_for("Whisper Small (Cardiology)", |_| false),
+            "user-whisper-small-cardiology"
+        );
+        assert_eq!(model_id_for("  !!  ", |_| false), "user-model");
+        assert_eq!(
+            model_id_for("Custom", |id| id == "user-custom"),
+            "user-custom-2"
+        );
+    }
+
+    #[test]
+    fn test_copy_model_directory() {
+        let root = tempfile::tempdir().unwrap();
+        let source = parakeet_dir(root.path());
+        let dest = root.path().join("models").join("user-custom");
+        fs::create_dir_all(dest.parent().unwrap()).unwrap();
+
+        copy_model(&source, &dest).unwrap();
+        assert!(validate_layout(&dest, &EngineType::Parakeet).is_ok());
+    }
+
+    #[test]
+    fn test_copy_model_is_independent_of_source() {
+        let root = tempfile::tempdir().unwrap();
+        let source = root.path().join("model.bin");
+        let dest = root.path().join("copy.bin");
+        fs::write(&source, b"original").unwrap();
+
+        copy_model(&source, &dest).unwrap();
+        fs::write(&source, b"edited afterwards").unwrap();
+        assert_eq!(fs::read(&dest).unwrap(), b"original");
+    }
+}
@@ -0,0 +1,431 @@
+use crate::managers::model::EngineType;
+use crate::managers::{catalog, integrity};
+use anyhow::{anyhow, Result};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+/// Manifest at the root of every model pack.
+pub const PACK_MANIFEST_FILE: &str = "handy-model-pack.json";
+
+/// Detached minisign signature over the manifest, made by whoever publishes
+/// the pack. The manifest lists every file's hash, so it covers the models too.
+pub const PACK_SIGNATURE_FILE: &str = "handy-model-pack.json.minisig";
+
+/// Models live under this directory inside the pack, by their `filename`.
+pub const PACK_MODELS_DIR: &str = "models";
+
+/// Manifest schema this build understands. Like the catalog, unknown fields
+/// are ignored, so exporters may add optional ones without bumping it.
+pub const PACK_FORMAT_VERSION: u32 = 1;
+
+/// Describes a set of models copied from one machine for offline install on another.
+///
+/// Layout:
+/// ```text
+/// pack/
+/// ├── handy-model-pack.json
+/// ├── handy-model-pack.json.minisig   (optional)
+/// └── models/
+///     ├── ggml-small.bin
+///     └── parakeet-tdt-0.6b-v3-int8/
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackManifest {
+    pub format_version: u32,
+    /// App version that exported the pack
+    pub app_version: String,
+    /// Seconds since the Unix epoch
+    pub created_at: u64,
+    pub models: Vec<PackModel>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackModel {
+    pub id: String,
+    pub name: String,
+    pub engine: EngineType,
+    pub filename: String,
+    pub is_directory: bool,
+    /// SHA-256 of the original download, carried over so `verify_model`
+    /// still lines up with the catalog after an offline install
+    pub source_sha256: String,
+    /// SHA-256 of every file, keyed by path relative to the model
+    pub files: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum PackInstallStatus {
+    Installed,
+    /// Identical files were already present
+    AlreadyInstalled,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct PackInstallResult {
+    pub model_id: String,
+    pub status: PackInstallStatus,
+    pub error: Option<String>,
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+pub fn read_manifest(pack_dir: &Path) -> Result<PackManifest> {
+    let path = pack_dir.join(PACK_MANIFEST_FILE);
+    let json = fs::read_to_string(&path)
+        .map_err(|e| anyhow!("No model pack manifest at {}: {}", path.display(), e))?;
+    let value: serde_json::Value =
+        serde_json::from_str(&json).map_err(|e| anyhow!("Invalid model pack manifest: {}", e))?;
+
+    // A pack from a newer format may not fit these types; say so rather than
+    // fail on whichever field changed
+    let format_version = value
+        .get("format_version")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| anyhow!("Invalid model pack manifest: missing format_version"))?;
+    check_format_version(format_version)?;
+
+    let manifest: PackManifest =
+        serde_json::from_value(value).map_err(|e| anyhow!("Invalid model pack manifest: {}", e))?;
+    validate_manifest(&manifest)?;
+    Ok(manifest)
+}
+
+/// Checks the pack's manifest signature against `public_key`.
+pub fn verify_manifest_signature(pack_dir: &Path, public_key: &str) -> Result<()> {
+    let path = pack_dir.join(PACK_SIGNATURE_FILE);
+    let signature = fs::read_to_string(&path)
+        .map_err(|e| anyhow!("Model pack isn't signed ({}): {}", path.display(), e))?;
+    integrity::verify_signature(&pack_dir.join(PACK_MANIFEST_FILE), &signature, public_key)
+}
+
+fn check_format_version(version: u64) -> Result<()> {
+    if version != u64::from(PACK_FORMAT_VERSION) {
+        return Err(anyhow!(
+            "Unsupported model pack format {} (expected {})",
+            version,
+            PACK_FORMAT_VERSION
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_manifest(manifest: &PackManifest) -> Result<()> {
+    check_format_version(manifest.format_version.into())?;
+
+    let mut ids = HashSet::new();
+    for model in &manifest.models {
+        crate::validation::validate_model_id(&model.id)
+            .map_err(|e| anyhow!("Pack model '{}': {}", model.id, e))?;
+
+        if !ids.insert(model.id.as_str()) {
+            return Err(anyhow!("Duplicate model in pack: {}", model.id));
+        }
+
+        if !catalog::is_plain_file_name(&model.filename) {
+            return Err(anyhow!("Pack model '{}' has an invalid filename", model.id));
+        }
+
+        if model.files.is_empty() {
+            return Err(anyhow!("Pack model '{}' lists no files", model.id));
+        }
+
+        for path in model.files.keys() {
+            if path.is_empty()
+                || path.starts_with('/')
+                || path.contains('\\')
+                || path.split('/').any(|part| part.is_empty() || part == "..")
+            {
+                return Err(anyhow!(
+                    "Pack model '{}' lists an invalid path: {}",
+                    model.id,
+                    path
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn model_path(pack_dir: &Path, model: &PackModel) -> PathBuf {
+    pack_dir.join(PACK_MODELS_DIR).join(&model.filename)
+}
+
+/// Hashes a model inside the pack and checks it against the manifest.
+pub fn verify_pack_model(pack_dir: &Path, model: &PackModel) -> Result<()> {
+    let path = model_path(pack_dir, model);
+
+    let actual = if model.is_directory {
+        if !path.is_dir() {
+            return Err(anyhow!(
+                "Model directory missing from pack: {}",
+                model.filename
+            ));
+        }
+        integrity::hash_tree(&path)?
+    } else {
+        if !path.is_file() {
+            return Err(anyhow!("Model file missing from pack: {}", model.filename));
+        }
+        BTreeMap::from([(model.filename.clone(), integrity::sha256_file(&path)?)])
+    };
+
+    if actual != model.files {
+        let detail = model
+            .files
+            .iter()
+            .find(|(file, hash)| actual.get(*file) != Some(*hash))
+            .map(|(file, _)| format!("checksum mismatch for {}", file))
+            .or_else(|| {
+                actual
+                    .keys()
+                    .find(|file| !model.files.contains_key(*file))
+                    .map(|file| format!("unexpected file {}", file))
+            })
+            .unwrap_or_default();
+        return Err(anyhow!("Pack model '{}' is corrupt: {}", model.id, detail));
+    }
+
+    Ok(())
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+pub fn is_archive(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    name.ends_with(".tar.gz") || name.ends_with(".tgz")
+}
+
+/// Writes a pack directory: manifest plus a copy of each model.
+pub fn write_pack_dir(
+    dest: &Path,
+    manifest: &PackManifest,
+    sources: &[(PathBuf, &PackModel)],
+) -> Result<()> {
+    if dest.exists() && fs::read_dir(dest)?.next().is_some() {
+        return Err(anyhow!(
+            "Export destination {} is not empty",
+            dest.display()
+        ));
+    }
+    let models_dir = dest.join(PACK_MODELS_DIR);
+    fs::create_dir_all(&models_dir)?;
+
+    for (source, model) in sources {
+        crate::managers::model_import::copy_model(source, &models_dir.join(&model.filename))?;
+    }
+
+    fs::write(
+        dest.join(PACK_MANIFEST_FILE),
+        serde_json::to_vec_pretty(manifest)?,
+    )?;
+    Ok(())
+}
+
+/// Writes a pack as a single `.tar.gz` with the same layout as a pack directory.
+pub fn write_pack_archive(
+    dest: &Path,
+    manifest: &PackManifest,
+    sources: &[(PathBuf, &PackModel)],
+) -> Result<()> {
+    if dest.exists() {
+        return Err(anyhow!(
+            "Export destination {} already exists",
+            dest.display()
+        ));
+    }
+
+    let tmp_path = dest.with_extension("partial");
+    let result = (|| -> Result<()> {
+        let encoder = GzEncoder::new(File::create(&tmp_path)?, Compression::fast());
+        let mut builder = tar::Builder::new(encoder);
+
+        let manifest_json = serde_json::to_vec_pretty(manifest)?;
+        let mut header = tar::Header::new_gnu();
+        header.set_size(manifest_json.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, PACK_MANIFEST_FILE, manifest_json.as_slice())?;
+
+        for (source, model) in sources {
+            let name = Path::new(PACK_MODELS_DIR).join(&model.filename);
+            if model.is_directory {
+                builder.append_dir_all(&name, source)?;
+            } else {
+                builder.append_path_with_name(source, &name)?;
+            }
+        }
+
+        builder.into_inner()?.finish()?;
+        Ok(())
+    })();
+
+    match result {
+        Ok(()) => {
+            fs::rename(&tmp_path, dest)?;
+            Ok(())
+        }
+        Err(e) => {
+            let _ = fs::remove_file(&tmp_path);
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_pack(root: &Path) -> (PathBuf, PackManifest) {
+        let pack = root.join("pack");
+        let models = pack.join(PACK_MODELS_DIR);
+        fs::create_dir_all(models.join("parakeet/sub")).unwrap();
+        fs::write(models.join("parakeet/vocab.txt"), b"<blk> 0").unwrap();
+        fs::write(models.join("parakeet/sub/encoder.onnx"), b"weights").unwrap();
+        fs::write(models.join("ggml-small.bin"), b"lmgg").unwrap();
+
+        let manifest = PackManifest {
+            format_version: PACK_FORMAT_VERSION,
+            app_version: "0.0.0".to_string(),
+            created_at: 0,
+            models: vec![
+                PackModel {
+                    id: "parakeet".to_string(),
+                    name: "Parakeet".to_string(),
+                    engine: EngineType::Parakeet,
+                    filename: "parakeet".to_string(),
+                    is_directory: true,
+                    source_sha256: String::new(),
+                    files: integrity::hash_tree(&models.join("parakeet")).unwrap(),
+                },
+                PackModel {
+                    id: "small".to_string(),
+                    name: "Whisper Small".to_string(),
+                    engine: EngineType::Whisper,
+                    filename: "ggml-small.bin".to_string(),
+                    is_directory: false,
+                    source_sha256: String::new(),
+                    files: BTreeMap::from([(
+                        "ggml-small.bin".to_string(),
+                        integrity::sha256_file(&models.join("ggml-small.bin")).unwrap(),
+                    )]),
+                },
+            ],
+        };
+        fs::write(
+            pack.join(PACK_MANIFEST_FILE),
+            serde_json::to_vec(&manifest).unwrap(),
+        )
+        .unwrap();
+        (pack, manifest)
+    }
+
+    #[test]
+    fn test_verify_pack_models() {
+        let root = tempfile::tempdir().unwrap();
+        let (pack, _) = build_pack(root.path());
+
+        let manifest = read_manifest(&pack).unwrap();
+        for model in &manifest.models {
+            assert!(verify_pack_model(&pack, model).is_ok());
+        }
+
+        fs::write(
+            pack.join(PACK_MODELS_DIR).join("parakeet/vocab.txt"),
+            b"<blk> 1",
+        )
+        .unwrap();
+        assert!(verify_pack_model(&pack, &manifest.models[0]).is_err());
+        assert!(verify_pack_model(&pack, &manifest.models[1]).is_ok());
+    }
+
+    #[test]
+    fn test_unsigned_pack_fails_signature_check() {
+        let root = tempfile::tempdir().unwrap();
+        let (pack, _) = build_pack(root.path());
+        let key = "RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3";
+
+        let err = verify_manifest_signature(&pack, key).unwrap_err();
+        assert!(err.to_string().contains("isn't signed"));
+
+        fs::write(pack.join(PACK_SIGNATURE_FILE), "not a signature").unwrap();
+        assert!(verify_manifest_signature(&pack, key).is_err());
+    }
+
+    #[test]
+    fn test_manifest_rejects_traversal() {
+        let root = tempfile::tempdir().unwrap();
+        let (_, mut manifest) = build_pack(root.path());
+        assert!(validate_manifest(&manifest).is_ok());
+
+        manifest.models[0].filename = "../outside".to_string();
+        assert!(validate_manifest(&manifest).is_err());
+
+        manifest.models[0].filename = "parakeet".to_string();
+        manifest.models[0]
+            .files
+            .insert("../../etc/passwd".to_string(), String::new());
+        assert!(validate_manifest(&manifest).is_err());
+    }
+
+    #[test]
+    fn test_manifest_ignores_unknown_fields_but_not_other_formats() {
+        let root = tempfile::tempdir().unwrap();
+        let (pack, manifest) = build_pack(root.path());
+        let write = |manifest: serde_json::Value| {
+            fs::write(
+                pack.join(PACK_MANIFEST_FILE),
+                serde_json::to_vec(&manifest).unwrap(),
+            )
+            .unwrap();
+        };
+
+        // Optional fields from a newer exporter
+        let mut extended = serde_json::to_value(&manifest).unwrap();
+        extended["exported_by"] = "workstation".into();
+        extended["models"][0]["quantization"] = "int8".into();
+        write(extended);
+        assert_eq!(read_manifest(&pack).unwrap().models.len(), 2);
+
+        // A later format is named as such, even though its fields don't fit
+        write(serde_json::json!({ "format_version": 2, "models": "elsewhere" }));
+        let err = read_manifest(&pack).unwrap_err();
+        assert!(err.to_string().contains("Unsupported model pack format 2"));
+    }
+
+    #[test]
+    fn test_archive_roundtrip() {
+        let root = tempfile::tempdir().unwrap();
+        let (pack, manifest) = build_pack(root.path());
+        let sources: Vec<_> = manifest
+            .models
+            .iter()
+            .map(|m| (model_path(&pack, m), m))
+            .collect();
+
+        let archive = root.path().join("export.tar.gz");
+        write_pack_archive(&archive, &manifest, &sources).unwrap();
+        assert!(is_archive(&archive));
+
+        let unpacked = root.path().join("unpacked");
+        fs::create_dir_all(&unpacked).unwrap();
+        tar::Archive::new(flate2::read::GzDecoder::new(File::open(&archive).unwrap()))
+            .unpack(&unpacked)
+            .unwrap();
+
+        let read_back = read_manifest(&unpacked).unwrap();
+        for model in &read_back.models {
+            assert!(verify_pack_model(&unpacked, model).is_ok());
+        }
+    }
+}
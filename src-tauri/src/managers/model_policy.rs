@@ -0,0 +1,298 @@
+use crate::settings::ClinicHours;
+use chrono::{Datelike, Local, Timelike};
+use serde::Serialize;
+use specta::Type;
+
+/// Why the transcription model was loaded or unloaded, reported with every
+/// `model-state-changed` event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelStateReason {
+    /// The user picked a model or unloaded it by hand
+    UserRequest,
+    /// A recording started and needed the model
+    OnDemand,
+    /// Loaded at startup because preloading is enabled
+    Preload,
+    /// Kept resident during configured clinic hours
+    ClinicHours,
+    /// No transcription within the unload timeout
+    IdleTimeout,
+    /// Unload timeout is set to unload right after each transcription
+    AfterTranscription,
+    /// Available system memory dropped below the configured threshold
+    LowMemory,
+}
+
+/// What the model watcher sees on each tick.
+#[derive(Debug, Clone)]
+pub struct PolicyInput {
+    pub loaded: bool,
+    pub idle_secs: u64,
+    /// `None` when the model should never be unloaded for inactivity
+    pub idle_timeout_secs: Option<u64>,
+    pub in_clinic_hours: bool,
+    /// `None` when it couldn't be measured
+    pub available_memory_mb: Option<u64>,
+    /// 0 disables memory-pressure unloading
+    pub low_memory_threshold_mb: u64,
+    /// Size of the selected model, used as headroom before reloading
+    pub model_size_mb: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolicyAction {
+    Load(ModelStateReason),
+    Unload(ModelStateReason),
+}
+
+/// Decides whether the watcher should load or unload the model.
+///
+/// Memory pressure wins over everything, clinic hours included: a model that
+/// pushes the machine into swap is worse than one that has to reload. A model
+/// unloaded for low memory comes back for clinic hours only once there is
+/// room for it on top of the threshold, so it doesn't flap. While memory
+/// can't be measured there is no telling, so it stays unloaded.
+pub fn evaluate(input: &PolicyInput) -> Option<PolicyAction> {
+    let threshold = input.low_memory_threshold_mb;
+    let available = input.available_memory_mb.filter(|_| threshold > 0);
+
+    if input.loaded {
+        if available.is_some_and(|mb| mb < threshold) {
+            return Some(PolicyAction::Unload(ModelStateReason::LowMemory));
+        }
+        if input.in_clinic_hours {
+            return None;
+        }
+        if input
+            .idle_timeout_secs
+            .is_some_and(|limit| input.idle_secs > limit)
+        {
+            return Some(PolicyAction::Unload(ModelStateReason::IdleTimeout));
+        }
+        return None;
+    }
+
+    let has_room = threshold == 0
+        || available.is_some_and(|mb| mb >= threshold.saturating_add(input.model_size_mb));
+    if input.in_clinic_hours && has_room {
+        return Some(PolicyAction::Load(ModelStateReason::ClinicHours));
+    }
+
+    None
+}
+
+/// Whether `now` falls inside the configured clinic hours.
+pub fn in_clinic_hours(hours: Option<&ClinicHours>) -> bool {
+    let Some(hours) = hours else {
+        return false;
+    };
+    let now = Local::now();
+    hours.contains(
+        now.weekday().number_from_monday() as u8,
+        (now.hour() * 60 + now.minute()) as u16,
+    )
+}
+
+/// `MemAvailable` from the contents of `/proc/meminfo`, in megabytes.
+pub fn parse_mem_available_mb(meminfo: &str) -> Option<u64> {
+    meminfo.lines().find_map(|line| {
+        let rest = line.strip_prefix("MemAvailable:")?;
+        let kb: u64 = rest.trim().strip_suffix("kB")?.trim().parse().ok()?;
+        Some(kb / 1024)
+    })
+}
+
+/// Free, inactive and speculative pages from `vm_stat` output, in
+/// megabytes; roughly what Activity Monitor counts as available.
+pub fn parse_vm_stat_available_mb(vm_stat: &str) -> Option<u64> {
+    let page_size: u64 = vm_stat
+        .lines()
+        .next()?
+        .split("page size of ")
+        .nth(1)?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()?;
+    let pages = |name: &str| -> Option<u64> {
+        vm_stat.lines().find_map(|line| {
+            let rest = line.strip_prefix(name)?.strip_prefix(':')?;
+            rest.trim().trim_end_matches('.').parse().ok()
+        })
+    };
+    let available = pages("Pages free")? + pages("Pages inactive")? + pages("Pages speculative")?;
+    Some(available * page_size / (1024 * 1024))
+}
+
+#[cfg(target_os = "linux")]
+pub fn available_memory_mb() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    parse_mem_available_mb(&meminfo)
+}
+
+#[cfg(target_os = "macos")]
+pub fn available_memory_mb() -> Option<u64> {
+    let output = std::process::Command::new("vm_stat").output().ok()?;
+    parse_vm_stat_available_mb(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(windows)]
+pub fn available_memory_mb() -> Option<u64> {
+    use windows::Win32::System::SystemInformation::{GlobalMemoryStatusEx, MEMORYSTATUSEX};
+
+    let mut status = MEMORYSTATUSEX {
+        dwLength: std::mem::size_of::<MEMORYSTATUSEX>() as u32,
+        ..Default::default()
+    };
+    unsafe { GlobalMemoryStatusEx(&mut status) }.ok()?;
+    Some(status.ullAvailPhys / (1024 * 1024))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+pub fn available_memory_mb() -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn idle_input() -> PolicyInput {
+        PolicyInput {
+            loaded: true,
+            idle_secs: 0,
+            idle_timeout_secs: Some(300),
+            in_clinic_hours: false,
+            available_memory_mb: Some(8_000),
+            low_memory_threshold_mb: 1_024,
+            model_size_mb: 500,
+        }
+    }
+
+    #[test]
+    fn test_idle_timeout() {
+        let mut input = idle_input();
+        assert_eq!(evaluate(&input), None);
+
+        input.idle_secs = 301;
+        assert_eq!(
+            evaluate(&input),
+            Some(PolicyAction::Unload(ModelStateReason::IdleTimeout))
+        );
+
+        input.idle_timeout_secs = None;
+        assert_eq!(evaluate(&input), None);
+    }
+
+    #[test]
+    fn test_clinic_hours_keep_model_resident() {
+        let mut input = idle_input();
+        input.idle_secs = 10_000;
+        input.in_clinic_hours = true;
+        assert_eq!(evaluate(&input), None);
+
+        input.loaded = false;
+        assert_eq!(
+            evaluate(&input),
+            Some(PolicyAction::Load(ModelStateReason::ClinicHours))
+        );
+    }
+
+    #[test]
+    fn test_low_memory_unloads_with_hysteresis() {
+        let mut input = idle_input();
+        input.in_clinic_hours = true;
+        input.available_memory_mb = Some(900);
+        assert_eq!(
+            evaluate(&input),
+            Some(PolicyAction::Unload(ModelStateReason::LowMemory))
+        );
+
+        // Unloading freed a little memory, but not enough to reload safely
+        input.loaded = false;
+        input.available_memory_mb = Some(1_300);
+        assert_eq!(evaluate(&input), None);
+
+        input.available_memory_mb = Some(1_600);
+        assert_eq!(
+            evaluate(&input),
+            Some(PolicyAction::Load(ModelStateReason::ClinicHours))
+        );
+
+        // Without a reading there's no knowing whether it fits
+        input.available_memory_mb = None;
+        assert_eq!(evaluate(&input), None);
+        input.available_memory_mb = Some(2_000);
+        input.model_size_mb = u64::MAX;
+        assert_eq!(evaluate(&input), None);
+
+        // A zero threshold turns memory checks off
+        input.loaded = true;
+        input.available_memory_mb = Some(10);
+        input.low_memory_threshold_mb = 0;
+        assert_eq!(evaluate(&input), None);
+        input.loaded = false;
+        input.available_memory_mb = None;
+        assert_eq!(
+            evaluate(&input),
+            Some(PolicyAction::Load(ModelStateReason::ClinicHours))
+        );
+    }
+
+    #[test]
+    fn test_parse_vm_stat() {
+        let vm_stat = "\
+Mach Virtual Memory Statistics: (page size of 16384 bytes)
+Pages free:                               10240.
+Pages active:                            400000.
+Pages inactive:                           40960.
+Pages speculative:                        14336.
+Pages throttled:                              0.
+Pages wired down:                        150000.
+Pages purgeable:                           5000.
+";
+        // (10240 + 40960 + 14336) pages of 16 KiB
+        assert_eq!(parse_vm_stat_available_mb(vm_stat), Some(1024));
+        assert_eq!(parse_vm_stat_available_mb(""), None);
+    }
+
+    #[test]
+    fn test_clinic_hours_contains() {
+        let weekdays = ClinicHours {
+            start: "08:30".to_string(),
+            end: "17:00".to_string(),
+            weekdays: vec![1, 2, 3, 4, 5],
+        };
+        assert!(weekdays.validate().is_ok());
+        assert!(weekdays.contains(1, 8 * 60 + 30));
+        assert!(!weekdays.contains(1, 17 * 60));
+        assert!(!weekdays.contains(6, 12 * 60));
+
+        // Overnight shifts wrap past midnight
+        let overnight = ClinicHours {
+            start: "22:00".to_string(),
+            end: "06:00".to_string(),
+            weekdays: Vec::new(),
+        };
+        assert!(overnight.contains(7, 23 * 60));
+        assert!(overnight.contains(1, 5 * 60));
+        assert!(!overnight.contains(1, 12 * 60));
+
+        let invalid = ClinicHours {
+            start: "25:00".to_string(),
+            end: "06:00".to_string(),
+            weekdays: vec![8],
+        };
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_mem_available() {
+        let meminfo = "MemTotal:       16318412 kB\n\
+                       MemFree:          512000 kB\n\
+                       MemAvailable:    2097152 kB\n";
+        assert_eq!(parse_mem_available_mb(meminfo), Some(2048));
+        assert_eq!(parse_mem_available_mb("MemTotal: 1 kB\n"), None);
+    }
+}
@@ -0,0 +1,586 @@
+use crate::helpers::focus::{self, FocusedWindow};
+use crate::managers::audio;
+use crate::managers::diarization::DiarizationManager;
+use crate::managers::transcription::TranscriptionManager;
+use crate::output;
+use crate::post_process::post_process_transcription;
+use crate::settings::{get_settings, AppSettings};
+use crate::tray::{change_tray_icon, TrayIconState};
+use crate::utils;
+use log::{debug, error, info, warn};
+use parking_lot::Mutex;
+use serde::Serialize;
+use specta::Type;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter, Manager};
+
+pub type JobId = u64;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    /// The microphone is still capturing audio for this job
+    Recording,
+    /// Audio captured, waiting for the transcription worker
+    Queued,
+    /// The worker is currently running inference for this job
+    Transcribing,
+}
+
+#[derive(Clone, Debug, Serialize, Type)]
+pub struct JobSummary {
+    pub id: JobId,
+    pub binding_id: String,
+    pub status: JobStatus,
+}
+
+/// Snapshot of the queue sent to the frontend and overlay on every change
+#[derive(Clone, Debug, Serialize, Type)]
+pub struct QueueState {
+    pub jobs: Vec<JobSummary>,
+    pub is_recording: bool,
+    /// Number of jobs captured but not yet pasted
+    pub pending: usize,
+}
+
+struct Job {
+    id: JobId,
+    binding_id: String,
+    status: JobStatus,
+    focus: Option<FocusedWindow>,
+    created_at: Instant,
+}
+
+struct WorkItem {
+    id: JobId,
+    samples: Vec<f32>,
+}
+
+/// Sent with `transcription-failed` when a job produced no text because
+/// something went wrong.
+#[derive(Clone, Debug, Serialize)]
+struct JobFailure {
+    job: JobId,
+    binding_id: String,
+    error: String,
+}
+
+/// The app side of the queue: running a job and showing the queue's state.
+/// `AppQueueHost` implements it over the app; tests use a stand-in.
+trait QueueHost: Send + Sync + 'static {
+    /// The window to paste into later, if pasting into it is turned on.
+    fn capture_focus(&self) -> Option<FocusedWindow>;
+    /// Transcribes a job's audio and delivers the text.
+    fn process(
+        &self,
+        id: JobId,
+        binding_id: &str,
+        focus: Option<FocusedWindow>,
+        samples: Vec<f32>,
+    ) -> anyhow::Result<()>;
+    /// Tells the frontend a job failed; its recording is lost.
+    fn report_failure(&self, failure: &JobFailure);
+    /// Sends the queue to the frontend and overlay.
+    fn emit_state(&self, state: &QueueState);
+    /// Points tray icon and overlay at the whole queue, then sends it.
+    fn refresh_ui(&self, state: &QueueState);
+}
+
+struct AppQueueHost {
+    app: AppHandle,
+}
+
+impl QueueHost for AppQueueHost {
+    fn capture_focus(&self) -> Option<FocusedWindow> {
+        if get_settings(&self.app).paste_into_original_window {
+            focus::capture_focused_window()
+        } else {
+            None
+        }
+    }
+
+    fn process(
+        &self,
+        id: JobId,
+        binding_id: &str,
+        focus: Option<FocusedWindow>,
+        samples: Vec<f32>,
+    ) -> anyhow::Result<()> {
+        let settings = get_settings(&self.app);
+        let final_text = transcribe_recording(&self.app, samples, &settings)?;
+        if !final_text.is_empty() {
+            output::deliver(&self.app, &settings, id, binding_id, &final_text, focus);
+        }
+        Ok(())
+    }
+
+    fn report_failure(&self, failure: &JobFailure) {
+        let _ = self.app.emit("transcription-failed", failure);
+    }
+
+    fn emit_state(&self, state: &QueueState) {
+        let _ = self.app.emit("transcription-queue-changed", state);
+
+        if let Some(overlay_window) = self.app.get_webview_window("recording_overlay") {
+            let _ = overlay_window.emit("transcription-queue-changed", state);
+        }
+    }
+
+    fn refresh_ui(&self, state: &QueueState) {
+        if state.is_recording {
+            // The recording overlay stays up, only the queue badge changes
+            change_tray_icon(&self.app, TrayIconState::Recording);
+        } else if state.pending > 0 {
+            change_tray_icon(&self.app, TrayIconState::Transcribing);
+            utils::show_transcribing_overlay(&self.app);
+        } else {
+            change_tray_icon(&self.app, TrayIconState::Idle);
+            utils::hide_recording_overlay(&self.app);
+        }
+
+        self.emit_state(state);
+    }
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+/// Turns each recording into an independent transcription job.
+///
+/// Jobs are transcribed one at a time on a dedicated worker thread in the order
+/// they were recorded, so results are always pasted in order while the user is
+/// free to start the next recording.
+pub struct TranscriptionQueue {
+    host: Arc<dyn QueueHost>,
+    next_id: AtomicU64,
+    jobs: Arc<Mutex<Vec<Job>>>,
+    work_tx: Mutex<Option<mpsc::Sender<WorkItem>>>,
+    worker_handle: Mutex<Option<thread::JoinHandle<()>>>,
+}
+
+impl TranscriptionQueue {
+    pub fn new(app_handle: &AppHandle) -> Result<Self, anyhow::Error> {
+        Self::with_host(Arc::new(AppQueueHost {
+            app: app_handle.clone(),
+        }))
+    }
+
+    fn with_host(host: Arc<dyn QueueHost>) -> Result<Self, anyhow::Error> {
+        let (work_tx, work_rx) = mpsc::channel::<WorkItem>();
+        let jobs = Arc::new(Mutex::new(Vec::new()));
+
+        let worker_handle = {
+            let host = host.clone();
+            let jobs = jobs.clone();
+            thread::Builder::new()
+                .name("transcription-queue".to_string())
+                .spawn(move || run_worker(host, jobs, work_rx))?
+        };
+
+        Ok(Self {
+            host,
+            next_id: AtomicU64::new(1),
+            jobs,
+            work_tx: Mutex::new(Some(work_tx)),
+            worker_handle: Mutex::new(Some(worker_handle)),
+        })
+    }
+
+    /// Registers a new job for a recording that is about to start.
+    pub fn begin_recording(&self, binding_id: &str) -> JobId {
+        let focus = self.host.capture_focus();
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.jobs.lock().push(Job {
+            id,
+            binding_id: binding_id.to_string(),
+            status: JobStatus::Recording,
+            focus,
+            created_at: Instant::now(),
+        });
+        debug!(
+            "Transcription job {} created for binding {}",
+            id, binding_id
+        );
+
+        emit_queue_state(self.host.as_ref(), &self.jobs);
+        id
+    }
+
+    /// Returns the job that is currently capturing audio, if any.
+    pub fn recording_job(&self) -> Option<JobId> {
+        self.jobs
+            .lock()
+            .iter()
+            .find(|job| job.status == JobStatus::Recording)
+            .map(|job| job.id)
+    }
+
+    /// Hands the captured audio of a job to the transcription worker.
+    pub fn submit(&self, id: JobId, samples: Vec<f32>) {
+        {
+            let mut jobs = self.jobs.lock();
+            match jobs.iter_mut().find(|job| job.id == id) {
+                Some(job) => job.status = JobStatus::Queued,
+                None => {
+                    warn!("Ignoring samples for unknown transcription job {}", id);
+                    return;
+                }
+            }
+        }
+
+        let sent = match self.work_tx.lock().as_ref() {
+            Some(tx) => tx.send(WorkItem { id, samples }).is_ok(),
+            None => false,
+        };
+
+        if sent {
+            debug!("Transcription job {} queued", id);
+            emit_queue_state(self.host.as_ref(), &self.jobs);
+        } else {
+            error!("Transcription worker is not running, dropping job {}", id);
+            self.discard(id);
+        }
+    }
+
+    /// Removes a job without transcribing it (no audio, cancelled, failed to start).
+    pub fn discard(&self, id: JobId) {
+        self.jobs.lock().retain(|job| job.id != id);
+        debug!("Transcription job {} discarded", id);
+        self.refresh_ui();
+    }
+
+    /// Drops the job belonging to the recording in progress. Jobs that already
+    /// finished recording keep going.
+    pub fn cancel_recording(&self) {
+        if let Some(id) = self.recording_job() {
+            self.discard(id);
+        }
+    }
+
+    pub fn get_state(&self) -> QueueState {
+        queue_state(&self.jobs.lock())
+    }
+
+    /// Number of jobs that finished recording but haven't been pasted yet.
+    pub fn pending_count(&self) -> usize {
+        queue_state(&self.jobs.lock()).pending
+    }
+
+    /// Updates tray icon and overlay so they reflect the whole queue rather than
+    /// a single recording.
+    pub fn refresh_ui(&self) {
+        refresh_ui(self.host.as_ref(), &self.jobs);
+    }
+}
+
+impl Drop for TranscriptionQueue {
+    fn drop(&mut self) {
+        // Closing the channel lets the worker finish the remaining jobs and exit
+        self.work_tx.lock().take();
+        if let Some(handle) = self.worker_handle.lock().take() {
+            if let Err(e) = handle.join() {
+                warn!("Failed to join transcription queue worker: {:?}", e);
+            }
+        }
+    }
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+fn queue_state(jobs: &[Job]) -> QueueState {
+    QueueState {
+        jobs: jobs
+            .iter()
+            .map(|job| JobSummary {
+                id: job.id,
+                binding_id: job.binding_id.clone(),
+                status: job.status.clone(),
+            })
+            .collect(),
+        is_recording: jobs.iter().any(|job| job.status == JobStatus::Recording),
+        pending: jobs
+            .iter()
+            .filter(|job| job.status != JobStatus::Recording)
+            .count(),
+    }
+}
+
+fn emit_queue_state(host: &dyn QueueHost, jobs: &Mutex<Vec<Job>>) {
+    let state = queue_state(&jobs.lock());
+    host.emit_state(&state);
+}
+
+fn refresh_ui(host: &dyn QueueHost, jobs: &Mutex<Vec<Job>>) {
+    let state = queue_state(&jobs.lock());
+    host.refresh_ui(&state);
+}
+
+fn run_worker(
+    host: Arc<dyn QueueHost>,
+    jobs: Arc<Mutex<Vec<Job>>>,
+    work_rx: mpsc::Receiver<WorkItem>,
+) {
+    debug!("Transcription queue worker started");
+
+    while let Ok(WorkItem { id, samples }) = work_rx.recv() {
+        let (binding_id, focus) = match jobs.lock().iter_mut().find(|job| job.id == id) {
+            Some(job) => {
+                debug!(
+                    "Transcription job {} waited {:?} since recording started",
+                    id,
+                    job.created_at.elapsed()
+                );
+                job.status = JobStatus::Transcribing;
+                (job.binding_id.clone(), job.focus.clone())
+            }
+            // Discarded while waiting in the channel
+            None => continue,
+        };
+        emit_queue_state(host.as_ref(), &jobs);
+
+        let transcription_time = Instant::now();
+        match host.process(id, &binding_id, focus, samples) {
+            Ok(()) => {
+                debug!(
+                    "Transcription job {} completed in {:?}",
+                    id,
+                    transcription_time.elapsed()
+                );
+            }
+            Err(err) => {
+                error!("Transcription job {} failed: {:#}", id, err);
+                host.report_failure(&JobFailure {
+                    job: id,
+                    binding_id,
+                    error: format!("{:#}", err),
+                });
+            }
+        }
+
+        jobs.lock().retain(|job| job.id != id);
+        refresh_ui(host.as_ref(), &jobs);
+    }
+
+    debug!("Transcription queue worker shutting down");
+}
+
+/// Turns recorded audio into the final text: transcription, speaker labels if
+/// diarisation is on, then post-processing. Empty if nothing was said.
+pub fn transcribe_recording(
+    app: &AppHandle,
+    samples: Vec<f32>,
+    settings: &AppSettings,
+) -> anyhow::Result<String> {
+    let tm = Arc::clone(&app.state::<Arc<TranscriptionManager>>());
+    if settings.diarization.enabled {
+        return transcribe_with_speakers(app, &tm, samples, settings);
+    }
+    let transcription = tm.transcribe(samples)?;
+    if transcription.is_empty() {
+        return Ok(transcription);
+    }
+    Ok(post_process_transcription(settings, &transcription))
+}
+
+/// Transcribes with segment timings and puts each speaker's label in front
+/// of their turns, post-processing every turn on its own. Falls back to the
+/// unlabelled transcript if the speakers can't be told apart.
+fn transcribe_with_speakers(
+    app: &AppHandle,
+    tm: &TranscriptionManager,
+    samples: Vec<f32>,
+    settings: &AppSettings,
+) -> anyhow::Result<String> {
+    let transcript = tm.transcribe_detailed(samples.clone())?;
+    if transcript.text.is_empty() {
+        return Ok(String::new());
+    }
+
+    let dm = app.state::<Arc<DiarizationManager>>();
+    let vad = || audio::vad_from_settings(app);
+    match dm.label(&samples, &transcript, &settings.diarization, vad) {
+        Ok(turns) => Ok(turns
+            .iter()
+            .map(|turn| {
+                let text = post_process_transcription(settings, &turn.text);
+                format!("{}: {}", turn.label, text)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")),
+        Err(e) => {
+            warn!("Diarisation failed, pasting without speaker labels: {}", e);
+            let _ = app.emit("diarization-failed", e.to_string());
+            Ok(post_process_transcription(settings, &transcript.text))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records what the worker does. Jobs in `failing` return an error, and
+    /// holding `gate` keeps the worker inside its current job.
+    struct TestHost {
+        failing: Vec<JobId>,
+        gate: Mutex<()>,
+        started: Mutex<mpsc::Sender<JobId>>,
+        processed: Mutex<Vec<JobId>>,
+        failures: Mutex<Vec<JobFailure>>,
+        last_state: Mutex<Option<QueueState>>,
+    }
+
+    impl TestHost {
+        fn new(failing: Vec<JobId>) -> (Arc<Self>, mpsc::Receiver<JobId>) {
+            let (started, started_rx) = mpsc::channel();
+            let host = Arc::new(Self {
+                failing,
+                gate: Mutex::new(()),
+                started: Mutex::new(started),
+                processed: Mutex::new(Vec::new()),
+                failures: Mutex::new(Vec::new()),
+                last_state: Mutex::new(None),
+            });
+            (host, started_rx)
+        }
+    }
+
+    impl QueueHost for TestHost {
+        fn capture_focus(&self) -> Option<FocusedWindow> {
+            None
+        }
+
+        fn process(
+            &self,
+            id: JobId,
+            _binding_id: &str,
+            _focus: Option<FocusedWindow>,
+            _samples: Vec<f32>,
+        ) -> anyhow::Result<()> {
+            let _ = self.started.lock().send(id);
+            let _gate = self.gate.lock();
+            self.processed.lock().push(id);
+            if self.failing.contains(&id) {
+                anyhow::bail!("model crashed");
+            }
+            Ok(())
+        }
+
+        fn report_failure(&self, failure: &JobFailure) {
+            self.failures.lock().push(failure.clone());
+        }
+
+        fn emit_state(&self, state: &QueueState) {
+            *self.last_state.lock() = Some(state.clone());
+        }
+
+        fn refresh_ui(&self, state: &QueueState) {
+            self.emit_state(state);
+        }
+    }
+
+    fn statuses(queue: &TranscriptionQueue) -> Vec<(JobId, JobStatus)> {
+        queue
+            .get_state()
+            .jobs
+            .into_iter()
+            .map(|job| (job.id, job.status))
+            .collect()
+    }
+
+    #[test]
+    fn test_jobs_run_in_recording_order() {
+        let (host, started) = TestHost::new(vec![]);
+        let queue = TranscriptionQueue::with_host(host.clone()).unwrap();
+        let gate = host.gate.lock();
+
+        let first = queue.begin_recording("transcribe");
+        queue.submit(first, vec![0.0; 16]);
+        assert_eq!(started.recv().unwrap(), first);
+
+        let second = queue.begin_recording("transcribe");
+        queue.submit(second, vec![0.0; 16]);
+        let third = queue.begin_recording("notes");
+        assert_eq!(queue.recording_job(), Some(third));
+        assert_eq!(
+            statuses(&queue),
+            vec![
+                (first, JobStatus::Transcribing),
+                (second, JobStatus::Queued),
+                (third, JobStatus::Recording),
+            ]
+        );
+        assert_eq!(queue.pending_count(), 2);
+
+        queue.submit(third, vec![0.0; 16]);
+        drop(gate);
+        drop(queue);
+        assert_eq!(*host.processed.lock(), vec![first, second, third]);
+    }
+
+    #[test]
+    fn test_cancelled_jobs_are_not_transcribed() {
+        let (host, started) = TestHost::new(vec![]);
+        let queue = TranscriptionQueue::with_host(host.clone()).unwrap();
+        let gate = host.gate.lock();
+
+        let first = queue.begin_recording("transcribe");
+        queue.submit(first, vec![0.0; 16]);
+        assert_eq!(started.recv().unwrap(), first);
+
+        // Discarded while waiting behind the first job
+        let queued = queue.begin_recording("transcribe");
+        queue.submit(queued, vec![0.0; 16]);
+        queue.discard(queued);
+
+        // Cancelling a recording leaves the jobs already recorded alone
+        let last = queue.begin_recording("transcribe");
+        queue.submit(last, vec![0.0; 16]);
+        let cancelled = queue.begin_recording("transcribe");
+        queue.cancel_recording();
+        assert_eq!(queue.recording_job(), None);
+        assert_eq!(
+            statuses(&queue),
+            vec![(first, JobStatus::Transcribing), (last, JobStatus::Queued)]
+        );
+
+        drop(gate);
+        drop(queue);
+        assert_eq!(*host.processed.lock(), vec![first, last]);
+        assert!(!host.processed.lock().contains(&cancelled));
+    }
+
+    #[test]
+    fn test_worker_carries_on_after_a_failed_job() {
+        let (host, started) = TestHost::new(vec![1]);
+        let queue = TranscriptionQueue::with_host(host.clone()).unwrap();
+        let gate = host.gate.lock();
+
+        let failing = queue.begin_recording("transcribe");
+        assert_eq!(failing, 1);
+        queue.submit(failing, vec![0.0; 16]);
+        assert_eq!(started.recv().unwrap(), failing);
+        let next = queue.begin_recording("transcribe");
+        queue.submit(next, vec![0.0; 16]);
+
+        drop(gate);
+        assert_eq!(started.recv().unwrap(), next);
+        drop(queue);
+        assert_eq!(*host.processed.lock(), vec![failing, next]);
+
+        // Only the failed job was reported, with its id and the reason
+        let failures = host.failures.lock();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].job, failing);
+        assert_eq!(failures[0].binding_id, "transcribe");
+        assert_eq!(failures[0].error, "model crashed");
+
+        // Both jobs left the queue, and the UI was told it's empty
+        let state = host.last_state.lock().clone().unwrap();
+        assert!(state.jobs.is_empty());
+        assert_eq!(state.pending, 0);
+        assert!(!state.is_recording);
+    }
+}
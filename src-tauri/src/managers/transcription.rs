@@ -1,15 +1,18 @@
 use crate::audio_toolkit::{apply_custom_words, normalize_measurements, normalize_times, normalize_years};
-use crate::managers::model::{EngineType, ModelManager};
-use crate::settings::{get_settings, ModelUnloadTimeout};
+use crate::host::Host;
+use crate::managers::engine_options::{self, ParakeetOptions};
+use crate::managers::model::{EngineType, ModelInfo, ModelManager};
+use crate::managers::model_policy::{self, ModelStateReason, PolicyAction, PolicyInput};
+use crate::settings::{AppSettings, ModelUnloadTimeout};
 use anyhow::Result;
 use log::{debug, error, info, warn};
 use parking_lot::{Condvar, Mutex, RwLock};
 use serde::Serialize;
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::thread;
 use std::time::{Duration, SystemTime};
-use tauri::{AppHandle, Emitter};
 use transcribe_rs::{
     engines::{
         parakeet::{
@@ -26,32 +29,109 @@ pub struct ModelStateEvent {
     pub model_id: Option<String>,
     pub model_name: Option<String>,
     pub error: Option<String>,
+    pub reason: Option<ModelStateReason>,
 }
 
-enum LoadedEngine {
+pub(crate) enum LoadedEngine {
     Whisper(WhisperEngine),
     Parakeet(ParakeetEngine),
 }
 
+/// Opens a model with the engine settings used for dictation.
+pub(crate) fn load_engine(
+    model_info: &ModelInfo,
+    model_path: &Path,
+    parakeet_options: &ParakeetOptions,
+) -> Result<LoadedEngine> {
+    match model_info.engine_type {
+        EngineType::Whisper => {
+            let mut engine = WhisperEngine::new();
+            engine.load_model(model_path).map_err(|e| {
+                anyhow::anyhow!("Failed to load whisper model {}: {}", model_info.id, e)
+            })?;
+            Ok(LoadedEngine::Whisper(engine))
+        }
+        EngineType::Parakeet => {
+            // Fail loudly rather than let the engine fall back to other weights
+            engine_options::validate(model_path, parakeet_options).map_err(|e| {
+                anyhow::anyhow!("Failed to load parakeet model {}: {}", model_info.id, e)
+            })?;
+
+            let mut engine = ParakeetEngine::new();
+            let params = engine_options::to_model_params(parakeet_options);
+            debug!(
+                "Loading parakeet model {} with {:?}",
+                model_info.id, parakeet_options
+            );
+
+            engine
+                .load_model_with_params(model_path, params)
+                .map_err(|e| {
+                    anyhow::anyhow!("Failed to load parakeet model {}: {}", model_info.id, e)
+                })?;
+            Ok(LoadedEngine::Parakeet(engine))
+        }
+    }
+}
+
+/// A finished transcription with segment timings, for subtitle output.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Transcript {
+    pub text: String,
+    pub segments: Vec<TranscriptSegment>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TranscriptSegment {
+    /// Seconds from the start of the audio
+    pub start: f32,
+    pub end: f32,
+    pub text: String,
+}
+
+/// Normalises years, measurements and times, then applies custom word
+/// correction. Runs on raw engine output before medical post-processing.
+pub fn finalize_text(text: &str, settings: &AppSettings) -> String {
+    let year_normalized = normalize_years(text);
+    let measurement_normalized = normalize_measurements(&year_normalized);
+    let time_normalized = normalize_times(&measurement_normalized);
+
+    // Then apply word correction if custom words are configured
+    let corrected_result = if !settings.custom_words.is_empty() {
+        apply_custom_words(
+            &time_normalized,
+            &settings.custom_words,
+            settings.word_correction_threshold,
+        )
+    } else {
+        time_normalized
+    };
+
+    corrected_result.trim().to_string()
+}
+
 #[derive(Clone)]
 pub struct TranscriptionManager {
     engine: Arc<RwLock<Option<LoadedEngine>>>,
     model_manager: Arc<ModelManager>,
-    app_handle: AppHandle,
+    host: Arc<dyn Host>,
     current_model_id: Arc<RwLock<Option<String>>>,
     last_activity: Arc<AtomicU64>,
     shutdown_signal: Arc<AtomicBool>,
     watcher_handle: Arc<Mutex<Option<thread::JoinHandle<()>>>>,
     is_loading: Arc<Mutex<bool>>,
     loading_condvar: Arc<Condvar>,
+    /// Set when the user unloads the model by hand, so clinic hours don't
+    /// immediately load it again
+    manually_unloaded: Arc<AtomicBool>,
 }
 
 impl TranscriptionManager {
-    pub fn new(app_handle: &AppHandle, model_manager: Arc<ModelManager>) -> Result<Self> {
+    pub fn new(host: Arc<dyn Host>, model_manager: Arc<ModelManager>) -> Result<Self> {
         let manager = Self {
             engine: Arc::new(RwLock::new(None)),
             model_manager,
-            app_handle: app_handle.clone(),
+            host,
             current_model_id: Arc::new(RwLock::new(None)),
             last_activity: Arc::new(AtomicU64::new(
                 SystemTime::now()
@@ -63,11 +143,11 @@ impl TranscriptionManager {
             watcher_handle: Arc::new(Mutex::new(None)),
             is_loading: Arc::new(Mutex::new(false)),
             loading_condvar: Arc::new(Condvar::new()),
+            manually_unloaded: Arc::new(AtomicBool::new(false)),
         };
 
-        // Start the idle watcher
+        // Start the model watcher: idle timeout, memory pressure and clinic hours
         {
-            let app_handle_cloned = app_handle.clone();
             let manager_cloned = manager.clone();
             let shutdown_signal = manager.shutdown_signal.clone();
             let handle = thread::spawn(move || {
@@ -79,48 +159,9 @@ impl TranscriptionManager {
                         break;
                     }
 
-                    let settings = get_settings(&app_handle_cloned);
-                    let timeout_seconds = settings.model_unload_timeout.to_seconds();
-
-                    if let Some(limit_seconds) = timeout_seconds {
-                        // Skip polling-based unloading for immediate timeout since it's handled directly in transcribe()
-                        if settings.model_unload_timeout == ModelUnloadTimeout::Immediately {
-                            continue;
-                        }
-
-                        let last = manager_cloned.last_activity.load(Ordering::Relaxed);
-                        let now_ms = SystemTime::now()
-                            .duration_since(SystemTime::UNIX_EPOCH)
-                            .map(|d| d.as_millis() as u64)
-                            .unwrap_or(0);
-
-                        if now_ms.saturating_sub(last) > limit_seconds * 1000 {
-                            // idle -> unload
-                            if manager_cloned.is_model_loaded() {
-                                let unload_start = std::time::Instant::now();
-                                debug!("Starting to unload model due to inactivity");
-
-                                if let Ok(()) = manager_cloned.unload_model() {
-                                    let _ = app_handle_cloned.emit(
-                                        "model-state-changed",
-                                        ModelStateEvent {
-                                            event_type: "unloaded".to_string(),
-                                            model_id: None,
-                                            model_name: None,
-                                            error: None,
-                                        },
-                                    );
-                                    let unload_duration = unload_start.elapsed();
-                                    debug!(
-                                        "Model unloaded due to inactivity (took {}ms)",
-                                        unload_duration.as_millis()
-                                    );
-                                }
-                            }
-                        }
-                    }
+                    manager_cloned.apply_model_policy();
                 }
-                debug!("Idle watcher thread shutting down gracefully");
+                debug!("Model watcher thread shutting down gracefully");
             });
             *manager.watcher_handle.lock() = Some(handle);
         }
@@ -133,9 +174,15 @@ impl TranscriptionManager {
         engine.is_some()
     }
 
+    /// Unloads the model at the user's request.
     pub fn unload_model(&self) -> Result<()> {
+        self.manually_unloaded.store(true, Ordering::Relaxed);
+        self.unload_model_with_reason(ModelStateReason::UserRequest)
+    }
+
+    pub fn unload_model_with_reason(&self, reason: ModelStateReason) -> Result<()> {
         let unload_start = std::time::Instant::now();
-        debug!("Starting to unload model");
+        debug!("Starting to unload model ({:?})", reason);
 
         {
             let mut engine = self.engine.write();
@@ -147,42 +194,51 @@ impl TranscriptionManager {
             }
             *engine = None; // Drop the engine to free memory
         }
-        {
+        let unloaded_model = {
             let mut current_model = self.current_model_id.write();
-            *current_model = None;
-        }
+            current_model.take()
+        };
 
         // Emit unloaded event
-        let _ = self.app_handle.emit(
+        self.host.emit(
             "model-state-changed",
             ModelStateEvent {
                 event_type: "unloaded".to_string(),
-                model_id: None,
+                model_id: unloaded_model,
                 model_name: None,
                 error: None,
+                reason: Some(reason),
             },
         );
 
         let unload_duration = unload_start.elapsed();
         debug!(
-            "Model unloaded manually (took {}ms)",
+            "Model unloaded ({:?}, took {}ms)",
+            reason,
             unload_duration.as_millis()
         );
         Ok(())
     }
 
+    /// Loads a model at the user's request.
     pub fn load_model(&self, model_id: &str) -> Result<()> {
+        self.load_model_with_reason(model_id, ModelStateReason::UserRequest)
+    }
+
+    pub fn load_model_with_reason(&self, model_id: &str, reason: ModelStateReason) -> Result<()> {
         let load_start = std::time::Instant::now();
-        debug!("Starting to load model: {}", model_id);
+        debug!("Starting to load model: {} ({:?})", model_id, reason);
+        self.manually_unloaded.store(false, Ordering::Relaxed);
 
         // Emit loading started event
-        let _ = self.app_handle.emit(
+        self.host.emit(
             "model-state-changed",
             ModelStateEvent {
                 event_type: "loading_started".to_string(),
                 model_id: Some(model_id.to_string()),
                 model_name: None,
                 error: None,
+                reason: Some(reason),
             },
         );
 
@@ -193,13 +249,14 @@ impl TranscriptionManager {
 
         if !model_info.is_downloaded {
             let error_msg = "Model not downloaded";
-            let _ = self.app_handle.emit(
+            self.host.emit(
                 "model-state-changed",
                 ModelStateEvent {
                     event_type: "loading_failed".to_string(),
                     model_id: Some(model_id.to_string()),
                     model_name: Some(model_info.name.clone()),
                     error: Some(error_msg.to_string()),
+                    reason: Some(reason),
                 },
             );
             return Err(anyhow::anyhow!(error_msg));
@@ -207,55 +264,22 @@ impl TranscriptionManager {
 
         let model_path = self.model_manager.get_model_path(model_id)?;
 
-        // Create appropriate engine based on model type
-        let loaded_engine = match model_info.engine_type {
-            EngineType::Whisper => {
-                let mut engine = WhisperEngine::new();
-                engine.load_model(&model_path).map_err(|e| {
-                    let error_msg = format!("Failed to load whisper model {}: {}", model_id, e);
-                    let _ = self.app_handle.emit(
-                        "model-state-changed",
-                        ModelStateEvent {
-                            event_type: "loading_failed".to_string(),
-                            model_id: Some(model_id.to_string()),
-                            model_name: Some(model_info.name.clone()),
-                            error: Some(error_msg.clone()),
-                        },
-                    );
-                    anyhow::anyhow!(error_msg)
-                })?;
-                LoadedEngine::Whisper(engine)
-            }
-            EngineType::Parakeet => {
-                let mut engine = ParakeetEngine::new();
-                // Use Int8 quantization for optimal performance
-                // Int8 provides ~2x faster inference with minimal accuracy trade-off
-                // Configure threading for CPU inference (use all available cores)
-                let num_threads = std::thread::available_parallelism()
-                    .map(|n| n.get())
-                    .unwrap_or(4); // Fallback to 4 threads if detection fails
-
-                let params = ParakeetModelParams::int8().with_threads(Some(num_threads));
-
-                engine
-                    .load_model_with_params(&model_path, params)
-                    .map_err(|e| {
-                        let error_msg =
-                            format!("Failed to load parakeet model {}: {}", model_id, e);
-                        let _ = self.app_handle.emit(
-                            "model-state-changed",
-                            ModelStateEvent {
-                                event_type: "loading_failed".to_string(),
-                                model_id: Some(model_id.to_string()),
-                                model_name: Some(model_info.name.clone()),
-                                error: Some(error_msg.clone()),
-                            },
-                        );
-                        anyhow::anyhow!(error_msg)
-                    })?;
-                LoadedEngine::Parakeet(engine)
-            }
-        };
+        let parakeet_options =
+            engine_options::options_for(&self.host.settings(), model_id, &model_path);
+        let loaded_engine =
+            load_engine(&model_info, &model_path, &parakeet_options).map_err(|e| {
+                self.host.emit(
+                    "model-state-changed",
+                    ModelStateEvent {
+                        event_type: "loading_failed".to_string(),
+                        model_id: Some(model_id.to_string()),
+                        model_name: Some(model_info.name.clone()),
+                        error: Some(e.to_string()),
+                        reason: Some(reason),
+                    },
+                );
+                e
+            })?;
 
         // Update the current engine and model ID
         {
@@ -268,13 +292,14 @@ impl TranscriptionManager {
         }
 
         // Emit loading completed event
-        let _ = self.app_handle.emit(
+        self.host.emit(
             "model-state-changed",
             ModelStateEvent {
                 event_type: "loading_completed".to_string(),
                 model_id: Some(model_id.to_string()),
                 model_name: Some(model_info.name.clone()),
                 error: None,
+                reason: Some(reason),
             },
         );
 
@@ -287,8 +312,40 @@ impl TranscriptionManager {
         Ok(())
     }
 
+    /// Loads the current model again so changed engine options take effect.
+    /// The running engine is only replaced once the new one has loaded, so a
+    /// failed reload leaves it in use. Does nothing when no model is loaded.
+    pub fn reload_model(&self) -> Result<()> {
+        let Some(model_id) = self.get_current_model() else {
+            return Ok(());
+        };
+        let result = self.load_model_with_reason(&model_id, ModelStateReason::UserRequest);
+
+        // The failure was announced, but the previous engine is still loaded
+        if result.is_err() && self.is_model_loaded() {
+            self.host.emit(
+                "model-state-changed",
+                ModelStateEvent {
+                    event_type: "loading_completed".to_string(),
+                    model_name: self
+                        .model_manager
+                        .get_model_info(&model_id)
+                        .map(|info| info.name),
+                    model_id: Some(model_id),
+                    error: None,
+                    reason: Some(ModelStateReason::UserRequest),
+                },
+            );
+        }
+        result
+    }
+
     /// Kicks off the model loading in a background thread if it's not already loaded
     pub fn initiate_model_load(&self) {
+        self.initiate_model_load_with_reason(ModelStateReason::OnDemand);
+    }
+
+    pub fn initiate_model_load_with_reason(&self, reason: ModelStateReason) {
         let mut is_loading = self.is_loading.lock();
         if *is_loading || self.is_model_loaded() {
             return;
@@ -302,8 +359,9 @@ impl TranscriptionManager {
         match thread::Builder::new()
             .name("model-loader".to_string())
             .spawn(move || {
-                let settings = get_settings(&self_clone.app_handle);
-                if let Err(e) = self_clone.load_model(&settings.selected_model) {
+                let settings = self_clone.host.settings();
+                if let Err(e) = self_clone.load_model_with_reason(&settings.selected_model, reason)
+                {
                     error!("Failed to load model: {}", e);
                 }
                 let mut is_loading = self_clone.is_loading.lock();
@@ -322,12 +380,78 @@ impl TranscriptionManager {
         }
     }
 
+    /// One tick of the model watcher: unloads on inactivity or memory pressure
+    /// and keeps the model loaded during clinic hours.
+    fn apply_model_policy(&self) {
+        if *self.is_loading.lock() {
+            return;
+        }
+
+        let settings = self.host.settings();
+
+        // Immediate unloading is handled directly in transcribe()
+        let idle_timeout_secs = match settings.model_unload_timeout {
+            ModelUnloadTimeout::Immediately => None,
+            timeout => timeout.to_seconds(),
+        };
+
+        let now_ms = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        let last = self.last_activity.load(Ordering::Relaxed);
+
+        let input = PolicyInput {
+            loaded: self.is_model_loaded(),
+            idle_secs: now_ms.saturating_sub(last) / 1000,
+            idle_timeout_secs,
+            in_clinic_hours: model_policy::in_clinic_hours(settings.clinic_hours.as_ref()),
+            available_memory_mb: model_policy::available_memory_mb(),
+            low_memory_threshold_mb: settings.low_memory_unload_threshold_mb,
+            model_size_mb: self
+                .model_manager
+                .get_model_info(&settings.selected_model)
+                .map(|m| m.size_mb)
+                .unwrap_or(0),
+        };
+
+        match model_policy::evaluate(&input) {
+            Some(PolicyAction::Unload(reason)) => {
+                if reason == ModelStateReason::LowMemory {
+                    warn!(
+                        "Available memory ({:?} MB) below {} MB, unloading model",
+                        input.available_memory_mb, input.low_memory_threshold_mb
+                    );
+                }
+                if let Err(e) = self.unload_model_with_reason(reason) {
+                    error!("Failed to unload model: {}", e);
+                }
+            }
+            Some(PolicyAction::Load(reason)) => {
+                if settings.selected_model.is_empty()
+                    || self.manually_unloaded.load(Ordering::Relaxed)
+                {
+                    return;
+                }
+                info!("Loading model for {:?}", reason);
+                self.initiate_model_load_with_reason(reason);
+            }
+            None => {}
+        }
+    }
+
     pub fn get_current_model(&self) -> Option<String> {
         let current_model = self.current_model_id.read();
         current_model.clone()
     }
 
     pub fn transcribe(&self, audio: Vec<f32>) -> Result<String> {
+        Ok(self.transcribe_detailed(audio)?.text)
+    }
+
+    /// Transcribes `audio` and keeps the engine's segment timings, each
+    /// segment normalised the same way as the full text.
+    pub fn transcribe_detailed(&self, audio: Vec<f32>) -> Result<Transcript> {
         // Update last activity timestamp
         self.last_activity.store(
             SystemTime::now()
@@ -343,7 +467,7 @@ impl TranscriptionManager {
 
         if audio.is_empty() {
             debug!("Empty audio vector");
-            return Ok(String::new());
+            return Ok(Transcript::default());
         }
 
         // Check if model is loaded, if not try to load it
@@ -361,7 +485,7 @@ impl TranscriptionManager {
         }
 
         // Get current settings for configuration
-        let settings = get_settings(&self.app_handle);
+        let settings = self.host.settings();
 
         // Perform transcription with the appropriate engine
         // NOTE: We must hold the write lock for the entire inference duration because:
@@ -419,21 +543,18 @@ impl TranscriptionManager {
             // Lock is released here after inference completes
         };
 
-        // Apply normalizations in sequence
-        let year_normalized = normalize_years(&result.text);
-        let measurement_normalized = normalize_measurements(&year_normalized);
-        let time_normalized = normalize_times(&measurement_normalized);
-
-        // Then apply word correction if custom words are configured
-        let corrected_result = if !settings.custom_words.is_empty() {
-            apply_custom_words(
-                &time_normalized,
-                &settings.custom_words,
-                settings.word_correction_threshold,
-            )
-        } else {
-            time_normalized
-        };
+        let final_result = finalize_text(&result.text, &settings);
+        let segments = result
+            .segments
+            .unwrap_or_default()
+            .into_iter()
+            .map(|segment| TranscriptSegment {
+                start: segment.start,
+                end: segment.end,
+                text: finalize_text(&segment.text, &settings),
+            })
+            .filter(|segment| !segment.text.is_empty())
+            .collect();
 
         let et = std::time::Instant::now();
         let translation_note = if settings.translate_to_english {
@@ -447,23 +568,27 @@ impl TranscriptionManager {
             translation_note
         );
 
-        let final_result = corrected_result.trim().to_string();
-
         if final_result.is_empty() {
             info!("Transcription result is empty");
         } else {
             info!("Transcription result: {}", final_result);
         }
 
-        // Check if we should immediately unload the model after transcription
-        if settings.model_unload_timeout == ModelUnloadTimeout::Immediately {
+        // Check if we should immediately unload the model after transcription;
+        // during clinic hours it stays resident
+        if settings.model_unload_timeout == ModelUnloadTimeout::Immediately
+            && !model_policy::in_clinic_hours(settings.clinic_hours.as_ref())
+        {
             info!("Immediately unloading model after transcription");
-            if let Err(e) = self.unload_model() {
+            if let Err(e) = self.unload_model_with_reason(ModelStateReason::AfterTranscription) {
                 error!("Failed to immediately unload model: {}", e);
             }
         }
 
-        Ok(final_result)
+        Ok(Transcript {
+            text: final_result,
+            segments,
+        })
     }
 }
 
@@ -70,7 +70,7 @@ impl MedicalVocabulary {
         vocab
     }
 
-    fn get_default_custom_vocab_path() -> Option<PathBuf> {
+    pub fn get_default_custom_vocab_path() -> Option<PathBuf> {
         #[cfg(target_os = "macos")]
         {
             let home = std::env::var("HOME").ok()?;
@@ -660,6 +660,65 @@ esophagus -> oesophagus
     }
 }
 
+/// A problem found in a custom vocabulary file, with its 1-based line number.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VocabIssue {
+    pub line: usize,
+    pub message: String,
+}
+
+/// Lints the text of a custom vocabulary file. Lines the loader would
+/// silently skip or shadow are reported instead.
+pub fn check_custom_vocab_txt(contents: &str) -> Vec<VocabIssue> {
+    let mut issues = Vec::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+
+    for (index, line) in contents.lines().enumerate() {
+        let line_no = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let key = if line.contains("->") {
+            let parts: Vec<&str> = line.split("->").collect();
+            if parts.len() != 2 {
+                issues.push(VocabIssue {
+                    line: line_no,
+                    message: "more than one '->' on a line; the line is ignored".to_string(),
+                });
+                continue;
+            }
+            let (wrong, correct) = (parts[0].trim(), parts[1].trim());
+            if wrong.is_empty() || correct.is_empty() {
+                issues.push(VocabIssue {
+                    line: line_no,
+                    message: "correction is missing a side of '->'".to_string(),
+                });
+                continue;
+            }
+            if wrong.eq_ignore_ascii_case(correct) {
+                issues.push(VocabIssue {
+                    line: line_no,
+                    message: format!("'{}' corrects to itself", wrong),
+                });
+            }
+            wrong.to_lowercase()
+        } else {
+            line.to_lowercase()
+        };
+
+        if let Some(first) = seen.insert(key, line_no) {
+            issues.push(VocabIssue {
+                line: line_no,
+                message: format!("duplicate of line {}", first),
+            });
+        }
+    }
+
+    issues
+}
+
 impl Default for MedicalVocabulary {
     fn default() -> Self {
         Self::new()
@@ -694,6 +753,26 @@ mod tests {
         assert!(result.contains("lisinopril"));
     }
 
+    #[test]
+    fn test_check_custom_vocab() {
+        let contents = "# comment\n\
+                        bronchoscopy\n\
+                        sugar diabetes -> diabetes mellitus\n\
+                        a -> b -> c\n\
+                        -> hypertension\n\
+                        Bronchoscopy\n\
+                        colour -> colour\n";
+        let issues = check_custom_vocab_txt(contents);
+        let lines: Vec<usize> = issues.iter().map(|i| i.line).collect();
+        assert_eq!(lines, vec![4, 5, 6, 7]);
+        assert!(issues[2].message.contains("line 2"));
+
+        assert!(
+            check_custom_vocab_txt("bronchoscopy\nheart attack -> myocardial infarction\n")
+                .is_empty()
+        );
+    }
+
     #[test]
     fn test_number_formatting() {
         let mut vocab = MedicalVocabulary::new();
@@ -0,0 +1,601 @@
+//! Where finished transcripts go. Each binding delivers to one or more
+//! sinks in turn, pasting into the focused application unless configured
+//! otherwise; one failing sink doesn't stop the others.
+
+use crate::helpers::focus::{self, FocusedWindow};
+use crate::managers::queue::JobId;
+use crate::settings::{AppSettings, OutputSinkConfig};
+use crate::utils;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, FixedOffset, Local};
+use log::{debug, error, info, warn};
+use serde::Serialize;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+/// Longest a webhook or socket gets before we give up on it
+const SINK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A finished transcript on its way out.
+pub struct Delivery<'a> {
+    /// Queue job it came from; 0 for audio sent through the control API
+    pub job: JobId,
+    pub binding_id: &'a str,
+    pub text: &'a str,
+    pub time: DateTime<FixedOffset>,
+}
+
+impl Delivery<'_> {
+    /// What the pipe and webhook sinks send.
+    fn payload(&self) -> serde_json::Value {
+        serde_json::json!({
+            "job": self.job,
+            "binding": self.binding_id,
+            "text": self.text,
+            "timestamp": self.time.to_rfc3339(),
+        })
+    }
+}
+
+pub trait OutputSink {
+    /// Short name for logs and error events.
+    fn name(&self) -> &'static str;
+    fn deliver(&self, delivery: &Delivery) -> Result<(), String>;
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct OutputFailure {
+    job: JobId,
+    sink: &'static str,
+    error: String,
+}
+
+/// Sends a transcript to every sink configured for its binding, in order.
+pub fn deliver(
+    app: &AppHandle,
+    settings: &AppSettings,
+    job: JobId,
+    binding_id: &str,
+    text: &str,
+    focus: Option<FocusedWindow>,
+) {
+    let delivery = Delivery {
+        job,
+        binding_id,
+        text,
+        time: Local::now().fixed_offset(),
+    };
+
+    for config in sinks_for(settings, binding_id) {
+        let sink = build(app, config, focus.clone());
+        match sink.deliver(&delivery) {
+            Ok(()) => debug!("Job {} delivered to {}", job, sink.name()),
+            Err(e) => {
+                error!("Failed to deliver job {} to {}: {}", job, sink.name(), e);
+                let failure = OutputFailure {
+                    job,
+                    sink: sink.name(),
+                    error: e,
+                };
+                let _ = app.emit("output-failed", failure);
+            }
+        }
+    }
+    info!("Transcription job {} delivered", job);
+}
+
+fn sinks_for<'a>(settings: &'a AppSettings, binding_id: &str) -> &'a [OutputSinkConfig] {
+    match settings.binding_outputs.get(binding_id) {
+        Some(outputs) if !outputs.is_empty() => outputs,
+        _ => &[OutputSinkConfig::Paste],
+    }
+}
+
+fn build(
+    app: &AppHandle,
+    config: &OutputSinkConfig,
+    focus: Option<FocusedWindow>,
+) -> Box<dyn OutputSink> {
+    match config {
+        OutputSinkConfig::Paste => Box::new(PasteSink {
+            app: app.clone(),
+            focus,
+        }),
+        OutputSinkConfig::File { path, template } => Box::new(FileSink {
+            path: path.clone(),
+            template: template.clone(),
+        }),
+        OutputSinkConfig::Pipe { path } => Box::new(PipeSink { path: path.clone() }),
+        OutputSinkConfig::Stdout => Box::new(StdoutSink),
+        OutputSinkConfig::Webhook { url } => Box::new(WebhookSink { url: url.clone() }),
+    }
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+/// Fails if `template` has a strftime field chrono doesn't know, which
+/// would otherwise only show up when formatting.
+pub fn check_template(template: &str) -> Result<(), String> {
+    if StrftimeItems::new(template).any(|item| matches!(item, Item::Error)) {
+        return Err(format!("Invalid date field in template: {}", template));
+    }
+    Ok(())
+}
+
+/// Fills in the strftime fields, then `{binding}` and `{text}`, so neither
+/// the binding nor the transcript are read as a template.
+fn expand(template: &str, delivery: &Delivery) -> Result<String, String> {
+    check_template(template)?;
+    Ok(delivery
+        .time
+        .format(template)
+        .to_string()
+        .replace("{binding}", delivery.binding_id)
+        .replace("{text}", delivery.text))
+}
+
+/// Only plain HTTP(S) to this machine, so transcripts can't leave it.
+pub fn check_local_url(url: &str) -> Result<(), String> {
+    local_addrs(url).map(|_| ())
+}
+
+/// Parses `url` and finds the addresses it points at, every one of which
+/// must be loopback. Host names are resolved rather than trusted, so
+/// `localhost` only passes if it really is this machine.
+fn local_addrs(url: &str) -> Result<(reqwest::Url, Vec<SocketAddr>), String> {
+    let parsed = reqwest::Url::parse(url).map_err(|e| format!("Invalid URL {}: {}", url, e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!("Webhook must use http or https: {}", url));
+    }
+
+    let not_local = || format!("Webhook must point at this machine: {}", url);
+    let host = parsed.host_str().ok_or_else(not_local)?;
+    let port = parsed.port_or_known_default().unwrap_or(80);
+    let addrs: Vec<SocketAddr> = match host
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse::<IpAddr>()
+    {
+        Ok(ip) => vec![SocketAddr::new(ip, port)],
+        Err(_) => (host, port)
+            .to_socket_addrs()
+            .map_err(|e| format!("Couldn't resolve {}: {}", host, e))?
+            .collect(),
+    };
+    if addrs.is_empty() || !addrs.iter().all(|addr| addr.ip().is_loopback()) {
+        return Err(not_local());
+    }
+    Ok((parsed, addrs))
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+/// Types or pastes into the focused application, on the main thread, and
+/// waits for it to finish so the next job can't interleave its keystrokes
+/// with this one.
+struct PasteSink {
+    app: AppHandle,
+    focus: Option<FocusedWindow>,
+}
+
+impl OutputSink for PasteSink {
+    fn name(&self) -> &'static str {
+        "paste"
+    }
+
+    fn deliver(&self, delivery: &Delivery) -> Result<(), String> {
+        let (done_tx, done_rx) = mpsc::channel();
+        let ah = self.app.clone();
+        let focus = self.focus.clone();
+        let text = delivery.text.to_string();
+        let paste_time = Instant::now();
+
+        self.app
+            .run_on_main_thread(move || {
+                if let Some(target) = focus.as_ref() {
+                    if !focus::is_focused(target) {
+                        if let Err(e) = focus::restore_focus(target) {
+                            warn!("Could not return focus to original window: {}", e);
+                        }
+                    }
+                }
+                let _ = done_tx.send(utils::paste(text, ah));
+            })
+            .map_err(|e| format!("Failed to run paste on main thread: {:?}", e))?;
+
+        done_rx
+            .recv()
+            .map_err(|_| "Paste was dropped before it ran".to_string())??;
+        debug!("Pasted in {:?}", paste_time.elapsed());
+        Ok(())
+    }
+}
+
+/// Appends to a file, created readable by the user only.
+struct FileSink {
+    path: String,
+    template: String,
+}
+
+impl OutputSink for FileSink {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+
+    fn deliver(&self, delivery: &Delivery) -> Result<(), String> {
+        let path = expand(
+            &self.path,
+            &Delivery {
+                text: "",
+                ..*delivery
+            },
+        )?;
+        let entry = expand(&self.template, delivery)?;
+
+        if let Some(parent) = Path::new(&path).parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+
+        let mut options = OpenOptions::new();
+        options.create(true).append(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+        options
+            .open(&path)
+            .and_then(|mut file| file.write_all(entry.as_bytes()))
+            .map_err(|e| format!("Failed to append to {}: {}", path, e))
+    }
+}
+
+/// Writes one JSON line to a named pipe or Unix domain socket. A pipe
+/// nobody is reading is an error rather than something to wait on, but
+/// once it's open a slow reader is waited for like any other.
+struct PipeSink {
+    path: String,
+}
+
+impl PipeSink {
+    fn line(delivery: &Delivery) -> Vec<u8> {
+        let mut line = delivery.payload().to_string().into_bytes();
+        line.push(b'\n');
+        line
+    }
+}
+
+#[cfg(unix)]
+impl OutputSink for PipeSink {
+    fn name(&self) -> &'static str {
+        "pipe"
+    }
+
+    fn deliver(&self, delivery: &Delivery) -> Result<(), String> {
+        use std::os::unix::fs::{FileTypeExt, OpenOptionsExt};
+        use std::os::unix::net::UnixStream;
+
+        let file_type = fs::metadata(&self.path)
+            .map_err(|e| format!("Failed to open {}: {}", self.path, e))?
+            .file_type();
+        let line = Self::line(delivery);
+
+        let result = if file_type.is_socket() {
+            UnixStream::connect(&self.path).and_then(|mut stream| {
+                stream.set_write_timeout(Some(SINK_TIMEOUT))?;
+                stream.write_all(&line)
+            })
+        } else if file_type.is_fifo() {
+            // Non-blocking only so the open fails when there's no reader;
+            // a long line can fill the pipe, and then we wait for it to drain
+            OpenOptions::new()
+                .write(true)
+                .custom_flags(libc::O_NONBLOCK)
+                .open(&self.path)
+                .and_then(|mut fifo| {
+                    set_blocking(&fifo)?;
+                    fifo.write_all(&line)
+                })
+        } else {
+            return Err(format!("{} is not a named pipe or socket", self.path));
+        };
+
+        result.map_err(|e| match e.raw_os_error() {
+            Some(libc::ENXIO) => format!("Nothing is reading from {}", self.path),
+            _ => format!("Failed to write to {}: {}", self.path, e),
+        })
+    }
+}
+
+#[cfg(unix)]
+fn set_blocking(file: &fs::File) -> std::io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    let fd = file.as_raw_fd();
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+    if flags < 0 || unsafe { libc::fcntl(fd, libc::F_SETFL, flags & !libc::O_NONBLOCK) } < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+impl OutputSink for PipeSink {
+    fn name(&self) -> &'static str {
+        "pipe"
+    }
+
+    fn deliver(&self, delivery: &Delivery) -> Result<(), String> {
+        // Windows named pipes (\\.\pipe\...) open like files
+        OpenOptions::new()
+            .write(true)
+            .open(&self.path)
+            .and_then(|mut pipe| pipe.write_all(&Self::line(delivery)))
+            .map_err(|e| format!("Failed to write to {}: {}", self.path, e))
+    }
+}
+
+struct StdoutSink;
+
+impl OutputSink for StdoutSink {
+    fn name(&self) -> &'static str {
+        "stdout"
+    }
+
+    fn deliver(&self, delivery: &Delivery) -> Result<(), String> {
+        let mut stdout = std::io::stdout().lock();
+        writeln!(stdout, "{}", delivery.text)
+            .and_then(|()| stdout.flush())
+            .map_err(|e| format!("Failed to write to stdout: {}", e))
+    }
+}
+
+/// POSTs the transcript as JSON. Proxies are bypassed, redirects refused and
+/// host names pinned to the loopback addresses they were checked against, so
+/// the request can't be routed off the machine.
+struct WebhookSink {
+    url: String,
+}
+
+impl OutputSink for WebhookSink {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn deliver(&self, delivery: &Delivery) -> Result<(), String> {
+        let (parsed, addrs) = local_addrs(&self.url)?;
+
+        let mut builder = reqwest::blocking::Client::builder()
+            .timeout(SINK_TIMEOUT)
+            .no_proxy()
+            .redirect(reqwest::redirect::Policy::none());
+        if let Some(domain) = parsed.domain() {
+            builder = builder.resolve_to_addrs(domain, &addrs);
+        }
+        let client = builder
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        let response = client
+            .post(parsed)
+            .json(&delivery.payload())
+            .send()
+            .map_err(|e| format!("Webhook {} failed: {}", self.url, e))?;
+
+        let status = response.status();
+        if status.is_redirection() {
+            // Following it could send the transcript somewhere else
+            return Err(format!(
+                "Webhook {} redirected ({}); redirects aren't followed",
+                self.url, status
+            ));
+        }
+        if !status.is_success() {
+            return Err(format!("Webhook {} failed: {}", self.url, status));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn delivery(text: &str) -> Delivery<'_> {
+        Delivery {
+            job: 7,
+            binding_id: "transcribe",
+            text,
+            time: FixedOffset::east_opt(0)
+                .unwrap()
+                .with_ymd_and_hms(2024, 3, 9, 14, 5, 0)
+                .unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_expand() {
+        let d = delivery("BP 120/80, 100% O2");
+        assert_eq!(
+            expand("%Y-%m-%d %H:%M {binding}: {text}\n", &d).unwrap(),
+            "2024-03-09 14:05 transcribe: BP 120/80, 100% O2\n"
+        );
+        // Placeholders in the transcript stay as they are
+        let d = delivery("say {binding} and %Y");
+        assert_eq!(expand("{text}", &d).unwrap(), "say {binding} and %Y");
+    }
+
+    #[test]
+    fn test_invalid_template() {
+        assert!(check_template("[%Y-%m-%d] {text}").is_ok());
+        assert!(check_template("%Q {text}").is_err());
+        assert!(expand("%Q", &delivery("x")).is_err());
+    }
+
+    #[test]
+    fn test_local_url() {
+        assert!(check_local_url("http://127.0.0.1:8080/notes").is_ok());
+        assert!(check_local_url("http://localhost/notes").is_ok());
+        assert!(check_local_url("https://[::1]:9000/").is_ok());
+        assert!(check_local_url("http://192.168.1.10/notes").is_err());
+        assert!(check_local_url("http://localhost.example.com/").is_err());
+        assert!(check_local_url("file:///tmp/notes").is_err());
+        assert!(check_local_url("not a url").is_err());
+    }
+
+    /// Answers one HTTP request on loopback with `response`.
+    fn serve_once(response: &'static str) -> (String, std::thread::JoinHandle<()>) {
+        use std::io::Read;
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/notes", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            stream
+                .set_read_timeout(Some(Duration::from_secs(5)))
+                .unwrap();
+            // Read the headers and body before answering
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            loop {
+                let n = stream.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request);
+                let complete = text.find("\r\n\r\n").is_some_and(|end| {
+                    let length = text[..end]
+                        .lines()
+                        .find_map(|l| {
+                            l.to_ascii_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|v| v.trim().parse::<usize>().unwrap())
+                        })
+                        .unwrap_or(0);
+                    request.len() >= end + 4 + length
+                });
+                if complete || n == 0 {
+                    break;
+                }
+            }
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn test_webhook_delivers_locally() {
+        let (url, server) = serve_once("HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n");
+        WebhookSink { url }.deliver(&delivery("hello")).unwrap();
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_webhook_refuses_redirects() {
+        let (url, server) = serve_once(
+            "HTTP/1.1 307 Temporary Redirect\r\n\
+             Location: http://example.com/collect\r\n\
+             Content-Length: 0\r\nConnection: close\r\n\r\n",
+        );
+        let err = WebhookSink { url }.deliver(&delivery("hello")).unwrap_err();
+        assert!(err.contains("redirected"), "{err}");
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_file_sink_appends() {
+        let dir = tempfile::tempdir().unwrap();
+        let sink = FileSink {
+            path: dir
+                .path()
+                .join("%Y-%m/{binding}.txt")
+                .to_string_lossy()
+                .into_owned(),
+            template: "[%H:%M] {text}\n".to_string(),
+        };
+        sink.deliver(&delivery("first")).unwrap();
+        sink.deliver(&delivery("second")).unwrap();
+
+        let written = fs::read_to_string(dir.path().join("2024-03/transcribe.txt")).unwrap();
+        assert_eq!(written, "[14:05] first\n[14:05] second\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pipe_sink_writes_json_lines_to_a_socket() {
+        use std::io::{BufRead, BufReader};
+        use std::os::unix::net::UnixListener;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("handy.sock");
+        let listener = UnixListener::bind(&path).unwrap();
+        let sink = PipeSink {
+            path: path.to_string_lossy().into_owned(),
+        };
+
+        sink.deliver(&delivery("line one\nline two")).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+        let mut line = String::new();
+        BufReader::new(stream).read_line(&mut line).unwrap();
+
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(value["text"], "line one\nline two");
+        assert_eq!(value["binding"], "transcribe");
+        assert_eq!(value["job"], 7);
+        assert_eq!(value["timestamp"], "2024-03-09T14:05:00+00:00");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pipe_sink_without_a_reader() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fifo");
+        let c_path = std::ffi::CString::new(path.to_string_lossy().as_bytes()).unwrap();
+        assert_eq!(unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) }, 0);
+
+        let sink = PipeSink {
+            path: path.to_string_lossy().into_owned(),
+        };
+        let err = sink.deliver(&delivery("x")).unwrap_err();
+        assert!(err.contains("Nothing is reading"), "{err}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pipe_sink_waits_for_a_slow_reader() {
+        use std::io::{BufRead, BufReader};
+        use std::os::unix::fs::OpenOptionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fifo");
+        let c_path = std::ffi::CString::new(path.to_string_lossy().as_bytes()).unwrap();
+        assert_eq!(unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) }, 0);
+
+        let reader = OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(&path)
+            .unwrap();
+        set_blocking(&reader).unwrap();
+        let reading = std::thread::spawn(move || {
+            // Give the writer time to fill the pipe first
+            std::thread::sleep(Duration::from_millis(100));
+            let mut line = String::new();
+            BufReader::new(reader).read_line(&mut line).unwrap();
+            line
+        });
+
+        // Several times what a pipe holds
+        let text = "word ".repeat(100_000);
+        let sink = PipeSink {
+            path: path.to_string_lossy().into_owned(),
+        };
+        sink.deliver(&delivery(&text)).unwrap();
+
+        let value: serde_json::Value = serde_json::from_str(&reading.join().unwrap()).unwrap();
+        assert_eq!(value["text"], text);
+    }
+}
@@ -0,0 +1,73 @@
+//! Local text post-processing applied to every finished transcription,
+//! shared by the desktop app and `handy-cli`.
+
+use crate::medical_vocab::MedicalVocabulary;
+use crate::settings::AppSettings;
+use ferrous_opencc::{config::BuiltinConfig, OpenCC};
+use log::{debug, error};
+
+// LLM post-processing has been removed for privacy and HIPAA compliance
+// All transcription is now processed locally only
+
+fn maybe_convert_chinese_variant(settings: &AppSettings, transcription: &str) -> Option<String> {
+    // Check if language is set to Simplified or Traditional Chinese
+    let is_simplified = settings.selected_language == "zh-Hans";
+    let is_traditional = settings.selected_language == "zh-Hant";
+
+    if !is_simplified && !is_traditional {
+        debug!("selected_language is not Simplified or Traditional Chinese; skipping translation");
+        return None;
+    }
+
+    debug!(
+        "Starting Chinese translation using OpenCC for language: {}",
+        settings.selected_language
+    );
+
+    // Use OpenCC to convert based on selected language
+    let config = if is_simplified {
+        // Convert Traditional Chinese to Simplified Chinese
+        BuiltinConfig::Tw2sp
+    } else {
+        // Convert Simplified Chinese to Traditional Chinese
+        BuiltinConfig::S2twp
+    };
+
+    match OpenCC::from_config(config) {
+        Ok(converter) => {
+            let converted = converter.convert(transcription);
+            debug!(
+                "OpenCC translation completed. Input length: {}, Output length: {}",
+                transcription.len(),
+                converted.len()
+            );
+            Some(converted)
+        }
+        Err(e) => {
+            error!("Failed to initialize OpenCC converter: {}. Falling back to original transcription.", e);
+            None
+        }
+    }
+}
+
+/// Applies local post-processing (medical vocabulary, Chinese variant conversion)
+/// to a finished transcription before it is pasted.
+pub fn post_process_transcription(settings: &AppSettings, transcription: &str) -> String {
+    let mut final_text = transcription.to_string();
+
+    // Apply medical vocabulary processing if enabled
+    if settings.medical_mode_enabled {
+        let mut medical_vocab = MedicalVocabulary::new();
+        final_text = medical_vocab.process_text(&final_text);
+    }
+
+    // Check if Chinese variant conversion is needed (local processing only)
+    if let Some(converted_text) = maybe_convert_chinese_variant(settings, transcription) {
+        final_text = converted_text;
+    }
+
+    // EPHEMERAL MODE: Transcriptions are not saved to disk
+    // Audio and text are processed in-memory only for privacy compliance
+    // This eliminates the need for data-at-rest encryption (PIPEDA Section 4.5)
+    final_text
+}
@@ -1,3 +1,5 @@
+use crate::audio_toolkit::audio::{ChannelPolicy, DspConfig};
+use crate::managers::engine_options::ParakeetOptions;
 use log::{debug, warn};
 use serde::de::{self, Visitor};
 use serde::{Deserialize, Deserializer, Serialize};
@@ -123,6 +125,22 @@ pub enum PasteMethod {
     CtrlShiftV,
 }
 
+/// Program that sends the paste keystroke, and types the text when pasting
+/// falls back to typing. The command-line tools are Linux only.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum PasteTool {
+    /// `wtype`, `dotool` or `ydotool` on Wayland, `xdotool` on X11, and the
+    /// built-in input simulation otherwise
+    #[default]
+    Auto,
+    Enigo,
+    Xdotool,
+    Ydotool,
+    Wtype,
+    Dotool,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
 #[serde(rename_all = "snake_case")]
 #[derive(Default)]
@@ -132,6 +150,29 @@ pub enum ClipboardHandling {
     CopyToClipboard,
 }
 
+/// How long text copied with `CopyToClipboard` stays on the clipboard.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+#[derive(Default)]
+pub enum ClipboardExpiry {
+    Never,
+    Sec30,
+    #[default]
+    Min2,
+    Min5,
+}
+
+impl ClipboardExpiry {
+    pub fn to_seconds(self) -> Option<u64> {
+        match self {
+            ClipboardExpiry::Never => None,
+            ClipboardExpiry::Sec30 => Some(30),
+            ClipboardExpiry::Min2 => Some(120),
+            ClipboardExpiry::Min5 => Some(300),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
 #[serde(rename_all = "snake_case")]
 pub enum RecordingRetentionPeriod {
@@ -166,6 +207,356 @@ impl ModelUnloadTimeout {
     }
 }
 
+/// Times of day when the transcription model stays loaded regardless of the
+/// unload timeout.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Type)]
+pub struct ClinicHours {
+    /// Local time, "HH:MM"
+    pub start: String,
+    /// Local time, "HH:MM"; earlier than `start` for shifts past midnight
+    pub end: String,
+    /// ISO weekdays the shift starts on, 1 = Monday ... 7 = Sunday; empty means every day
+    #[serde(default)]
+    pub weekdays: Vec<u8>,
+}
+
+fn parse_time_of_day(value: &str) -> Option<u16> {
+    let (hours, minutes) = value.trim().split_once(':')?;
+    let hours: u16 = hours.parse().ok()?;
+    let minutes: u16 = minutes.parse().ok()?;
+    (hours < 24 && minutes < 60).then_some(hours * 60 + minutes)
+}
+
+impl ClinicHours {
+    pub fn validate(&self) -> Result<(), String> {
+        if parse_time_of_day(&self.start).is_none() || parse_time_of_day(&self.end).is_none() {
+            return Err("Clinic hours must be given as HH:MM".to_string());
+        }
+        if self.start == self.end {
+            return Err("Clinic hours must not start and end at the same time".to_string());
+        }
+        if self.weekdays.iter().any(|d| !(1..=7).contains(d)) {
+            return Err("Weekdays must be between 1 (Monday) and 7 (Sunday)".to_string());
+        }
+        Ok(())
+    }
+
+    /// Whether `minute` (minutes since midnight) on `weekday` falls inside the hours.
+    pub fn contains(&self, weekday: u8, minute: u16) -> bool {
+        let (Some(start), Some(end)) =
+            (parse_time_of_day(&self.start), parse_time_of_day(&self.end))
+        else {
+            return false;
+        };
+        let runs_on = |day: u8| self.weekdays.is_empty() || self.weekdays.contains(&day);
+
+        if start < end {
+            runs_on(weekday) && minute >= start && minute < end
+        } else if minute >= start {
+            runs_on(weekday)
+        } else if minute < end {
+            // Early-morning part of a shift that started the day before
+            runs_on(if weekday == 1 { 7 } else { weekday - 1 })
+        } else {
+            false
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum VadBackend {
+    #[default]
+    Silero,
+    /// Energy and zero-crossing detector; also the fallback when Silero fails to load
+    Energy,
+}
+
+/// Voice activity detection tuning. Frame counts are 30 ms frames.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Type)]
+pub struct VadSettings {
+    #[serde(default)]
+    pub backend: VadBackend,
+    /// Silero speech probability above which a frame counts as speech
+    #[serde(default = "default_vad_threshold")]
+    pub threshold: f32,
+    /// dB above the noise floor for the energy detector
+    #[serde(default = "default_vad_energy_margin_db")]
+    pub energy_margin_db: f32,
+    /// Frames kept from before speech was detected
+    #[serde(default = "default_vad_prefill_frames")]
+    pub prefill_frames: u32,
+    /// Frames kept after speech stops
+    #[serde(default = "default_vad_hangover_frames")]
+    pub hangover_frames: u32,
+    /// Consecutive speech frames needed before speech starts
+    #[serde(default = "default_vad_onset_frames")]
+    pub onset_frames: u32,
+}
+
+fn default_vad_threshold() -> f32 {
+    0.3
+}
+
+fn default_vad_energy_margin_db() -> f32 {
+    10.0
+}
+
+fn default_vad_prefill_frames() -> u32 {
+    15
+}
+
+fn default_vad_hangover_frames() -> u32 {
+    15
+}
+
+fn default_vad_onset_frames() -> u32 {
+    2
+}
+
+impl Default for VadSettings {
+    fn default() -> Self {
+        Self {
+            backend: VadBackend::default(),
+            threshold: default_vad_threshold(),
+            energy_margin_db: default_vad_energy_margin_db(),
+            prefill_frames: default_vad_prefill_frames(),
+            hangover_frames: default_vad_hangover_frames(),
+            onset_frames: default_vad_onset_frames(),
+        }
+    }
+}
+
+impl VadSettings {
+    /// Longest prefill or hangover accepted, in frames (3 s)
+    const MAX_FRAMES: u32 = 100;
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&self.threshold) {
+            return Err("VAD threshold must be between 0 and 1".to_string());
+        }
+        if !(0.0..=40.0).contains(&self.energy_margin_db) {
+            return Err("Energy margin must be between 0 and 40 dB".to_string());
+        }
+        if self.prefill_frames > Self::MAX_FRAMES || self.hangover_frames > Self::MAX_FRAMES {
+            return Err(format!(
+                "Prefill and hangover must be at most {} frames",
+                Self::MAX_FRAMES
+            ));
+        }
+        if !(1..=Self::MAX_FRAMES).contains(&self.onset_frames) {
+            return Err(format!(
+                "Onset must be between 1 and {} frames",
+                Self::MAX_FRAMES
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Hands-free endpointing: end a recording without a key press.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Type)]
+pub struct AutoStopSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Silence after speech that ends the recording; 0 to stop only on the
+    /// other limits
+    #[serde(default = "default_auto_stop_trailing_silence_ms")]
+    pub trailing_silence_ms: u32,
+    /// Longest recording; 0 for no limit
+    #[serde(default = "default_auto_stop_max_duration_secs")]
+    pub max_duration_secs: u32,
+    /// Cancel a recording in which nothing was said for this long; 0 to wait forever
+    #[serde(default = "default_auto_stop_no_speech_timeout_secs")]
+    pub no_speech_timeout_secs: u32,
+}
+
+fn default_auto_stop_trailing_silence_ms() -> u32 {
+    1500
+}
+
+fn default_auto_stop_max_duration_secs() -> u32 {
+    300
+}
+
+fn default_auto_stop_no_speech_timeout_secs() -> u32 {
+    10
+}
+
+impl Default for AutoStopSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            trailing_silence_ms: default_auto_stop_trailing_silence_ms(),
+            max_duration_secs: default_auto_stop_max_duration_secs(),
+            no_speech_timeout_secs: default_auto_stop_no_speech_timeout_secs(),
+        }
+    }
+}
+
+impl AutoStopSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.trailing_silence_ms != 0 && !(300..=10_000).contains(&self.trailing_silence_ms) {
+            return Err("Trailing silence must be off or between 300 ms and 10 s".to_string());
+        }
+        if self.max_duration_secs > 3600 {
+            return Err("Maximum recording length must be at most an hour".to_string());
+        }
+        if self.no_speech_timeout_secs > 600 {
+            return Err("No-speech timeout must be at most 10 minutes".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Speaker-attributed output for encounter recordings.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Type)]
+pub struct DiarizationSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// WeSpeaker-style speaker embedding model (.onnx)
+    #[serde(default)]
+    pub model_path: Option<String>,
+    /// One label per expected speaker, in order of who speaks first
+    #[serde(default = "default_diarization_speaker_labels")]
+    pub speaker_labels: Vec<String>,
+}
+
+fn default_diarization_speaker_labels() -> Vec<String> {
+    vec!["Clinician".to_string(), "Patient".to_string()]
+}
+
+impl Default for DiarizationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            model_path: None,
+            speaker_labels: default_diarization_speaker_labels(),
+        }
+    }
+}
+
+impl DiarizationSettings {
+    /// Most speakers we try to tell apart in one recording
+    pub const MAX_SPEAKERS: usize = 4;
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=Self::MAX_SPEAKERS).contains(&self.speaker_labels.len()) {
+            return Err(format!(
+                "Between 1 and {} speakers are supported",
+                Self::MAX_SPEAKERS
+            ));
+        }
+        if self.speaker_labels.iter().any(|l| l.trim().is_empty()) {
+            return Err("Speaker labels can't be empty".to_string());
+        }
+        if self.enabled && self.model_path.is_none() {
+            return Err("Choose a speaker model before enabling diarisation".to_string());
+        }
+        Ok(())
+    }
+
+    /// Puts the default labels back if the stored ones can't be used, e.g.
+    /// after the settings file was edited by hand. True if it did.
+    pub fn repair_labels(&mut self) -> bool {
+        let usable = (1..=Self::MAX_SPEAKERS).contains(&self.speaker_labels.len())
+            && self.speaker_labels.iter().all(|l| !l.trim().is_empty());
+        if !usable {
+            self.speaker_labels = default_diarization_speaker_labels();
+        }
+        !usable
+    }
+}
+
+/// Where a finished transcript goes. Bindings without an entry in
+/// `binding_outputs` paste it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Type)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OutputSinkConfig {
+    /// Type or paste into the focused application, per `paste_method`
+    Paste,
+    /// Append to a file. `path` may contain strftime fields and `{binding}`;
+    /// `template` may contain strftime fields, `{binding}` and `{text}`
+    File {
+        path: String,
+        #[serde(default = "default_output_file_template")]
+        template: String,
+    },
+    /// Write one JSON line to a named pipe or Unix domain socket
+    Pipe { path: String },
+    /// Print to standard output, for when Handy runs from a terminal
+    Stdout,
+    /// POST JSON to a URL on this machine
+    Webhook { url: String },
+}
+
+/// What a real-time signal does to its binding.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalMode {
+    #[default]
+    Toggle,
+    Start,
+    Stop,
+}
+
+/// Maps `SIGRTMIN + offset` to a binding (Linux only). Offsets 0 and 1 are
+/// taken by "stop transcribing" and "cancel".
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Type)]
+pub struct SignalBinding {
+    pub offset: u8,
+    pub binding_id: String,
+    #[serde(default)]
+    pub mode: SignalMode,
+}
+
+impl SignalBinding {
+    pub const FIRST_OFFSET: u8 = 2;
+    pub const LAST_OFFSET: u8 = 15;
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !(Self::FIRST_OFFSET..=Self::LAST_OFFSET).contains(&self.offset) {
+            return Err(format!(
+                "Signal offsets run from SIGRTMIN+{} to SIGRTMIN+{}",
+                Self::FIRST_OFFSET,
+                Self::LAST_OFFSET
+            ));
+        }
+        if self.binding_id == "cancel" {
+            return Err("Cancel already has SIGRTMIN+1".to_string());
+        }
+        Ok(())
+    }
+}
+
+fn default_output_file_template() -> String {
+    "[%Y-%m-%d %H:%M:%S] {text}\n".to_string()
+}
+
+impl OutputSinkConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            OutputSinkConfig::Paste | OutputSinkConfig::Stdout => Ok(()),
+            OutputSinkConfig::File { path, template } => {
+                crate::validation::validate_file_path(path, false)?;
+                if !std::path::Path::new(path).is_absolute() {
+                    return Err(format!("Output file must be an absolute path: {}", path));
+                }
+                crate::output::check_template(path)?;
+                crate::output::check_template(template)
+            }
+            OutputSinkConfig::Pipe { path } => {
+                crate::validation::validate_file_path(path, false)?;
+                if !std::path::Path::new(path).is_absolute() {
+                    return Err(format!("Pipe must be an absolute path: {}", path));
+                }
+                Ok(())
+            }
+            OutputSinkConfig::Webhook { url } => crate::output::check_local_url(url),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
 #[serde(rename_all = "snake_case")]
 pub enum SoundTheme {
@@ -216,6 +607,9 @@ pub struct AppSettings {
     pub selected_microphone: Option<String>,
     #[serde(default)]
     pub clamshell_microphone: Option<String>,
+    /// How each microphone's channels become mono, by device name; averaged if absent
+    #[serde(default)]
+    pub microphone_channels: HashMap<String, ChannelPolicy>,
     #[serde(default)]
     pub selected_output_device: Option<String>,
     #[serde(default = "default_translate_to_english")]
@@ -242,6 +636,17 @@ pub struct AppSettings {
     pub paste_method: PasteMethod,
     #[serde(default)]
     pub clipboard_handling: ClipboardHandling,
+    /// Clears text copied with `CopyToClipboard` after this long, if the
+    /// clipboard still holds it
+    #[serde(default)]
+    pub clipboard_expiry: ClipboardExpiry,
+    #[serde(default)]
+    pub paste_tool: PasteTool,
+    /// Type the text out when the clipboard can't be written or the paste
+    /// keystroke can't be sent. Not when the target just didn't read it in
+    /// time, since it may still paste and the text would appear twice
+    #[serde(default = "default_paste_fallback_to_typing")]
+    pub paste_fallback_to_typing: bool,
     #[serde(default)]
     pub mute_while_recording: bool,
     #[serde(default)]
@@ -254,12 +659,58 @@ pub struct AppSettings {
     pub setup_completed: bool,
     #[serde(default)]
     pub hide_privacy_notice: bool,
+    #[serde(default)]
+    pub paste_into_original_window: bool,
+    #[serde(default)]
+    pub model_catalog_url: Option<String>,
+    #[serde(default)]
+    pub model_signing_public_key: Option<String>,
+    /// Slowest real-time factor a benchmarked model may have to be recommended
+    #[serde(default = "default_model_latency_target_rtf")]
+    pub model_latency_target_rtf: f64,
+    /// Load the selected model when the app starts (including at login with autostart)
+    #[serde(default)]
+    pub preload_model_on_startup: bool,
+    /// Unload the model when available system memory drops below this; 0 disables
+    #[serde(default = "default_low_memory_unload_threshold_mb")]
+    pub low_memory_unload_threshold_mb: u64,
+    #[serde(default)]
+    pub clinic_hours: Option<ClinicHours>,
+    /// Parakeet engine options keyed by model id; missing entries use the defaults
+    #[serde(default)]
+    pub parakeet_model_options: HashMap<String, ParakeetOptions>,
+    #[serde(default)]
+    pub vad: VadSettings,
+    #[serde(default)]
+    pub auto_stop: AutoStopSettings,
+    /// Audio kept from just before recording starts, always-on microphone only; 0 disables
+    #[serde(default = "default_pre_roll_ms")]
+    pub pre_roll_ms: u32,
+    /// High-pass, noise suppression and gain stages; all off by default
+    #[serde(default)]
+    pub dsp: DspConfig,
+    /// Label who said what; off by default
+    #[serde(default)]
+    pub diarization: DiarizationSettings,
+    /// Where each binding's transcripts go, by binding id; missing bindings paste
+    #[serde(default)]
+    pub binding_outputs: HashMap<String, Vec<OutputSinkConfig>>,
+    /// Accept commands and audio from local scripts over the control socket
+    #[serde(default)]
+    pub ipc_enabled: bool,
+    /// Extra real-time signals and the bindings they drive
+    #[serde(default)]
+    pub signal_bindings: Vec<SignalBinding>,
 }
 
 fn default_model() -> String {
     "".to_string()
 }
 
+fn default_paste_fallback_to_typing() -> bool {
+    true
+}
+
 fn default_always_on_microphone() -> bool {
     false
 }
@@ -301,6 +752,18 @@ fn default_log_level() -> LogLevel {
     LogLevel::Warn
 }
 
+fn default_low_memory_unload_threshold_mb() -> u64 {
+    512
+}
+
+fn default_pre_roll_ms() -> u32 {
+    500
+}
+
+fn default_model_latency_target_rtf() -> f64 {
+    0.3
+}
+
 fn default_word_correction_threshold() -> f64 {
     0.18
 }
@@ -382,6 +845,7 @@ pub fn get_default_settings() -> AppSettings {
         always_on_microphone: false,
         selected_microphone: None,
         clamshell_microphone: None,
+        microphone_channels: HashMap::new(),
         selected_output_device: None,
         translate_to_english: false,
         selected_language: "auto".to_string(),
@@ -395,12 +859,31 @@ pub fn get_default_settings() -> AppSettings {
         recording_retention_period: default_recording_retention_period(),
         paste_method: PasteMethod::default(),
         clipboard_handling: ClipboardHandling::default(),
+        clipboard_expiry: ClipboardExpiry::default(),
+        paste_tool: PasteTool::default(),
+        paste_fallback_to_typing: default_paste_fallback_to_typing(),
         mute_while_recording: false,
         append_trailing_space: false,
         app_language: default_app_language(),
         medical_mode_enabled: true,
         setup_completed: false,
         hide_privacy_notice: false,
+        paste_into_original_window: false,
+        model_catalog_url: None,
+        model_signing_public_key: None,
+        model_latency_target_rtf: default_model_latency_target_rtf(),
+        preload_model_on_startup: false,
+        low_memory_unload_threshold_mb: default_low_memory_unload_threshold_mb(),
+        clinic_hours: None,
+        parakeet_model_options: HashMap::new(),
+        vad: VadSettings::default(),
+        auto_stop: AutoStopSettings::default(),
+        pre_roll_ms: default_pre_roll_ms(),
+        dsp: DspConfig::default(),
+        diarization: DiarizationSettings::default(),
+        binding_outputs: HashMap::new(),
+        ipc_enabled: false,
+        signal_bindings: Vec::new(),
     }
 }
 
@@ -568,11 +1051,16 @@ pub fn get_settings(app: &AppHandle) -> AppSettings {
         changed = true;
     }
 
+    if settings.diarization.repair_labels() {
+        warn!("Stored speaker labels were unusable; using the defaults");
+        changed = true;
+    }
+
     if changed {
         if let Ok(value) = serde_json::to_value(&settings) {
             store.set("settings", value);
         } else {
-            warn!("Failed to serialize settings after merging vocabulary");
+            warn!("Failed to serialize updated settings");
         }
     }
 
@@ -1,20 +1,27 @@
 use log::{debug, error, warn};
 use serde::Serialize;
 use specta::Type;
+use std::collections::HashSet;
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_autostart::ManagerExt;
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
 
 use crate::actions::ACTION_MAP;
+use crate::audio_toolkit::audio::DspConfig;
 use crate::managers::audio::AudioRecordingManager;
+use crate::managers::diarization::DiarizationManager;
 use crate::settings::ShortcutBinding;
 use crate::settings::{
     self,
     get_settings,
+    ClipboardExpiry,
     ClipboardHandling,
+    OutputSinkConfig,
     OverlayPosition,
     PasteMethod,
+    PasteTool,
+    SignalBinding,
     SoundTheme,
     //     APPLE_INTELLIGENCE_DEFAULT_MODEL_ID, APPLE_INTELLIGENCE_PROVIDER_ID,
 };
@@ -376,6 +383,105 @@ pub fn change_clipboard_handling_setting(app: AppHandle, handling: String) -> Re
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn change_clipboard_expiry_setting(
+    app: AppHandle,
+    expiry: ClipboardExpiry,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.clipboard_expiry = expiry;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+/// Sets where a binding's transcripts go; every sink runs, in order.
+#[tauri::command]
+#[specta::specta]
+pub fn change_binding_outputs_setting(
+    app: AppHandle,
+    binding_id: String,
+    outputs: Vec<OutputSinkConfig>,
+) -> Result<(), String> {
+    if outputs.is_empty() {
+        return Err("Choose at least one output".to_string());
+    }
+    for output in &outputs {
+        output.validate()?;
+    }
+
+    let mut settings = settings::get_settings(&app);
+    if !settings.bindings.contains_key(&binding_id) {
+        return Err(format!("Binding with id '{}' not found", binding_id));
+    }
+    settings.binding_outputs.insert(binding_id, outputs);
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_signal_bindings_setting(
+    app: AppHandle,
+    bindings: Vec<SignalBinding>,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    let mut offsets = HashSet::new();
+    for binding in &bindings {
+        binding.validate()?;
+        if !settings.bindings.contains_key(&binding.binding_id) {
+            return Err(format!(
+                "Binding with id '{}' not found",
+                binding.binding_id
+            ));
+        }
+        if !offsets.insert(binding.offset) {
+            return Err(format!(
+                "SIGRTMIN+{} is mapped more than once",
+                binding.offset
+            ));
+        }
+    }
+
+    settings.signal_bindings = bindings;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_ipc_enabled_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    let previous = settings.ipc_enabled;
+    settings.ipc_enabled = enabled;
+    settings::write_settings(&app, settings.clone());
+
+    if let Err(e) = crate::ipc::app::apply_setting(&app) {
+        settings.ipc_enabled = previous;
+        settings::write_settings(&app, settings);
+        return Err(e);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_paste_tool_setting(app: AppHandle, tool: PasteTool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.paste_tool = tool;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_paste_fallback_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.paste_fallback_to_typing = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 // Post-processing commands removed - feature deprecated for privacy/HIPAA compliance
 
 #[tauri::command]
@@ -438,6 +544,183 @@ pub fn change_hide_privacy_notice_setting(app: AppHandle, hide: bool) -> Result<
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn change_paste_into_original_window_setting(
+    app: AppHandle,
+    enabled: bool,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.paste_into_original_window = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_model_catalog_url_setting(app: AppHandle, url: Option<String>) -> Result<(), String> {
+    let url = url.map(|u| u.trim().to_string()).filter(|u| !u.is_empty());
+
+    if let Some(url) = &url {
+        crate::managers::catalog::validate_catalog_url(url).map_err(|e| e.to_string())?;
+    }
+
+    let mut settings = settings::get_settings(&app);
+    settings.model_catalog_url = url;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_model_signing_public_key_setting(
+    app: AppHandle,
+    public_key: Option<String>,
+) -> Result<(), String> {
+    let public_key = public_key
+        .map(|k| k.trim().to_string())
+        .filter(|k| !k.is_empty());
+
+    if let Some(key) = &public_key {
+        crate::managers::integrity::parse_public_key(key).map_err(|e| e.to_string())?;
+    }
+
+    let mut settings = settings::get_settings(&app);
+    settings.model_signing_public_key = public_key;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_model_latency_target_setting(app: AppHandle, rtf: f64) -> Result<(), String> {
+    if !rtf.is_finite() || rtf <= 0.0 {
+        return Err("Latency target must be a positive real-time factor".to_string());
+    }
+
+    let mut settings = settings::get_settings(&app);
+    settings.model_latency_target_rtf = rtf;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_preload_model_on_startup_setting(
+    app: AppHandle,
+    enabled: bool,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.preload_model_on_startup = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_low_memory_unload_threshold_setting(
+    app: AppHandle,
+    threshold_mb: u64,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.low_memory_unload_threshold_mb = threshold_mb;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_clinic_hours_setting(
+    app: AppHandle,
+    clinic_hours: Option<settings::ClinicHours>,
+) -> Result<(), String> {
+    if let Some(hours) = &clinic_hours {
+        hours.validate()?;
+    }
+
+    let mut settings = settings::get_settings(&app);
+    settings.clinic_hours = clinic_hours;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_auto_stop_setting(
+    app: AppHandle,
+    auto_stop: settings::AutoStopSettings,
+) -> Result<(), String> {
+    auto_stop.validate()?;
+
+    let mut settings = settings::get_settings(&app);
+    settings.auto_stop = auto_stop;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_pre_roll_setting(app: AppHandle, pre_roll_ms: u32) -> Result<(), String> {
+    if pre_roll_ms > 3000 {
+        return Err("Pre-roll must be at most 3000 ms".to_string());
+    }
+
+    let mut settings = settings::get_settings(&app);
+    settings.pre_roll_ms = pre_roll_ms;
+    settings::write_settings(&app, settings);
+
+    let rm = app.state::<Arc<AudioRecordingManager>>();
+    rm.apply_pre_roll();
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_dsp_settings(app: AppHandle, dsp: DspConfig) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.dsp = dsp;
+    settings::write_settings(&app, settings);
+
+    let rm = app.state::<Arc<AudioRecordingManager>>();
+    rm.apply_dsp_settings();
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_vad_settings(app: AppHandle, vad: settings::VadSettings) -> Result<(), String> {
+    vad.validate()?;
+
+    let mut settings = settings::get_settings(&app);
+    settings.vad = vad;
+    settings::write_settings(&app, settings);
+
+    // The speaker model's VAD is rebuilt with it on next use
+    app.state::<Arc<DiarizationManager>>().unload();
+
+    let rm = app.state::<Arc<AudioRecordingManager>>();
+    rm.apply_vad_settings()
+        .map_err(|e| format!("Failed to apply VAD settings: {}", e))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_diarization_settings(
+    app: AppHandle,
+    diarization: settings::DiarizationSettings,
+) -> Result<(), String> {
+    diarization.validate()?;
+
+    let mut settings = settings::get_settings(&app);
+    settings.diarization = diarization;
+    settings::write_settings(&app, settings);
+
+    // Loaded again with the new model or speaker count on next use
+    let dm = app.state::<Arc<DiarizationManager>>();
+    dm.unload();
+    Ok(())
+}
+
 /// Determine whether a shortcut string contains at least one non-modifier key.
 /// We allow single non-modifier keys (e.g. "f5" or "space") but disallow
 /// modifier-only combos (e.g. "ctrl" or "ctrl+shift").
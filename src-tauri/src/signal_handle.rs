@@ -1,71 +1,310 @@
-use crate::actions::ACTION_MAP;
-use crate::ManagedToggleState;
+//! Control by POSIX signals, for hotkey daemons and scripts:
+//!
+//! | Signal         | Action                                  |
+//! |----------------|-----------------------------------------|
+//! | `SIGUSR1`      | start transcribing                      |
+//! | `SIGUSR2`      | toggle transcribing                     |
+//! | `SIGRTMIN`     | stop transcribing (Linux)               |
+//! | `SIGRTMIN+1`   | cancel (Linux)                          |
+//! | `SIGRTMIN+2`.. | per `signal_bindings` (Linux)           |
+//!
+//! macOS and the BSDs have no real-time signals, so there only the first
+//! two work; stopping, cancelling and other bindings need the control API.
+//! `SIGHUP` is left alone, since terminals and service managers send it too.
+//!
+//! The running app also holds a lock on [`PID_FILE`] in its data directory,
+//! which is how `handy-ctl` finds it when the control API is off.
+
+use crate::actions;
+use crate::settings::{get_settings, SignalBinding, SignalMode};
 use log::{debug, info, warn};
+use signal_hook::consts::{SIGUSR1, SIGUSR2};
+use signal_hook::iterator::Signals;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::os::fd::AsRawFd;
+use std::path::Path;
 use std::thread;
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 
-#[cfg(unix)]
-use signal_hook::consts::SIGUSR2;
-#[cfg(unix)]
-use signal_hook::iterator::Signals;
+/// Holds the running app's pid, locked for as long as it runs.
+pub const PID_FILE: &str = "handy.pid";
 
-#[cfg(unix)]
-pub fn setup_signal_handler(app_handle: AppHandle, mut signals: Signals) {
-    let app_handle_for_signal = app_handle.clone();
+/// Starts transcribing
+pub const SIGNAL_START: i32 = SIGUSR1;
+/// Toggles transcribing
+pub const SIGNAL_TOGGLE: i32 = SIGUSR2;
+
+/// What a signal asks for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SignalCommand {
+    Binding {
+        binding_id: String,
+        mode: SignalMode,
+    },
+    Cancel,
+}
+
+/// Offset from `SIGRTMIN` that stops transcribing
+const RT_STOP: i32 = 0;
+/// Offset from `SIGRTMIN` that cancels
+const RT_CANCEL: i32 = 1;
+
+/// Every signal the handler listens for.
+pub fn handled_signals() -> Vec<i32> {
+    let mut signals = vec![SIGNAL_START, SIGNAL_TOGGLE];
+    if let Some(min) = rt_min() {
+        signals.extend(
+            (min..=min + i32::from(SignalBinding::LAST_OFFSET)).filter(|&sig| sig <= rt_max()),
+        );
+    }
+    signals
+}
+
+/// The signal that stops transcribing, where there is one.
+pub fn stop_signal() -> Option<i32> {
+    rt_min().map(|min| min + RT_STOP)
+}
+
+/// The signal that cancels, where there is one.
+pub fn cancel_signal() -> Option<i32> {
+    rt_min().map(|min| min + RT_CANCEL)
+}
+
+#[cfg(target_os = "linux")]
+fn rt_min() -> Option<i32> {
+    Some(libc::SIGRTMIN())
+}
+
+#[cfg(target_os = "linux")]
+fn rt_max() -> i32 {
+    libc::SIGRTMAX()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn rt_min() -> Option<i32> {
+    None
+}
+
+#[cfg(not(target_os = "linux"))]
+fn rt_max() -> i32 {
+    0
+}
+
+fn command_for(
+    signal: i32,
+    rt_min: Option<i32>,
+    bindings: &[SignalBinding],
+) -> Option<SignalCommand> {
+    let transcribe = |mode| {
+        Some(SignalCommand::Binding {
+            binding_id: "transcribe".to_string(),
+            mode,
+        })
+    };
+    if signal == SIGUSR1 {
+        return transcribe(SignalMode::Start);
+    }
+    if signal == SIGUSR2 {
+        return transcribe(SignalMode::Toggle);
+    }
+
+    match signal - rt_min? {
+        RT_STOP => transcribe(SignalMode::Stop),
+        RT_CANCEL => Some(SignalCommand::Cancel),
+        offset => bindings
+            .iter()
+            .find(|b| i32::from(b.offset) == offset)
+            .map(|b| SignalCommand::Binding {
+                binding_id: b.binding_id.clone(),
+                mode: b.mode,
+            }),
+    }
+}
+
+fn signal_name(signal: i32, rt_min: Option<i32>) -> String {
+    match rt_min {
+        _ if signal == SIGUSR1 => "SIGUSR1".to_string(),
+        _ if signal == SIGUSR2 => "SIGUSR2".to_string(),
+        Some(min) if signal == min => "SIGRTMIN".to_string(),
+        Some(min) if signal > min => format!("SIGRTMIN+{}", signal - min),
+        _ => format!("signal {}", signal),
+    }
+}
+
+/// Keeps [`PID_FILE`] locked until dropped.
+pub struct PidLock {
+    _file: File,
+}
 
-    debug!("SIGUSR2 signal handler registered successfully");
+/// Writes our pid to [`PID_FILE`] in `data_dir` and locks it.
+pub fn lock_pid_file(data_dir: &Path) -> io::Result<PidLock> {
+    std::fs::create_dir_all(data_dir)?;
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(data_dir.join(PID_FILE))?;
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    file.set_len(0)?;
+    write!(file, "{}", std::process::id())?;
+    file.flush()?;
+    Ok(PidLock { _file: file })
+}
+
+/// The pid of the app running on `data_dir`, or None if it isn't running.
+pub fn running_pid(data_dir: &Path) -> io::Result<Option<i32>> {
+    let mut file = match File::open(data_dir.join(PID_FILE)) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_SH | libc::LOCK_NB) } == 0 {
+        // Nobody holds the lock, so the app that wrote the file has exited
+        return Ok(None);
+    }
+    let error = io::Error::last_os_error();
+    if error.kind() != io::ErrorKind::WouldBlock {
+        return Err(error);
+    }
+
+    let mut pid = String::new();
+    file.read_to_string(&mut pid)?;
+    pid.trim()
+        .parse()
+        .map(Some)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Malformed pid file"))
+}
+
+pub fn setup_signal_handler(app_handle: AppHandle, mut signals: Signals) {
+    debug!("Signal handler registered successfully");
     thread::spawn(move || {
-        debug!("SIGUSR2 signal handler thread started");
+        debug!("Signal handler thread started");
         for sig in signals.forever() {
-            match sig {
-                SIGUSR2 => {
-                    debug!("Received SIGUSR2 signal (signal number: {sig})");
-
-                    let binding_id = "transcribe";
-                    let shortcut_string = "SIGUSR2";
-
-                    if let Some(action) = ACTION_MAP.get(binding_id) {
-                        // Determine action and update state while holding the lock,
-                        // but RELEASE the lock before calling the action to avoid deadlocks.
-                        // (Actions may need to acquire the lock themselves, e.g., cancel_current_operation)
-                        let should_start: bool;
-                        {
-                            let toggle_state_manager =
-                                app_handle_for_signal.state::<ManagedToggleState>();
-
-                            let mut states = match toggle_state_manager.lock() {
-                                Ok(s) => s,
-                                Err(e) => {
-                                    warn!("Failed to lock toggle state manager: {e}");
-                                    continue;
-                                }
-                            };
-
-                            let is_currently_active = states
-                                .active_toggles
-                                .entry(binding_id.to_string())
-                                .or_insert(false);
-
-                            should_start = !*is_currently_active;
-                            *is_currently_active = should_start;
-                        } // Lock released here
-
-                        // Now call the action without holding the lock
-                        if should_start {
-                            debug!("SIGUSR2: Starting transcription (was inactive)");
-                            action.start(&app_handle_for_signal, binding_id, shortcut_string);
-                            info!("SIGUSR2: Transcription started");
-                        } else {
-                            debug!("SIGUSR2: Stopping transcription (was active)");
-                            action.stop(&app_handle_for_signal, binding_id, shortcut_string);
-                            debug!("SIGUSR2: Transcription stopped");
-                        }
-                    } else {
-                        warn!("No action defined in ACTION_MAP for binding ID '{binding_id}'");
-                    }
-                }
-                _ => unreachable!(),
-            }
+            handle_signal(&app_handle, sig);
         }
     });
 }
+
+fn handle_signal(app: &AppHandle, sig: i32) {
+    let rt_min = rt_min();
+    let name = signal_name(sig, rt_min);
+    debug!("Received {} (signal number: {sig})", name);
+
+    let bindings = get_settings(app).signal_bindings;
+    let Some(command) = command_for(sig, rt_min, &bindings) else {
+        warn!("Nothing is mapped to {}", name);
+        return;
+    };
+
+    let result = match &command {
+        SignalCommand::Cancel => {
+            crate::utils::cancel_current_operation(app);
+            Ok(())
+        }
+        SignalCommand::Binding { binding_id, mode } => match mode {
+            SignalMode::Toggle => actions::toggle_binding(app, binding_id, &name).map(|_| ()),
+            SignalMode::Start => {
+                actions::set_binding_active(app, binding_id, true, &name).map(|_| ())
+            }
+            SignalMode::Stop => {
+                actions::set_binding_active(app, binding_id, false, &name).map(|_| ())
+            }
+        },
+    };
+    match result {
+        Ok(()) => info!("{}: {:?} done", name, command),
+        Err(e) => warn!("{}: {:?} failed: {}", name, command, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RT_MIN: i32 = 34;
+
+    fn binding(id: &str, mode: SignalMode) -> Option<SignalCommand> {
+        Some(SignalCommand::Binding {
+            binding_id: id.to_string(),
+            mode,
+        })
+    }
+
+    #[test]
+    fn user_signals_drive_transcription() {
+        assert_eq!(
+            command_for(SIGUSR1, None, &[]),
+            binding("transcribe", SignalMode::Start)
+        );
+        assert_eq!(
+            command_for(SIGUSR2, None, &[]),
+            binding("transcribe", SignalMode::Toggle)
+        );
+    }
+
+    #[test]
+    fn hangup_is_left_alone() {
+        use signal_hook::consts::SIGHUP;
+
+        assert!(!handled_signals().contains(&SIGHUP));
+        assert_eq!(command_for(SIGHUP, None, &[]), None);
+        assert_eq!(command_for(SIGHUP, Some(RT_MIN), &[]), None);
+    }
+
+    #[test]
+    fn pid_file_is_only_trusted_while_locked() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(running_pid(dir.path()).unwrap(), None);
+
+        let lock = lock_pid_file(dir.path()).unwrap();
+        assert_eq!(
+            running_pid(dir.path()).unwrap(),
+            Some(std::process::id() as i32)
+        );
+        // A second instance can't take it over
+        assert!(lock_pid_file(dir.path()).is_err());
+
+        drop(lock);
+        assert_eq!(running_pid(dir.path()).unwrap(), None);
+    }
+
+    #[test]
+    fn fixed_realtime_signals_stop_and_cancel() {
+        assert_eq!(
+            command_for(RT_MIN, Some(RT_MIN), &[]),
+            binding("transcribe", SignalMode::Stop)
+        );
+        assert_eq!(
+            command_for(RT_MIN + 1, Some(RT_MIN), &[]),
+            Some(SignalCommand::Cancel)
+        );
+    }
+
+    #[test]
+    fn other_realtime_signals_follow_the_settings() {
+        let bindings = [SignalBinding {
+            offset: 3,
+            binding_id: "notes".to_string(),
+            mode: SignalMode::Start,
+        }];
+        assert_eq!(
+            command_for(RT_MIN + 3, Some(RT_MIN), &bindings),
+            binding("notes", SignalMode::Start)
+        );
+        assert_eq!(command_for(RT_MIN + 4, Some(RT_MIN), &bindings), None);
+        // Without real-time signals only the user signals mean anything
+        assert_eq!(command_for(RT_MIN + 3, None, &bindings), None);
+    }
+
+    #[test]
+    fn signals_are_named_for_logs() {
+        assert_eq!(signal_name(SIGUSR1, Some(RT_MIN)), "SIGUSR1");
+        assert_eq!(signal_name(RT_MIN, Some(RT_MIN)), "SIGRTMIN");
+        assert_eq!(signal_name(RT_MIN + 5, Some(RT_MIN)), "SIGRTMIN+5");
+        assert_eq!(signal_name(RT_MIN + 5, None), "signal 39");
+    }
+}
@@ -0,0 +1,104 @@
+//! SubRip and WebVTT rendering for timed transcripts.
+
+use crate::managers::transcription::TranscriptSegment;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubtitleFormat {
+    Srt,
+    WebVtt,
+}
+
+impl SubtitleFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "srt" => Some(SubtitleFormat::Srt),
+            "vtt" | "webvtt" => Some(SubtitleFormat::WebVtt),
+            _ => None,
+        }
+    }
+}
+
+/// `HH:MM:SS,mmm` for SRT, `HH:MM:SS.mmm` for WebVTT.
+fn timestamp(secs: f32, format: SubtitleFormat) -> String {
+    let total_ms = (secs.max(0.0) as f64 * 1000.0).round() as u64;
+    let (hours, rest) = (total_ms / 3_600_000, total_ms % 3_600_000);
+    let (minutes, rest) = (rest / 60_000, rest % 60_000);
+    let (seconds, millis) = (rest / 1000, rest % 1000);
+    let separator = match format {
+        SubtitleFormat::Srt => ',',
+        SubtitleFormat::WebVtt => '.',
+    };
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        hours, minutes, seconds, separator, millis
+    )
+}
+
+pub fn render(segments: &[TranscriptSegment], format: SubtitleFormat) -> String {
+    let mut out = String::new();
+    if format == SubtitleFormat::WebVtt {
+        out.push_str("WEBVTT\n\n");
+    }
+
+    for (index, segment) in segments.iter().enumerate() {
+        if format == SubtitleFormat::Srt {
+            out.push_str(&format!("{}\n", index + 1));
+        }
+        // Players reject cues that end before they start
+        let end = segment.end.max(segment.start);
+        out.push_str(&format!(
+            "{} --> {}\n{}\n\n",
+            timestamp(segment.start, format),
+            timestamp(end, format),
+            segment.text.trim()
+        ));
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segments() -> Vec<TranscriptSegment> {
+        vec![
+            TranscriptSegment {
+                start: 0.0,
+                end: 2.5,
+                text: "Blood pressure 120/80.".to_string(),
+            },
+            TranscriptSegment {
+                start: 3661.25,
+                end: 3662.0,
+                text: " Follow up in two weeks. ".to_string(),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_render_srt() {
+        assert_eq!(
+            render(&segments(), SubtitleFormat::Srt),
+            "1\n00:00:00,000 --> 00:00:02,500\nBlood pressure 120/80.\n\n\
+             2\n01:01:01,250 --> 01:01:02,000\nFollow up in two weeks.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_render_webvtt() {
+        let vtt = render(&segments(), SubtitleFormat::WebVtt);
+        assert!(vtt.starts_with("WEBVTT\n\n00:00:00.000 --> 00:00:02.500\n"));
+        assert!(vtt.contains("01:01:01.250 --> 01:01:02.000\nFollow up in two weeks.\n"));
+    }
+
+    #[test]
+    fn test_format_names() {
+        assert_eq!(SubtitleFormat::from_name("SRT"), Some(SubtitleFormat::Srt));
+        assert_eq!(
+            SubtitleFormat::from_name("vtt"),
+            Some(SubtitleFormat::WebVtt)
+        );
+        assert_eq!(SubtitleFormat::from_name("txt"), None);
+    }
+}
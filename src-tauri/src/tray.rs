@@ -1,6 +1,8 @@
+use crate::managers::queue::TranscriptionQueue;
 use crate::settings;
 use crate::tray_i18n::get_tray_translations;
 use log::error;
+use std::sync::Arc;
 use tauri::image::Image;
 use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
 use tauri::tray::TrayIcon;
@@ -128,7 +130,35 @@ fn try_update_tray_menu(
         PredefinedMenuItem::separator(app).map_err(|e| format!("Failed to create separator: {}", e))
     };
 
+    // Recordings that are waiting to be transcribed and pasted
+    let pending = app
+        .try_state::<Arc<TranscriptionQueue>>()
+        .map(|queue| queue.pending_count())
+        .unwrap_or(0);
+    let queued_label = strings.queued.replace("{{count}}", &pending.to_string());
+    let queued_i = MenuItem::with_id(app, "queued", &queued_label, false, None::<&str>)
+        .map_err(|e| format!("Failed to create queued menu item: {}", e))?;
+
     let menu = match state {
+        TrayIconState::Recording | TrayIconState::Transcribing if pending > 0 => {
+            let cancel_i = MenuItem::with_id(app, "cancel", &strings.cancel, true, None::<&str>)
+                .map_err(|e| format!("Failed to create cancel menu item: {}", e))?;
+            Menu::with_items(
+                app,
+                &[
+                    &version_i,
+                    &separator()?,
+                    &queued_i,
+                    &cancel_i,
+                    &separator()?,
+                    &settings_i,
+                    // &check_updates_i,
+                    &separator()?,
+                    &quit_i,
+                ],
+            )
+            .map_err(|e| format!("Failed to create recording menu: {}", e))?
+        }
         TrayIconState::Recording | TrayIconState::Transcribing => {
             let cancel_i = MenuItem::with_id(app, "cancel", &strings.cancel, true, None::<&str>)
                 .map_err(|e| format!("Failed to create cancel menu item: {}", e))?;
@@ -166,6 +196,13 @@ fn try_update_tray_menu(
         .map_err(|e| format!("Failed to set tray menu: {}", e))?;
     tray.set_icon_as_template(true)
         .map_err(|e| format!("Failed to set icon as template: {}", e))?;
+    let tooltip = if pending > 0 {
+        format!("Handy ({})", queued_label)
+    } else {
+        "Handy".to_string()
+    };
+    tray.set_tooltip(Some(tooltip))
+        .map_err(|e| format!("Failed to set tray tooltip: {}", e))?;
 
     Ok(())
 }
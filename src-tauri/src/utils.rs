@@ -1,4 +1,5 @@
 use crate::managers::audio::AudioRecordingManager;
+use crate::managers::queue::TranscriptionQueue;
 use crate::shortcut;
 use crate::ManagedToggleState;
 use log::{info, warn};
@@ -12,7 +13,7 @@ pub use crate::overlay::*;
 pub use crate::tray::*;
 
 /// Centralized cancellation function that can be called from anywhere in the app.
-/// Handles cancelling both recording and transcription operations and updates UI state.
+/// Cancels the recording in progress and updates UI state.
 pub fn cancel_current_operation(app: &AppHandle) {
     info!("Initiating operation cancellation...");
 
@@ -32,9 +33,12 @@ pub fn cancel_current_operation(app: &AppHandle) {
     let audio_manager = app.state::<Arc<AudioRecordingManager>>();
     audio_manager.cancel_recording();
 
-    // Update tray icon and hide overlay
-    change_tray_icon(app, crate::tray::TrayIconState::Idle);
-    hide_recording_overlay(app);
+    // Drop the job for the cancelled recording. Earlier recordings that are
+    // still queued for transcription are left alone, and the tray/overlay are
+    // updated to reflect whatever is left in the queue.
+    let queue = app.state::<Arc<TranscriptionQueue>>();
+    queue.cancel_recording();
+    queue.refresh_ui();
 
     info!("Operation cancellation completed - returned to idle state");
 }
@@ -0,0 +1,243 @@
+//! Drives the real recorder, resampler and VAD from non-device sources, so
+//! the capture pipeline can be exercised on a headless machine.
+
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use handy_app_lib::audio_toolkit::{
+    audio::{ActiveSource, AutoStopConfig, AutoStopReason, DspConfig, QualityWarning},
+    vad::SmoothedVad,
+    AudioRecorder, AudioSource, FileSource, Pacing, SileroVad, SyntheticSource,
+};
+
+fn resource(path: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("resources")
+        .join(path)
+}
+
+fn recorder_with_vad() -> AudioRecorder {
+    let silero = SileroVad::new(resource("models/silero_vad_v4.onnx"), 0.3).unwrap();
+    AudioRecorder::new()
+        .unwrap()
+        .with_vad(Box::new(SmoothedVad::new(Box::new(silero), 15, 15, 2)))
+}
+
+#[test]
+fn synthetic_source_is_resampled_to_16k() {
+    let mut recorder = AudioRecorder::new().unwrap();
+    let source =
+        SyntheticSource::new(48000, Pacing::Unpaced).tone(440.0, 0.5, Duration::from_secs(2));
+    recorder.open_source(Box::new(source)).unwrap();
+    recorder.start().unwrap();
+    let samples = recorder.finish().unwrap();
+    recorder.close().unwrap();
+
+    // Allow for the resampler's last partial frame
+    assert!(
+        (31_000..=32_500).contains(&samples.len()),
+        "got {} samples",
+        samples.len()
+    );
+}
+
+#[test]
+fn configuring_before_start_keeps_the_start_of_a_finite_source() {
+    let mut recorder = AudioRecorder::new().unwrap();
+    let source =
+        SyntheticSource::new(48000, Pacing::Unpaced).tone(440.0, 0.5, Duration::from_secs(2));
+    recorder.open_source(Box::new(source)).unwrap();
+    recorder.set_dsp(DspConfig::default()).unwrap();
+    recorder.set_auto_stop(None).unwrap();
+    // Time for the source to run ahead if the settings had released it
+    std::thread::sleep(Duration::from_millis(200));
+    recorder.start().unwrap();
+    let samples = recorder.finish().unwrap();
+    recorder.close().unwrap();
+
+    assert!(
+        (31_000..=32_500).contains(&samples.len()),
+        "got {} samples",
+        samples.len()
+    );
+}
+
+#[test]
+fn vad_drops_silence_and_noise() {
+    let mut recorder = recorder_with_vad();
+    let source = SyntheticSource::new(16000, Pacing::Unpaced)
+        .silence(Duration::from_secs(1))
+        .noise(0.01, Duration::from_secs(2));
+    recorder.open_source(Box::new(source)).unwrap();
+    recorder.start().unwrap();
+    let samples = recorder.finish().unwrap();
+    recorder.close().unwrap();
+
+    assert!(samples.len() < 16000 / 2, "kept {} samples", samples.len());
+}
+
+#[test]
+fn vad_keeps_speech_from_file() {
+    let path = resource("benchmark/reference.wav");
+    let (original, rate) = handy_app_lib::audio_toolkit::audio::decode_audio_file(&path).unwrap();
+    let original_16k = original.len() as u64 * 16000 / rate as u64;
+
+    let mut recorder = recorder_with_vad();
+    recorder
+        .open_source(Box::new(FileSource::new(&path, Pacing::Unpaced)))
+        .unwrap();
+    recorder.start().unwrap();
+    let samples = recorder.finish().unwrap();
+    recorder.close().unwrap();
+
+    // Most of the clip is speech; the pauses between phrases are trimmed
+    let kept = samples.len() as u64;
+    assert!(kept > original_16k / 2, "kept {kept} of {original_16k}");
+    assert!(
+        kept <= original_16k + 16000,
+        "kept {kept} of {original_16k}"
+    );
+}
+
+#[test]
+fn stop_before_finish_returns_partial_recording() {
+    let mut recorder = AudioRecorder::new().unwrap();
+    let source =
+        SyntheticSource::new(16000, Pacing::RealTime).tone(300.0, 0.3, Duration::from_secs(5));
+    recorder.open_source(Box::new(source)).unwrap();
+    recorder.start().unwrap();
+    std::thread::sleep(Duration::from_millis(500));
+    let samples = recorder.stop().unwrap();
+    recorder.close().unwrap();
+
+    assert!(!samples.is_empty());
+    assert!(samples.len() < 16000 * 3, "got {} samples", samples.len());
+}
+
+#[test]
+fn quality_report_flags_a_dead_microphone() {
+    let mut recorder = recorder_with_vad();
+    let source = SyntheticSource::new(48000, Pacing::RealTime).silence(Duration::from_secs(2));
+    recorder.open_source(Box::new(source)).unwrap();
+    recorder.start().unwrap();
+    std::thread::sleep(Duration::from_millis(500));
+    let (samples, report) = recorder.stop_with_report().unwrap();
+    recorder.close().unwrap();
+
+    assert!(samples.is_empty(), "kept {} samples", samples.len());
+    assert!(report.duration_secs > 0.2, "{report:?}");
+    assert_eq!(report.warnings, vec![QualityWarning::Silent]);
+}
+
+/// Runs `source` through a VAD recorder with auto-stop and returns the first
+/// reason reported.
+fn auto_stop_reason(source: SyntheticSource) -> Option<AutoStopReason> {
+    let (tx, rx) = mpsc::channel();
+    let mut recorder = recorder_with_vad().with_auto_stop_callback(move |reason| {
+        let _ = tx.send(reason);
+    });
+    recorder.open_source(Box::new(source)).unwrap();
+    recorder
+        .set_auto_stop(Some(AutoStopConfig {
+            trailing_silence: Some(Duration::from_millis(1500)),
+            max_duration: None,
+            no_speech_timeout: Some(Duration::from_secs(3)),
+        }))
+        .unwrap();
+    recorder.start().unwrap();
+    recorder.finish().unwrap();
+    recorder.close().unwrap();
+    rx.try_recv().ok()
+}
+
+#[test]
+fn auto_stop_after_trailing_silence() {
+    let path = resource("benchmark/reference.wav");
+    let (speech, rate) = handy_app_lib::audio_toolkit::audio::decode_audio_file(&path).unwrap();
+    let source = SyntheticSource::new(rate, Pacing::Unpaced)
+        .silence(Duration::from_millis(500))
+        .samples(&speech)
+        .silence(Duration::from_secs(4));
+
+    assert_eq!(
+        auto_stop_reason(source),
+        Some(AutoStopReason::TrailingSilence)
+    );
+}
+
+#[test]
+fn auto_stop_without_speech() {
+    let source = SyntheticSource::new(16000, Pacing::Unpaced)
+        .silence(Duration::from_secs(2))
+        .noise(0.01, Duration::from_secs(3));
+
+    assert_eq!(auto_stop_reason(source), Some(AutoStopReason::NoSpeech));
+}
+
+#[test]
+fn switching_source_keeps_recording() {
+    let mut recorder = AudioRecorder::new().unwrap();
+    let first =
+        SyntheticSource::new(48000, Pacing::Unpaced).tone(440.0, 0.5, Duration::from_secs(1));
+    recorder.open_source(Box::new(first)).unwrap();
+    recorder.start().unwrap();
+
+    // A replacement at a different rate, like a headset standing in for a
+    // built-in microphone
+    let second =
+        SyntheticSource::new(16000, Pacing::Unpaced).tone(300.0, 0.5, Duration::from_secs(1));
+    recorder.switch_source(Box::new(second)).unwrap();
+    let samples = recorder.finish().unwrap();
+    recorder.close().unwrap();
+
+    // Whatever the first source delivered before the switch is kept
+    assert!(
+        (16_000..=32_500).contains(&samples.len()),
+        "got {} samples",
+        samples.len()
+    );
+}
+
+struct BrokenSource;
+
+impl AudioSource for BrokenSource {
+    fn describe(&self) -> String {
+        "broken source".into()
+    }
+
+    fn start(
+        self: Box<Self>,
+        _sample_tx: mpsc::Sender<Vec<f32>>,
+    ) -> Result<ActiveSource, Box<dyn std::error::Error>> {
+        Err("device unavailable".into())
+    }
+}
+
+#[test]
+fn source_failure_is_reported_and_recoverable() {
+    let (tx, rx) = mpsc::channel();
+    let mut recorder = AudioRecorder::new()
+        .unwrap()
+        .with_error_callback(move |error| {
+            let _ = tx.send(error);
+        });
+    recorder.open_source(Box::new(BrokenSource)).unwrap();
+    recorder.start().unwrap();
+
+    let error = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert!(error.contains("device unavailable"), "{error}");
+
+    // The recorder is still there to carry on from another source
+    let source =
+        SyntheticSource::new(16000, Pacing::Unpaced).tone(300.0, 0.5, Duration::from_secs(1));
+    recorder.switch_source(Box::new(source)).unwrap();
+    let samples = recorder.finish().unwrap();
+    recorder.close().unwrap();
+
+    assert!(
+        (15_500..=16_500).contains(&samples.len()),
+        "got {} samples",
+        samples.len()
+    );
+}
@@ -0,0 +1,590 @@
+//! Runs the control server against a stand-in for the app and talks to it
+//! with the same client scripts would use.
+
+use base64::Engine as _;
+use handy_app_lib::audio_toolkit::PcmFormat;
+use handy_app_lib::ipc::protocol::{INVALID_PARAMS, METHOD_NOT_FOUND, UNAUTHORIZED};
+use handy_app_lib::ipc::{AudioBuffer, ClientError, ControlApi, Endpoint, IpcClient, IpcServer};
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const TOKEN: &str = "0123456789abcdef";
+
+#[derive(Default)]
+struct MockApi {
+    calls: Mutex<Vec<String>>,
+    audio: Mutex<Option<AudioBuffer>>,
+}
+
+impl MockApi {
+    fn calls(&self) -> Vec<String> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    fn record(&self, call: String) {
+        self.calls.lock().unwrap().push(call);
+    }
+}
+
+impl ControlApi for MockApi {
+    fn start_recording(&self, binding_id: &str) -> Result<bool, String> {
+        self.record(format!("start {}", binding_id));
+        match binding_id {
+            "missing" => Err("Unknown binding: missing".to_string()),
+            _ => Ok(true),
+        }
+    }
+
+    fn stop_recording(&self, binding_id: &str) -> Result<bool, String> {
+        self.record(format!("stop {}", binding_id));
+        Ok(false)
+    }
+
+    fn toggle_recording(&self, binding_id: &str) -> Result<bool, String> {
+        self.record(format!("toggle {}", binding_id));
+        Ok(true)
+    }
+
+    fn cancel(&self) -> Result<(), String> {
+        self.record("cancel".to_string());
+        Ok(())
+    }
+
+    fn state(&self) -> Result<Value, String> {
+        Ok(json!({ "recording": false }))
+    }
+
+    fn transcribe(&self, audio: AudioBuffer, deliver_to: Option<&str>) -> Result<String, String> {
+        self.record(format!("transcribe {:?}", deliver_to));
+        *self.audio.lock().unwrap() = Some(audio);
+        Ok("hello world".to_string())
+    }
+
+    fn list_models(&self) -> Result<Value, String> {
+        Ok(json!({ "selected": "small", "models": [] }))
+    }
+
+    fn select_model(&self, model_id: &str) -> Result<(), String> {
+        self.record(format!("select {}", model_id));
+        Ok(())
+    }
+}
+
+fn start_server(dir: &tempfile::TempDir) -> (IpcServer, Arc<MockApi>) {
+    let api = Arc::new(MockApi::default());
+    let server = IpcServer::start(
+        Endpoint::for_data_dir(dir.path()),
+        TOKEN.to_string(),
+        api.clone(),
+    )
+    .unwrap();
+    (server, api)
+}
+
+fn connect(server: &IpcServer) -> IpcClient {
+    let client = IpcClient::connect(server.endpoint(), TOKEN).unwrap();
+    client.set_timeout(Some(Duration::from_secs(5))).unwrap();
+    client
+}
+
+fn rpc_code(result: Result<Value, ClientError>) -> i64 {
+    match result {
+        Err(ClientError::Rpc(e)) => e.code,
+        other => panic!("expected an RPC error, got {:?}", other),
+    }
+}
+
+fn b64(bytes: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+/// Sends raw lines, bypassing the client, and returns what comes back.
+#[cfg(unix)]
+fn raw_exchange(server: &IpcServer, lines: &[&str]) -> Vec<Value> {
+    use std::io::{BufRead, BufReader, Write};
+
+    let Endpoint::Unix(path) = server.endpoint() else {
+        unreachable!()
+    };
+    let mut stream = std::os::unix::net::UnixStream::connect(path).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    for line in lines {
+        writeln!(stream, "{}", line).unwrap();
+    }
+    // The server answers everything, then sees the end and hangs up
+    let _ = stream.shutdown(std::net::Shutdown::Write);
+    BufReader::new(stream)
+        .lines()
+        .map_while(Result::ok)
+        .map(|line| serde_json::from_str(&line).unwrap())
+        .collect()
+}
+
+#[test]
+fn wrong_token_is_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let (server, _api) = start_server(&dir);
+
+    match IpcClient::connect(server.endpoint(), "not-the-token") {
+        Err(ClientError::Rpc(e)) => assert_eq!(e.code, UNAUTHORIZED),
+        other => panic!("expected an auth error, got {:?}", other.err()),
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn requests_before_auth_close_the_connection() {
+    let dir = tempfile::tempdir().unwrap();
+    let (server, api) = start_server(&dir);
+
+    let replies = raw_exchange(
+        &server,
+        &[
+            r#"{"jsonrpc":"2.0","id":1,"method":"recording.start"}"#,
+            r#"{"jsonrpc":"2.0","id":2,"method":"recording.start"}"#,
+        ],
+    );
+    assert_eq!(replies.len(), 1);
+    assert_eq!(replies[0]["error"]["code"], UNAUTHORIZED);
+    assert!(api.calls().is_empty());
+}
+
+#[cfg(unix)]
+#[test]
+fn long_lines_before_auth_close_the_connection() {
+    use std::io::{Read, Write};
+
+    let dir = tempfile::tempdir().unwrap();
+    let (server, _api) = start_server(&dir);
+    let Endpoint::Unix(path) = server.endpoint() else {
+        unreachable!()
+    };
+
+    // A valid auth request, padded past what an unauthenticated client may send
+    let auth = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "auth",
+        "params": { "token": TOKEN, "padding": "x".repeat(64 * 1024) },
+    });
+    let mut stream = std::os::unix::net::UnixStream::connect(path).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    // The server may hang up before all of it is written
+    let _ = writeln!(stream, "{}", auth);
+
+    let mut reply = Vec::new();
+    let _ = stream.read_to_end(&mut reply);
+    assert!(reply.is_empty());
+
+    // Once authenticated, large requests are fine
+    let mut client = connect(&server);
+    let pcm = vec![0u8; 64 * 1024];
+    client
+        .call(
+            "transcribe",
+            json!({ "pcm": b64(&pcm), "rate": 16000, "channels": 1, "format": "s16le" }),
+        )
+        .unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn malformed_lines_get_protocol_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let (server, _api) = start_server(&dir);
+
+    let auth = format!(
+        r#"{{"jsonrpc":"2.0","id":1,"method":"auth","params":{{"token":"{}"}}}}"#,
+        TOKEN
+    );
+    let replies = raw_exchange(
+        &server,
+        &[
+            &auth,
+            "not json",
+            r#"{"jsonrpc":"1.0","id":3,"method":"state"}"#,
+            r#"{"jsonrpc":"2.0","method":"recording.cancel"}"#,
+            r#"{"jsonrpc":"2.0","id":"last","method":"state"}"#,
+        ],
+    );
+    assert_eq!(replies.len(), 4, "notifications get no response");
+    assert_eq!(replies[0]["result"], true);
+    assert_eq!(replies[1]["error"]["code"], -32700);
+    assert_eq!(replies[2]["error"]["code"], -32600);
+    assert_eq!(replies[2]["id"], 3);
+    assert_eq!(replies[3]["id"], "last");
+}
+
+#[test]
+fn recording_methods_default_to_the_transcribe_binding() {
+    let dir = tempfile::tempdir().unwrap();
+    let (server, api) = start_server(&dir);
+    let mut client = connect(&server);
+
+    assert_eq!(
+        client.call("recording.start", Value::Null).unwrap(),
+        json!(true)
+    );
+    assert_eq!(
+        client
+            .call("recording.stop", json!({ "binding": "notes" }))
+            .unwrap(),
+        json!(false)
+    );
+    assert_eq!(
+        client.call("recording.toggle", Value::Null).unwrap(),
+        json!(true)
+    );
+    assert_eq!(
+        client.call("recording.cancel", json!({})).unwrap(),
+        Value::Null
+    );
+    assert_eq!(
+        api.calls(),
+        vec![
+            "start transcribe",
+            "stop notes",
+            "toggle transcribe",
+            "cancel"
+        ]
+    );
+}
+
+#[test]
+fn app_errors_are_reported() {
+    let dir = tempfile::tempdir().unwrap();
+    let (server, _api) = start_server(&dir);
+    let mut client = connect(&server);
+
+    match client.call("recording.start", json!({ "binding": "missing" })) {
+        Err(ClientError::Rpc(e)) => {
+            assert_eq!(e.code, -32000);
+            assert!(e.message.contains("missing"));
+        }
+        other => panic!("expected an app error, got {:?}", other),
+    }
+    // The connection survives an error
+    assert_eq!(
+        client.call("state", Value::Null).unwrap()["recording"],
+        false
+    );
+}
+
+#[test]
+fn models_can_be_listed_and_selected() {
+    let dir = tempfile::tempdir().unwrap();
+    let (server, api) = start_server(&dir);
+    let mut client = connect(&server);
+
+    assert_eq!(
+        client.call("models.list", Value::Null).unwrap()["selected"],
+        "small"
+    );
+    client
+        .call("models.select", json!({ "id": "parakeet-tdt-0.6b-v3" }))
+        .unwrap();
+    assert_eq!(api.calls(), vec!["select parakeet-tdt-0.6b-v3"]);
+
+    assert_eq!(
+        rpc_code(client.call("models.select", json!({}))),
+        INVALID_PARAMS
+    );
+}
+
+#[test]
+fn transcribe_decodes_wav_audio() {
+    let dir = tempfile::tempdir().unwrap();
+    let (server, api) = start_server(&dir);
+    let mut client = connect(&server);
+
+    let wav = b"RIFF....WAVEfmt ".to_vec();
+    let result = client
+        .call(
+            "transcribe",
+            json!({ "wav": b64(&wav), "deliver": "transcribe" }),
+        )
+        .unwrap();
+
+    assert_eq!(result, json!({ "text": "hello world" }));
+    assert_eq!(api.calls(), vec![r#"transcribe Some("transcribe")"#]);
+    assert_eq!(*api.audio.lock().unwrap(), Some(AudioBuffer::Wav(wav)));
+}
+
+#[test]
+fn transcribe_decodes_pcm_audio() {
+    let dir = tempfile::tempdir().unwrap();
+    let (server, api) = start_server(&dir);
+    let mut client = connect(&server);
+
+    let pcm: Vec<u8> = (0..64u8).collect();
+    client
+        .call(
+            "transcribe",
+            json!({ "pcm": b64(&pcm), "rate": 48000, "channels": 2, "format": "f32le" }),
+        )
+        .unwrap();
+
+    assert_eq!(
+        *api.audio.lock().unwrap(),
+        Some(AudioBuffer::Pcm {
+            data: pcm,
+            sample_rate: 48000,
+            channels: 2,
+            format: PcmFormat::F32Le,
+        })
+    );
+    assert_eq!(api.calls(), vec!["transcribe None"]);
+}
+
+#[test]
+fn transcribe_rejects_bad_audio_params() {
+    let dir = tempfile::tempdir().unwrap();
+    let (server, api) = start_server(&dir);
+    let mut client = connect(&server);
+
+    let pcm = b64(&[0u8; 32]);
+    for params in [
+        json!({}),
+        json!({ "wav": "AAAA", "pcm": pcm }),
+        json!({ "wav": "not base64!" }),
+        json!({ "pcm": pcm }),
+        json!({ "pcm": pcm, "rate": 16000, "channels": 0 }),
+        json!({ "pcm": pcm, "rate": 16000, "format": "u8" }),
+        json!({ "wav": "AAAA", "unexpected": 1 }),
+    ] {
+        assert_eq!(
+            rpc_code(client.call("transcribe", params.clone())),
+            INVALID_PARAMS,
+            "{}",
+            params
+        );
+    }
+    assert!(api.calls().is_empty());
+}
+
+#[test]
+fn unknown_methods_are_reported() {
+    let dir = tempfile::tempdir().unwrap();
+    let (server, _api) = start_server(&dir);
+    let mut client = connect(&server);
+
+    assert_eq!(
+        rpc_code(client.call("recording.pause", Value::Null)),
+        METHOD_NOT_FOUND
+    );
+}
+
+#[test]
+fn events_reach_only_subscribed_clients() {
+    let dir = tempfile::tempdir().unwrap();
+    let (server, _api) = start_server(&dir);
+    let mut all = connect(&server);
+    let mut queue_only = connect(&server);
+    let mut unsubscribed = connect(&server);
+
+    all.call("events.subscribe", Value::Null).unwrap();
+    queue_only
+        .call(
+            "events.subscribe",
+            json!({ "events": ["transcription-queue-changed"] }),
+        )
+        .unwrap();
+
+    let publisher = server.publisher();
+    publisher.publish("model-state-changed", json!({ "event_type": "loading" }));
+    publisher.publish("transcription-queue-changed", json!({ "pending": 1 }));
+
+    let first = all.next_event().unwrap();
+    assert_eq!(first.event, "model-state-changed");
+    assert_eq!(first.payload["event_type"], "loading");
+    assert_eq!(
+        all.next_event().unwrap().event,
+        "transcription-queue-changed"
+    );
+
+    let event = queue_only.next_event().unwrap();
+    assert_eq!(event.event, "transcription-queue-changed");
+    assert_eq!(event.payload["pending"], 1);
+
+    // A client that never subscribed sees only its own responses
+    unsubscribed
+        .set_timeout(Some(Duration::from_millis(200)))
+        .unwrap();
+    assert!(matches!(unsubscribed.next_event(), Err(ClientError::Io(_))));
+}
+
+#[test]
+fn events_arriving_during_a_call_are_kept() {
+    let dir = tempfile::tempdir().unwrap();
+    let (server, _api) = start_server(&dir);
+    let mut client = connect(&server);
+
+    client.call("events.subscribe", Value::Null).unwrap();
+    server
+        .publisher()
+        .publish("output-failed", json!({ "sink": "webhook" }));
+    assert_eq!(
+        client.call("state", Value::Null).unwrap()["recording"],
+        false
+    );
+    assert_eq!(client.next_event().unwrap().event, "output-failed");
+
+    client.call("events.unsubscribe", Value::Null).unwrap();
+    server.publisher().publish("output-failed", Value::Null);
+    client
+        .set_timeout(Some(Duration::from_millis(200)))
+        .unwrap();
+    assert!(client.next_event().is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn slow_subscribers_are_dropped_without_holding_up_others() {
+    use std::io::{Read, Write};
+
+    let dir = tempfile::tempdir().unwrap();
+    let (server, _api) = start_server(&dir);
+    let Endpoint::Unix(path) = server.endpoint() else {
+        unreachable!()
+    };
+
+    // Subscribes, then never reads again
+    let mut slow = std::os::unix::net::UnixStream::connect(path).unwrap();
+    writeln!(
+        slow,
+        r#"{{"jsonrpc":"2.0","id":1,"method":"auth","params":{{"token":"{}"}}}}"#,
+        TOKEN
+    )
+    .unwrap();
+    writeln!(
+        slow,
+        r#"{{"jsonrpc":"2.0","id":2,"method":"events.subscribe"}}"#
+    )
+    .unwrap();
+
+    let mut fast = connect(&server);
+    fast.call("events.subscribe", Value::Null).unwrap();
+    // Let the slow client's subscription land before publishing
+    std::thread::sleep(Duration::from_millis(100));
+
+    let padding = "x".repeat(2048);
+    let publisher = server.publisher();
+    let started = std::time::Instant::now();
+    for n in 0..1000 {
+        publisher.publish("output-failed", json!({ "n": n, "padding": padding }));
+        assert_eq!(fast.next_event().unwrap().payload["n"], n);
+    }
+    // Waiting on the slow client would have taken the 2 s write timeout
+    assert!(started.elapsed() < Duration::from_secs(2));
+
+    // The slow client was hung up on once it fell too far behind
+    slow.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+    let mut rest = Vec::new();
+    slow.read_to_end(&mut rest).unwrap();
+    assert!(rest.len() < 1000 * padding.len());
+}
+
+#[cfg(unix)]
+#[test]
+fn connections_that_never_authenticate_dont_take_client_slots() {
+    let dir = tempfile::tempdir().unwrap();
+    let (server, _api) = start_server(&dir);
+    let Endpoint::Unix(path) = server.endpoint() else {
+        unreachable!()
+    };
+
+    let _idle: Vec<_> = (0..4)
+        .map(|_| std::os::unix::net::UnixStream::connect(path).unwrap())
+        .collect();
+    let clients: Vec<_> = (0..16).map(|_| connect(&server)).collect();
+
+    match IpcClient::connect(server.endpoint(), TOKEN) {
+        Err(ClientError::Rpc(e)) => assert!(e.message.contains("Too many")),
+        other => panic!("expected to be refused, got {:?}", other.err()),
+    }
+    drop(clients);
+}
+
+#[test]
+fn tcp_endpoint_serves_loopback_only() {
+    let api = Arc::new(MockApi::default());
+    let server = IpcServer::start(
+        Endpoint::Tcp(SocketAddr::from(([127, 0, 0, 1], 0))),
+        TOKEN.to_string(),
+        api.clone(),
+    )
+    .unwrap();
+    let Endpoint::Tcp(addr) = server.endpoint() else {
+        panic!("expected a TCP endpoint");
+    };
+    assert_ne!(addr.port(), 0);
+
+    let mut client = connect(&server);
+    client.call("recording.start", Value::Null).unwrap();
+    assert_eq!(api.calls(), vec!["start transcribe"]);
+
+    let public = IpcServer::start(
+        Endpoint::Tcp(SocketAddr::from(([0, 0, 0, 0], 0))),
+        TOKEN.to_string(),
+        api,
+    );
+    assert!(public.is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn socket_is_private_and_removed_on_drop() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let (server, _api) = start_server(&dir);
+    let Endpoint::Unix(path) = server.endpoint().clone() else {
+        unreachable!()
+    };
+    let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+
+    // A second server for the same directory must not steal the socket
+    let api = Arc::new(MockApi::default());
+    assert!(IpcServer::start(Endpoint::for_data_dir(dir.path()), TOKEN.to_string(), api).is_err());
+
+    drop(server);
+    assert!(!path.exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn stale_socket_is_replaced() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(handy_app_lib::ipc::SOCKET_FILE);
+    drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+    assert!(path.exists());
+
+    let (server, _api) = start_server(&dir);
+    let mut client = connect(&server);
+    assert!(client.call("state", Value::Null).is_ok());
+}
+
+#[test]
+fn discover_reads_the_token_file() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join(handy_app_lib::ipc::TOKEN_FILE), "abc\n").unwrap();
+    #[cfg(not(unix))]
+    std::fs::write(dir.path().join(handy_app_lib::ipc::PORT_FILE), "4242").unwrap();
+
+    let (endpoint, token) = Endpoint::discover(dir.path()).unwrap();
+    assert_eq!(token, "abc");
+    #[cfg(unix)]
+    assert_eq!(endpoint, Endpoint::for_data_dir(dir.path()));
+    #[cfg(not(unix))]
+    assert_eq!(
+        endpoint,
+        Endpoint::Tcp(SocketAddr::from(([127, 0, 0, 1], 4242)))
+    );
+}
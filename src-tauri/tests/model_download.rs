@@ -0,0 +1,201 @@
+//! Downloads models from a local HTTP server through the same path the app
+//! uses: fetch to a partial file, check it against the catalog, then extract
+//! and swap the model into place.
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use handy_app_lib::host::{HeadlessHost, Host};
+use handy_app_lib::managers::integrity::VerificationStatus;
+use handy_app_lib::managers::model::ModelManager;
+use sha2::{Digest, Sha256};
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+const ENCODER_BYTES: &[u8] = b"encoder weights";
+const VOCAB: &str = "hello\nworld\n";
+
+/// A `.tar.gz` holding a single model directory, as the catalog publishes them.
+fn model_archive() -> Vec<u8> {
+    let mut archive = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    for (path, data) in [
+        ("test-model/encoder.onnx", ENCODER_BYTES),
+        ("test-model/vocab.txt", VOCAB.as_bytes()),
+    ] {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        archive.append_data(&mut header, path, data).unwrap();
+    }
+    archive.into_inner().unwrap().finish().unwrap()
+}
+
+/// Serves `routes` over plain HTTP/1.1, one request per connection.
+fn serve(routes: Vec<(&'static str, Vec<u8>)>) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                match stream.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => request.extend_from_slice(&buf[..n]),
+                }
+            }
+            let request = String::from_utf8_lossy(&request);
+            let path = request.split_whitespace().nth(1).unwrap_or_default();
+
+            let response = match routes.iter().find(|(route, _)| *route == path) {
+                Some((_, body)) => {
+                    let mut response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        body.len()
+                    )
+                    .into_bytes();
+                    response.extend_from_slice(body);
+                    response
+                }
+                None => b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    .to_vec(),
+            };
+            let _ = stream.write_all(&response);
+        }
+    });
+
+    addr
+}
+
+/// A cached catalog newer than the bundled one, so it replaces it on startup.
+fn write_catalog(data_dir: &Path, addr: SocketAddr, sha256: &str) {
+    let model = |id: &str, routes: &[&str]| {
+        serde_json::json!({
+            "id": id,
+            "name": id,
+            "description": "Test model",
+            "engine": "Parakeet",
+            "filename": id,
+            "is_directory": true,
+            "urls": routes
+                .iter()
+                .map(|route| format!("http://{}{}", addr, route))
+                .collect::<Vec<_>>(),
+            "size_mb": 1,
+            "sha256": sha256,
+            "accuracy_score": 0.5,
+            "speed_score": 0.5
+        })
+    };
+    let catalog = serde_json::json!({
+        "schema_version": 1,
+        "catalog_version": u32::MAX,
+        "models": [
+            model("good", &["/good.tar.gz"]),
+            model("corrupt", &["/corrupt.tar.gz"]),
+            model("mirrored", &["/missing.tar.gz", "/good.tar.gz"]),
+            model("missing", &["/missing.tar.gz", "/gone.tar.gz"])
+        ]
+    });
+    std::fs::write(
+        data_dir.join("model_catalog.json"),
+        serde_json::to_vec(&catalog).unwrap(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn downloads_are_verified_before_they_are_installed() {
+    let good = model_archive();
+    let sha256 = hex::encode(Sha256::digest(&good));
+
+    // Same length, one byte flipped in the middle
+    let mut corrupt = good.clone();
+    let middle = corrupt.len() / 2;
+    corrupt[middle] ^= 0xff;
+
+    let addr = serve(vec![("/good.tar.gz", good), ("/corrupt.tar.gz", corrupt)]);
+
+    let data_dir = tempfile::tempdir().unwrap();
+    write_catalog(data_dir.path(), addr, &sha256);
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let seen = events.clone();
+    let host = HeadlessHost::new(data_dir.path().to_path_buf(), data_dir.path().join("res"))
+        .with_event_handler(move |event, _| seen.lock().unwrap().push(event.to_string()));
+    let host: Arc<dyn Host> = Arc::new(host);
+    let manager = ModelManager::new(host).unwrap();
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let models_dir = data_dir.path().join("models");
+
+    // A good archive is checked, extracted and moved into place
+    runtime.block_on(manager.download_model("good")).unwrap();
+
+    let model_dir = models_dir.join("good");
+    assert_eq!(
+        std::fs::read(model_dir.join("encoder.onnx")).unwrap(),
+        ENCODER_BYTES
+    );
+    assert_eq!(
+        std::fs::read_to_string(model_dir.join("vocab.txt")).unwrap(),
+        VOCAB
+    );
+    assert!(!models_dir.join("good.partial").exists());
+    assert!(!models_dir.join("good.extracting").exists());
+    assert!(manager.get_model_info("good").unwrap().is_downloaded);
+    assert_eq!(
+        manager.verify_model("good").unwrap().status,
+        VerificationStatus::Verified
+    );
+
+    // A corrupted one is rejected and nothing of it is left behind
+    let error = runtime
+        .block_on(manager.download_model("corrupt"))
+        .unwrap_err();
+    assert!(
+        error.to_string().to_lowercase().contains("checksum"),
+        "unexpected error: {}",
+        error
+    );
+    assert!(!models_dir.join("corrupt").exists());
+    assert!(!models_dir.join("corrupt.partial").exists());
+    assert!(!models_dir.join("corrupt.extracting").exists());
+
+    let info = manager.get_model_info("corrupt").unwrap();
+    assert!(!info.is_downloaded);
+    assert_eq!(info.partial_size, 0);
+
+    // A mirror that doesn't have the model hands over to the next one
+    runtime
+        .block_on(manager.download_model("mirrored"))
+        .unwrap();
+    assert!(manager.get_model_info("mirrored").unwrap().is_downloaded);
+
+    // And only when none of them has it does the download fail
+    let error = runtime
+        .block_on(manager.download_model("missing"))
+        .unwrap_err();
+    assert!(
+        format!("{:#}", error).contains("404"),
+        "unexpected error: {:#}",
+        error
+    );
+    assert!(!manager.get_model_info("missing").unwrap().is_downloaded);
+
+    let events = events.lock().unwrap();
+    assert!(events.iter().any(|e| e == "model-verification-failed"));
+    assert_eq!(
+        events
+            .iter()
+            .filter(|e| *e == "model-extraction-started")
+            .count(),
+        2
+    );
+}
@@ -125,6 +125,57 @@ async changeClipboardHandlingSetting(handling: string) : Promise<Result<null, st
     else return { status: "error", error: e  as any };
 }
 },
+async changeClipboardExpirySetting(expiry: ClipboardExpiry) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_clipboard_expiry_setting", { expiry }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Sets where a binding's transcripts go; every sink runs, in order.
+ */
+async changeBindingOutputsSetting(bindingId: string, outputs: OutputSinkConfig[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_binding_outputs_setting", { bindingId, outputs }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeIpcEnabledSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_ipc_enabled_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeSignalBindingsSetting(bindings: SignalBinding[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_signal_bindings_setting", { bindings }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changePasteToolSetting(tool: PasteTool) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_paste_tool_setting", { tool }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changePasteFallbackSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_paste_fallback_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async updateCustomWords(words: string[]) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("update_custom_words", { words }) };
@@ -207,6 +258,102 @@ async changeHidePrivacyNoticeSetting(hide: boolean) : Promise<Result<null, strin
     else return { status: "error", error: e  as any };
 }
 },
+async changePasteIntoOriginalWindowSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_paste_into_original_window_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeModelCatalogUrlSetting(url: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_model_catalog_url_setting", { url }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeModelSigningPublicKeySetting(publicKey: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_model_signing_public_key_setting", { publicKey }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeModelLatencyTargetSetting(rtf: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_model_latency_target_setting", { rtf }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changePreloadModelOnStartupSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_preload_model_on_startup_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeLowMemoryUnloadThresholdSetting(thresholdMb: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_low_memory_unload_threshold_setting", { thresholdMb }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeClinicHoursSetting(clinicHours: ClinicHours | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_clinic_hours_setting", { clinicHours }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeAutoStopSetting(autoStop: AutoStopSettings) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_auto_stop_setting", { autoStop }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changePreRollSetting(preRollMs: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_pre_roll_setting", { preRollMs }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeDspSettings(dsp: DspConfig) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_dsp_settings", { dsp }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeVadSettings(vad: VadSettings) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_vad_settings", { vad }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeDiarizationSettings(diarization: DiarizationSettings) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_diarization_settings", { diarization }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async changeUpdateChecksSetting(enabled: boolean) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("change_update_checks_setting", { enabled }) };
@@ -354,6 +501,14 @@ async cancelDownload(modelId: string) : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+async getDownloadQueue() : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_download_queue") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async setActiveModel(modelId: string) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("set_active_model", { modelId }) };
@@ -410,6 +565,90 @@ async getRecommendedFirstModel() : Promise<Result<string, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Runs each downloaded model (or just `model_ids`) on the bundled reference
+ * clip and stores the measurements for model recommendations.
+ */
+async benchmarkModels(modelIds: string[] | null) : Promise<Result<BenchmarkResult[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("benchmark_models", { modelIds }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getBenchmarkResults() : Promise<Result<BenchmarkResult[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_benchmark_results") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getParakeetModelOptions(modelId: string) : Promise<Result<ParakeetModelOptions, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_parakeet_model_options", { modelId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Saves engine options for a Parakeet model. If that model is loaded it is
+ * reloaded straight away so the options apply without a restart.
+ */
+async setParakeetModelOptions(modelId: string, options: ParakeetOptions) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_parakeet_model_options", { modelId, options }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async refreshModelCatalog() : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("refresh_model_catalog") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Re-checks installed models against their checksums. Checks every downloaded
+ * model when `model_id` is `None`.
+ */
+async verifyModel(modelId: string | null) : Promise<Result<ModelVerification[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("verify_model", { modelId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async importModel(path: string, engineType: EngineType, displayName: string) : Promise<Result<ModelInfo, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_model", { path, engineType, displayName }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async installModelPack(path: string, allowUnknown: boolean) : Promise<Result<PackInstallResult[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("install_model_pack", { path, allowUnknown }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async exportModelPack(modelIds: string[], destination: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_model_pack", { modelIds, destination }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async updateMicrophoneMode(alwaysOn: boolean) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("update_microphone_mode", { alwaysOn }) };
@@ -450,6 +689,50 @@ async getSelectedMicrophone() : Promise<Result<string, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Sets how `device_name`'s channels are folded into mono. Fails if the
+ * device is connected and doesn't have the chosen channel. Takes effect
+ * straight away if the device is in use, or when the recording in
+ * progress ends.
+ */
+async setMicrophoneChannelPolicy(deviceName: string, policy: ChannelPolicy) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_microphone_channel_policy", { deviceName, policy }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getMicrophoneChannelPolicy(deviceName: string) : Promise<ChannelPolicy> {
+    return await TAURI_INVOKE("get_microphone_channel_policy", { deviceName });
+},
+/**
+ * Starts streaming `vad-calibration` events with the VAD's decision for
+ * every 30 ms frame, so thresholds can be tuned against the room.
+ */
+async startVadCalibration() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_vad_calibration") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async stopVadCalibration() : Promise<void> {
+    await TAURI_INVOKE("stop_vad_calibration");
+},
+/**
+ * Records five seconds from the current microphone and reports its levels,
+ * clipping and speech-to-noise ratio. Nothing is transcribed or kept.
+ */
+async testMicrophone() : Promise<Result<QualityReport, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("test_microphone") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async getAvailableOutputDevices() : Promise<Result<AudioDevice[], string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("get_available_output_devices") };
@@ -518,6 +801,14 @@ async unloadModelManually() : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+async getTranscriptionQueue() : Promise<Result<QueueState, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_transcription_queue") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
  * Checks if the Mac is a laptop by detecting battery presence
  * 
@@ -544,22 +835,264 @@ async isLaptop() : Promise<Result<boolean, string>> {
 
 /** user-defined types **/
 
-export type AppSettings = { bindings: Partial<{ [key in string]: ShortcutBinding }>; push_to_talk: boolean; audio_feedback: boolean; audio_feedback_volume?: number; sound_theme?: SoundTheme; start_hidden?: boolean; autostart_enabled?: boolean; update_checks_enabled?: boolean; selected_model?: string; always_on_microphone?: boolean; selected_microphone?: string | null; clamshell_microphone?: string | null; selected_output_device?: string | null; translate_to_english?: boolean; selected_language?: string; overlay_position?: OverlayPosition; debug_mode?: boolean; log_level?: LogLevel; custom_words?: string[]; model_unload_timeout?: ModelUnloadTimeout; word_correction_threshold?: number; history_limit?: number; recording_retention_period?: RecordingRetentionPeriod; paste_method?: PasteMethod; clipboard_handling?: ClipboardHandling; mute_while_recording?: boolean; append_trailing_space?: boolean; app_language?: string; medical_mode_enabled?: boolean; setup_completed?: boolean; hide_privacy_notice?: boolean }
-export type AudioDevice = { index: string; name: string; is_default: boolean }
+export type AppSettings = { bindings: Partial<{ [key in string]: ShortcutBinding }>; push_to_talk: boolean; audio_feedback: boolean; audio_feedback_volume?: number; sound_theme?: SoundTheme; start_hidden?: boolean; autostart_enabled?: boolean; update_checks_enabled?: boolean; selected_model?: string; always_on_microphone?: boolean; selected_microphone?: string | null; clamshell_microphone?: string | null; 
+/**
+ * How each microphone's channels become mono, by device name; averaged if absent
+ */
+microphone_channels?: Partial<{ [key in string]: ChannelPolicy }>; selected_output_device?: string | null; translate_to_english?: boolean; selected_language?: string; overlay_position?: OverlayPosition; debug_mode?: boolean; log_level?: LogLevel; custom_words?: string[]; model_unload_timeout?: ModelUnloadTimeout; word_correction_threshold?: number; history_limit?: number; recording_retention_period?: RecordingRetentionPeriod; paste_method?: PasteMethod; clipboard_handling?: ClipboardHandling; 
+/**
+ * Clears text copied with `CopyToClipboard` after this long, if the
+ * clipboard still holds it
+ */
+clipboard_expiry?: ClipboardExpiry; paste_tool?: PasteTool; 
+/**
+ * Type the text out when the clipboard can't be written or the paste
+ * keystroke can't be sent. Not when the target just didn't read it in
+ * time, since it may still paste and the text would appear twice
+ */
+paste_fallback_to_typing?: boolean; mute_while_recording?: boolean; append_trailing_space?: boolean; app_language?: string; medical_mode_enabled?: boolean; setup_completed?: boolean; hide_privacy_notice?: boolean; paste_into_original_window?: boolean; model_catalog_url?: string | null; model_signing_public_key?: string | null; 
+/**
+ * Slowest real-time factor a benchmarked model may have to be recommended
+ */
+model_latency_target_rtf?: number; 
+/**
+ * Load the selected model when the app starts (including at login with autostart)
+ */
+preload_model_on_startup?: boolean; 
+/**
+ * Unload the model when available system memory drops below this; 0 disables
+ */
+low_memory_unload_threshold_mb?: number; clinic_hours?: ClinicHours | null; 
+/**
+ * Parakeet engine options keyed by model id; missing entries use the defaults
+ */
+parakeet_model_options?: Partial<{ [key in string]: ParakeetOptions }>; vad?: VadSettings; auto_stop?: AutoStopSettings; 
+/**
+ * Audio kept from just before recording starts, always-on microphone only; 0 disables
+ */
+pre_roll_ms?: number; 
+/**
+ * High-pass, noise suppression and gain stages; all off by default
+ */
+dsp?: DspConfig; 
+/**
+ * Label who said what; off by default
+ */
+diarization?: DiarizationSettings; 
+/**
+ * Where each binding's transcripts go, by binding id; missing bindings paste
+ */
+binding_outputs?: Partial<{ [key in string]: OutputSinkConfig[] }>; 
+/**
+ * Accept commands and audio from local scripts over the control socket
+ */
+ipc_enabled?: boolean; 
+/**
+ * Extra real-time signals and the bindings they drive
+ */
+signal_bindings?: SignalBinding[] }
+export type AudioDevice = { index: string; name: string; is_default: boolean; 
+/**
+ * Channel counts the device can be opened with
+ */
+channels: number[]; 
+/**
+ * Common sample rates the device supports
+ */
+sample_rates: number[] }
+/**
+ * Hands-free endpointing: end a recording without a key press.
+ */
+export type AutoStopSettings = { enabled?: boolean; 
+/**
+ * Silence after speech that ends the recording; 0 to stop only on the
+ * other limits
+ */
+trailing_silence_ms?: number; 
+/**
+ * Longest recording; 0 for no limit
+ */
+max_duration_secs?: number; 
+/**
+ * Cancel a recording in which nothing was said for this long; 0 to wait forever
+ */
+no_speech_timeout_secs?: number }
+/**
+ * How one model performed on this machine.
+ */
+export type BenchmarkResult = { model_id: string; load_ms: number; transcribe_ms: number; audio_ms: number; 
+/**
+ * Processing time divided by audio duration; below 1.0 is faster than real time
+ */
+real_time_factor: number; 
+/**
+ * Resident memory added while loading and running the model, when the
+ * platform can report it
+ */
+peak_memory_mb: number | null; 
+/**
+ * Word error rate against the reference transcript, 0.0 is a perfect match
+ */
+word_error_rate: number; transcript: string; 
+/**
+ * Seconds since the Unix epoch
+ */
+measured_at: number }
 export type BindingResponse = { success: boolean; binding: ShortcutBinding | null; error: string | null }
+/**
+ * How a multi-channel input is turned into the mono signal we record.
+ */
+export type ChannelPolicy = { mode: "average" } | { mode: "channel"; index: number } | { mode: "loudest" }
+/**
+ * Times of day when the transcription model stays loaded regardless of the
+ * unload timeout.
+ */
+export type ClinicHours = { 
+/**
+ * Local time, "HH:MM"
+ */
+start: string; 
+/**
+ * Local time, "HH:MM"; earlier than `start` for shifts past midnight
+ */
+end: string; 
+/**
+ * ISO weekdays the shift starts on, 1 = Monday ... 7 = Sunday; empty means every day
+ */
+weekdays?: number[] }
+/**
+ * How long text copied with `CopyToClipboard` stays on the clipboard.
+ */
+export type ClipboardExpiry = "never" | "sec_30" | "min_2" | "min_5"
 export type ClipboardHandling = "dont_modify" | "copy_to_clipboard"
 export type CustomSounds = { start: boolean; stop: boolean }
+/**
+ * Speaker-attributed output for encounter recordings.
+ */
+export type DiarizationSettings = { enabled?: boolean; 
+/**
+ * WeSpeaker-style speaker embedding model (.onnx)
+ */
+model_path?: string | null; 
+/**
+ * One label per expected speaker, in order of who speaks first
+ */
+speaker_labels?: string[] }
+/**
+ * Optional clean-up between the resampler and the VAD. Each stage can be
+ * turned on separately.
+ */
+export type DspConfig = { 
+/**
+ * Remove DC offset and rumble below speech
+ */
+high_pass: boolean; 
+/**
+ * Spectral subtraction of steady background noise (HVAC, fans)
+ */
+noise_suppression: boolean; 
+/**
+ * Bring quiet or very loud recordings to a standard level before transcription
+ */
+normalize_gain: boolean }
 export type EngineType = "Whisper" | "Parakeet"
 export type GithubRelease = { tagName: string; name: string; htmlUrl: string; publishedAt: string }
+export type JobStatus = "recording" | "queued" | "transcribing"
+export type JobSummary = { id: number; binding_id: string; status: JobStatus }
 export type LogLevel = "trace" | "debug" | "info" | "warn" | "error"
-export type ModelInfo = { id: string; name: string; description: string; filename: string; url: string | null; size_mb: number; is_downloaded: boolean; is_downloading: boolean; partial_size: number; is_directory: boolean; engine_type: EngineType; accuracy_score: number; speed_score: number; platform_recommended: boolean }
+export type ModelInfo = { id: string; name: string; description: string; filename: string; url: string | null; urls: string[]; size_mb: number; sha256: string | null; languages: string[]; license: string | null; is_downloaded: boolean; is_downloading: boolean; partial_size: number; is_directory: boolean; engine_type: EngineType; accuracy_score: number; speed_score: number; platform_recommended: boolean }
 export type ModelLoadStatus = { is_loaded: boolean; current_model: string | null }
 export type ModelUnloadTimeout = "never" | "immediately" | "min_2" | "min_5" | "min_10" | "min_15" | "hour_1" | "sec_5"
+export type ModelVerification = { model_id: string; status: VerificationStatus; detail: string | null }
+/**
+ * Where a finished transcript goes. Bindings without an entry in
+ * `binding_outputs` paste it.
+ */
+export type OutputSinkConfig = { type: "paste" } | { type: "file"; path: string; template?: string } | { type: "pipe"; path: string } | { type: "stdout" } | { type: "webhook"; url: string }
 export type OverlayPosition = "none" | "top" | "bottom"
+export type PackInstallResult = { model_id: string; status: PackInstallStatus; error: string | null }
+export type PackInstallStatus = "installed" | "already_installed" | "failed"
+/**
+ * What a model directory and this machine can run, for the settings UI.
+ */
+export type ParakeetCapabilities = { quantizations: ParakeetQuantization[]; execution_providers: ParakeetExecutionProvider[]; max_threads: number }
+export type ParakeetExecutionProvider = "cpu" | "core_ml" | "cuda" | "direct_ml"
+export type ParakeetModelOptions = { options: ParakeetOptions; capabilities: ParakeetCapabilities }
+/**
+ * Engine options for one Parakeet model, persisted per model id.
+ */
+export type ParakeetOptions = { quantization?: ParakeetQuantization; 
+/**
+ * Intra-op threads; `None` uses every core. A lower cap keeps other
+ * applications responsive while a transcription runs.
+ */
+threads?: number | null; execution_provider?: ParakeetExecutionProvider }
+export type ParakeetQuantization = "fp_32" | "int_8"
 export type PasteMethod = "ctrl_v" | "direct" | "none" | "shift_insert" | "ctrl_shift_v"
+/**
+ * Program that sends the paste keystroke, and types the text when pasting
+ * falls back to typing. The command-line tools are Linux only.
+ */
+export type PasteTool = "auto" | "enigo" | "xdotool" | "ydotool" | "wtype" | "dotool"
+/**
+ * Input levels over one recording, measured before any processing.
+ */
+export type QualityReport = { duration_secs: number; rms_db: number; peak_db: number; 
+/**
+ * Share of samples at full scale
+ */
+clipping_ratio: number; 
+/**
+ * Speech level over the noise level, when the VAD saw both
+ */
+snr_db: number | null; warnings: QualityWarning[] }
+export type QualityWarning = "silent" | "too_quiet" | "clipping"
+/**
+ * Snapshot of the queue sent to the frontend and overlay on every change
+ */
+export type QueueState = { jobs: JobSummary[]; is_recording: boolean; 
+/**
+ * Number of jobs captured but not yet pasted
+ */
+pending: number }
 export type RecordingRetentionPeriod = "never" | "preserve_limit" | "days_3" | "weeks_2" | "months_3"
 export type ShortcutBinding = { id: string; name: string; description: string; default_binding: string; current_binding: string }
+/**
+ * Maps `SIGRTMIN + offset` to a binding (Linux only). Offsets 0 and 1 are
+ * taken by "stop transcribing" and "cancel".
+ */
+export type SignalBinding = { offset: number; binding_id: string; mode?: SignalMode }
+/**
+ * What a real-time signal does to its binding.
+ */
+export type SignalMode = "toggle" | "start" | "stop"
 export type SoundTheme = "marimba" | "pop" | "custom"
+export type VadBackend = "silero" | "energy"
+/**
+ * Voice activity detection tuning. Frame counts are 30 ms frames.
+ */
+export type VadSettings = { backend?: VadBackend; 
+/**
+ * Silero speech probability above which a frame counts as speech
+ */
+threshold?: number; 
+/**
+ * dB above the noise floor for the energy detector
+ */
+energy_margin_db?: number; 
+/**
+ * Frames kept from before speech was detected
+ */
+prefill_frames?: number; 
+/**
+ * Frames kept after speech stops
+ */
+hangover_frames?: number; 
+/**
+ * Consecutive speech frames needed before speech starts
+ */
+onset_frames?: number }
+export type VerificationStatus = "verified" | "mismatch" | "not_installed" | "unverifiable"
 
 /** tauri-specta globals **/
 
@@ -12,6 +12,7 @@ interface ModelStateEvent {
   model_id?: string;
   model_name?: string;
   error?: string;
+  reason?: string;
 }
 
 interface DownloadProgress {
@@ -0,0 +1,47 @@
+import React from "react";
+import { useTranslation } from "react-i18next";
+import { ToggleSwitch } from "../ui/ToggleSwitch";
+import { useSettings } from "../../hooks/useSettings";
+
+interface AudioProcessingProps {
+  descriptionMode?: "inline" | "tooltip";
+  grouped?: boolean;
+}
+
+const STAGES = ["high_pass", "noise_suppression", "normalize_gain"] as const;
+
+const LABEL_KEYS: Record<(typeof STAGES)[number], string> = {
+  high_pass: "highPass",
+  noise_suppression: "noiseSuppression",
+  normalize_gain: "normalizeGain",
+};
+
+export const AudioProcessing: React.FC<AudioProcessingProps> = React.memo(
+  ({ descriptionMode = "tooltip", grouped = false }) => {
+    const { t } = useTranslation();
+    const { getSetting, updateSetting, isUpdating } = useSettings();
+    const dsp = getSetting("dsp");
+    if (!dsp) return null;
+
+    return (
+      <>
+        {STAGES.map((stage) => (
+          <ToggleSwitch
+            key={stage}
+            checked={dsp[stage]}
+            onChange={(enabled) =>
+              updateSetting("dsp", { ...dsp, [stage]: enabled })
+            }
+            isUpdating={isUpdating("dsp")}
+            label={t(`settings.advanced.dsp.${LABEL_KEYS[stage]}.label`)}
+            description={t(
+              `settings.advanced.dsp.${LABEL_KEYS[stage]}.description`,
+            )}
+            descriptionMode={descriptionMode}
+            grouped={grouped}
+          />
+        ))}
+      </>
+    );
+  },
+);
@@ -0,0 +1,95 @@
+import React from "react";
+import { useTranslation } from "react-i18next";
+import type { AutoStopSettings } from "@/bindings";
+import { Slider } from "../ui/Slider";
+import { ToggleSwitch } from "../ui/ToggleSwitch";
+import { useSettings } from "../../hooks/useSettings";
+
+interface AutoStopProps {
+  descriptionMode?: "inline" | "tooltip";
+  grouped?: boolean;
+}
+
+export const AutoStop: React.FC<AutoStopProps> = React.memo(
+  ({ descriptionMode = "tooltip", grouped = false }) => {
+    const { t } = useTranslation();
+    const { getSetting, updateSetting, isUpdating } = useSettings();
+    const autoStop = getSetting("auto_stop");
+    if (!autoStop) return null;
+
+    const update = (changes: Partial<AutoStopSettings>) =>
+      updateSetting("auto_stop", { ...autoStop, ...changes });
+    const enabled = autoStop.enabled ?? false;
+    const off = t("settings.advanced.autoStop.off");
+
+    return (
+      <>
+        <ToggleSwitch
+          checked={enabled}
+          onChange={(value) => update({ enabled: value })}
+          isUpdating={isUpdating("auto_stop")}
+          label={t("settings.advanced.autoStop.label")}
+          description={t("settings.advanced.autoStop.description")}
+          descriptionMode={descriptionMode}
+          grouped={grouped}
+        />
+        {enabled && (
+          <>
+            {/* Anything under the 0.3 s minimum turns the silence limit off */}
+            <Slider
+              value={(autoStop.trailing_silence_ms ?? 1500) / 1000}
+              onChange={(value) =>
+                update({
+                  trailing_silence_ms:
+                    value < 0.3 ? 0 : Math.round(value * 1000),
+                })
+              }
+              min={0}
+              max={10}
+              step={0.1}
+              label={t("settings.advanced.autoStop.trailingSilence.title")}
+              description={t(
+                "settings.advanced.autoStop.trailingSilence.description",
+              )}
+              descriptionMode={descriptionMode}
+              grouped={grouped}
+              formatValue={(value) =>
+                value < 0.3 ? off : `${value.toFixed(1)} s`
+              }
+            />
+            <Slider
+              value={(autoStop.max_duration_secs ?? 300) / 60}
+              onChange={(value) =>
+                update({ max_duration_secs: Math.round(value * 60) })
+              }
+              min={0}
+              max={60}
+              step={1}
+              label={t("settings.advanced.autoStop.maxDuration.title")}
+              description={t(
+                "settings.advanced.autoStop.maxDuration.description",
+              )}
+              descriptionMode={descriptionMode}
+              grouped={grouped}
+              formatValue={(value) => (value === 0 ? off : `${value} min`)}
+            />
+            <Slider
+              value={autoStop.no_speech_timeout_secs ?? 10}
+              onChange={(value) => update({ no_speech_timeout_secs: value })}
+              min={0}
+              max={60}
+              step={1}
+              label={t("settings.advanced.autoStop.noSpeechTimeout.title")}
+              description={t(
+                "settings.advanced.autoStop.noSpeechTimeout.description",
+              )}
+              descriptionMode={descriptionMode}
+              grouped={grouped}
+              formatValue={(value) => (value === 0 ? off : `${value} s`)}
+            />
+          </>
+        )}
+      </>
+    );
+  },
+);
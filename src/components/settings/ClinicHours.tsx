@@ -0,0 +1,103 @@
+import React from "react";
+import { useTranslation } from "react-i18next";
+import type { ClinicHours as Hours } from "@/bindings";
+import { Input } from "../ui/Input";
+import { SettingContainer } from "../ui/SettingContainer";
+import { ToggleSwitch } from "../ui/ToggleSwitch";
+import { useSettings } from "../../hooks/useSettings";
+
+interface ClinicHoursProps {
+  descriptionMode?: "inline" | "tooltip";
+  grouped?: boolean;
+}
+
+const DEFAULT_HOURS: Hours = {
+  start: "08:00",
+  end: "18:00",
+  weekdays: [1, 2, 3, 4, 5],
+};
+
+// ISO weekdays, Monday first
+const WEEKDAYS = [1, 2, 3, 4, 5, 6, 7];
+
+export const ClinicHours: React.FC<ClinicHoursProps> = React.memo(
+  ({ descriptionMode = "tooltip", grouped = false }) => {
+    const { t } = useTranslation();
+    const { getSetting, updateSetting, isUpdating } = useSettings();
+    const hours = getSetting("clinic_hours") ?? null;
+    const updating = isUpdating("clinic_hours");
+
+    const update = (changes: Partial<Hours>) =>
+      hours && updateSetting("clinic_hours", { ...hours, ...changes });
+
+    const toggleWeekday = (day: number) => {
+      const weekdays = hours?.weekdays ?? [];
+      update({
+        weekdays: weekdays.includes(day)
+          ? weekdays.filter((d) => d !== day)
+          : [...weekdays, day].sort((a, b) => a - b),
+      });
+    };
+
+    return (
+      <>
+        <ToggleSwitch
+          checked={hours !== null}
+          onChange={(enabled) =>
+            updateSetting("clinic_hours", enabled ? DEFAULT_HOURS : null)
+          }
+          isUpdating={updating}
+          label={t("settings.models.clinicHours.label")}
+          description={t("settings.models.clinicHours.description")}
+          descriptionMode={descriptionMode}
+          grouped={grouped}
+        />
+        {hours && (
+          <SettingContainer
+            title={t("settings.models.clinicHours.schedule.title")}
+            description={t("settings.models.clinicHours.schedule.description")}
+            descriptionMode={descriptionMode}
+            grouped={grouped}
+            layout="stacked"
+          >
+            <div className="space-y-2">
+              <div className="flex items-center gap-2">
+                <Input
+                  type="time"
+                  variant="compact"
+                  value={hours.start}
+                  onChange={(e) => update({ start: e.target.value })}
+                  disabled={updating}
+                />
+                <span className="text-sm">
+                  {t("settings.models.clinicHours.schedule.to")}
+                </span>
+                <Input
+                  type="time"
+                  variant="compact"
+                  value={hours.end}
+                  onChange={(e) => update({ end: e.target.value })}
+                  disabled={updating}
+                />
+              </div>
+              {/* No days selected means every day */}
+              <div className="flex gap-1">
+                {WEEKDAYS.map((day) => (
+                  <label key={day} className="flex items-center gap-1 text-sm">
+                    <input
+                      type="checkbox"
+                      checked={(hours.weekdays ?? []).includes(day)}
+                      onChange={() => toggleWeekday(day)}
+                      disabled={updating}
+                    />
+                    {t(`settings.models.clinicHours.weekdays.${day}`)}
+                  </label>
+                ))}
+              </div>
+            </div>
+          </SettingContainer>
+        )}
+      </>
+    );
+  },
+);
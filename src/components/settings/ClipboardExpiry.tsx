@@ -0,0 +1,58 @@
+import React from "react";
+import { useTranslation } from "react-i18next";
+import { Dropdown } from "../ui/Dropdown";
+import { SettingContainer } from "../ui/SettingContainer";
+import { useSettings } from "../../hooks/useSettings";
+import type { ClipboardExpiry } from "@/bindings";
+
+interface ClipboardExpiryProps {
+  descriptionMode?: "inline" | "tooltip";
+  grouped?: boolean;
+}
+
+export const ClipboardExpirySetting: React.FC<ClipboardExpiryProps> =
+  React.memo(({ descriptionMode = "tooltip", grouped = false }) => {
+    const { t } = useTranslation();
+    const { getSetting, updateSetting, isUpdating } = useSettings();
+
+    // Only text left on the clipboard on purpose can expire
+    const copying = getSetting("clipboard_handling") === "copy_to_clipboard";
+
+    const options = [
+      {
+        value: "never",
+        label: t("settings.advanced.clipboardExpiry.options.never"),
+      },
+      {
+        value: "sec_30",
+        label: t("settings.advanced.clipboardExpiry.options.sec30"),
+      },
+      {
+        value: "min_2",
+        label: t("settings.advanced.clipboardExpiry.options.min2"),
+      },
+      {
+        value: "min_5",
+        label: t("settings.advanced.clipboardExpiry.options.min5"),
+      },
+    ];
+
+    return (
+      <SettingContainer
+        title={t("settings.advanced.clipboardExpiry.title")}
+        description={t("settings.advanced.clipboardExpiry.description")}
+        descriptionMode={descriptionMode}
+        grouped={grouped}
+        disabled={!copying}
+      >
+        <Dropdown
+          options={options}
+          selectedValue={getSetting("clipboard_expiry") ?? "never"}
+          onSelect={(value) =>
+            updateSetting("clipboard_expiry", value as ClipboardExpiry)
+          }
+          disabled={!copying || isUpdating("clipboard_expiry")}
+        />
+      </SettingContainer>
+    );
+  });
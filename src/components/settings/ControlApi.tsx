@@ -0,0 +1,28 @@
+import React from "react";
+import { useTranslation } from "react-i18next";
+import { ToggleSwitch } from "../ui/ToggleSwitch";
+import { useSettings } from "../../hooks/useSettings";
+
+interface ControlApiProps {
+  descriptionMode?: "inline" | "tooltip";
+  grouped?: boolean;
+}
+
+export const ControlApi: React.FC<ControlApiProps> = React.memo(
+  ({ descriptionMode = "tooltip", grouped = false }) => {
+    const { t } = useTranslation();
+    const { getSetting, updateSetting, isUpdating } = useSettings();
+
+    return (
+      <ToggleSwitch
+        checked={getSetting("ipc_enabled") ?? false}
+        onChange={(enabled) => updateSetting("ipc_enabled", enabled)}
+        isUpdating={isUpdating("ipc_enabled")}
+        label={t("settings.advanced.controlApi.label")}
+        description={t("settings.advanced.controlApi.description")}
+        descriptionMode={descriptionMode}
+        grouped={grouped}
+      />
+    );
+  },
+);
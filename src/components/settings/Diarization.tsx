@@ -0,0 +1,97 @@
+import React, { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import type { DiarizationSettings } from "@/bindings";
+import { Input } from "../ui/Input";
+import { SettingContainer } from "../ui/SettingContainer";
+import { ToggleSwitch } from "../ui/ToggleSwitch";
+import { useSettings } from "../../hooks/useSettings";
+
+interface DiarizationProps {
+  descriptionMode?: "inline" | "tooltip";
+  grouped?: boolean;
+}
+
+export const Diarization: React.FC<DiarizationProps> = React.memo(
+  ({ descriptionMode = "tooltip", grouped = false }) => {
+    const { t } = useTranslation();
+    const { getSetting, updateSetting, isUpdating } = useSettings();
+    const diarization = getSetting("diarization");
+    const [modelPath, setModelPath] = useState("");
+    const [labels, setLabels] = useState("");
+
+    useEffect(() => {
+      setModelPath(diarization?.model_path ?? "");
+      setLabels((diarization?.speaker_labels ?? []).join(", "));
+    }, [diarization]);
+
+    if (!diarization) return null;
+
+    const update = (changes: Partial<DiarizationSettings>) =>
+      updateSetting("diarization", { ...diarization, ...changes });
+
+    const commitModelPath = () => {
+      const path = modelPath.trim() || null;
+      if (path !== (diarization.model_path ?? null)) {
+        update({ model_path: path });
+      }
+    };
+
+    const commitLabels = () => {
+      const speakerLabels = labels
+        .split(",")
+        .map((label) => label.trim())
+        .filter((label) => label.length > 0);
+      const current = diarization.speaker_labels ?? [];
+      if (speakerLabels.join(",") !== current.join(",")) {
+        update({ speaker_labels: speakerLabels });
+      }
+    };
+
+    return (
+      <>
+        <ToggleSwitch
+          checked={diarization.enabled ?? false}
+          onChange={(enabled) => update({ enabled })}
+          isUpdating={isUpdating("diarization")}
+          label={t("settings.advanced.diarization.label")}
+          description={t("settings.advanced.diarization.description")}
+          descriptionMode={descriptionMode}
+          grouped={grouped}
+        />
+        <SettingContainer
+          title={t("settings.advanced.diarization.modelPath.title")}
+          description={t("settings.advanced.diarization.modelPath.description")}
+          descriptionMode={descriptionMode}
+          grouped={grouped}
+        >
+          <Input
+            type="text"
+            className="w-64"
+            value={modelPath}
+            onChange={(e) => setModelPath(e.target.value)}
+            onBlur={commitModelPath}
+            placeholder={t(
+              "settings.advanced.diarization.modelPath.placeholder",
+            )}
+            disabled={isUpdating("diarization")}
+          />
+        </SettingContainer>
+        <SettingContainer
+          title={t("settings.advanced.diarization.speakers.title")}
+          description={t("settings.advanced.diarization.speakers.description")}
+          descriptionMode={descriptionMode}
+          grouped={grouped}
+        >
+          <Input
+            type="text"
+            className="w-64"
+            value={labels}
+            onChange={(e) => setLabels(e.target.value)}
+            onBlur={commitLabels}
+            disabled={isUpdating("diarization")}
+          />
+        </SettingContainer>
+      </>
+    );
+  },
+);
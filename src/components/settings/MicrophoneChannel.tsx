@@ -0,0 +1,96 @@
+import React, { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { toast } from "sonner";
+import { commands, type ChannelPolicy } from "@/bindings";
+import { Dropdown } from "../ui/Dropdown";
+import { SettingContainer } from "../ui/SettingContainer";
+import { useSettings } from "../../hooks/useSettings";
+
+interface MicrophoneChannelProps {
+  descriptionMode?: "inline" | "tooltip";
+  grouped?: boolean;
+}
+
+const policyValue = (policy: ChannelPolicy) =>
+  policy.mode === "channel" ? `channel:${policy.index}` : policy.mode;
+
+const parsePolicy = (value: string): ChannelPolicy =>
+  value.startsWith("channel:")
+    ? { mode: "channel", index: Number(value.slice("channel:".length)) }
+    : { mode: value as "average" | "loudest" };
+
+export const MicrophoneChannel: React.FC<MicrophoneChannelProps> = React.memo(
+  ({ descriptionMode = "tooltip", grouped = false }) => {
+    const { t } = useTranslation();
+    const { getSetting, audioDevices } = useSettings();
+    const [policy, setPolicy] = useState<ChannelPolicy>({ mode: "average" });
+    const [updating, setUpdating] = useState(false);
+
+    const selected = getSetting("selected_microphone");
+    // The system default can change under us, so policies are per named device
+    const deviceName =
+      selected && selected !== "Default" && selected !== "default"
+        ? selected
+        : null;
+    const device = audioDevices.find((d) => d.name === deviceName);
+    const channelCount = Math.max(0, ...(device?.channels ?? []));
+
+    useEffect(() => {
+      if (!deviceName) return;
+      commands.getMicrophoneChannelPolicy(deviceName).then(setPolicy);
+    }, [deviceName]);
+
+    const handleSelect = async (value: string) => {
+      if (!deviceName) return;
+      const next = parsePolicy(value);
+      setUpdating(true);
+      try {
+        const result = await commands.setMicrophoneChannelPolicy(
+          deviceName,
+          next,
+        );
+        if (result.status === "ok") {
+          setPolicy(next);
+        } else {
+          toast.error(result.error);
+        }
+      } finally {
+        setUpdating(false);
+      }
+    };
+
+    const options = [
+      {
+        value: "average",
+        label: t("settings.sound.microphoneChannel.options.average"),
+      },
+      {
+        value: "loudest",
+        label: t("settings.sound.microphoneChannel.options.loudest"),
+      },
+      ...Array.from({ length: channelCount }, (_, index) => ({
+        value: `channel:${index}`,
+        label: t("settings.sound.microphoneChannel.options.channel", {
+          number: index + 1,
+        }),
+      })),
+    ];
+
+    return (
+      <SettingContainer
+        title={t("settings.sound.microphoneChannel.title")}
+        description={t("settings.sound.microphoneChannel.description")}
+        descriptionMode={descriptionMode}
+        grouped={grouped}
+        disabled={!deviceName}
+      >
+        <Dropdown
+          options={options}
+          selectedValue={deviceName ? policyValue(policy) : "average"}
+          onSelect={handleSelect}
+          disabled={!deviceName || updating}
+        />
+      </SettingContainer>
+    );
+  },
+);
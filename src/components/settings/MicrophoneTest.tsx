@@ -0,0 +1,85 @@
+import React, { useState } from "react";
+import { useTranslation } from "react-i18next";
+import { toast } from "sonner";
+import { commands, type QualityReport } from "@/bindings";
+import { Button } from "../ui/Button";
+import { SettingContainer } from "../ui/SettingContainer";
+
+interface MicrophoneTestProps {
+  descriptionMode?: "inline" | "tooltip";
+  grouped?: boolean;
+}
+
+export const MicrophoneTest: React.FC<MicrophoneTestProps> = React.memo(
+  ({ descriptionMode = "tooltip", grouped = false }) => {
+    const { t } = useTranslation();
+    const [testing, setTesting] = useState(false);
+    const [report, setReport] = useState<QualityReport | null>(null);
+
+    const handleTest = async () => {
+      setTesting(true);
+      setReport(null);
+      try {
+        const result = await commands.testMicrophone();
+        if (result.status === "ok") {
+          setReport(result.data);
+        } else {
+          toast.error(result.error);
+        }
+      } finally {
+        setTesting(false);
+      }
+    };
+
+    return (
+      <SettingContainer
+        title={t("settings.sound.microphoneTest.title")}
+        description={t("settings.sound.microphoneTest.description")}
+        descriptionMode={descriptionMode}
+        grouped={grouped}
+        layout="stacked"
+      >
+        <div className="space-y-2">
+          <Button
+            variant="secondary"
+            size="sm"
+            onClick={handleTest}
+            disabled={testing}
+          >
+            {testing
+              ? t("settings.sound.microphoneTest.testing")
+              : t("settings.sound.microphoneTest.button")}
+          </Button>
+          {report && (
+            <div className="text-sm space-y-1">
+              <p>
+                {t("settings.sound.microphoneTest.levels", {
+                  rms: report.rms_db.toFixed(1),
+                  peak: report.peak_db.toFixed(1),
+                })}
+              </p>
+              {report.snr_db !== null && (
+                <p>
+                  {t("settings.sound.microphoneTest.snr", {
+                    snr: report.snr_db.toFixed(1),
+                  })}
+                </p>
+              )}
+              {report.warnings.length === 0 ? (
+                <p className="text-mid-gray">
+                  {t("settings.sound.microphoneTest.ok")}
+                </p>
+              ) : (
+                report.warnings.map((warning) => (
+                  <p key={warning} className="text-red-500">
+                    {t(`settings.sound.microphoneTest.warnings.${warning}`)}
+                  </p>
+                ))
+              )}
+            </div>
+          )}
+        </div>
+      </SettingContainer>
+    );
+  },
+);
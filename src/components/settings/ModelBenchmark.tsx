@@ -0,0 +1,88 @@
+import React, { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { toast } from "sonner";
+import { commands, type BenchmarkResult } from "@/bindings";
+import { Button } from "../ui/Button";
+import { SettingContainer } from "../ui/SettingContainer";
+import { Slider } from "../ui/Slider";
+import { useSettings } from "../../hooks/useSettings";
+
+interface ModelBenchmarkProps {
+  descriptionMode?: "inline" | "tooltip";
+  grouped?: boolean;
+}
+
+export const ModelBenchmark: React.FC<ModelBenchmarkProps> = React.memo(
+  ({ descriptionMode = "tooltip", grouped = false }) => {
+    const { t } = useTranslation();
+    const { getSetting, updateSetting } = useSettings();
+    const [benchmarking, setBenchmarking] = useState(false);
+    const [benchmarks, setBenchmarks] = useState<BenchmarkResult[]>([]);
+
+    useEffect(() => {
+      commands.getBenchmarkResults().then((result) => {
+        if (result.status === "ok") setBenchmarks(result.data);
+      });
+    }, []);
+
+    const handleBenchmark = async () => {
+      setBenchmarking(true);
+      try {
+        const result = await commands.benchmarkModels(null);
+        if (result.status === "ok") {
+          setBenchmarks(result.data);
+        } else {
+          toast.error(result.error);
+        }
+      } finally {
+        setBenchmarking(false);
+      }
+    };
+
+    return (
+      <>
+        <Slider
+          value={getSetting("model_latency_target_rtf") ?? 0.3}
+          onChange={(value) => updateSetting("model_latency_target_rtf", value)}
+          min={0.05}
+          max={1}
+          step={0.05}
+          label={t("settings.models.latencyTarget.title")}
+          description={t("settings.models.latencyTarget.description")}
+          descriptionMode={descriptionMode}
+          grouped={grouped}
+          formatValue={(value) => `${value.toFixed(2)}×`}
+        />
+        <SettingContainer
+          title={t("settings.models.benchmark.title")}
+          description={t("settings.models.benchmark.description")}
+          descriptionMode={descriptionMode}
+          grouped={grouped}
+          layout="stacked"
+        >
+          <div className="space-y-2">
+            <Button
+              variant="secondary"
+              size="sm"
+              onClick={handleBenchmark}
+              disabled={benchmarking}
+            >
+              {benchmarking
+                ? t("settings.models.benchmark.running")
+                : t("settings.models.benchmark.button")}
+            </Button>
+            {benchmarks.map((benchmark) => (
+              <p key={benchmark.model_id} className="text-sm">
+                {t("settings.models.benchmark.result", {
+                  model: benchmark.model_id,
+                  rtf: benchmark.real_time_factor.toFixed(2),
+                  wer: (benchmark.word_error_rate * 100).toFixed(1),
+                })}
+              </p>
+            ))}
+          </div>
+        </SettingContainer>
+      </>
+    );
+  },
+);
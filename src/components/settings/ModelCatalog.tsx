@@ -0,0 +1,78 @@
+import React, { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { toast } from "sonner";
+import { commands } from "@/bindings";
+import { Button } from "../ui/Button";
+import { Input } from "../ui/Input";
+import { SettingContainer } from "../ui/SettingContainer";
+import { useSettings } from "../../hooks/useSettings";
+
+interface ModelCatalogProps {
+  descriptionMode?: "inline" | "tooltip";
+  grouped?: boolean;
+}
+
+export const ModelCatalog: React.FC<ModelCatalogProps> = React.memo(
+  ({ descriptionMode = "tooltip", grouped = false }) => {
+    const { t } = useTranslation();
+    const { getSetting, updateSetting, isUpdating } = useSettings();
+    const savedUrl = getSetting("model_catalog_url") ?? "";
+    const [url, setUrl] = useState("");
+    const [refreshing, setRefreshing] = useState(false);
+
+    useEffect(() => {
+      setUrl(savedUrl);
+    }, [savedUrl]);
+
+    const commitUrl = () => {
+      if (url.trim() !== savedUrl) {
+        updateSetting("model_catalog_url", url.trim() || null);
+      }
+    };
+
+    const handleRefresh = async () => {
+      setRefreshing(true);
+      try {
+        const result = await commands.refreshModelCatalog();
+        if (result.status === "ok") {
+          toast.success(
+            t("settings.models.catalog.refreshed", { count: result.data }),
+          );
+        } else {
+          toast.error(result.error);
+        }
+      } finally {
+        setRefreshing(false);
+      }
+    };
+
+    return (
+      <SettingContainer
+        title={t("settings.models.catalog.url.title")}
+        description={t("settings.models.catalog.url.description")}
+        descriptionMode={descriptionMode}
+        grouped={grouped}
+      >
+        <div className="flex items-center gap-2">
+          <Input
+            type="text"
+            className="w-64"
+            value={url}
+            onChange={(e) => setUrl(e.target.value)}
+            onBlur={commitUrl}
+            placeholder={t("settings.models.catalog.url.placeholder")}
+            disabled={isUpdating("model_catalog_url")}
+          />
+          <Button
+            variant="secondary"
+            size="sm"
+            onClick={handleRefresh}
+            disabled={refreshing || !savedUrl}
+          >
+            {t("settings.models.catalog.refresh")}
+          </Button>
+        </div>
+      </SettingContainer>
+    );
+  },
+);
@@ -0,0 +1,90 @@
+import React, { useState } from "react";
+import { useTranslation } from "react-i18next";
+import { toast } from "sonner";
+import { commands, type EngineType } from "@/bindings";
+import { Button } from "../ui/Button";
+import { Dropdown } from "../ui/Dropdown";
+import { Input } from "../ui/Input";
+import { SettingContainer } from "../ui/SettingContainer";
+
+interface ModelImportProps {
+  descriptionMode?: "inline" | "tooltip";
+  grouped?: boolean;
+}
+
+export const ModelImport: React.FC<ModelImportProps> = React.memo(
+  ({ descriptionMode = "tooltip", grouped = false }) => {
+    const { t } = useTranslation();
+    const [busy, setBusy] = useState(false);
+    const [path, setPath] = useState("");
+    const [engineType, setEngineType] = useState<EngineType>("Whisper");
+    const [displayName, setDisplayName] = useState("");
+
+    const handleImport = async () => {
+      setBusy(true);
+      try {
+        const result = await commands.importModel(
+          path.trim(),
+          engineType,
+          displayName.trim(),
+        );
+        if (result.status === "ok") {
+          toast.success(
+            t("settings.models.import.done", { name: result.data.name }),
+          );
+          setPath("");
+          setDisplayName("");
+        } else {
+          toast.error(result.error);
+        }
+      } finally {
+        setBusy(false);
+      }
+    };
+
+    return (
+      <SettingContainer
+        title={t("settings.models.import.title")}
+        description={t("settings.models.import.description")}
+        descriptionMode={descriptionMode}
+        grouped={grouped}
+        layout="stacked"
+      >
+        <div className="flex flex-wrap items-center gap-2">
+          <Input
+            type="text"
+            className="flex-1"
+            value={path}
+            onChange={(e) => setPath(e.target.value)}
+            placeholder={t("settings.models.import.pathPlaceholder")}
+            disabled={busy}
+          />
+          <Dropdown
+            options={[
+              { value: "Whisper", label: "Whisper" },
+              { value: "Parakeet", label: "Parakeet" },
+            ]}
+            selectedValue={engineType}
+            onSelect={(value) => setEngineType(value as EngineType)}
+            disabled={busy}
+          />
+          <Input
+            type="text"
+            value={displayName}
+            onChange={(e) => setDisplayName(e.target.value)}
+            placeholder={t("settings.models.import.namePlaceholder")}
+            disabled={busy}
+          />
+          <Button
+            variant="secondary"
+            size="sm"
+            onClick={handleImport}
+            disabled={busy || !path.trim() || !displayName.trim()}
+          >
+            {t("settings.models.import.button")}
+          </Button>
+        </div>
+      </SettingContainer>
+    );
+  },
+);
@@ -0,0 +1,100 @@
+import React, { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { toast } from "sonner";
+import { commands, type ModelVerification } from "@/bindings";
+import { Button } from "../ui/Button";
+import { Input } from "../ui/Input";
+import { SettingContainer } from "../ui/SettingContainer";
+import { useSettings } from "../../hooks/useSettings";
+
+interface ModelIntegrityProps {
+  descriptionMode?: "inline" | "tooltip";
+  grouped?: boolean;
+}
+
+export const ModelIntegrity: React.FC<ModelIntegrityProps> = React.memo(
+  ({ descriptionMode = "tooltip", grouped = false }) => {
+    const { t } = useTranslation();
+    const { getSetting, updateSetting, isUpdating } = useSettings();
+    const savedKey = getSetting("model_signing_public_key") ?? "";
+    const [publicKey, setPublicKey] = useState("");
+    const [verifying, setVerifying] = useState(false);
+    const [verifications, setVerifications] = useState<ModelVerification[]>(
+      [],
+    );
+
+    useEffect(() => {
+      setPublicKey(savedKey);
+    }, [savedKey]);
+
+    const commitKey = () => {
+      if (publicKey.trim() !== savedKey) {
+        updateSetting("model_signing_public_key", publicKey.trim() || null);
+      }
+    };
+
+    const handleVerify = async () => {
+      setVerifying(true);
+      try {
+        const result = await commands.verifyModel(null);
+        if (result.status === "ok") {
+          setVerifications(result.data);
+        } else {
+          toast.error(result.error);
+        }
+      } finally {
+        setVerifying(false);
+      }
+    };
+
+    return (
+      <>
+        <SettingContainer
+          title={t("settings.models.catalog.publicKey.title")}
+          description={t("settings.models.catalog.publicKey.description")}
+          descriptionMode={descriptionMode}
+          grouped={grouped}
+        >
+          <Input
+            type="text"
+            className="w-64"
+            value={publicKey}
+            onChange={(e) => setPublicKey(e.target.value)}
+            onBlur={commitKey}
+            disabled={isUpdating("model_signing_public_key")}
+          />
+        </SettingContainer>
+        <SettingContainer
+          title={t("settings.models.verify.title")}
+          description={t("settings.models.verify.description")}
+          descriptionMode={descriptionMode}
+          grouped={grouped}
+          layout="stacked"
+        >
+          <div className="space-y-2">
+            <Button
+              variant="secondary"
+              size="sm"
+              onClick={handleVerify}
+              disabled={verifying}
+            >
+              {verifying
+                ? t("settings.models.verify.running")
+                : t("settings.models.verify.button")}
+            </Button>
+            {verifications.map((verification) => (
+              <p key={verification.model_id} className="text-sm">
+                {t("settings.models.verify.result", {
+                  model: verification.model_id,
+                  status: t(
+                    `settings.models.verify.status.${verification.status}`,
+                  ),
+                })}
+              </p>
+            ))}
+          </div>
+        </SettingContainer>
+      </>
+    );
+  },
+);
@@ -0,0 +1,49 @@
+import React from "react";
+import { useTranslation } from "react-i18next";
+import { Slider } from "../ui/Slider";
+import { ToggleSwitch } from "../ui/ToggleSwitch";
+import { useSettings } from "../../hooks/useSettings";
+
+interface ModelLoadingProps {
+  descriptionMode?: "inline" | "tooltip";
+  grouped?: boolean;
+}
+
+export const ModelLoading: React.FC<ModelLoadingProps> = React.memo(
+  ({ descriptionMode = "tooltip", grouped = false }) => {
+    const { t } = useTranslation();
+    const { getSetting, updateSetting, isUpdating } = useSettings();
+
+    return (
+      <>
+        <ToggleSwitch
+          checked={getSetting("preload_model_on_startup") ?? false}
+          onChange={(enabled) =>
+            updateSetting("preload_model_on_startup", enabled)
+          }
+          isUpdating={isUpdating("preload_model_on_startup")}
+          label={t("settings.models.preload.label")}
+          description={t("settings.models.preload.description")}
+          descriptionMode={descriptionMode}
+          grouped={grouped}
+        />
+        <Slider
+          value={getSetting("low_memory_unload_threshold_mb") ?? 512}
+          onChange={(value) =>
+            updateSetting("low_memory_unload_threshold_mb", value)
+          }
+          min={0}
+          max={4096}
+          step={128}
+          label={t("settings.models.lowMemory.title")}
+          description={t("settings.models.lowMemory.description")}
+          descriptionMode={descriptionMode}
+          grouped={grouped}
+          formatValue={(value) =>
+            value === 0 ? t("settings.models.lowMemory.off") : `${value} MB`
+          }
+        />
+      </>
+    );
+  },
+);
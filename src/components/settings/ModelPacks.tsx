@@ -0,0 +1,151 @@
+import React, { useState } from "react";
+import { useTranslation } from "react-i18next";
+import { toast } from "sonner";
+import { commands } from "@/bindings";
+import { Button } from "../ui/Button";
+import { Input } from "../ui/Input";
+import { SettingContainer } from "../ui/SettingContainer";
+
+interface ModelPacksProps {
+  descriptionMode?: "inline" | "tooltip";
+  grouped?: boolean;
+}
+
+export const ModelPacks: React.FC<ModelPacksProps> = React.memo(
+  ({ descriptionMode = "tooltip", grouped = false }) => {
+    const { t } = useTranslation();
+    const [busy, setBusy] = useState(false);
+    const [packPath, setPackPath] = useState("");
+    const [allowUnknown, setAllowUnknown] = useState(false);
+    const [exportIds, setExportIds] = useState("");
+    const [exportPath, setExportPath] = useState("");
+
+    const run = async (action: () => Promise<void>) => {
+      setBusy(true);
+      try {
+        await action();
+      } finally {
+        setBusy(false);
+      }
+    };
+
+    const handleInstall = () =>
+      run(async () => {
+        const result = await commands.installModelPack(
+          packPath.trim(),
+          allowUnknown,
+        );
+        if (result.status !== "ok") {
+          toast.error(result.error);
+          return;
+        }
+        for (const model of result.data) {
+          if (model.status === "failed") {
+            toast.error(
+              t("settings.models.pack.failed", {
+                model: model.model_id,
+                error: model.error ?? "",
+              }),
+            );
+          } else {
+            toast.success(
+              t(`settings.models.pack.${model.status}`, {
+                model: model.model_id,
+              }),
+            );
+          }
+        }
+      });
+
+    const handleExport = () =>
+      run(async () => {
+        const modelIds = exportIds
+          .split(",")
+          .map((id) => id.trim())
+          .filter((id) => id.length > 0);
+        const result = await commands.exportModelPack(
+          modelIds,
+          exportPath.trim(),
+        );
+        if (result.status === "ok") {
+          toast.success(t("settings.models.export.done"));
+        } else {
+          toast.error(result.error);
+        }
+      });
+
+    return (
+      <>
+        <SettingContainer
+          title={t("settings.models.pack.title")}
+          description={t("settings.models.pack.description")}
+          descriptionMode={descriptionMode}
+          grouped={grouped}
+          layout="stacked"
+        >
+          <div className="flex flex-wrap items-center gap-2">
+            <Input
+              type="text"
+              className="flex-1"
+              value={packPath}
+              onChange={(e) => setPackPath(e.target.value)}
+              placeholder={t("settings.models.pack.pathPlaceholder")}
+              disabled={busy}
+            />
+            {/* Models the catalog doesn't list are refused unless allowed */}
+            <label className="flex items-center gap-1 text-sm">
+              <input
+                type="checkbox"
+                checked={allowUnknown}
+                onChange={(e) => setAllowUnknown(e.target.checked)}
+                disabled={busy}
+              />
+              {t("settings.models.pack.allowUnknown")}
+            </label>
+            <Button
+              variant="secondary"
+              size="sm"
+              onClick={handleInstall}
+              disabled={busy || !packPath.trim()}
+            >
+              {t("settings.models.pack.button")}
+            </Button>
+          </div>
+        </SettingContainer>
+        <SettingContainer
+          title={t("settings.models.export.title")}
+          description={t("settings.models.export.description")}
+          descriptionMode={descriptionMode}
+          grouped={grouped}
+          layout="stacked"
+        >
+          <div className="flex flex-wrap items-center gap-2">
+            <Input
+              type="text"
+              value={exportIds}
+              onChange={(e) => setExportIds(e.target.value)}
+              placeholder={t("settings.models.export.modelsPlaceholder")}
+              disabled={busy}
+            />
+            <Input
+              type="text"
+              className="flex-1"
+              value={exportPath}
+              onChange={(e) => setExportPath(e.target.value)}
+              placeholder={t("settings.models.export.pathPlaceholder")}
+              disabled={busy}
+            />
+            <Button
+              variant="secondary"
+              size="sm"
+              onClick={handleExport}
+              disabled={busy || !exportIds.trim() || !exportPath.trim()}
+            >
+              {t("settings.models.export.button")}
+            </Button>
+          </div>
+        </SettingContainer>
+      </>
+    );
+  },
+);
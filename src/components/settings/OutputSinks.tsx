@@ -0,0 +1,155 @@
+import React, { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { toast } from "sonner";
+import { commands, type OutputSinkConfig } from "@/bindings";
+import { Button } from "../ui/Button";
+import { Dropdown } from "../ui/Dropdown";
+import { Input } from "../ui/Input";
+import { SettingContainer } from "../ui/SettingContainer";
+import { useSettings } from "../../hooks/useSettings";
+
+interface OutputSinksProps {
+  descriptionMode?: "inline" | "tooltip";
+  grouped?: boolean;
+}
+
+type SinkType = OutputSinkConfig["type"];
+
+const SINK_TYPES: SinkType[] = ["paste", "file", "pipe", "stdout", "webhook"];
+
+const BINDING_ID = "transcribe";
+
+const emptySink = (type: SinkType): OutputSinkConfig => {
+  switch (type) {
+    case "file":
+    case "pipe":
+      return { type, path: "" };
+    case "webhook":
+      return { type, url: "" };
+    default:
+      return { type };
+  }
+};
+
+const sinkTarget = (sink: OutputSinkConfig) =>
+  sink.type === "webhook"
+    ? sink.url
+    : sink.type === "file" || sink.type === "pipe"
+      ? sink.path
+      : null;
+
+const withTarget = (sink: OutputSinkConfig, target: string) =>
+  sink.type === "webhook"
+    ? { ...sink, url: target }
+    : sink.type === "file" || sink.type === "pipe"
+      ? { ...sink, path: target }
+      : sink;
+
+export const OutputSinks: React.FC<OutputSinksProps> = React.memo(
+  ({ descriptionMode = "tooltip", grouped = false }) => {
+    const { t } = useTranslation();
+    const { getSetting, refreshSettings } = useSettings();
+    const saved = getSetting("binding_outputs")?.[BINDING_ID];
+    // Edited locally so a half-typed path isn't rejected on every keystroke
+    const [sinks, setSinks] = useState<OutputSinkConfig[]>([]);
+    const [updating, setUpdating] = useState(false);
+
+    useEffect(() => {
+      setSinks(saved ?? [{ type: "paste" }]);
+    }, [saved]);
+
+    const commit = async (next: OutputSinkConfig[]) => {
+      setSinks(next);
+      if (JSON.stringify(next) === JSON.stringify(saved)) return;
+      setUpdating(true);
+      try {
+        const result = await commands.changeBindingOutputsSetting(
+          BINDING_ID,
+          next,
+        );
+        if (result.status === "ok") {
+          await refreshSettings();
+        } else {
+          toast.error(result.error);
+        }
+      } finally {
+        setUpdating(false);
+      }
+    };
+
+    const replace = (index: number, sink: OutputSinkConfig) =>
+      sinks.map((current, i) => (i === index ? sink : current));
+
+    const typeOptions = SINK_TYPES.map((type) => ({
+      value: type,
+      label: t(`settings.advanced.outputs.types.${type}`),
+    }));
+
+    return (
+      <SettingContainer
+        title={t("settings.advanced.outputs.title")}
+        description={t("settings.advanced.outputs.description")}
+        descriptionMode={descriptionMode}
+        grouped={grouped}
+        layout="stacked"
+      >
+        <div className="space-y-2">
+          {sinks.map((sink, index) => {
+            const target = sinkTarget(sink);
+            return (
+              <div key={index} className="flex items-center gap-2">
+                <Dropdown
+                  options={typeOptions}
+                  selectedValue={sink.type}
+                  onSelect={(value) => {
+                    const next = replace(index, emptySink(value as SinkType));
+                    // New file, pipe and webhook outputs wait for a target
+                    if (sinkTarget(next[index]) === null) {
+                      commit(next);
+                    } else {
+                      setSinks(next);
+                    }
+                  }}
+                  disabled={updating}
+                />
+                {target !== null && (
+                  <Input
+                    type="text"
+                    className="flex-1"
+                    value={target}
+                    onChange={(e) =>
+                      setSinks(replace(index, withTarget(sink, e.target.value)))
+                    }
+                    onBlur={() => commit(sinks)}
+                    placeholder={t(
+                      sink.type === "webhook"
+                        ? "settings.advanced.outputs.urlPlaceholder"
+                        : "settings.advanced.outputs.pathPlaceholder",
+                    )}
+                    disabled={updating}
+                  />
+                )}
+                <Button
+                  variant="secondary"
+                  size="sm"
+                  onClick={() => commit(sinks.filter((_, i) => i !== index))}
+                  disabled={updating || sinks.length === 1}
+                >
+                  {t("settings.advanced.outputs.remove")}
+                </Button>
+              </div>
+            );
+          })}
+          <Button
+            variant="secondary"
+            size="sm"
+            onClick={() => setSinks([...sinks, { type: "file", path: "" }])}
+            disabled={updating}
+          >
+            {t("settings.advanced.outputs.add")}
+          </Button>
+        </div>
+      </SettingContainer>
+    );
+  },
+);
@@ -0,0 +1,30 @@
+import React from "react";
+import { useTranslation } from "react-i18next";
+import { ToggleSwitch } from "../ui/ToggleSwitch";
+import { useSettings } from "../../hooks/useSettings";
+
+interface PasteFallbackProps {
+  descriptionMode?: "inline" | "tooltip";
+  grouped?: boolean;
+}
+
+export const PasteFallback: React.FC<PasteFallbackProps> = React.memo(
+  ({ descriptionMode = "tooltip", grouped = false }) => {
+    const { t } = useTranslation();
+    const { getSetting, updateSetting, isUpdating } = useSettings();
+
+    return (
+      <ToggleSwitch
+        checked={getSetting("paste_fallback_to_typing") ?? false}
+        onChange={(enabled) =>
+          updateSetting("paste_fallback_to_typing", enabled)
+        }
+        isUpdating={isUpdating("paste_fallback_to_typing")}
+        label={t("settings.advanced.pasteFallback.label")}
+        description={t("settings.advanced.pasteFallback.description")}
+        descriptionMode={descriptionMode}
+        grouped={grouped}
+      />
+    );
+  },
+);
@@ -0,0 +1,29 @@
+import React from "react";
+import { useTranslation } from "react-i18next";
+import { ToggleSwitch } from "../ui/ToggleSwitch";
+import { useSettings } from "../../hooks/useSettings";
+
+interface PasteIntoOriginalWindowProps {
+  descriptionMode?: "inline" | "tooltip";
+  grouped?: boolean;
+}
+
+export const PasteIntoOriginalWindow: React.FC<PasteIntoOriginalWindowProps> =
+  React.memo(({ descriptionMode = "tooltip", grouped = false }) => {
+    const { t } = useTranslation();
+    const { getSetting, updateSetting, isUpdating } = useSettings();
+
+    return (
+      <ToggleSwitch
+        checked={getSetting("paste_into_original_window") ?? false}
+        onChange={(enabled) =>
+          updateSetting("paste_into_original_window", enabled)
+        }
+        isUpdating={isUpdating("paste_into_original_window")}
+        label={t("settings.advanced.pasteIntoOriginalWindow.label")}
+        description={t("settings.advanced.pasteIntoOriginalWindow.description")}
+        descriptionMode={descriptionMode}
+        grouped={grouped}
+      />
+    );
+  });
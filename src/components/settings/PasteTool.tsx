@@ -0,0 +1,57 @@
+import React, { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { type as getOsType } from "@tauri-apps/plugin-os";
+import { Dropdown } from "../ui/Dropdown";
+import { SettingContainer } from "../ui/SettingContainer";
+import { useSettings } from "../../hooks/useSettings";
+import type { PasteTool } from "@/bindings";
+
+interface PasteToolProps {
+  descriptionMode?: "inline" | "tooltip";
+  grouped?: boolean;
+}
+
+// Command-line tools that only exist on Linux
+const LINUX_TOOLS: PasteTool[] = ["xdotool", "ydotool", "wtype", "dotool"];
+
+export const PasteToolSetting: React.FC<PasteToolProps> = React.memo(
+  ({ descriptionMode = "tooltip", grouped = false }) => {
+    const { t } = useTranslation();
+    const { getSetting, updateSetting, isUpdating } = useSettings();
+    const [osType, setOsType] = useState<string>("unknown");
+
+    useEffect(() => {
+      setOsType(getOsType());
+    }, []);
+
+    const options = [
+      {
+        value: "auto",
+        label: t("settings.advanced.pasteTool.options.auto"),
+      },
+      {
+        value: "enigo",
+        label: t("settings.advanced.pasteTool.options.enigo"),
+      },
+      ...(osType === "linux"
+        ? LINUX_TOOLS.map((tool) => ({ value: tool, label: tool }))
+        : []),
+    ];
+
+    return (
+      <SettingContainer
+        title={t("settings.advanced.pasteTool.title")}
+        description={t("settings.advanced.pasteTool.description")}
+        descriptionMode={descriptionMode}
+        grouped={grouped}
+      >
+        <Dropdown
+          options={options}
+          selectedValue={getSetting("paste_tool") ?? "auto"}
+          onSelect={(value) => updateSetting("paste_tool", value as PasteTool)}
+          disabled={isUpdating("paste_tool")}
+        />
+      </SettingContainer>
+    );
+  },
+);
@@ -0,0 +1,123 @@
+import React, { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { type as getOsType } from "@tauri-apps/plugin-os";
+import type { SignalBinding, SignalMode } from "@/bindings";
+import { Button } from "../ui/Button";
+import { Dropdown } from "../ui/Dropdown";
+import { SettingContainer } from "../ui/SettingContainer";
+import { useSettings } from "../../hooks/useSettings";
+
+interface SignalBindingsProps {
+  descriptionMode?: "inline" | "tooltip";
+  grouped?: boolean;
+}
+
+// SIGRTMIN and SIGRTMIN+1 are taken by stop and cancel
+const OFFSETS = Array.from({ length: 14 }, (_, i) => i + 2);
+const MODES: SignalMode[] = ["toggle", "start", "stop"];
+
+export const SignalBindings: React.FC<SignalBindingsProps> = React.memo(
+  ({ descriptionMode = "tooltip", grouped = false }) => {
+    const { t } = useTranslation();
+    const { getSetting, updateSetting, isUpdating } = useSettings();
+    const [osType, setOsType] = useState<string>("unknown");
+
+    useEffect(() => {
+      setOsType(getOsType());
+    }, []);
+
+    // Real-time signals only exist on Linux
+    if (osType !== "linux") return null;
+
+    const signalBindings = getSetting("signal_bindings") ?? [];
+    const bindings = getSetting("bindings") ?? {};
+    const bindingOptions = Object.keys(bindings)
+      .filter((id) => id !== "cancel")
+      .map((id) => ({ value: id, label: bindings[id]?.name ?? id }));
+    const updating = isUpdating("signal_bindings");
+
+    const update = (next: SignalBinding[]) =>
+      updateSetting("signal_bindings", next);
+    const replace = (index: number, changes: Partial<SignalBinding>) =>
+      update(
+        signalBindings.map((binding, i) =>
+          i === index ? { ...binding, ...changes } : binding,
+        ),
+      );
+
+    const used = new Set(signalBindings.map((binding) => binding.offset));
+    const freeOffset = OFFSETS.find((offset) => !used.has(offset));
+
+    return (
+      <SettingContainer
+        title={t("settings.advanced.signalBindings.title")}
+        description={t("settings.advanced.signalBindings.description")}
+        descriptionMode={descriptionMode}
+        grouped={grouped}
+        layout="stacked"
+      >
+        <div className="space-y-2">
+          {signalBindings.map((binding, index) => (
+            <div key={binding.offset} className="flex items-center gap-2">
+              <Dropdown
+                options={OFFSETS.map((offset) => ({
+                  value: String(offset),
+                  label: `SIGRTMIN+${offset}`,
+                  disabled: offset !== binding.offset && used.has(offset),
+                }))}
+                selectedValue={String(binding.offset)}
+                onSelect={(value) => replace(index, { offset: Number(value) })}
+                disabled={updating}
+              />
+              <Dropdown
+                options={bindingOptions}
+                selectedValue={binding.binding_id}
+                onSelect={(value) => replace(index, { binding_id: value })}
+                disabled={updating}
+              />
+              <Dropdown
+                options={MODES.map((mode) => ({
+                  value: mode,
+                  label: t(`settings.advanced.signalBindings.modes.${mode}`),
+                }))}
+                selectedValue={binding.mode ?? "toggle"}
+                onSelect={(value) =>
+                  replace(index, { mode: value as SignalMode })
+                }
+                disabled={updating}
+              />
+              <Button
+                variant="secondary"
+                size="sm"
+                onClick={() =>
+                  update(signalBindings.filter((_, i) => i !== index))
+                }
+                disabled={updating}
+              >
+                {t("settings.advanced.signalBindings.remove")}
+              </Button>
+            </div>
+          ))}
+          <Button
+            variant="secondary"
+            size="sm"
+            onClick={() =>
+              freeOffset !== undefined &&
+              update([
+                ...signalBindings,
+                {
+                  offset: freeOffset,
+                  binding_id: "transcribe",
+                  mode: "toggle",
+                },
+              ])
+            }
+            disabled={updating || freeOffset === undefined}
+          >
+            {t("settings.advanced.signalBindings.add")}
+          </Button>
+        </div>
+      </SettingContainer>
+    );
+  },
+);
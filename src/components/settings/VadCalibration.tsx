@@ -0,0 +1,113 @@
+import React, { useEffect, useRef, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { listen } from "@tauri-apps/api/event";
+import { toast } from "sonner";
+import { commands } from "@/bindings";
+import { Button } from "../ui/Button";
+import { SettingContainer } from "../ui/SettingContainer";
+
+interface VadCalibrationProps {
+  descriptionMode?: "inline" | "tooltip";
+  grouped?: boolean;
+}
+
+/** Payload of `vad-calibration`, one per 30 ms frame */
+interface VadDecision {
+  speech: boolean;
+  score: number | null;
+}
+
+// Three seconds of frames
+const HISTORY = 100;
+
+export const VadCalibration: React.FC<VadCalibrationProps> = React.memo(
+  ({ descriptionMode = "tooltip", grouped = false }) => {
+    const { t } = useTranslation();
+    const [running, setRunning] = useState(false);
+    const [decisions, setDecisions] = useState<VadDecision[]>([]);
+    const runningRef = useRef(false);
+
+    useEffect(() => {
+      if (!running) return;
+      const unlisten = listen<VadDecision>("vad-calibration", (event) => {
+        setDecisions((prev) => [...prev.slice(1 - HISTORY), event.payload]);
+      });
+      return () => {
+        unlisten.then((fn) => fn());
+      };
+    }, [running]);
+
+    // Leaving the page ends calibration so the microphone isn't held open
+    useEffect(
+      () => () => {
+        if (runningRef.current) commands.stopVadCalibration();
+      },
+      [],
+    );
+
+    const handleToggle = async () => {
+      if (running) {
+        await commands.stopVadCalibration();
+        runningRef.current = false;
+        setRunning(false);
+        return;
+      }
+      const result = await commands.startVadCalibration();
+      if (result.status === "ok") {
+        setDecisions([]);
+        runningRef.current = true;
+        setRunning(true);
+      } else {
+        toast.error(result.error);
+      }
+    };
+
+    const latest = decisions[decisions.length - 1];
+
+    return (
+      <SettingContainer
+        title={t("settings.advanced.vad.calibration.title")}
+        description={t("settings.advanced.vad.calibration.description")}
+        descriptionMode={descriptionMode}
+        grouped={grouped}
+        layout="stacked"
+      >
+        <div className="space-y-2">
+          <div className="flex items-center gap-3">
+            <Button variant="secondary" size="sm" onClick={handleToggle}>
+              {running
+                ? t("settings.advanced.vad.calibration.stop")
+                : t("settings.advanced.vad.calibration.start")}
+            </Button>
+            {running && latest && (
+              <span
+                className={`text-sm font-medium ${
+                  latest.speech ? "text-logo-primary" : "text-mid-gray"
+                }`}
+              >
+                {latest.speech
+                  ? t("settings.advanced.vad.calibration.speech")
+                  : t("settings.advanced.vad.calibration.noise")}
+                {latest.score !== null && ` (${latest.score.toFixed(2)})`}
+              </span>
+            )}
+          </div>
+          {running && (
+            <div className="flex items-end gap-px h-8">
+              {decisions.map((decision, i) => (
+                <div
+                  key={i}
+                  className={
+                    decision.speech
+                      ? "flex-1 h-full rounded-sm bg-logo-primary"
+                      : "flex-1 h-1/4 rounded-sm bg-mid-gray/30"
+                  }
+                />
+              ))}
+            </div>
+          )}
+        </div>
+      </SettingContainer>
+    );
+  },
+);
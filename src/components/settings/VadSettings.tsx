@@ -0,0 +1,116 @@
+import React from "react";
+import { useTranslation } from "react-i18next";
+import type { VadBackend, VadSettings as Vad } from "@/bindings";
+import { Dropdown } from "../ui/Dropdown";
+import { SettingContainer } from "../ui/SettingContainer";
+import { Slider } from "../ui/Slider";
+import { useSettings } from "../../hooks/useSettings";
+
+interface VadSettingsProps {
+  descriptionMode?: "inline" | "tooltip";
+  grouped?: boolean;
+}
+
+// Frames are 30 ms long
+const formatFrames = (frames: number) => `${frames * 30} ms`;
+
+export const VadSettings: React.FC<VadSettingsProps> = React.memo(
+  ({ descriptionMode = "tooltip", grouped = false }) => {
+    const { t } = useTranslation();
+    const { getSetting, updateSetting, isUpdating } = useSettings();
+    const vad = getSetting("vad");
+    if (!vad) return null;
+
+    const update = (changes: Partial<Vad>) =>
+      updateSetting("vad", { ...vad, ...changes });
+
+    return (
+      <>
+        <SettingContainer
+          title={t("settings.advanced.vad.backend.title")}
+          description={t("settings.advanced.vad.backend.description")}
+          descriptionMode={descriptionMode}
+          grouped={grouped}
+        >
+          <Dropdown
+            options={[
+              {
+                value: "silero",
+                label: t("settings.advanced.vad.backend.options.silero"),
+              },
+              {
+                value: "energy",
+                label: t("settings.advanced.vad.backend.options.energy"),
+              },
+            ]}
+            selectedValue={vad.backend ?? "silero"}
+            onSelect={(value) => update({ backend: value as VadBackend })}
+            disabled={isUpdating("vad")}
+          />
+        </SettingContainer>
+        {vad.backend === "energy" ? (
+          <Slider
+            value={vad.energy_margin_db ?? 10}
+            onChange={(value) => update({ energy_margin_db: value })}
+            min={0}
+            max={40}
+            step={1}
+            label={t("settings.advanced.vad.energyMargin.title")}
+            description={t("settings.advanced.vad.energyMargin.description")}
+            descriptionMode={descriptionMode}
+            grouped={grouped}
+            formatValue={(value) => `${value.toFixed(0)} dB`}
+          />
+        ) : (
+          <Slider
+            value={vad.threshold ?? 0.3}
+            onChange={(value) => update({ threshold: value })}
+            min={0.05}
+            max={0.95}
+            step={0.05}
+            label={t("settings.advanced.vad.threshold.title")}
+            description={t("settings.advanced.vad.threshold.description")}
+            descriptionMode={descriptionMode}
+            grouped={grouped}
+          />
+        )}
+        <Slider
+          value={vad.prefill_frames ?? 15}
+          onChange={(value) => update({ prefill_frames: value })}
+          min={0}
+          max={100}
+          step={1}
+          label={t("settings.advanced.vad.prefill.title")}
+          description={t("settings.advanced.vad.prefill.description")}
+          descriptionMode={descriptionMode}
+          grouped={grouped}
+          formatValue={formatFrames}
+        />
+        <Slider
+          value={vad.hangover_frames ?? 15}
+          onChange={(value) => update({ hangover_frames: value })}
+          min={0}
+          max={100}
+          step={1}
+          label={t("settings.advanced.vad.hangover.title")}
+          description={t("settings.advanced.vad.hangover.description")}
+          descriptionMode={descriptionMode}
+          grouped={grouped}
+          formatValue={formatFrames}
+        />
+        <Slider
+          value={vad.onset_frames ?? 2}
+          onChange={(value) => update({ onset_frames: value })}
+          min={1}
+          max={20}
+          step={1}
+          label={t("settings.advanced.vad.onset.title")}
+          description={t("settings.advanced.vad.onset.description")}
+          descriptionMode={descriptionMode}
+          grouped={grouped}
+          formatValue={formatFrames}
+        />
+      </>
+    );
+  },
+);
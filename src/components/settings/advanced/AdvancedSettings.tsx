@@ -10,6 +10,27 @@ import { AutostartToggle } from "../AutostartToggle";
 import { PasteMethodSetting } from "../PasteMethod";
 import { OpenCustomVocab } from "../OpenCustomVocab";
 import { MedicalModeToggle } from "../MedicalModeToggle";
+import { ClipboardHandlingSetting } from "../ClipboardHandling";
+import { ClipboardExpirySetting } from "../ClipboardExpiry";
+import { PasteToolSetting } from "../PasteTool";
+import { PasteFallback } from "../PasteFallback";
+import { PasteIntoOriginalWindow } from "../PasteIntoOriginalWindow";
+import { OutputSinks } from "../OutputSinks";
+import { ControlApi } from "../ControlApi";
+import { SignalBindings } from "../SignalBindings";
+import { VadSettings } from "../VadSettings";
+import { VadCalibration } from "../VadCalibration";
+import { AutoStop } from "../AutoStop";
+import { PreRoll } from "../PreRoll";
+import { AudioProcessing } from "../AudioProcessing";
+import { Diarization } from "../Diarization";
+import { ModelLoading } from "../ModelLoading";
+import { ClinicHours } from "../ClinicHours";
+import { ModelBenchmark } from "../ModelBenchmark";
+import { ModelCatalog } from "../ModelCatalog";
+import { ModelIntegrity } from "../ModelIntegrity";
+import { ModelImport } from "../ModelImport";
+import { ModelPacks } from "../ModelPacks";
 
 export const AdvancedSettings: React.FC = () => {
   const { t } = useTranslation();
@@ -26,6 +47,33 @@ export const AdvancedSettings: React.FC = () => {
         <MedicalModeToggle descriptionMode="tooltip" grouped={true} />
         <OpenCustomVocab descriptionMode="tooltip" grouped={true} />
       </SettingsGroup>
+      <SettingsGroup title={t("settings.advanced.groups.recording")}>
+        <VadSettings descriptionMode="tooltip" grouped={true} />
+        <VadCalibration descriptionMode="tooltip" grouped={true} />
+        <AutoStop descriptionMode="tooltip" grouped={true} />
+        <PreRoll descriptionMode="tooltip" grouped={true} />
+        <AudioProcessing descriptionMode="tooltip" grouped={true} />
+        <Diarization descriptionMode="tooltip" grouped={true} />
+      </SettingsGroup>
+      <SettingsGroup title={t("settings.advanced.groups.output")}>
+        <PasteToolSetting descriptionMode="tooltip" grouped={true} />
+        <PasteFallback descriptionMode="tooltip" grouped={true} />
+        <PasteIntoOriginalWindow descriptionMode="tooltip" grouped={true} />
+        <ClipboardHandlingSetting descriptionMode="tooltip" grouped={true} />
+        <ClipboardExpirySetting descriptionMode="tooltip" grouped={true} />
+        <OutputSinks descriptionMode="tooltip" grouped={true} />
+        <ControlApi descriptionMode="tooltip" grouped={true} />
+        <SignalBindings descriptionMode="tooltip" grouped={true} />
+      </SettingsGroup>
+      <SettingsGroup title={t("settings.models.title")}>
+        <ModelLoading descriptionMode="tooltip" grouped={true} />
+        <ClinicHours descriptionMode="tooltip" grouped={true} />
+        <ModelBenchmark descriptionMode="tooltip" grouped={true} />
+        <ModelCatalog descriptionMode="tooltip" grouped={true} />
+        <ModelIntegrity descriptionMode="tooltip" grouped={true} />
+        <ModelImport descriptionMode="tooltip" grouped={true} />
+        <ModelPacks descriptionMode="tooltip" grouped={true} />
+      </SettingsGroup>
     </div>
   );
 };
@@ -1,6 +1,8 @@
 import React from "react";
 import { useTranslation } from "react-i18next";
 import { MicrophoneSelector } from "../MicrophoneSelector";
+import { MicrophoneChannel } from "../MicrophoneChannel";
+import { MicrophoneTest } from "../MicrophoneTest";
 import { LanguageSelector } from "../LanguageSelector";
 import { HandyShortcut } from "../HandyShortcut";
 import { SettingsGroup } from "../../ui/SettingsGroup";
@@ -24,6 +26,8 @@ export const GeneralSettings: React.FC = React.memo(() => {
       </SettingsGroup>
       <SettingsGroup title={t("settings.sound.title")}>
         <MicrophoneSelector descriptionMode="tooltip" grouped={true} />
+        <MicrophoneChannel descriptionMode="tooltip" grouped={true} />
+        <MicrophoneTest descriptionMode="tooltip" grouped={true} />
         <AudioFeedback descriptionMode="tooltip" grouped={true} />
         <OutputDeviceSelector
           descriptionMode="tooltip"
@@ -12,11 +12,17 @@ import { syncLanguageFromSettings } from "@/i18n";
 
 type OverlayState = "recording" | "transcribing";
 
+interface QueueState {
+  is_recording: boolean;
+  pending: number;
+}
+
 const RecordingOverlay: React.FC = () => {
   const { t } = useTranslation();
   const [isVisible, setIsVisible] = useState(false);
   const [state, setState] = useState<OverlayState>("recording");
   const [levels, setLevels] = useState<number[]>(Array(16).fill(0));
+  const [pending, setPending] = useState(0);
   const smoothedLevelsRef = useRef<number[]>(Array(16).fill(0));
 
   useEffect(() => {
@@ -49,11 +55,20 @@ const RecordingOverlay: React.FC = () => {
         setLevels(smoothed.slice(0, 9));
       });
 
+      // Listen for transcription queue changes
+      const unlistenQueue = await listen<QueueState>(
+        "transcription-queue-changed",
+        (event) => {
+          setPending(event.payload.pending);
+        },
+      );
+
       // Cleanup function
       return () => {
         unlistenShow();
         unlistenHide();
         unlistenLevel();
+        unlistenQueue();
       };
     };
 
@@ -94,6 +109,7 @@ const RecordingOverlay: React.FC = () => {
       </div>
 
       <div className="overlay-right">
+        {pending > 0 && <div className="queue-badge">{pending}</div>}
         {state === "recording" && (
           <div
             className="cancel-button"
@@ -1,9 +1,19 @@
 import { create } from "zustand";
 import { subscribeWithSelector } from "zustand/middleware";
+import { toast } from "sonner";
 import type {
   AppSettings as Settings,
   AudioDevice,
+  AutoStopSettings,
+  ClinicHours,
+  ClipboardExpiry,
+  DiarizationSettings,
+  DspConfig,
   LogLevel,
+  PasteTool,
+  Result,
+  SignalBinding,
+  VadSettings,
 } from "@/bindings";
 import { commands } from "@/bindings";
 
@@ -51,6 +61,15 @@ const DEFAULT_AUDIO_DEVICE: AudioDevice = {
   index: "default",
   name: "Default",
   is_default: true,
+  channels: [],
+  sample_rates: [],
+};
+
+// Commands that validate their input report failures as a Result; throwing
+// rolls the optimistic update back
+const orThrow = async (result: Promise<Result<null, string>>) => {
+  const r = await result;
+  if (r.status === "error") throw new Error(r.error);
 };
 
 const settingUpdaters: {
@@ -101,6 +120,16 @@ const settingUpdaters: {
   paste_method: (value) => commands.changePasteMethodSetting(value as string),
   clipboard_handling: (value) =>
     commands.changeClipboardHandlingSetting(value as string),
+  clipboard_expiry: (value) =>
+    commands.changeClipboardExpirySetting(value as ClipboardExpiry),
+  paste_tool: (value) => commands.changePasteToolSetting(value as PasteTool),
+  paste_fallback_to_typing: (value) =>
+    commands.changePasteFallbackSetting(value as boolean),
+  paste_into_original_window: (value) =>
+    commands.changePasteIntoOriginalWindowSetting(value as boolean),
+  ipc_enabled: (value) => commands.changeIpcEnabledSetting(value as boolean),
+  signal_bindings: (value) =>
+    orThrow(commands.changeSignalBindingsSetting(value as SignalBinding[])),
   // EPHEMERAL MODE: History limit removed - no persistent history
   // history_limit: (value) => commands.updateHistoryLimit(value as number),
   // EPHEMERAL MODE: Post-processing removed - LLM features disabled
@@ -120,6 +149,28 @@ const settingUpdaters: {
     value ? commands.markSetupCompleted() : Promise.resolve(),
   hide_privacy_notice: (value) =>
     commands.changeHidePrivacyNoticeSetting(value as boolean),
+  model_catalog_url: (value) =>
+    orThrow(commands.changeModelCatalogUrlSetting(value as string | null)),
+  model_signing_public_key: (value) =>
+    orThrow(
+      commands.changeModelSigningPublicKeySetting(value as string | null),
+    ),
+  model_latency_target_rtf: (value) =>
+    orThrow(commands.changeModelLatencyTargetSetting(value as number)),
+  preload_model_on_startup: (value) =>
+    commands.changePreloadModelOnStartupSetting(value as boolean),
+  low_memory_unload_threshold_mb: (value) =>
+    commands.changeLowMemoryUnloadThresholdSetting(value as number),
+  clinic_hours: (value) =>
+    orThrow(commands.changeClinicHoursSetting(value as ClinicHours | null)),
+  vad: (value) => orThrow(commands.changeVadSettings(value as VadSettings)),
+  auto_stop: (value) =>
+    orThrow(commands.changeAutoStopSetting(value as AutoStopSettings)),
+  pre_roll_ms: (value) =>
+    orThrow(commands.changePreRollSetting(value as number)),
+  dsp: (value) => orThrow(commands.changeDspSettings(value as DspConfig)),
+  diarization: (value) =>
+    orThrow(commands.changeDiarizationSettings(value as DiarizationSettings)),
 };
 
 export const useSettingsStore = create<SettingsStore>()(
@@ -257,6 +308,7 @@ export const useSettingsStore = create<SettingsStore>()(
         }
       } catch (error) {
         console.error(`Failed to update setting ${String(key)}:`, error);
+        toast.error(error instanceof Error ? error.message : String(error));
         if (settings) {
           set({ settings: { ...settings, [key]: originalValue } });
         }